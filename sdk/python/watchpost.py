@@ -291,18 +291,37 @@ class Watchpost:
         method: str = "GET",
         interval_seconds: int = 600,
         timeout_ms: int = 10000,
+        connect_timeout_ms: Optional[int] = None,
+        description: Optional[str] = None,
+        runbook_url: Optional[str] = None,
         expected_status: int = 200,
         body_contains: Optional[str] = None,
+        body_not_contains: Optional[str] = None,
+        min_body_bytes: Optional[int] = None,
+        auth_type: Optional[str] = None,
+        auth_username: Optional[str] = None,
+        auth_password: Optional[str] = None,
+        auth_token: Optional[str] = None,
+        client_cert_pem: Optional[str] = None,
+        client_key_pem: Optional[str] = None,
+        ca_bundle_pem: Optional[str] = None,
+        insecure_skip_tls_verify: bool = False,
+        max_body_bytes: Optional[int] = None,
         headers: Optional[Dict[str, str]] = None,
         is_public: bool = False,
         group_name: Optional[str] = None,
         tags: Optional[List[str]] = None,
         follow_redirects: Optional[bool] = None,
+        max_redirects: Optional[int] = None,
+        expected_final_url_prefix: Optional[str] = None,
+        expected_first_status: Optional[int] = None,
         response_time_threshold_ms: Optional[int] = None,
         confirmation_threshold: Optional[int] = None,
         sla_target: Optional[float] = None,
         sla_period_days: Optional[int] = None,
         consensus_threshold: Optional[int] = None,
+        consensus_mode: Optional[str] = None,
+        steps: Optional[List[Dict[str, Any]]] = None,
         max_messages: Optional[int] = None,
         dns_record_type: Optional[str] = None,
         dns_expected: Optional[str] = None,
@@ -315,7 +334,8 @@ class Watchpost:
                  http: "https://example.com/health"
                  tcp: "example.com:5432"
                  dns: "example.com"
-            monitor_type: "http" (default), "tcp", or "dns".
+            monitor_type: "http" (default), "tcp", "dns", or "multistep"
+                 (multistep requires ``steps``).
         """
         payload: Dict[str, Any] = {
             "name": name,
@@ -327,8 +347,36 @@ class Watchpost:
             "expected_status": expected_status,
             "is_public": is_public,
         }
+        if connect_timeout_ms is not None:
+            payload["connect_timeout_ms"] = connect_timeout_ms
+        if description is not None:
+            payload["description"] = description
+        if runbook_url is not None:
+            payload["runbook_url"] = runbook_url
         if body_contains is not None:
             payload["body_contains"] = body_contains
+        if body_not_contains is not None:
+            payload["body_not_contains"] = body_not_contains
+        if auth_type is not None:
+            payload["auth_type"] = auth_type
+        if auth_username is not None:
+            payload["auth_username"] = auth_username
+        if auth_password is not None:
+            payload["auth_password"] = auth_password
+        if auth_token is not None:
+            payload["auth_token"] = auth_token
+        if client_cert_pem is not None:
+            payload["client_cert_pem"] = client_cert_pem
+        if client_key_pem is not None:
+            payload["client_key_pem"] = client_key_pem
+        if ca_bundle_pem is not None:
+            payload["ca_bundle_pem"] = ca_bundle_pem
+        if insecure_skip_tls_verify:
+            payload["insecure_skip_tls_verify"] = True
+        if min_body_bytes is not None:
+            payload["min_body_bytes"] = min_body_bytes
+        if max_body_bytes is not None:
+            payload["max_body_bytes"] = max_body_bytes
         if headers is not None:
             payload["headers"] = headers
         if group_name is not None:
@@ -337,6 +385,12 @@ class Watchpost:
             payload["tags"] = tags
         if follow_redirects is not None:
             payload["follow_redirects"] = follow_redirects
+        if max_redirects is not None:
+            payload["max_redirects"] = max_redirects
+        if expected_final_url_prefix is not None:
+            payload["expected_final_url_prefix"] = expected_final_url_prefix
+        if expected_first_status is not None:
+            payload["expected_first_status"] = expected_first_status
         if response_time_threshold_ms is not None:
             payload["response_time_threshold_ms"] = response_time_threshold_ms
         if confirmation_threshold is not None:
@@ -347,6 +401,10 @@ class Watchpost:
             payload["sla_period_days"] = sla_period_days
         if consensus_threshold is not None:
             payload["consensus_threshold"] = consensus_threshold
+        if consensus_mode is not None:
+            payload["consensus_mode"] = consensus_mode
+        if steps is not None:
+            payload["steps"] = steps
         if dns_record_type is not None:
             payload["dns_record_type"] = dns_record_type
         if dns_expected is not None:
@@ -395,9 +453,13 @@ class Watchpost:
         return self._flatten_monitor_response(self._patch(f"/api/v1/monitors/{monitor_id}", fields, key=key))
 
     def delete_monitor(self, monitor_id: str, key: str) -> None:
-        """Delete a monitor and all its data."""
+        """Delete a monitor. It can be restored until the server's grace period ends."""
         self._delete(f"/api/v1/monitors/{monitor_id}", key=key)
 
+    def restore_monitor(self, monitor_id: str, key: str) -> Dict:
+        """Restore a deleted monitor within the grace period."""
+        return self._post(f"/api/v1/monitors/{monitor_id}/restore", key=key)
+
     def pause_monitor(self, monitor_id: str, key: str) -> Dict:
         """Pause monitoring checks."""
         return self._flatten_monitor_response(self._post(f"/api/v1/monitors/{monitor_id}/pause", key=key))
@@ -406,6 +468,29 @@ class Watchpost:
         """Resume monitoring checks."""
         return self._flatten_monitor_response(self._post(f"/api/v1/monitors/{monitor_id}/resume", key=key))
 
+    def check_now(self, monitor_id: str, key: str) -> Dict:
+        """Run a check immediately and return the recorded heartbeat."""
+        return self._post(f"/api/v1/monitors/{monitor_id}/check-now", key=key)
+
+    def get_check_state(self, monitor_id: str, key: str) -> Dict:
+        """Checker state: consecutive failures/successes, last check duration, next due time."""
+        return self._get(f"/api/v1/monitors/{monitor_id}/check-state", key=key)
+
+    def archive_monitor(self, monitor_id: str, key: str) -> Dict:
+        """Archive a monitor: checks stop and its config becomes read-only, history is kept."""
+        return self._post(f"/api/v1/monitors/{monitor_id}/archive", key=key)
+
+    def unarchive_monitor(self, monitor_id: str, key: str) -> Dict:
+        """Return an archived monitor to service."""
+        return self._post(f"/api/v1/monitors/{monitor_id}/unarchive", key=key)
+
+    def rotate_key(self, monitor_id: str, key: str) -> Dict:
+        """Replace the monitor's manage key. The old key stops working immediately.
+
+        Returns a dict with the new ``manage_key`` and ``manage_url``.
+        """
+        return self._post(f"/api/v1/monitors/{monitor_id}/rotate-key", key=key)
+
     def export_monitor(self, monitor_id: str, key: str) -> Dict:
         """Export monitor config for backup/migration."""
         return self._get(f"/api/v1/monitors/{monitor_id}/export", key=key)
@@ -428,6 +513,36 @@ class Watchpost:
             result["created"] = [self._flatten_monitor_response(m) for m in result["created"]]
         return result
 
+    def bulk_action(
+        self,
+        action: str,
+        *,
+        tag: Optional[str] = None,
+        ids: Optional[List[str]] = None,
+        manage_keys: Optional[Dict[str, str]] = None,
+        key: Optional[str] = None,
+    ) -> Dict:
+        """Pause, resume, or delete monitors selected by tag or ids.
+
+        Args:
+            action: "pause", "resume", or "delete".
+            tag: Act on every monitor with this tag.
+            ids: Or act on these monitor ids.
+            manage_keys: Manage key per monitor id.
+            key: Admin key (covers all monitors) or a write-scoped API key.
+
+        Returns:
+            Dict with action, results (per monitor), succeeded, failed.
+        """
+        body: Dict[str, Any] = {"action": action}
+        if tag is not None:
+            body["tag"] = tag
+        if ids is not None:
+            body["ids"] = ids
+        if manage_keys:
+            body["manage_keys"] = manage_keys
+        return self._post("/api/v1/monitors/bulk-action", body, key=key)
+
     # ------------------------------------------------------------------
     # Heartbeats (check history)
     # ------------------------------------------------------------------
@@ -461,6 +576,19 @@ class Watchpost:
         """Get uptime stats (24h/7d/30d/90d) for a monitor."""
         return self._get(f"/api/v1/monitors/{monitor_id}/uptime")
 
+    def get_latency_histogram(
+        self,
+        monitor_id: str,
+        *,
+        buckets: Optional[List[int]] = None,
+        period: str = "24h",
+    ) -> Dict:
+        """Response-time histogram of successful checks (per-bucket counts, last bucket unbounded)."""
+        params: Dict[str, Any] = {"period": period}
+        if buckets:
+            params["buckets"] = ",".join(str(b) for b in buckets)
+        return self._get(f"/api/v1/monitors/{monitor_id}/latency-histogram", params=params)
+
     def get_uptime_history(
         self,
         monitor_id: Optional[str] = None,
@@ -497,6 +625,36 @@ class Watchpost:
             params["limit"] = limit
         return self._get(f"/api/v1/monitors/{monitor_id}/incidents", params=params)
 
+    def list_all_incidents(
+        self,
+        *,
+        status: Optional[str] = None,
+        monitor_id: Optional[str] = None,
+        since: Optional[str] = None,
+        search: Optional[str] = None,
+        after: Optional[int] = None,
+        limit: Optional[int] = None,
+        key: Optional[str] = None,
+    ) -> Any:
+        """List recent incidents across all monitors.
+
+        Args:
+            status: ``"open"`` or ``"resolved"``.
+            monitor_id: Only incidents for this monitor.
+            since: Only incidents started at or after this timestamp.
+            search: Substring match on the incident cause.
+            after: Seq cursor (returns oldest first).
+            limit: Max results (default 20, max 100).
+            key: Admin key to include private monitors (or the monitor's
+                manage key together with ``monitor_id``).
+        """
+        params: Dict[str, Any] = {}
+        for name, value in (("status", status), ("monitor_id", monitor_id), ("since", since),
+                            ("search", search), ("after", after), ("limit", limit)):
+            if value is not None:
+                params[name] = value
+        return self._get("/api/v1/incidents", key=key, params=params)
+
     def get_incident(self, incident_id: str) -> Dict:
         """Get single incident detail (includes notes_count)."""
         return self._get(f"/api/v1/incidents/{incident_id}")
@@ -528,6 +686,36 @@ class Watchpost:
     # Incident Notes
     # ------------------------------------------------------------------
 
+    def create_manual_incident(
+        self,
+        monitor_id: str,
+        key: str,
+        cause: str,
+        *,
+        severity: Optional[str] = None,
+        auto_resolve: bool = False,
+    ) -> Dict:
+        """Open a manual incident for an outage checks haven't caught.
+
+        Args:
+            monitor_id: Monitor UUID.
+            key: Manage key.
+            cause: What is happening.
+            severity: ``"info"``, ``"warning"``, or ``"critical"`` (default).
+            auto_resolve: Let the checker close it when the monitor recovers.
+        """
+        body: Dict[str, Any] = {"cause": cause, "auto_resolve": auto_resolve}
+        if severity is not None:
+            body["severity"] = severity
+        return self._post(f"/api/v1/monitors/{monitor_id}/incidents", body, key=key)
+
+    def resolve_incident(self, incident_id: str, key: str, *, note: Optional[str] = None) -> Dict:
+        """Resolve an open incident."""
+        body: Dict[str, Any] = {}
+        if note is not None:
+            body["note"] = note
+        return self._post(f"/api/v1/incidents/{incident_id}/resolve", body, key=key)
+
     def add_incident_note(
         self,
         incident_id: str,
@@ -578,8 +766,10 @@ class Watchpost:
         Args:
             monitor_id: Monitor UUID.
             name: Channel label.
-            channel_type: "webhook" or "email".
-            config: Channel config — {"url": "..."} for webhook, {"address": "..."} for email.
+            channel_type: "webhook", "email", "ntfy", "teams", or "opsgenie".
+            config: Channel config — {"url": "..."} for webhook or teams, {"address": "..."} for email,
+                    {"topic": "...", "server": "https://ntfy.sh"} for ntfy,
+                    {"api_key": "...", "region": "us"} for opsgenie.
                     For webhook, optionally add "payload_format": "chat" for simple text payloads.
             key: Manage key.
         """
@@ -647,6 +837,8 @@ class Watchpost:
         starts_at: str,
         ends_at: str,
         key: str,
+        *,
+        remind_minutes_before: Optional[int] = None,
     ) -> Dict:
         """Schedule a maintenance window.
 
@@ -656,12 +848,13 @@ class Watchpost:
             starts_at: ISO-8601 start time.
             ends_at: ISO-8601 end time.
             key: Manage key.
+            remind_minutes_before: Send a ``maintenance.upcoming`` notification
+                this many minutes before the window starts.
         """
-        return self._post(
-            f"/api/v1/monitors/{monitor_id}/maintenance",
-            {"title": title, "starts_at": starts_at, "ends_at": ends_at},
-            key=key,
-        )
+        body: Dict[str, Any] = {"title": title, "starts_at": starts_at, "ends_at": ends_at}
+        if remind_minutes_before is not None:
+            body["remind_minutes_before"] = remind_minutes_before
+        return self._post(f"/api/v1/monitors/{monitor_id}/maintenance", body, key=key)
 
     def list_maintenance(self, monitor_id: str) -> Any:
         """List maintenance windows for a monitor."""
@@ -683,6 +876,16 @@ class Watchpost:
         """
         return self._get(f"/api/v1/monitors/{monitor_id}/sla")
 
+    def get_sla_report(self, monitor_id: str, period: str = "month", format: str = "json") -> Union[Dict, str]:
+        """Get per-day uptime for the last week or month with the aggregate against the SLA target.
+
+        Returns a dict for format="json" or the CSV text for format="csv".
+        """
+        return self._get(
+            f"/api/v1/monitors/{monitor_id}/sla/report",
+            params={"period": period, "format": format},
+        )
+
     # ------------------------------------------------------------------
     # Badges
     # ------------------------------------------------------------------
@@ -693,12 +896,18 @@ class Watchpost:
         *,
         period: str = "24h",
         label: Optional[str] = None,
+        warn: Optional[float] = None,
+        crit: Optional[float] = None,
+        color: Optional[str] = None,
     ) -> str:
         """Get SVG uptime badge (shields.io style).
 
         Args:
             period: "24h", "7d", "30d", or "90d".
             label: Custom badge label.
+            warn: Uptime % at or above which the badge is green.
+            crit: Uptime % below which the badge is red.
+            color: Hex color override, e.g. "007ec6".
 
         Returns:
             SVG string.
@@ -706,9 +915,40 @@ class Watchpost:
         params: Dict[str, Any] = {"period": period}
         if label:
             params["label"] = label
+        if warn is not None:
+            params["warn"] = warn
+        if crit is not None:
+            params["crit"] = crit
+        if color:
+            params["color"] = color
         return self._get(f"/api/v1/monitors/{monitor_id}/badge/uptime", params=params, raw=True).decode()
 
-    def get_status_badge(self, monitor_id: str, *, label: Optional[str] = None) -> str:
+    def get_uptime_badge_json(
+        self,
+        monitor_id: str,
+        *,
+        period: str = "24h",
+        label: Optional[str] = None,
+        warn: Optional[float] = None,
+        crit: Optional[float] = None,
+    ) -> dict:
+        """Get the uptime badge as a Shields.io endpoint JSON object.
+
+        Returns:
+            Dict with schemaVersion, label, message, and color.
+        """
+        params: Dict[str, Any] = {"period": period}
+        if label:
+            params["label"] = label
+        if warn is not None:
+            params["warn"] = warn
+        if crit is not None:
+            params["crit"] = crit
+        return self._get(f"/api/v1/monitors/{monitor_id}/badge/uptime.json", params=params)
+
+    def get_status_badge(
+        self, monitor_id: str, *, label: Optional[str] = None, color: Optional[str] = None
+    ) -> str:
         """Get SVG status badge.
 
         Returns:
@@ -717,8 +957,43 @@ class Watchpost:
         params: Dict[str, Any] = {}
         if label:
             params["label"] = label
+        if color:
+            params["color"] = color
         return self._get(f"/api/v1/monitors/{monitor_id}/badge/status", params=params, raw=True).decode()
 
+    def get_response_badge(
+        self,
+        monitor_id: str,
+        *,
+        period: str = "24h",
+        label: Optional[str] = None,
+        warn: Optional[float] = None,
+        crit: Optional[float] = None,
+        color: Optional[str] = None,
+    ) -> str:
+        """Get SVG average response time badge.
+
+        Args:
+            period: "24h", "7d", "30d", or "90d".
+            label: Custom badge label.
+            warn: Response time in ms at or above which the badge is amber.
+            crit: Response time in ms at or above which the badge is red.
+            color: Hex color override, e.g. "007ec6".
+
+        Returns:
+            SVG string.
+        """
+        params: Dict[str, Any] = {"period": period}
+        if label:
+            params["label"] = label
+        if warn is not None:
+            params["warn"] = warn
+        if crit is not None:
+            params["crit"] = crit
+        if color:
+            params["color"] = color
+        return self._get(f"/api/v1/monitors/{monitor_id}/badge/response", params=params, raw=True).decode()
+
     # ------------------------------------------------------------------
     # Tags & Groups
     # ------------------------------------------------------------------
@@ -727,10 +1002,18 @@ class Watchpost:
         """List all unique tags across public monitors."""
         return self._get("/api/v1/tags")
 
+    def rename_tag(self, from_tag: str, to_tag: str, key: str) -> Dict:
+        """Rename a tag across all monitors, merging if the target exists (admin key required)."""
+        return self._post("/api/v1/tags/rename", {"from": from_tag, "to": to_tag}, key=key)
+
     def list_groups(self) -> List[str]:
         """List all unique group names across public monitors."""
         return self._get("/api/v1/groups")
 
+    def get_group_uptime(self, group: str) -> Dict:
+        """Uptime and latency pooled across a group's public monitors (24h/7d/30d)."""
+        return self._get(f"/api/v1/groups/{urllib.parse.quote(group, safe='')}/uptime")
+
     # ------------------------------------------------------------------
     # Status / Dashboard
     # ------------------------------------------------------------------
@@ -777,6 +1060,55 @@ class Watchpost:
         """Verify admin key validity."""
         return self._get("/api/v1/admin/verify", key=key)
 
+    def create_api_key(self, name: str, scopes: List[str], admin_key: str) -> Dict:
+        """Create a scoped API key (``read``, ``write``, ``admin``). The ``key`` is only returned here."""
+        return self._post("/api/v1/api-keys", {"name": name, "scopes": scopes}, key=admin_key)
+
+    def list_api_keys(self, admin_key: str) -> List[Dict]:
+        """List API keys (without their secrets)."""
+        return self._get("/api/v1/api-keys", key=admin_key)
+
+    def delete_api_key(self, api_key_id: str, admin_key: str) -> None:
+        """Revoke an API key."""
+        self._delete(f"/api/v1/api-keys/{api_key_id}", key=admin_key)
+
+    def audit_log(
+        self,
+        admin_key: str,
+        monitor_id: Optional[str] = None,
+        action: Optional[str] = None,
+        limit: Optional[int] = None,
+        after: Optional[int] = None,
+    ) -> List[Dict]:
+        """List management actions (newest first, or oldest first after a ``seq`` cursor)."""
+        params = {k: v for k, v in {"monitor_id": monitor_id, "action": action, "limit": limit, "after": after}.items() if v is not None}
+        return self._get("/api/v1/audit-log", key=admin_key, params=params)
+
+    def db_stats(self, admin_key: str) -> Dict:
+        """Table row counts, database size, and the oldest retained heartbeat."""
+        return self._get("/api/v1/admin/stats", key=admin_key)
+
+    def prune_heartbeats(self, admin_key: str, days: Optional[int] = None) -> Dict:
+        """Delete heartbeats older than ``days`` now (default: the server's retention)."""
+        params = {"days": days} if days is not None else None
+        return self._post("/api/v1/admin/prune", key=admin_key, params=params)
+
+    def vacuum(self, admin_key: str) -> Dict:
+        """Compact the database file. Returns sizes before/after and ``reclaimed_bytes``."""
+        return self._post("/api/v1/admin/vacuum", key=admin_key)
+
+    def pause_all_monitors(self, admin_key: str) -> Dict:
+        """Pause every monitor. Returns ``{"paused": n}`` (monitors that were running)."""
+        return self._post("/api/v1/admin/monitors/pause-all", key=admin_key)
+
+    def resume_all_monitors(self, admin_key: str) -> Dict:
+        """Resume every paused monitor. Returns ``{"resumed": n}``."""
+        return self._post("/api/v1/admin/monitors/resume-all", key=admin_key)
+
+    def rotate_admin_key(self, key: str) -> Dict:
+        """Replace the admin key. Returns a dict with the new ``admin_key``."""
+        return self._post("/api/v1/admin/rotate-key", key=key)
+
     # ------------------------------------------------------------------
     # Settings / Branding
     # ------------------------------------------------------------------
@@ -786,11 +1118,16 @@ class Watchpost:
         return self._get("/api/v1/settings")
 
     def update_settings(self, key: str, **fields) -> Dict:
-        """Update status page branding (admin key required).
+        """Update global settings (admin key required).
 
         Args:
             key: Admin key.
-            **fields: title, description, logo_url. Empty string clears a field.
+            **fields: title, description, logo_url (empty string clears),
+                default_headers, incident_cause_templates (dict of template
+                name to text; {} restores the built-ins), monitor_defaults
+                (dict of interval_seconds, timeout_ms, confirmation_threshold;
+                None resets a field), unknown_status_treatment ("unknown",
+                "operational" or "excluded"; "" resets).
         """
         return self._put("/api/v1/settings", fields, key=key)
 
@@ -977,13 +1314,14 @@ class Watchpost:
         """Delete a status page (monitors are not deleted)."""
         self._delete(f"/api/v1/status-pages/{slug_or_id}", key=key)
 
-    def add_monitors_to_page(self, slug_or_id: str, monitor_ids: List[str], key: str) -> Dict:
-        """Add monitors to a status page."""
-        return self._post(
-            f"/api/v1/status-pages/{slug_or_id}/monitors",
-            {"monitor_ids": monitor_ids},
-            key=key,
-        )
+    def add_monitors_to_page(
+        self, slug_or_id: str, monitor_ids: List[str], key: str, *, section: Optional[str] = None
+    ) -> Dict:
+        """Add monitors to a status page, optionally under a named section ("" clears it)."""
+        body: Dict[str, Any] = {"monitor_ids": monitor_ids}
+        if section is not None:
+            body["section"] = section
+        return self._post(f"/api/v1/status-pages/{slug_or_id}/monitors", body, key=key)
 
     def remove_monitor_from_page(self, slug_or_id: str, monitor_id: str, key: str) -> None:
         """Remove a monitor from a status page."""
@@ -993,6 +1331,10 @@ class Watchpost:
         """List monitors on a status page."""
         return self._get(f"/api/v1/status-pages/{slug_or_id}/monitors")
 
+    def subscribe_to_page(self, slug_or_id: str, email: str) -> Dict:
+        """Subscribe an email to a status page. A confirmation link is emailed."""
+        return self._post(f"/api/v1/status-pages/{slug_or_id}/subscribe", {"email": email})
+
     # ------------------------------------------------------------------
     # SSE Event Streams
     # ------------------------------------------------------------------
@@ -60,6 +60,47 @@ impl<'r> FromRequest<'r> for OptionalManageToken {
     }
 }
 
+/// Permission level of an account-level API key. Each scope includes the ones before it:
+/// `read` can view any monitor's config, `write` can also manage any monitor, and `admin`
+/// can do anything the admin key can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Scope {
+    Read,
+    Write,
+    Admin,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 3] = [Scope::Read, Scope::Write, Scope::Admin];
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|scope| scope.as_str() == s)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+            Scope::Admin => "admin",
+        }
+    }
+}
+
+/// Highest scope granted to `token` by the API key store, or None if it isn't an API key.
+pub fn api_key_scope(conn: &rusqlite::Connection, token: &str) -> Option<Scope> {
+    let scopes: String = conn.query_row(
+        "SELECT scopes FROM api_keys WHERE key_hash = ?1",
+        rusqlite::params![hash_key(token)],
+        |row| row.get(0),
+    ).ok()?;
+    scopes.split(',').filter_map(Scope::parse).max()
+}
+
+/// Whether `token` is an API key granting at least `needed`.
+pub fn api_key_allows(conn: &rusqlite::Connection, token: &str, needed: Scope) -> bool {
+    api_key_scope(conn, token).is_some_and(|scope| scope >= needed)
+}
+
 pub fn hash_key(key: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(key.as_bytes());
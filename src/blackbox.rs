@@ -0,0 +1,153 @@
+use crate::models::CreateMonitor;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A blackbox_exporter module (the subset of `blackbox.yml` that maps onto monitor settings).
+#[derive(Debug, Default, Deserialize)]
+struct Module {
+    prober: Option<String>,
+    timeout: Option<String>,
+    #[serde(default)]
+    http: HttpProbe,
+    #[serde(default)]
+    dns: DnsProbe,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct HttpProbe {
+    method: Option<String>,
+    #[serde(default)]
+    valid_status_codes: Vec<u16>,
+    #[serde(default)]
+    no_follow_redirects: bool,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DnsProbe {
+    query_name: Option<String>,
+    query_type: Option<String>,
+}
+
+/// A Prometheus file_sd target group: `{targets: [...], labels: {module: ..., job: ...}}`.
+#[derive(Debug, Deserialize)]
+struct TargetGroup {
+    #[serde(default)]
+    targets: Vec<String>,
+    #[serde(default)]
+    labels: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum BlackboxFile {
+    /// Plain file_sd list; modules are inferred from their names (`http_2xx`, `tcp_connect`, ...)
+    Groups(Vec<TargetGroup>),
+    /// Module definitions from blackbox.yml alongside the target groups that use them
+    WithModules {
+        #[serde(default)]
+        modules: HashMap<String, Module>,
+        targets: Vec<TargetGroup>,
+    },
+}
+
+/// Module used when a target group has no `module` label (blackbox_exporter's own default).
+const DEFAULT_MODULE: &str = "http_2xx";
+
+/// Parse a blackbox_exporter targets file into one entry per target, each either a
+/// monitor definition or the reason that target can't be represented as a monitor.
+pub fn parse_targets(yaml: &str) -> Result<Vec<Result<CreateMonitor, String>>, String> {
+    let file: BlackboxFile = serde_yaml::from_str(yaml)
+        .map_err(|e| format!("Invalid blackbox targets YAML: {}", e))?;
+    let (modules, groups) = match file {
+        BlackboxFile::Groups(groups) => (HashMap::new(), groups),
+        BlackboxFile::WithModules { modules, targets } => (modules, targets),
+    };
+
+    let mut entries = Vec::new();
+    for group in &groups {
+        let module_name = group.labels.get("module").map(|m| m.as_str()).unwrap_or(DEFAULT_MODULE);
+        for target in &group.targets {
+            entries.push(target_to_monitor(target.trim(), module_name, modules.get(module_name), &group.labels));
+        }
+    }
+    Ok(entries)
+}
+
+fn target_to_monitor(
+    target: &str,
+    module_name: &str,
+    module: Option<&Module>,
+    labels: &HashMap<String, String>,
+) -> Result<CreateMonitor, String> {
+    if target.is_empty() {
+        return Err("Target is empty".into());
+    }
+    // Without a module definition the prober is the name's prefix, as in the stock blackbox.yml
+    let prober = match module.and_then(|m| m.prober.as_deref()) {
+        Some(p) => p.to_lowercase(),
+        None => module_name.split('_').next().unwrap_or_default().to_lowercase(),
+    };
+    let default_module = Module::default();
+    let module = module.unwrap_or(&default_module);
+
+    let mut monitor = serde_json::json!({
+        "name": labels.get("name").map(|n| n.as_str()).unwrap_or(target),
+        "group_name": labels.get("job"),
+    });
+    match prober.as_str() {
+        "http" => {
+            let url = if target.contains("://") { target.to_string() } else { format!("http://{}", target) };
+            monitor["url"] = url.into();
+            monitor["monitor_type"] = "http".into();
+            if let Some(method) = &module.http.method {
+                monitor["method"] = method.to_uppercase().into();
+            }
+            if let Some(&status) = module.http.valid_status_codes.first() {
+                monitor["expected_status"] = status.into();
+            }
+            if module.http.no_follow_redirects {
+                monitor["follow_redirects"] = false.into();
+            }
+            if !module.http.headers.is_empty() {
+                monitor["headers"] = serde_json::json!(module.http.headers);
+            }
+        }
+        "tcp" => {
+            monitor["url"] = target.into();
+            monitor["monitor_type"] = "tcp".into();
+        }
+        "dns" => {
+            // A blackbox DNS target is the resolver to ask; the name being checked lives in the module
+            let query_name = module.dns.query_name.as_deref().ok_or_else(|| {
+                format!("DNS module '{}' has no query_name (include its definition under 'modules')", module_name)
+            })?;
+            monitor["url"] = query_name.trim_end_matches('.').into();
+            monitor["monitor_type"] = "dns".into();
+            monitor["dns_record_type"] = module.dns.query_type.as_deref().unwrap_or("A").to_uppercase().into();
+        }
+        other => {
+            return Err(format!("Module '{}' uses the '{}' prober, which has no monitor equivalent (supported: http, tcp, dns)", module_name, other));
+        }
+    }
+    if let Some(ms) = module.timeout.as_deref().and_then(parse_duration_ms) {
+        monitor["timeout_ms"] = ms.into();
+    }
+
+    serde_json::from_value(monitor).map_err(|e| e.to_string())
+}
+
+/// Parse a Prometheus duration like `5s`, `500ms`, or `1m` into milliseconds.
+fn parse_duration_ms(s: &str) -> Option<u32> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit())?;
+    let value: u32 = s[..split].parse().ok()?;
+    let factor = match &s[split..] {
+        "ms" => 1,
+        "s" => 1000,
+        "m" => 60_000,
+        _ => return None,
+    };
+    value.checked_mul(factor)
+}
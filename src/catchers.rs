@@ -1,59 +1,67 @@
 use rocket::catch;
 use rocket::serde::json::Json;
 use rocket::Request;
+use crate::logging::request_id;
 
 #[catch(400)]
-pub fn bad_request(_req: &Request) -> Json<serde_json::Value> {
+pub fn bad_request(req: &Request) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "error": "Bad request",
-        "code": "BAD_REQUEST"
+        "code": "BAD_REQUEST",
+        "request_id": request_id(req)
     }))
 }
 
 #[catch(401)]
-pub fn unauthorized(_req: &Request) -> Json<serde_json::Value> {
+pub fn unauthorized(req: &Request) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "error": "Missing or invalid authentication. Provide manage_key via Authorization: Bearer, X-API-Key header, or ?key= query param.",
-        "code": "UNAUTHORIZED"
+        "code": "UNAUTHORIZED",
+        "request_id": request_id(req)
     }))
 }
 
 #[catch(403)]
-pub fn forbidden(_req: &Request) -> Json<serde_json::Value> {
+pub fn forbidden(req: &Request) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "error": "Forbidden",
-        "code": "FORBIDDEN"
+        "code": "FORBIDDEN",
+        "request_id": request_id(req)
     }))
 }
 
 #[catch(404)]
-pub fn not_found(_req: &Request) -> Json<serde_json::Value> {
+pub fn not_found(req: &Request) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "error": "Not found",
-        "code": "NOT_FOUND"
+        "code": "NOT_FOUND",
+        "request_id": request_id(req)
     }))
 }
 
 #[catch(422)]
-pub fn unprocessable_entity(_req: &Request) -> Json<serde_json::Value> {
+pub fn unprocessable_entity(req: &Request) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "error": "Unprocessable entity. Check that your JSON body is valid and matches the expected schema.",
-        "code": "UNPROCESSABLE_ENTITY"
+        "code": "UNPROCESSABLE_ENTITY",
+        "request_id": request_id(req)
     }))
 }
 
 #[catch(429)]
-pub fn too_many_requests(_req: &Request) -> Json<serde_json::Value> {
+pub fn too_many_requests(req: &Request) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "error": "Rate limit exceeded",
-        "code": "RATE_LIMIT_EXCEEDED"
+        "code": "RATE_LIMIT_EXCEEDED",
+        "request_id": request_id(req)
     }))
 }
 
 #[catch(500)]
-pub fn internal_error(_req: &Request) -> Json<serde_json::Value> {
+pub fn internal_error(req: &Request) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "error": "Internal server error",
-        "code": "INTERNAL_ERROR"
+        "code": "INTERNAL_ERROR",
+        "request_id": request_id(req)
     }))
 }
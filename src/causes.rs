@@ -0,0 +1,162 @@
+use crate::checker::FailureKind;
+use rusqlite::params;
+
+/// Settings key holding admin overrides: a JSON object of template name → template.
+pub const SETTING_KEY: &str = "incident_cause_templates";
+
+/// Template names an admin can override, with the built-in text for each.
+/// `timeout` and `status` fall back to an overridden `down` before their built-in.
+pub const DEFAULT_TEMPLATES: [(&str, &str); 5] = [
+    ("down", "{error}"),
+    ("timeout", "{error}"),
+    ("status", "{error}"),
+    ("dependency", "Caused by dependency {dependency}"),
+    ("consensus", "Consensus: {down}/{total} locations report down ({rule})"),
+];
+
+/// Longest template accepted in settings.
+const MAX_TEMPLATE_LEN: usize = 500;
+
+/// What opened an incident; picks the template and fills its placeholders.
+pub enum Cause<'a> {
+    /// A check run by this server failed
+    Check { error: Option<&'a str>, status_code: Option<u16>, kind: Option<FailureKind> },
+    /// A monitor this one depends on is down
+    Dependency { name: &'a str },
+    /// Enough locations agree the monitor is down
+    Consensus { down: u32, total: u32, rule: &'a str, locations: &'a [String] },
+}
+
+impl Cause<'_> {
+    fn template_name(&self) -> &'static str {
+        match self {
+            Cause::Check { kind: Some(FailureKind::Timeout | FailureKind::ConnectTimeout), .. } => "timeout",
+            Cause::Check { kind: Some(FailureKind::Status), .. } => "status",
+            Cause::Check { .. } => "down",
+            Cause::Dependency { .. } => "dependency",
+            Cause::Consensus { .. } => "consensus",
+        }
+    }
+
+    /// Placeholder values; anything not listed renders as an empty string.
+    fn value(&self, placeholder: &str) -> String {
+        match (self, placeholder) {
+            (Cause::Check { error, .. }, "error") => error.unwrap_or("Monitor is down").to_string(),
+            (Cause::Check { status_code, .. }, "status_code") => status_code.map(|c| c.to_string()).unwrap_or_default(),
+            (Cause::Check { .. }, "location") => "local".to_string(),
+            (Cause::Dependency { name }, "dependency") => name.to_string(),
+            (Cause::Consensus { down, .. }, "down") => down.to_string(),
+            (Cause::Consensus { total, .. }, "total") => total.to_string(),
+            (Cause::Consensus { rule, .. }, "rule") => rule.to_string(),
+            (Cause::Consensus { locations, .. }, "location") => locations.join(", "),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Substitute `{placeholder}`s in one pass, so values containing braces are left alone.
+/// Unknown placeholders render empty; a `{` without a closing `}` is kept as-is.
+pub fn render(template: &str, cause: &Cause) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        match rest[open..].find('}') {
+            Some(close) => {
+                out.push_str(&cause.value(&rest[open + 1..open + close]));
+                rest = &rest[open + close + 1..];
+            }
+            None => {
+                out.push_str(&rest[open..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn load_overrides(conn: &rusqlite::Connection) -> serde_json::Map<String, serde_json::Value> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?1", params![SETTING_KEY], |r| r.get::<_, String>(0))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+/// The incident cause for `cause`, using the admin's template if one is set.
+pub fn incident_cause(conn: &rusqlite::Connection, cause: &Cause) -> String {
+    let overrides = load_overrides(conn);
+    let name = cause.template_name();
+    let fallback = if matches!(name, "timeout" | "status") { Some("down") } else { None };
+    let template = [Some(name), fallback]
+        .into_iter()
+        .flatten()
+        .find_map(|n| overrides.get(n).and_then(|v| v.as_str()))
+        .or_else(|| DEFAULT_TEMPLATES.iter().find(|(n, _)| *n == name).map(|(_, t)| *t))
+        .unwrap_or("{error}");
+    render(template, cause)
+}
+
+/// Overrides must be an object of known template name → non-empty string.
+pub fn validate_templates(templates: &serde_json::Value) -> Result<(), String> {
+    let obj = templates.as_object().ok_or("incident_cause_templates must be a JSON object")?;
+    for (name, template) in obj {
+        if !DEFAULT_TEMPLATES.iter().any(|(n, _)| n == name) {
+            let known: Vec<&str> = DEFAULT_TEMPLATES.iter().map(|(n, _)| *n).collect();
+            return Err(format!("incident_cause_templates: unknown template '{}' (expected one of: {})", name, known.join(", ")));
+        }
+        match template.as_str() {
+            Some(t) if !t.trim().is_empty() && t.len() <= MAX_TEMPLATE_LEN => {}
+            _ => return Err(format!("incident_cause_templates: '{}' must be a non-empty string of at most {} characters", name, MAX_TEMPLATE_LEN)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conn_with(overrides: serde_json::Value) -> rusqlite::Connection {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)").unwrap();
+        conn.execute("INSERT INTO settings (key, value) VALUES (?1, ?2)", params![SETTING_KEY, overrides.to_string()]).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_timeout_and_bad_status_use_their_own_templates() {
+        let conn = conn_with(serde_json::json!({
+            "timeout": "Zeitüberschreitung: {error}",
+            "status": "Unerwarteter Status {status_code}",
+        }));
+        let timeout = Cause::Check { error: Some("Request timed out"), status_code: None, kind: Some(FailureKind::Timeout) };
+        let bad_status = Cause::Check { error: Some("Expected 200, got 503"), status_code: Some(503), kind: Some(FailureKind::Status) };
+        assert_eq!(incident_cause(&conn, &timeout), "Zeitüberschreitung: Request timed out");
+        assert_eq!(incident_cause(&conn, &bad_status), "Unerwarteter Status 503");
+
+        // A body mismatch carries a status code but isn't a status failure
+        let body = Cause::Check { error: Some("Body match failed"), status_code: Some(200), kind: Some(FailureKind::Body) };
+        assert_eq!(incident_cause(&conn, &body), "Body match failed");
+    }
+
+    #[test]
+    fn test_down_override_covers_timeout_and_status() {
+        let conn = conn_with(serde_json::json!({"down": "[{location}] {error} {nope}"}));
+        let timeout = Cause::Check { error: Some("TCP connect timed out"), status_code: None, kind: Some(FailureKind::Timeout) };
+        let bad_status = Cause::Check { error: Some("Expected 200, got 500"), status_code: Some(500), kind: Some(FailureKind::Status) };
+        assert_eq!(incident_cause(&conn, &timeout), "[local] TCP connect timed out ");
+        assert_eq!(incident_cause(&conn, &bad_status), "[local] Expected 200, got 500 ");
+    }
+
+    #[test]
+    fn test_defaults_and_consensus_locations() {
+        let conn = conn_with(serde_json::json!({}));
+        let none = Cause::Check { error: None, status_code: None, kind: None };
+        assert_eq!(incident_cause(&conn, &none), "Monitor is down");
+        let locations = vec!["eu-west".to_string(), "us-east".to_string()];
+        let consensus = Cause::Consensus { down: 2, total: 3, rule: "majority", locations: &locations };
+        assert_eq!(incident_cause(&conn, &consensus), "Consensus: 2/3 locations report down (majority)");
+        assert_eq!(render("{location} {unclosed", &consensus), "eu-west, us-east {unclosed");
+    }
+}
@@ -1,21 +1,98 @@
+use crate::causes::{incident_cause, Cause};
 use crate::db::Db;
-use crate::notifications::{self, WebhookPayload, WebhookMonitor, WebhookIncident};
+use crate::consensus::ConsensusRule;
+use crate::models::{CheckStep, MonitorCheckState};
+use crate::notifications::{self, WebhookPayload, WebhookMonitor, WebhookIncident, WebhookMaintenance};
 use crate::routes::is_in_maintenance;
 use crate::sse::{EventBroadcaster, SseEvent};
 use rusqlite::params;
-use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicI64, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
+use tokio::sync::{Notify, OwnedSemaphorePermit, Semaphore};
 use tokio::time;
 
 /// Heartbeat retention: delete heartbeats older than this many days.
 /// Configurable via HEARTBEAT_RETENTION_DAYS env var. Default: 90.
-fn retention_days() -> u32 {
+pub fn retention_days() -> u32 {
     std::env::var("HEARTBEAT_RETENTION_DAYS")
         .ok()
         .and_then(|v| v.parse().ok())
         .unwrap_or(90)
 }
 
+/// How long shutdown waits for in-flight checks to finish before abandoning
+/// them. Configurable via CHECKER_SHUTDOWN_GRACE_SECS env var. Default: 10.
+pub fn shutdown_grace() -> Duration {
+    Duration::from_secs(
+        std::env::var("CHECKER_SHUTDOWN_GRACE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(10),
+    )
+}
+
+/// Read cap for HTTP response bodies: bytes past this are never buffered.
+/// Configurable via MAX_BODY_READ_BYTES env var. Default: 1 MiB.
+fn max_body_read_bytes() -> usize {
+    std::env::var("MAX_BODY_READ_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(1024 * 1024)
+}
+
+/// Downsampling: raw heartbeats older than this many days are folded into
+/// hourly aggregates and deleted. Configurable via HEARTBEAT_DOWNSAMPLE_DAYS
+/// env var. Default: 0 (off). Values below 3 are raised to 3 so the daily
+/// rollup always recomputes from raw data.
+fn downsample_days() -> u32 {
+    let days: u32 = std::env::var("HEARTBEAT_DOWNSAMPLE_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    if days == 0 { 0 } else { days.max(3) }
+}
+
+/// Schedule jitter as a percentage of each monitor's interval (0-50).
+/// Configurable via CHECK_JITTER_PCT env var. Default: 0 (off).
+fn check_jitter_pct() -> u32 {
+    std::env::var("CHECK_JITTER_PCT")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0)
+        .min(50)
+}
+
+/// User-Agent sent with HTTP checks unless a monitor sets its own.
+/// Configurable via CHECKER_USER_AGENT env var. Default: watchpost/<version>.
+fn checker_user_agent() -> String {
+    std::env::var("CHECKER_USER_AGENT")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| concat!("watchpost/", env!("CARGO_PKG_VERSION")).to_string())
+}
+
+/// Proxy for HTTP checks on monitors without their own `proxy_url`.
+/// Configurable via CHECKER_PROXY env var. Default: none.
+fn checker_proxy() -> Option<String> {
+    std::env::var("CHECKER_PROXY").ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Maximum number of checks in flight at once.
+/// Configurable via CHECKER_MAX_CONCURRENCY env var. Default: 50.
+fn checker_max_concurrency() -> usize {
+    std::env::var("CHECKER_MAX_CONCURRENCY")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(50)
+}
+
 /// Probe stale threshold: auto-disable locations that haven't reported in this many minutes.
 /// Configurable via PROBE_STALE_MINUTES env var. Default: 30.
 fn probe_stale_minutes() -> u32 {
@@ -25,6 +102,106 @@ fn probe_stale_minutes() -> u32 {
         .unwrap_or(30)
 }
 
+/// Incidents still open after this many consecutive `up` heartbeats are closed
+/// as a safety net for missed recovery transitions.
+/// Configurable via INCIDENT_AUTO_CLOSE_CHECKS env var. Default: 10. 0 disables.
+fn incident_auto_close_checks() -> u32 {
+    std::env::var("INCIDENT_AUTO_CLOSE_CHECKS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(10)
+}
+
+/// Resolve open incidents whose monitor's last `checks` heartbeats, all taken
+/// since the incident started, are `up`. Returns the number of incidents closed.
+pub fn auto_close_recovered_incidents(db: &Db, checks: u32) -> usize {
+    if checks == 0 {
+        return 0;
+    }
+    let conn = db.conn();
+    conn.execute(
+        "UPDATE incidents SET resolved_at = datetime('now'), resolution_note = 'auto-closed after sustained recovery'
+         WHERE resolved_at IS NULL AND auto_resolve = 1 AND ?1 = (
+             SELECT COUNT(*) FROM (
+                 SELECT status, checked_at FROM heartbeats
+                 WHERE monitor_id = incidents.monitor_id ORDER BY seq DESC LIMIT ?1
+             ) recent
+             WHERE recent.status = 'up' AND recent.checked_at >= incidents.started_at
+         )",
+        params![checks],
+    )
+    .unwrap_or(0)
+}
+
+/// Whether a maintenance window's reminder is due at `now`: the lead time
+/// before `starts_at` has been reached and the window hasn't started yet.
+pub fn maintenance_reminder_due(now: chrono::NaiveDateTime, starts_at: &str, remind_minutes_before: u32) -> bool {
+    let Ok(starts) = chrono::NaiveDateTime::parse_from_str(starts_at, "%Y-%m-%dT%H:%M:%SZ")
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(starts_at, "%Y-%m-%dT%H:%M:%S"))
+    else {
+        return false;
+    };
+    now < starts && now >= starts - chrono::Duration::minutes(remind_minutes_before as i64)
+}
+
+/// Send `maintenance.upcoming` to the monitor's channels for each window whose
+/// reminder is due, once per window. Returns the number of reminders sent.
+async fn send_maintenance_reminders(db: &Db, http_client: &reqwest::Client, broadcaster: &EventBroadcaster) -> usize {
+    let now = chrono::Utc::now().naive_utc();
+    let timestamp = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let due: Vec<WebhookPayload> = {
+        let conn = db.conn();
+        let Ok(mut stmt) = conn.prepare(
+            "SELECT w.id, w.title, w.starts_at, w.ends_at, w.remind_minutes_before, m.id, m.name, m.url, m.current_status, m.runbook_url
+             FROM maintenance_windows w JOIN monitors m ON m.id = w.monitor_id
+             WHERE w.remind_minutes_before IS NOT NULL AND w.reminder_sent_at IS NULL
+               AND w.starts_at > ?1 AND m.is_paused = 0 AND m.deleted_at IS NULL",
+        ) else {
+            return 0;
+        };
+        let windows: Vec<(u32, WebhookPayload)> = stmt.query_map(params![timestamp], |row| {
+            Ok((row.get(4)?, WebhookPayload {
+                event: "maintenance.upcoming".to_string(),
+                monitor: WebhookMonitor {
+                    id: row.get(5)?,
+                    name: row.get(6)?,
+                    url: row.get(7)?,
+                    current_status: row.get(8)?,
+                    runbook_url: row.get(9)?,
+                },
+                incident: None,
+                maintenance: Some(WebhookMaintenance {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    starts_at: row.get(2)?,
+                    ends_at: row.get(3)?,
+                }),
+                timestamp: timestamp.clone(),
+            }))
+        }).map(|rows| rows.filter_map(|r| r.ok()).collect()).unwrap_or_default();
+        drop(stmt);
+
+        windows.into_iter()
+            .filter_map(|(lead, payload)| {
+                let window = payload.maintenance.as_ref()?;
+                if !maintenance_reminder_due(now, &window.starts_at, lead) {
+                    return None;
+                }
+                conn.execute(
+                    "UPDATE maintenance_windows SET reminder_sent_at = datetime('now') WHERE id = ?1",
+                    params![window.id],
+                ).ok()?;
+                Some(payload)
+            })
+            .collect()
+    };
+
+    for payload in &due {
+        dispatch_alert(db, http_client, broadcaster, payload, "maintenance").await;
+    }
+    due.len()
+}
+
 /// Check for stale probe locations and auto-disable them.
 /// Returns the number of locations disabled.
 pub fn disable_stale_locations(db: &Db, stale_minutes: u32) -> usize {
@@ -46,28 +223,138 @@ pub fn prune_heartbeats(db: &Db, days: u32) -> usize {
     .unwrap_or(0)
 }
 
+/// Purge monitors soft-deleted more than `days` ago. Returns the number of monitors removed.
+pub fn purge_deleted_monitors(db: &Db, days: u32) -> usize {
+    let conn = db.conn();
+    conn.execute(
+        "DELETE FROM monitors WHERE deleted_at IS NOT NULL AND deleted_at < datetime('now', ?1)",
+        params![format!("-{} days", days)],
+    )
+    .unwrap_or(0)
+}
+
+/// Roll completed UTC days of heartbeats into `daily_uptime`. The most recent
+/// rolled-up day and the one before it are recomputed each run so late probe
+/// submissions are picked up. Returns the number of rollup rows written.
+pub fn rollup_daily_uptime(db: &Db) -> usize {
+    let conn = db.conn();
+    conn.execute(
+        "INSERT OR REPLACE INTO daily_uptime (monitor_id, day, total_checks, up_checks, down_checks, up_rt_sum, up_rt_count)
+         SELECT monitor_id, date(checked_at), COUNT(*),
+                SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END),
+                SUM(CASE WHEN status = 'down' THEN 1 ELSE 0 END),
+                SUM(CASE WHEN status = 'up' THEN response_time_ms ELSE 0 END),
+                SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END)
+         FROM heartbeats
+         WHERE checked_at < date('now')
+           AND checked_at >= COALESCE((SELECT date(MAX(day), '-1 day') FROM daily_uptime), '0000-01-01')
+         GROUP BY monitor_id, date(checked_at)",
+        [],
+    )
+    .unwrap_or(0)
+}
+
+/// Fold raw heartbeats older than `days` into `heartbeat_hourly` and delete
+/// them, in one transaction. Hours that already have an aggregate (e.g. from
+/// late probe submissions) are added to. Returns the number of raw rows folded.
+pub fn downsample_heartbeats(db: &Db, days: u32) -> usize {
+    let conn = db.conn();
+    let fold = || -> rusqlite::Result<usize> {
+        let tx = conn.unchecked_transaction()?;
+        // Fix the cutoff once so the aggregate and delete cover the same rows
+        let cutoff: String = tx.query_row("SELECT datetime('now', ?1)", params![format!("-{} days", days)], |r| r.get(0))?;
+        tx.execute(
+            "INSERT INTO heartbeat_hourly (monitor_id, hour, total_checks, up_checks, down_checks, up_rt_sum, up_rt_count, min_response_ms, max_response_ms)
+             SELECT monitor_id, strftime('%Y-%m-%d %H:00:00', checked_at), COUNT(*),
+                    SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN status = 'down' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN status = 'up' THEN response_time_ms ELSE 0 END),
+                    SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END),
+                    MIN(response_time_ms), MAX(response_time_ms)
+             FROM heartbeats
+             WHERE checked_at < ?1
+             GROUP BY monitor_id, strftime('%Y-%m-%d %H:00:00', checked_at)
+             ON CONFLICT (monitor_id, hour) DO UPDATE SET
+                total_checks = total_checks + excluded.total_checks,
+                up_checks = up_checks + excluded.up_checks,
+                down_checks = down_checks + excluded.down_checks,
+                up_rt_sum = up_rt_sum + excluded.up_rt_sum,
+                up_rt_count = up_rt_count + excluded.up_rt_count,
+                min_response_ms = MIN(min_response_ms, excluded.min_response_ms),
+                max_response_ms = MAX(max_response_ms, excluded.max_response_ms)",
+            params![cutoff],
+        )?;
+        let deleted = tx.execute("DELETE FROM heartbeats WHERE checked_at < ?1", params![cutoff])?;
+        tx.commit()?;
+        Ok(deleted)
+    };
+    fold().unwrap_or_else(|e| {
+        tracing::error!(error = %e, "Downsampling failed");
+        0
+    })
+}
+
 // ─── Monitor Check Model ────────────────────────────────────────────────────
 
+#[cfg_attr(test, derive(Default))]
 struct MonitorCheck {
     id: String,
     name: String,
     url: String,
     method: String,
     timeout_ms: u32,
+    /// None = half of `timeout_ms`; see `connect_timeout()`
+    connect_timeout_ms: Option<u32>,
+    runbook_url: Option<String>,
     expected_status: u16,
     body_contains: Option<String>,
+    body_not_contains: Option<String>,
+    min_body_bytes: Option<u32>,
+    max_body_bytes: Option<u32>,
+    auth_type: Option<String>,
+    auth_username: Option<String>,
+    auth_password: Option<String>,
+    auth_token: Option<String>,
+    client_cert_pem: Option<String>,
+    client_key_pem: Option<String>,
+    insecure_skip_tls_verify: bool,
+    ca_bundle_pem: Option<String>,
+    /// Ignored for TCP and DNS checks
+    proxy_url: Option<String>,
     headers: Option<String>,
-    confirmation_threshold: u32,
+    /// Failures needed to go down: `down_confirmation_threshold`, else `confirmation_threshold`
+    down_confirmation_threshold: u32,
+    /// Successes needed to leave down: `up_confirmation_threshold`, else 1
+    up_confirmation_threshold: u32,
     consecutive_failures: u32,
+    consecutive_successes: u32,
     current_status: String,
-    #[allow(dead_code)]
+    /// Seconds the monitor must stay up before its incident resolves (None = resolve immediately)
+    recovery_cooldown_seconds: Option<u32>,
+    /// When a recovery deferred by the cooldown began
+    recovery_pending_since: Option<String>,
     interval_seconds: u32,
     response_time_threshold_ms: Option<u32>,
     follow_redirects: bool,
+    max_redirects: u32,
+    expected_final_url_prefix: Option<String>,
+    /// Status the response must have before any redirect is followed
+    expected_first_status: Option<u16>,
     monitor_type: String,
     dns_record_type: String,
     dns_expected: Option<String>,
+    dns_min_records: Option<u32>,
+    dns_min_ttl: Option<u32>,
+    /// None for the system resolver; otherwise a DoH preset name or endpoint URL
+    dns_resolver: Option<String>,
+    /// TCP probe: written after connecting
+    tcp_send: Option<String>,
+    /// TCP probe: substring the response must contain
+    tcp_expect: Option<String>,
     consensus_threshold: Option<u32>,
+    consensus_mode: Option<String>,
+    /// JSON array of `CheckStep` for multistep monitors
+    steps: Option<String>,
 }
 
 /// Result of executing a check (before incident lifecycle processing).
@@ -76,171 +363,953 @@ struct CheckResult {
     response_time_ms: u32,
     status_code: Option<u16>,
     error_message: Option<String>,
+    failure_kind: Option<FailureKind>,
     /// Extra data to include in the check.completed SSE event.
     extra_sse_data: Option<serde_json::Value>,
 }
 
+/// Why a check failed, so failures can be grouped without parsing messages.
+/// Failures that fit none of these (e.g. a redirect loop) have no kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// The whole check ran past `timeout_ms`
+    Timeout,
+    /// DNS, TCP connect, or the TLS handshake ran past the connect timeout
+    ConnectTimeout,
+    Dns,
+    ConnectionRefused,
+    Tls,
+    /// Unexpected HTTP status
+    Status,
+    /// Response content failed an assertion (body match, size, TCP expect)
+    Body,
+}
+
+impl FailureKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FailureKind::Timeout => "timeout",
+            FailureKind::ConnectTimeout => "connect_timeout",
+            FailureKind::Dns => "dns",
+            FailureKind::ConnectionRefused => "connection_refused",
+            FailureKind::Tls => "tls",
+            FailureKind::Status => "status",
+            FailureKind::Body => "body",
+        }
+    }
+}
+
+// ─── Heartbeat Batching ─────────────────────────────────────────────────────
+
+/// Flush buffered heartbeats once this many are pending...
+const HEARTBEAT_BATCH_SIZE: usize = 50;
+/// ...or once the oldest has waited this long.
+const HEARTBEAT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A heartbeat waiting to be written. `checked_at` is captured when the check
+/// finishes, not when the batch lands.
+struct PendingHeartbeat {
+    id: String,
+    monitor_id: String,
+    status: String,
+    response_time_ms: u32,
+    status_code: Option<u16>,
+    error_message: Option<String>,
+    failure_kind: Option<&'static str>,
+    checked_at: String,
+}
+
+impl PendingHeartbeat {
+    fn from_result(monitor_id: &str, result: &CheckResult) -> Self {
+        PendingHeartbeat {
+            id: uuid::Uuid::new_v4().to_string(),
+            monitor_id: monitor_id.to_string(),
+            status: result.status.clone(),
+            response_time_ms: result.response_time_ms,
+            status_code: result.status_code,
+            error_message: result.error_message.clone(),
+            failure_kind: result.failure_kind.map(FailureKind::as_str),
+            checked_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        }
+    }
+}
+
+/// Heartbeats from a check cycle, written together in one transaction so the
+/// checker takes the DB lock once per batch rather than once per check.
+#[derive(Default)]
+struct HeartbeatBuffer {
+    pending: Mutex<Vec<PendingHeartbeat>>,
+}
+
+impl HeartbeatBuffer {
+    fn push(&self, hb: PendingHeartbeat) {
+        self.pending.lock().unwrap().push(hb);
+    }
+
+    fn len(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// Write all pending heartbeats. `seq` values are assigned here, under the
+    /// DB lock, continuing from the current maximum in buffer order, so they
+    /// stay unique and monotonic alongside probe-submitted heartbeats.
+    /// Returns the number of rows written.
+    fn flush(&self, db: &Db) -> usize {
+        let batch = std::mem::take(&mut *self.pending.lock().unwrap());
+        if batch.is_empty() {
+            return 0;
+        }
+        let conn = db.conn();
+        let write = || -> rusqlite::Result<usize> {
+            let tx = conn.unchecked_transaction()?;
+            let mut seq: i64 = tx.query_row("SELECT COALESCE(MAX(seq), 0) FROM heartbeats", [], |r| r.get(0))?;
+            {
+                let mut stmt = tx.prepare(
+                    "INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, status_code, error_message, failure_kind, checked_at, seq) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                )?;
+                for hb in &batch {
+                    seq += 1;
+                    stmt.execute(params![hb.id, hb.monitor_id, hb.status, hb.response_time_ms, hb.status_code, hb.error_message, hb.failure_kind, hb.checked_at, seq])?;
+                }
+            }
+            tx.commit()?;
+            Ok(batch.len())
+        };
+        match write() {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::error!(count = batch.len(), error = %e, "Failed to write heartbeats");
+                0
+            }
+        }
+    }
+}
+
 // ─── Background Checker Loop ────────────────────────────────────────────────
 
-/// Background check scheduler. Runs in a tokio task.
-pub async fn run_checker(db: Arc<Db>, broadcaster: Arc<EventBroadcaster>, shutdown: rocket::Shutdown) {
-    println!("🔍 Checker: starting initialization...");
+/// Monitors with a check running, shared by the scheduler and check-now so a
+/// monitor is never checked twice at once.
+#[derive(Default)]
+struct InFlight {
+    ids: Mutex<HashSet<String>>,
+    released: Notify,
+}
 
-    // Webhook client for consensus evaluation (shared, built once)
-    let consensus_client = match reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("❌ Checker: failed to build consensus client: {e}");
-            return;
+impl InFlight {
+    /// Mark `monitor_id` as being checked; false if a check is already running.
+    fn claim(&self, monitor_id: &str) -> bool {
+        self.ids.lock().unwrap().insert(monitor_id.to_string())
+    }
+
+    fn release(&self, monitor_id: &str) {
+        self.ids.lock().unwrap().remove(monitor_id);
+        self.released.notify_waiters();
+    }
+
+    fn snapshot(&self) -> HashSet<String> {
+        self.ids.lock().unwrap().clone()
+    }
+
+    /// Claim `monitor_id`, first waiting out any check already running on it.
+    /// The claim is released when the guard drops.
+    async fn claim_when_idle(self: &Arc<Self>, monitor_id: &str) -> InFlightGuard {
+        loop {
+            // Created before the claim attempt so a release in between isn't missed
+            let released = self.released.notified();
+            if self.claim(monitor_id) {
+                return InFlightGuard(self.clone(), monitor_id.to_string());
+            }
+            released.await;
         }
-    };
+    }
+}
 
-    // Wait 10s for server to warm up (reduced from 30s for faster first check)
-    println!("🔍 Checker: waiting 10s for server warmup...");
-    tokio::select! {
-        _ = time::sleep(Duration::from_secs(10)) => {},
-        _ = shutdown.clone() => {
-            println!("🔍 Checker: shutdown during warmup");
-            return;
-        },
+struct InFlightGuard(Arc<InFlight>, String);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.release(&self.1);
     }
+}
 
-    let client_follow = match reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::limited(10))
-        .timeout(Duration::from_secs(60))
-        .build()
+/// Runs checks as independent tasks, at most `max` at a time. A slow host only
+/// ties up its own slot, so other monitors keep being checked.
+struct CheckPool {
+    semaphore: Arc<Semaphore>,
+    max: usize,
+    /// Running check tasks, so shutdown can abandon stragglers
+    tasks: Mutex<Vec<tokio::task::AbortHandle>>,
+    /// Monitors with a check in flight, so the scheduler doesn't pick them twice
+    in_flight: Arc<InFlight>,
+    active: Arc<AtomicUsize>,
+    /// Highest number of simultaneously running checks seen
+    peak: Arc<AtomicUsize>,
+}
+
+impl CheckPool {
+    fn new(max: usize, in_flight: Arc<InFlight>) -> Self {
+        CheckPool {
+            semaphore: Arc::new(Semaphore::new(max)),
+            max,
+            tasks: Mutex::new(Vec::new()),
+            in_flight,
+            active: Arc::new(AtomicUsize::new(0)),
+            peak: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Wait for a free slot.
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        self.semaphore.clone().acquire_owned().await.expect("check pool semaphore closed")
+    }
+
+    fn in_flight(&self) -> HashSet<String> {
+        self.in_flight.snapshot()
+    }
+
+    /// Run a monitor's check in the background, holding `permit` until it finishes.
+    /// Skipped if a check-now claimed the monitor after it was picked.
+    fn spawn<F>(&self, monitor_id: String, permit: OwnedSemaphorePermit, check: F)
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
     {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("❌ Checker: failed to build HTTP client (follow redirects): {e}");
+        if !self.in_flight.claim(&monitor_id) {
             return;
         }
-    };
+        let in_flight = self.in_flight.clone();
+        let active = self.active.clone();
+        let peak = self.peak.clone();
+        let handle = tokio::spawn(async move {
+            let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now_active, Ordering::SeqCst);
+            check.await;
+            active.fetch_sub(1, Ordering::SeqCst);
+            in_flight.release(&monitor_id);
+            drop(permit);
+        });
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|t| !t.is_finished());
+        tasks.push(handle.abort_handle());
+    }
+
+    /// Wait up to `deadline` for every running check to finish, then abort
+    /// whatever is left. Returns the number of checks abandoned.
+    async fn drain(&self, deadline: Duration) -> usize {
+        if time::timeout(deadline, self.semaphore.acquire_many(self.max as u32)).await.is_ok() {
+            return 0;
+        }
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+        let stragglers: Vec<_> = tasks.into_iter().filter(|t| !t.is_finished()).collect();
+        for task in &stragglers {
+            task.abort();
+        }
+        stragglers.len()
+    }
+}
+
+/// Stop the checker cleanly: no new checks are scheduled, running ones get
+/// `grace` to finish, and their heartbeats are written before returning. A
+/// check that misses the deadline is aborted and writes nothing.
+async fn drain_checks(pool: &CheckPool, heartbeats: &HeartbeatBuffer, db: &Db, grace: Duration) {
+    let in_flight = pool.in_flight().len();
+    if in_flight > 0 {
+        tracing::info!(in_flight, grace_secs = grace.as_secs(), "Checker shutting down, waiting for in-flight checks");
+    }
+    let abandoned = pool.drain(grace).await;
+    if abandoned > 0 {
+        tracing::warn!(abandoned, grace_secs = grace.as_secs(), "Checker abandoned checks still running after the grace period");
+    }
+    heartbeats.flush(db);
+}
 
-    let client_no_follow = match reqwest::Client::builder()
-        .redirect(reqwest::redirect::Policy::none())
+/// Redirect hops followed when a monitor doesn't set `max_redirects`.
+const DEFAULT_MAX_REDIRECTS: u32 = 10;
+
+/// Most per-monitor clients kept in `CheckerClients::custom` at once.
+const MAX_CUSTOM_CLIENTS: usize = 256;
+
+/// (follow_redirects, max_redirects, proxy, connect timeout, TLS) for a non-default check client.
+type ClientKey = (bool, u32, Option<String>, Option<Duration>, ClientTls);
+
+/// Per-monitor TLS settings, which need a client of their own.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+struct ClientTls {
+    /// mTLS client certificate followed by its private key (PEM)
+    identity_pem: Option<String>,
+    /// Accept any server certificate (`insecure_skip_tls_verify`)
+    accept_invalid_certs: bool,
+    /// CA certificates trusted on top of the built-in roots (PEM)
+    ca_bundle_pem: Option<String>,
+}
+
+fn identity_pem(cert_pem: &str, key_pem: &str) -> String {
+    format!("{}\n{}\n", cert_pem.trim(), key_pem.trim())
+}
+
+/// Check that a client certificate and key load as an mTLS identity.
+pub fn validate_client_identity(cert_pem: &str, key_pem: &str) -> Result<(), String> {
+    let identity = reqwest::Identity::from_pem(identity_pem(cert_pem, key_pem).as_bytes()).map_err(|e| e.to_string())?;
+    reqwest::Client::builder().identity(identity).build().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Check that a PEM bundle holds at least one certificate that loads as a trust root.
+pub fn validate_ca_bundle(pem: &str) -> Result<(), String> {
+    let certs = reqwest::Certificate::from_pem_bundle(pem.as_bytes()).map_err(|e| e.to_string())?;
+    if certs.is_empty() {
+        return Err("no certificates found".into());
+    }
+    reqwest::Client::builder().tls_certs_merge(certs).build().map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// HTTP clients shared across checks, built once.
+pub struct CheckerClients {
+    follow: reqwest::Client,
+    no_follow: reqwest::Client,
+    /// Clients for non-default redirect caps or per-monitor proxies, built on first use
+    custom: Mutex<HashMap<ClientKey, reqwest::Client>>,
+    /// Notification delivery (never follows redirects) and DNS-over-HTTPS lookups
+    webhook: reqwest::Client,
+    /// User-Agent for check requests; a monitor's own header takes precedence
+    user_agent: String,
+    /// Global proxy (CHECKER_PROXY), used unless a monitor sets its own
+    proxy: Option<String>,
+    /// Hosts checks may not connect to (CHECKER_ALLOW_TARGETS / CHECKER_DENY_TARGETS)
+    target_policy: Arc<TargetPolicy>,
+    /// HTTP responses shared between identical checks (CHECK_RESULT_CACHE_SECS)
+    result_cache: ResultCache,
+}
+
+/// Build a check client with the given redirect policy, optional proxy, optional connect timeout,
+/// and TLS settings. A target policy is enforced on every redirect hop and, without a proxy,
+/// on every address the client resolves.
+fn build_check_client(follow_redirects: bool, max_redirects: u32, user_agent: &str, proxy: Option<&str>, connect_timeout: Option<Duration>, tls: &ClientTls, target_policy: &Arc<TargetPolicy>) -> Result<reqwest::Client, String> {
+    let policy = if !follow_redirects {
+        reqwest::redirect::Policy::none()
+    } else if target_policy.is_unrestricted() {
+        reqwest::redirect::Policy::limited(max_redirects as usize)
+    } else {
+        let hops = target_policy.clone();
+        reqwest::redirect::Policy::custom(move |attempt| match hops.blocked_hop(attempt.url()) {
+            Some(reason) => attempt.error(TargetBlocked(reason)),
+            None => reqwest::redirect::Policy::limited(max_redirects as usize).redirect(attempt),
+        })
+    };
+    let mut builder = reqwest::Client::builder()
+        .redirect(policy)
         .timeout(Duration::from_secs(60))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("❌ Checker: failed to build HTTP client (no redirects): {e}");
-            return;
+        .user_agent(user_agent);
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(reqwest::Proxy::all(proxy).map_err(|e| format!("invalid proxy URL: {e}"))?);
+    } else if !target_policy.is_unrestricted() {
+        // Through a proxy, the proxy does the resolving
+        builder = builder.dns_resolver(PolicyResolver(target_policy.clone()));
+    }
+    if let Some(timeout) = connect_timeout {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(pem) = &tls.identity_pem {
+        builder = builder.identity(reqwest::Identity::from_pem(pem.as_bytes()).map_err(|e| format!("invalid client certificate: {e}"))?);
+    }
+    if let Some(pem) = &tls.ca_bundle_pem {
+        let certs = reqwest::Certificate::from_pem_bundle(pem.as_bytes()).map_err(|e| format!("invalid CA bundle: {e}"))?;
+        builder = builder.tls_certs_merge(certs);
+    }
+    if tls.accept_invalid_certs {
+        builder = builder.tls_danger_accept_invalid_certs(true);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+impl CheckerClients {
+    pub fn new() -> Result<Self, String> {
+        Ok(Self::with_options(&checker_user_agent(), checker_proxy().as_deref())?
+            .with_target_policy(TargetPolicy::from_env())
+            .with_result_cache(ResultCache::new(check_result_cache_ttl())))
+    }
+
+    pub fn with_result_cache(mut self, cache: ResultCache) -> Self {
+        self.result_cache = cache;
+        self
+    }
+
+    /// Enforce `policy`; the shared clients are rebuilt so it applies to their redirects
+    /// and DNS lookups too.
+    pub fn with_target_policy(mut self, policy: TargetPolicy) -> Self {
+        self.target_policy = Arc::new(policy);
+        let proxy = self.proxy.as_deref();
+        // The same settings built fine in `with_options`, so keep those clients if this fails
+        if let Ok(follow) = build_check_client(true, DEFAULT_MAX_REDIRECTS, &self.user_agent, proxy, None, &ClientTls::default(), &self.target_policy) {
+            self.follow = follow;
+        }
+        if let Ok(no_follow) = build_check_client(false, 0, &self.user_agent, proxy, None, &ClientTls::default(), &self.target_policy) {
+            self.no_follow = no_follow;
         }
+        self.custom.lock().unwrap().clear();
+        self
+    }
+
+    pub fn with_options(user_agent: &str, proxy: Option<&str>) -> Result<Self, String> {
+        let unrestricted = Arc::new(TargetPolicy::default());
+        let follow = build_check_client(true, DEFAULT_MAX_REDIRECTS, user_agent, proxy, None, &ClientTls::default(), &unrestricted)
+            .map_err(|e| format!("failed to build HTTP client (follow redirects): {e}"))?;
+        let no_follow = build_check_client(false, 0, user_agent, proxy, None, &ClientTls::default(), &unrestricted)
+            .map_err(|e| format!("failed to build HTTP client (no redirects): {e}"))?;
+        // Notifications never follow redirects: a receiver could bounce them to an internal address
+        let webhook = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| format!("failed to build webhook client: {e}"))?;
+        Ok(CheckerClients {
+            follow,
+            no_follow,
+            custom: Mutex::new(HashMap::new()),
+            webhook,
+            user_agent: user_agent.to_string(),
+            proxy: proxy.map(|p| p.to_string()),
+            target_policy: unrestricted,
+            result_cache: ResultCache::default(),
+        })
+    }
+
+    /// Client for a monitor's redirect, proxy, and connect timeout settings. Clients are cheap
+    /// to clone (they share a connection pool), so this hands back an owned handle.
+    fn http(&self, monitor: &MonitorCheck) -> reqwest::Client {
+        self.client_for(monitor, monitor.follow_redirects)
+    }
+
+    /// Like `http`, but never follows redirects, so the response is the first hop's.
+    fn first_hop(&self, monitor: &MonitorCheck) -> reqwest::Client {
+        self.client_for(monitor, false)
+    }
+
+    fn client_for(&self, monitor: &MonitorCheck, follow_redirects: bool) -> reqwest::Client {
+        let max_redirects = monitor.max_redirects;
+        let proxy = monitor.proxy_url.as_deref().or(self.proxy.as_deref());
+        // A connect timeout as long as the request's own adds nothing, so the shared clients do
+        let connect_timeout = Some(monitor.connect_timeout())
+            .filter(|t| *t < Duration::from_millis(monitor.timeout_ms as u64));
+        let tls = monitor.client_tls();
+        if proxy == self.proxy.as_deref() && connect_timeout.is_none() && tls == ClientTls::default() {
+            if !follow_redirects {
+                return self.no_follow.clone();
+            }
+            if max_redirects == DEFAULT_MAX_REDIRECTS {
+                return self.follow.clone();
+            }
+        }
+        let max_redirects = if follow_redirects { max_redirects } else { 0 };
+        let key = (follow_redirects, max_redirects, proxy.map(|p| p.to_string()), connect_timeout, tls);
+        let mut custom = self.custom.lock().unwrap();
+        if let Some(client) = custom.get(&key) {
+            return client.clone();
+        }
+        match build_check_client(follow_redirects, max_redirects, &self.user_agent, proxy, connect_timeout, &key.4, &self.target_policy) {
+            Ok(client) => {
+                // Keys carry TLS material, so edited settings would otherwise pile up forever;
+                // dropping the lot is fine since in-flight checks hold their own handles
+                if custom.len() >= MAX_CUSTOM_CLIENTS {
+                    custom.clear();
+                }
+                custom.insert(key, client.clone());
+                client
+            }
+            Err(e) => {
+                tracing::warn!(max_redirects, proxy = ?proxy.map(crate::routes::redact_url_password), error = %e, "Failed to build HTTP client");
+                if follow_redirects { self.follow.clone() } else { self.no_follow.clone() }
+            }
+        }
+    }
+}
+
+// ─── Target Policy ──────────────────────────────────────────────────────────
+
+/// One entry of CHECKER_ALLOW_TARGETS / CHECKER_DENY_TARGETS.
+#[derive(Debug, Clone, PartialEq)]
+enum TargetRule {
+    /// Network such as `10.0.0.0/8`; a bare address is a /32 (or /128)
+    Cidr(IpAddr, u8),
+    /// Hostname, also matching its subdomains
+    Host(String),
+}
+
+impl TargetRule {
+    fn parse(entry: &str) -> Result<Self, String> {
+        let entry = entry.trim();
+        let (addr, prefix) = match entry.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (entry, None),
+        };
+        match addr.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+            Ok(ip) => {
+                let max = if ip.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix {
+                    Some(p) => p.parse::<u8>().ok().filter(|&p| p <= max)
+                        .ok_or_else(|| format!("invalid prefix length in '{}'", entry))?,
+                    None => max,
+                };
+                Ok(TargetRule::Cidr(ip, prefix))
+            }
+            Err(_) if prefix.is_none() && !entry.is_empty() && !entry.contains(char::is_whitespace) => {
+                Ok(TargetRule::Host(entry.trim_start_matches("*.").trim_end_matches('.').to_lowercase()))
+            }
+            Err(_) => Err(format!("'{}' is not a hostname, IP address, or CIDR", entry)),
+        }
+    }
+
+    fn matches_ip(&self, ip: IpAddr) -> bool {
+        let TargetRule::Cidr(net, prefix) = *self else { return false };
+        // Compare IPv4-mapped IPv6 addresses as the IPv4 address they carry
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            v4 => v4,
+        };
+        match (net, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_host(&self, host: &str) -> bool {
+        let TargetRule::Host(rule) = self else { return false };
+        host == rule || host.strip_suffix(rule.as_str()).is_some_and(|sub| sub.ends_with('.'))
+    }
+}
+
+/// Opt-in limits on what HTTP, multistep, and TCP checks may connect to, so monitors
+/// can't be used to probe internal networks. Deny rules win; once an allowlist is set,
+/// anything it doesn't cover is blocked.
+#[derive(Debug, Clone, Default)]
+pub struct TargetPolicy {
+    allow: Vec<TargetRule>,
+    deny: Vec<TargetRule>,
+}
+
+impl TargetPolicy {
+    /// Build from comma-separated hostnames, IPs, and CIDRs (e.g. `10.0.0.0/8, metadata.internal`).
+    pub fn new(allow: &str, deny: &str) -> Result<Self, String> {
+        let parse = |list: &str| -> Result<Vec<TargetRule>, String> {
+            list.split(',').filter(|e| !e.trim().is_empty()).map(TargetRule::parse).collect()
+        };
+        Ok(TargetPolicy { allow: parse(allow)?, deny: parse(deny)? })
+    }
+
+    /// From CHECKER_ALLOW_TARGETS / CHECKER_DENY_TARGETS. Default: no restrictions.
+    /// A malformed list is ignored with a warning rather than keeping the checker from starting.
+    pub fn from_env() -> Self {
+        let allow = std::env::var("CHECKER_ALLOW_TARGETS").unwrap_or_default();
+        let deny = std::env::var("CHECKER_DENY_TARGETS").unwrap_or_default();
+        TargetPolicy::new(&allow, &deny).unwrap_or_else(|e| {
+            tracing::warn!(error = %e, "Ignoring invalid CHECKER_ALLOW_TARGETS / CHECKER_DENY_TARGETS");
+            TargetPolicy::default()
+        })
+    }
+
+    fn is_unrestricted(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Why connecting to `host:port` is blocked, if it is. The host is resolved so CIDR
+    /// rules apply to the addresses a check would actually dial.
+    pub async fn blocked_reason(&self, host: &str, port: u16) -> Option<String> {
+        if self.is_unrestricted() {
+            return None;
+        }
+        let host = normalize_policy_host(host);
+        let ips: Vec<IpAddr> = match host.parse::<IpAddr>() {
+            Ok(ip) => vec![ip],
+            Err(_) => tokio::net::lookup_host((host.as_str(), port)).await
+                .map(|addrs| addrs.map(|a| a.ip()).collect())
+                .unwrap_or_default(),
+        };
+        self.verdict(&host, &ips)
+    }
+
+    /// Why `host`, resolved to `ips`, is blocked, if it is.
+    fn verdict(&self, host: &str, ips: &[IpAddr]) -> Option<String> {
+        let denied = |rule: &TargetRule| rule.matches_host(host) || ips.iter().any(|&ip| rule.matches_ip(ip));
+        if self.deny.iter().any(denied) {
+            return Some(format!("{} is on the checker's deny list", host));
+        }
+        let allowed = self.allow.iter().any(|rule| rule.matches_host(host))
+            || (!ips.is_empty() && ips.iter().all(|&ip| self.allow.iter().any(|rule| rule.matches_ip(ip))));
+        if !self.allow.is_empty() && !allowed {
+            return Some(format!("{} is not on the checker's allow list", host));
+        }
+        None
+    }
+
+    /// Why a redirect to `url` is blocked, if it is. IP literals are judged here since they
+    /// skip DNS; for hostnames only host rules apply, and `PolicyResolver` vets the addresses.
+    fn blocked_hop(&self, url: &reqwest::Url) -> Option<String> {
+        let host = normalize_policy_host(url.host_str().unwrap_or_default());
+        match host.parse::<IpAddr>() {
+            Ok(ip) => self.verdict(&host, &[ip]),
+            Err(_) if self.deny.iter().any(|rule| rule.matches_host(&host)) => {
+                Some(format!("{} is on the checker's deny list", host))
+            }
+            Err(_) => None,
+        }
+    }
+}
+
+fn normalize_policy_host(host: &str) -> String {
+    host.trim_start_matches('[').trim_end_matches(']').trim_end_matches('.').to_lowercase()
+}
+
+/// A request the target policy refused, raised from the redirect policy or the resolver.
+#[derive(Debug)]
+struct TargetBlocked(String);
+
+impl std::fmt::Display for TargetBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Blocked by target policy: {}", self.0)
+    }
+}
+
+impl std::error::Error for TargetBlocked {}
+
+/// DNS for check clients that drops addresses the target policy blocks, so a host that
+/// passed the pre-check can't be re-pointed at a denied network before the connect.
+struct PolicyResolver(Arc<TargetPolicy>);
+
+impl reqwest::dns::Resolve for PolicyResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        let policy = self.0.clone();
+        let host = normalize_policy_host(name.as_str());
+        Box::pin(async move {
+            // Port 0 is replaced with the URL's port
+            let resolved: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+            let mut blocked = None;
+            let allowed: Vec<SocketAddr> = resolved.into_iter()
+                .filter(|addr| match policy.verdict(&host, &[addr.ip()]) {
+                    Some(reason) => {
+                        blocked = Some(reason);
+                        false
+                    }
+                    None => true,
+                })
+                .collect();
+            match blocked {
+                Some(reason) if allowed.is_empty() => Err(Box::new(TargetBlocked(reason)) as Box<dyn std::error::Error + Send + Sync>),
+                _ => Ok(Box::new(allowed.into_iter()) as reqwest::dns::Addrs),
+            }
+        })
+    }
+}
+
+/// `(host, port)` a check would connect to, for policy enforcement.
+fn check_target(monitor_type: &str, url: &str) -> Option<(String, u16)> {
+    if monitor_type == "tcp" {
+        let (host, port) = url.strip_prefix("tcp://").unwrap_or(url).rsplit_once(':')?;
+        return Some((host.to_string(), port.parse().ok()?));
+    }
+    let parsed = reqwest::Url::parse(url.trim()).ok()?;
+    Some((parsed.host_str()?.to_string(), parsed.port_or_known_default()?))
+}
+
+/// Heartbeat for a check the target policy refused to run.
+async fn target_policy_block(policy: &TargetPolicy, m: &MonitorCheck) -> Option<CheckResult> {
+    let urls: Vec<String> = match m.monitor_type.as_str() {
+        "dns" => return None,
+        "multistep" => m.steps.as_deref()
+            .and_then(|s| serde_json::from_str::<Vec<CheckStep>>(s).ok())
+            .unwrap_or_default()
+            .into_iter()
+            .map(|step| step.url)
+            .collect(),
+        _ => vec![m.url.clone()],
     };
+    for url in urls {
+        let Some((host, port)) = check_target(&m.monitor_type, &url) else { continue };
+        if let Some(reason) = policy.blocked_reason(&host, port).await {
+            return Some(CheckResult {
+                status: "down".into(),
+                response_time_ms: 0,
+                status_code: None,
+                error_message: Some(format!("Blocked by target policy: {}", reason)),
+                failure_kind: None,
+                extra_sse_data: None,
+            });
+        }
+    }
+    None
+}
+
+/// Columns loaded into a `MonitorCheck`, in `row_to_monitor_check` order.
+const MONITOR_CHECK_COLUMNS: &str = "id, name, url, method, timeout_ms, expected_status, body_contains, headers, COALESCE(down_confirmation_threshold, confirmation_threshold), consecutive_failures, current_status, interval_seconds, response_time_threshold_ms, follow_redirects, COALESCE(monitor_type, 'http'), COALESCE(dns_record_type, 'A'), dns_expected, consensus_threshold, consensus_mode, steps, body_not_contains, max_redirects, expected_final_url_prefix, min_body_bytes, max_body_bytes, auth_type, auth_username, auth_password, auth_token, proxy_url, dns_min_records, dns_min_ttl, dns_resolver, tcp_send, tcp_expect, recovery_cooldown_seconds, recovery_pending_since, COALESCE(up_confirmation_threshold, 1), consecutive_successes, connect_timeout_ms, runbook_url, expected_first_status, client_cert_pem, client_key_pem, insecure_skip_tls_verify, ca_bundle_pem";
+
+fn row_to_monitor_check(row: &rusqlite::Row) -> rusqlite::Result<MonitorCheck> {
+    let headers_str: Option<String> = row.get(7)?;
+    Ok(MonitorCheck {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        method: row.get(3)?,
+        timeout_ms: row.get(4)?,
+        expected_status: row.get(5)?,
+        body_contains: row.get(6)?,
+        headers: headers_str,
+        down_confirmation_threshold: row.get(8)?,
+        consecutive_failures: row.get(9)?,
+        current_status: row.get(10)?,
+        interval_seconds: row.get(11)?,
+        response_time_threshold_ms: row.get(12)?,
+        follow_redirects: row.get::<_, i32>(13).unwrap_or(1) != 0,
+        monitor_type: row.get(14)?,
+        dns_record_type: row.get(15)?,
+        dns_expected: row.get(16)?,
+        consensus_threshold: row.get(17)?,
+        consensus_mode: row.get(18)?,
+        steps: row.get(19)?,
+        body_not_contains: row.get(20)?,
+        max_redirects: row.get(21)?,
+        expected_final_url_prefix: row.get(22)?,
+        min_body_bytes: row.get(23)?,
+        max_body_bytes: row.get(24)?,
+        auth_type: row.get(25)?,
+        auth_username: row.get(26)?,
+        auth_password: row.get(27)?,
+        auth_token: row.get(28)?,
+        proxy_url: row.get(29)?,
+        dns_min_records: row.get(30)?,
+        dns_min_ttl: row.get(31)?,
+        dns_resolver: row.get(32)?,
+        tcp_send: row.get(33)?,
+        tcp_expect: row.get(34)?,
+        recovery_cooldown_seconds: row.get(35)?,
+        recovery_pending_since: row.get(36)?,
+        up_confirmation_threshold: row.get(37)?,
+        consecutive_successes: row.get(38)?,
+        connect_timeout_ms: row.get(39)?,
+        runbook_url: row.get(40)?,
+        expected_first_status: row.get(41)?,
+        client_cert_pem: row.get(42)?,
+        client_key_pem: row.get(43)?,
+        insecure_skip_tls_verify: row.get::<_, i32>(44).unwrap_or(0) != 0,
+        ca_bundle_pem: row.get(45)?,
+    })
+}
+
+impl MonitorCheck {
+    /// Limit on reaching the target (DNS, TCP connect, TLS handshake), never more than the
+    /// overall timeout. Defaults to half of it, so a stalled connect still reads as one.
+    fn connect_timeout(&self) -> Duration {
+        let ms = self.connect_timeout_ms.unwrap_or(self.timeout_ms / 2).min(self.timeout_ms);
+        Duration::from_millis(ms as u64)
+    }
+
+    fn client_tls(&self) -> ClientTls {
+        ClientTls {
+            identity_pem: self.client_cert_pem.as_deref().zip(self.client_key_pem.as_deref())
+                .map(|(cert, key)| identity_pem(cert, key)),
+            accept_invalid_certs: self.insecure_skip_tls_verify,
+            ca_bundle_pem: self.ca_bundle_pem.clone(),
+        }
+    }
+}
+
+/// Liveness of the checker task, reported by `/health`.
+#[derive(Default)]
+pub struct CheckerHealth {
+    restarts: AtomicU64,
+    /// Unix seconds of the last check-loop iteration; 0 until the first one.
+    last_run: AtomicI64,
+}
+
+impl CheckerHealth {
+    pub fn record_run(&self) {
+        self.last_run.store(chrono::Utc::now().timestamp(), Ordering::Relaxed);
+    }
+
+    pub fn restarts(&self) -> u64 {
+        self.restarts.load(Ordering::Relaxed)
+    }
+
+    pub fn last_run_at(&self) -> Option<String> {
+        match self.last_run.load(Ordering::Relaxed) {
+            0 => None,
+            ts => chrono::DateTime::from_timestamp(ts, 0).map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string()),
+        }
+    }
+}
+
+/// Per-monitor checker state (confirmation counters, timing), published after
+/// every check and read by `GET /monitors/{id}/check-state`.
+#[derive(Default)]
+pub struct CheckStates {
+    states: Mutex<HashMap<String, MonitorCheckState>>,
+    in_flight: Arc<InFlight>,
+}
+
+impl CheckStates {
+    pub fn get(&self, monitor_id: &str) -> Option<MonitorCheckState> {
+        self.states.lock().unwrap_or_else(|e| e.into_inner()).get(monitor_id).cloned()
+    }
+
+    fn publish(&self, monitor_id: &str, state: MonitorCheckState) {
+        self.states.lock().unwrap_or_else(|e| e.into_inner()).insert(monitor_id.to_string(), state);
+    }
+}
+
+/// Restart backoff for the supervised checker: doubles per consecutive
+/// failure up to this cap, and resets once a run outlives it.
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// Keep a background task alive: respawn it whenever it exits or panics before
+/// `shutdown` fires, waiting `base_backoff` (doubling, capped) between
+/// attempts. Each respawn is counted in `health`.
+pub async fn supervise<F, Fut, S>(name: &str, mut spawn: F, health: Arc<CheckerHealth>, base_backoff: Duration, shutdown: S)
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+    S: Future<Output = ()> + Clone,
+{
+    let mut backoff = base_backoff;
+    loop {
+        let started = std::time::Instant::now();
+        match tokio::spawn(spawn()).await {
+            Ok(()) => tracing::warn!(task = name, "Task exited"),
+            Err(e) => tracing::error!(task = name, error = %e, "Task failed"),
+        }
+        if started.elapsed() >= RESTART_BACKOFF_MAX {
+            backoff = base_backoff;
+        }
+        tokio::select! {
+            biased;
+            _ = shutdown.clone() => return,
+            _ = time::sleep(backoff) => {},
+        }
+        health.restarts.fetch_add(1, Ordering::Relaxed);
+        tracing::warn!(task = name, restarts = health.restarts(), "Restarting task");
+        backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+    }
+}
+
+/// Background check scheduler. Runs in a tokio task.
+pub async fn run_checker(db: Arc<Db>, broadcaster: Arc<EventBroadcaster>, clients: Arc<CheckerClients>, health: Arc<CheckerHealth>, states: Arc<CheckStates>, shutdown: rocket::Shutdown) {
+    tracing::info!("Checker starting");
 
-    // Shared webhook client for TCP/DNS checks (which don't have their own HTTP client)
-    let webhook_client = match reqwest::Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-    {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("❌ Checker: failed to build webhook client: {e}");
+    // Wait 10s for server to warm up (reduced from 30s for faster first check)
+    tracing::info!("Checker waiting 10s for server warmup");
+    tokio::select! {
+        _ = time::sleep(Duration::from_secs(10)) => {},
+        _ = shutdown.clone() => {
+            tracing::info!("Checker shutdown during warmup");
             return;
-        }
-    };
+        },
+    }
+
+    let max_concurrency = checker_max_concurrency();
+    let pool = CheckPool::new(max_concurrency, states.in_flight.clone());
+    let heartbeats = Arc::new(HeartbeatBuffer::default());
+    let mut last_flush = std::time::Instant::now();
 
-    println!("✅ Checker: initialized, entering check loop");
+    tracing::info!(max_concurrency, "Checker initialized, entering check loop");
 
     // Track last retention run so we only prune once per hour
     let mut last_retention = std::time::Instant::now() - Duration::from_secs(3600);
     // Track last probe health check (every 5 minutes)
     let mut last_probe_health = std::time::Instant::now() - Duration::from_secs(300);
+    // Track last upcoming-maintenance reminder sweep (every minute)
+    let mut last_reminder_sweep = std::time::Instant::now() - Duration::from_secs(60);
+    let jitter_pct = check_jitter_pct();
+    if jitter_pct > 0 {
+        tracing::info!(jitter_pct, "Spreading checks with interval jitter");
+    }
 
     loop {
-        // Run heartbeat retention every hour
+        health.record_run();
+
+        // Write buffered heartbeats in one batch
+        if heartbeats.len() >= HEARTBEAT_BATCH_SIZE || last_flush.elapsed() >= HEARTBEAT_FLUSH_INTERVAL {
+            heartbeats.flush(&db);
+            last_flush = std::time::Instant::now();
+        }
+
+        // Roll up daily uptime, downsample, then run heartbeat retention, every hour
         if last_retention.elapsed() >= Duration::from_secs(3600) {
+            let rolled = rollup_daily_uptime(&db);
+            if rolled > 0 {
+                tracing::info!(rows = rolled, "Rollup: wrote daily uptime rows");
+            }
+            let ds_days = downsample_days();
+            if ds_days > 0 {
+                let folded = downsample_heartbeats(&db, ds_days);
+                if folded > 0 {
+                    tracing::info!(heartbeats = folded, older_than_days = ds_days, "Downsampling: folded heartbeats into hourly aggregates");
+                }
+            }
             let days = retention_days();
             let deleted = prune_heartbeats(&db, days);
             if deleted > 0 {
-                println!("🗑️  Retention: pruned {} heartbeats older than {} days", deleted, days);
+                tracing::info!(heartbeats = deleted, older_than_days = days, "Retention: pruned heartbeats");
+            }
+            let restore_days = crate::routes::monitor_restore_days();
+            let purged = purge_deleted_monitors(&db, restore_days);
+            if purged > 0 {
+                tracing::info!(monitors = purged, older_than_days = restore_days, "Recycle bin: purged deleted monitors");
             }
             last_retention = std::time::Instant::now();
         }
 
-        // Run probe health check every 5 minutes
+        // Run probe health check and the incident auto-close sweep every 5 minutes
         if last_probe_health.elapsed() >= Duration::from_secs(300) {
             let stale_minutes = probe_stale_minutes();
             let disabled = disable_stale_locations(&db, stale_minutes);
             if disabled > 0 {
-                println!("🔌 Probe health: auto-disabled {} stale locations (>{} min)", disabled, stale_minutes);
+                tracing::info!(locations = disabled, stale_minutes, "Probe health: auto-disabled stale locations");
+            }
+            let checks = incident_auto_close_checks();
+            let closed = auto_close_recovered_incidents(&db, checks);
+            if closed > 0 {
+                tracing::info!(incidents = closed, consecutive_up_checks = checks, "Incidents: auto-closed recovered incidents");
             }
             last_probe_health = std::time::Instant::now();
         }
 
-        // Find the next monitor due for a check
-        let monitor = {
-            let conn = db.conn();
-            conn.query_row(
-                "SELECT id, name, url, method, timeout_ms, expected_status, body_contains, headers, confirmation_threshold, consecutive_failures, current_status, interval_seconds, response_time_threshold_ms, follow_redirects, COALESCE(monitor_type, 'http'), COALESCE(dns_record_type, 'A'), dns_expected, consensus_threshold
-                 FROM monitors
-                 WHERE is_paused = 0
-                   AND (last_checked_at IS NULL OR datetime(last_checked_at, '+' || interval_seconds || ' seconds') <= datetime('now'))
-                 ORDER BY last_checked_at ASC NULLS FIRST
-                 LIMIT 1",
-                [],
-                |row| {
-                    let headers_str: Option<String> = row.get(7)?;
-                    Ok(MonitorCheck {
-                        id: row.get(0)?,
-                        name: row.get(1)?,
-                        url: row.get(2)?,
-                        method: row.get(3)?,
-                        timeout_ms: row.get(4)?,
-                        expected_status: row.get(5)?,
-                        body_contains: row.get(6)?,
-                        headers: headers_str,
-                        confirmation_threshold: row.get(8)?,
-                        consecutive_failures: row.get(9)?,
-                        current_status: row.get(10)?,
-                        interval_seconds: row.get(11)?,
-                        response_time_threshold_ms: row.get(12)?,
-                        follow_redirects: row.get::<_, i32>(13).unwrap_or(1) != 0,
-                        monitor_type: row.get(14)?,
-                        dns_record_type: row.get(15)?,
-                        dns_expected: row.get(16)?,
-                        consensus_threshold: row.get(17)?,
-                    })
-                },
-            ).ok()
+        // Remind channels about maintenance windows starting soon
+        if last_reminder_sweep.elapsed() >= Duration::from_secs(60) {
+            let sent = send_maintenance_reminders(&db, &clients.webhook, &broadcaster).await;
+            if sent > 0 {
+                tracing::info!(reminders = sent, "Maintenance: sent upcoming-window reminders");
+            }
+            last_reminder_sweep = std::time::Instant::now();
+        }
+
+        // Wait for a free slot, then find the next monitor due for a check
+        let permit = tokio::select! {
+            permit = pool.acquire() => permit,
+            _ = shutdown.clone() => {
+                drain_checks(&pool, &heartbeats, &db, shutdown_grace()).await;
+                return;
+            }
         };
+        let monitor = next_due_monitor(&db, jitter_pct, &pool.in_flight());
 
         match monitor {
             Some(m) => {
-                println!("🔍 Checking: {} ({})", m.name, m.url);
-                // Execute the appropriate check type
-                let result = match m.monitor_type.as_str() {
-                    "tcp" => execute_tcp_check(&m).await,
-                    "dns" => execute_dns_check(&m).await,
-                    _ => {
-                        let client = if m.follow_redirects { &client_follow } else { &client_no_follow };
-                        execute_http_check(client, &m).await
-                    }
-                };
-
-                if m.consensus_threshold.is_some() {
-                    // Consensus mode: write heartbeat + update timing, then defer to consensus
-                    process_check_result_heartbeat_only(&db, &broadcaster, &m, result).await;
-                    // Evaluate consensus across all locations
-                    crate::consensus::evaluate_and_apply(&db, &broadcaster, &consensus_client, &m.id).await;
-                } else {
-                    // Single-location mode: full incident lifecycle
-                    let notif_client = match m.monitor_type.as_str() {
-                        "tcp" | "dns" => &webhook_client,
-                        _ => if m.follow_redirects { &client_follow } else { &client_no_follow },
-                    };
-                    process_check_result(&db, &broadcaster, notif_client, &m, result).await;
-                }
+                let (db, broadcaster, clients, states, heartbeats) = (db.clone(), broadcaster.clone(), clients.clone(), states.clone(), heartbeats.clone());
+                pool.spawn(m.id.clone(), permit, async move {
+                    run_check(&db, &broadcaster, &clients, &states, &heartbeats, &m, false).await;
+                });
             }
             None => {
+                drop(permit);
                 // No monitors due — sleep a bit before checking again
                 tokio::select! {
                     _ = time::sleep(Duration::from_secs(10)) => {},
-                    _ = shutdown.clone() => return,
+                    _ = shutdown.clone() => {
+                        drain_checks(&pool, &heartbeats, &db, shutdown_grace()).await;
+                        return;
+                    }
                 }
             }
         }
@@ -248,15 +1317,300 @@ pub async fn run_checker(db: Arc<Db>, broadcaster: Arc<EventBroadcaster>, shutdo
         // Brief yield between checks
         tokio::select! {
             _ = time::sleep(Duration::from_millis(100)) => {},
-            _ = shutdown.clone() => return,
+            _ = shutdown.clone() => {
+                drain_checks(&pool, &heartbeats, &db, shutdown_grace()).await;
+                return;
+            }
+        }
+    }
+}
+
+/// Find the next monitor due for a check, skipping monitors already being
+/// checked. Monitors past their base interval are candidates; with jitter on,
+/// each also waits out its own offset.
+fn next_due_monitor(db: &Db, jitter_pct: u32, in_flight: &HashSet<String>) -> Option<MonitorCheck> {
+    let conn = db.conn();
+    // In-flight monitors and jitter can hold back the oldest candidates, so look past them
+    let limit = in_flight.len() + if jitter_pct == 0 { 1 } else { 100 };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {MONITOR_CHECK_COLUMNS}, last_checked_at AS last_checked_at
+         FROM monitors
+         WHERE is_paused = 0 AND is_archived = 0 AND deleted_at IS NULL
+           AND (last_checked_at IS NULL OR datetime(last_checked_at, '+' || interval_seconds || ' seconds') <= datetime('now'))
+         ORDER BY last_checked_at ASC NULLS FIRST
+         LIMIT {limit}"
+    )).ok()?;
+    let rows = stmt.query_map([], |row| {
+        Ok((row_to_monitor_check(row)?, row.get::<_, Option<String>>("last_checked_at")?))
+    }).ok()?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let due = rows.flatten().find_map(|(m, last_checked_at)| {
+        if in_flight.contains(&m.id) {
+            return None;
+        }
+        let last = last_checked_at.as_deref()
+            .and_then(|s| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").ok());
+        match last {
+            Some(last) if next_due_at(last, &m.id, m.interval_seconds, jitter_pct) > now => None,
+            _ => Some(m),
+        }
+    });
+    due
+}
+
+/// When a monitor is next due: its interval after the last check, plus its
+/// jitter offset.
+fn next_due_at(last_checked_at: chrono::NaiveDateTime, monitor_id: &str, interval_seconds: u32, jitter_pct: u32) -> chrono::NaiveDateTime {
+    let offset = jitter_offset_secs(monitor_id, interval_seconds, jitter_pct);
+    last_checked_at + chrono::Duration::seconds(interval_seconds as i64 + offset as i64)
+}
+
+/// When the checker will next pick up a monitor last checked at `last_checked_at` (the stored
+/// `%Y-%m-%d %H:%M:%S` form), in that same form. `None` if the timestamp doesn't parse.
+pub fn scheduled_next_check(last_checked_at: &str, monitor_id: &str, interval_seconds: u32) -> Option<String> {
+    let last = chrono::NaiveDateTime::parse_from_str(last_checked_at, "%Y-%m-%d %H:%M:%S").ok()?;
+    Some(next_due_at(last, monitor_id, interval_seconds, check_jitter_pct())
+        .format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Per-monitor schedule offset, up to `jitter_pct`% of the interval. Derived
+/// from the monitor id so it is stable across restarts, while monitors that
+/// share an interval drift apart instead of firing together.
+fn jitter_offset_secs(monitor_id: &str, interval_seconds: u32, jitter_pct: u32) -> u32 {
+    let max = interval_seconds as u64 * jitter_pct.min(50) as u64 / 100;
+    if max == 0 {
+        return 0;
+    }
+    let digest = Sha256::digest(monitor_id.as_bytes());
+    let seed = u64::from_be_bytes(digest[..8].try_into().unwrap());
+    (seed % (max + 1)) as u32
+}
+
+/// Check a single monitor immediately, regardless of its schedule, running the
+/// full result pipeline (heartbeat, status, incidents, notifications). Waits for
+/// a scheduled check already running on the monitor rather than racing it.
+/// `fresh` bypasses the result cache so the caller sees the target's current response.
+/// Returns false if the monitor doesn't exist.
+pub async fn check_monitor(db: &Db, broadcaster: &EventBroadcaster, clients: &CheckerClients, states: &CheckStates, monitor_id: &str, fresh: bool) -> bool {
+    // Claimed before loading so the row reflects whatever a running check recorded
+    let _claim = states.in_flight.claim_when_idle(monitor_id).await;
+    let monitor = {
+        let conn = db.conn();
+        conn.query_row(
+            &format!("SELECT {MONITOR_CHECK_COLUMNS} FROM monitors WHERE id = ?1 AND deleted_at IS NULL"),
+            params![monitor_id],
+            row_to_monitor_check,
+        ).ok()
+    };
+    match monitor {
+        Some(m) => {
+            let heartbeats = HeartbeatBuffer::default();
+            run_check(db, broadcaster, clients, states, &heartbeats, &m, fresh).await;
+            heartbeats.flush(db);
+            true
         }
+        None => false,
     }
 }
 
+/// Execute the type-specific check for a monitor and process its result.
+/// `fresh` skips the shared result cache, for checks asked for explicitly.
+async fn run_check(db: &Db, broadcaster: &EventBroadcaster, clients: &CheckerClients, states: &CheckStates, heartbeats: &HeartbeatBuffer, m: &MonitorCheck, fresh: bool) {
+    let started = std::time::Instant::now();
+    // Execute the appropriate check type, unless the target is off-limits
+    let result = match target_policy_block(&clients.target_policy, m).await {
+        Some(blocked) => blocked,
+        None => match m.monitor_type.as_str() {
+            "tcp" => execute_tcp_check(m).await,
+            "dns" => execute_dns_check(&clients.webhook, m).await,
+            "multistep" => {
+                let defaults = crate::routes::load_default_headers(&db.read());
+                execute_multistep_check(&clients.http(m), &clients.target_policy, m, &defaults).await
+            }
+            _ => {
+                let defaults = crate::routes::load_default_headers(&db.read());
+                match first_hop_failure(clients, m, &defaults).await {
+                    Some(failed) => failed,
+                    None => {
+                        let cache = Some(&clients.result_cache).filter(|_| !fresh);
+                        execute_http_check(&clients.http(m), m, &defaults, cache).await
+                    }
+                }
+            }
+        },
+    };
+    let duration = started.elapsed();
+    let last_result = result.status.clone();
+    tracing::info!(
+        monitor_id = %m.id,
+        monitor = %m.name,
+        monitor_type = %m.monitor_type,
+        status = %result.status,
+        duration_ms = duration.as_millis() as u64,
+        status_code = result.status_code,
+        error = result.error_message.as_deref(),
+        failure_kind = result.failure_kind.map(FailureKind::as_str),
+        "check completed"
+    );
+
+    let (failures, successes) = if ConsensusRule::from_config(m.consensus_mode.as_deref(), m.consensus_threshold).is_some() {
+        // Consensus mode: write heartbeat + update timing, then defer to consensus
+        heartbeats.push(PendingHeartbeat::from_result(&m.id, &result));
+        let counters = process_check_result_heartbeat_only(db, broadcaster, m, result).await;
+        // Evaluation reads the latest heartbeat per location, so this one must land first
+        heartbeats.flush(db);
+        // Evaluate consensus across all locations
+        crate::consensus::evaluate_and_apply(db, broadcaster, &clients.webhook, &m.id).await;
+        counters
+    } else {
+        // Single-location mode: full incident lifecycle
+        // Failures inside a maintenance window are recorded as planned downtime, which SLAs exclude
+        let mut hb = PendingHeartbeat::from_result(&m.id, &result);
+        if result.status == "down" && is_in_maintenance(db, &m.id) {
+            hb.status = "maintenance".to_string();
+        }
+        heartbeats.push(hb);
+        // Latency, SLA, and burn-rate rules read heartbeat history, which must include this check
+        if has_heartbeat_window_rules(db, &m.id) {
+            heartbeats.flush(db);
+        }
+        process_check_result(db, broadcaster, &clients.webhook, m, result).await
+    };
+
+    let now = chrono::Utc::now().naive_utc();
+    states.publish(&m.id, MonitorCheckState {
+        last_result,
+        consecutive_failures: failures,
+        consecutive_successes: successes,
+        down_confirmation_threshold: m.down_confirmation_threshold,
+        up_confirmation_threshold: m.up_confirmation_threshold,
+        last_check_duration_ms: duration.as_millis() as u64,
+        last_checked_at: now.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        next_due_at: next_due_at(now, &m.id, m.interval_seconds, check_jitter_pct())
+            .format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+    });
+}
+
 // ─── Check Execution (type-specific) ────────────────────────────────────────
 
+/// Add the global default headers to a request, skipping any name the monitor
+/// (or step) sets itself. Header names compare case-insensitively.
+fn with_default_headers(
+    mut req: reqwest::RequestBuilder,
+    defaults: &[(String, String)],
+    own: Option<&serde_json::Map<String, serde_json::Value>>,
+) -> reqwest::RequestBuilder {
+    for (k, v) in defaults {
+        let overridden = own.is_some_and(|o| o.keys().any(|ok| ok.eq_ignore_ascii_case(k)));
+        if !overridden {
+            req = req.header(k.as_str(), v.as_str());
+        }
+    }
+    req
+}
+
 /// Execute an HTTP health check. Returns the raw check result.
-async fn execute_http_check(client: &reqwest::Client, monitor: &MonitorCheck) -> CheckResult {
+/// With the result cache enabled, monitors making the identical request share one fetch;
+/// `None` always fetches.
+async fn execute_http_check(client: &reqwest::Client, monitor: &MonitorCheck, default_headers: &[(String, String)], cache: Option<&ResultCache>) -> CheckResult {
+    let fetched = if let Some(cache) = cache.filter(|c| c.enabled()) {
+        // Always read the body so the shared response can serve any monitor's assertions
+        let key = http_cache_key(monitor, default_headers);
+        cache.get_or_fetch(key, || fetch_http(client, monitor, default_headers, true)).await
+    } else {
+        let needs_body = monitor.body_contains.is_some() || monitor.body_not_contains.is_some()
+            || monitor.min_body_bytes.is_some() || monitor.max_body_bytes.is_some();
+        fetch_http(client, monitor, default_headers, needs_body).await
+    };
+
+    let (status, status_code, error_message, failure_kind, elapsed_ms) = match fetched {
+        HttpFetch::Response { code, final_url, body, elapsed_ms } => {
+            let down = |msg: String, kind: Option<FailureKind>| ("down".to_string(), Some(code), Some(msg), kind);
+            let rt_threshold = || {
+                let (status, status_code, error_message) = check_rt_threshold(monitor.response_time_threshold_ms, elapsed_ms, code);
+                (status, status_code, error_message, None)
+            };
+            // Without redirects followed this response is the first hop
+            let first_status = monitor.expected_first_status.filter(|&s| !monitor.follow_redirects && s != code);
+            let (status, status_code, error_message, failure_kind) = if let Some(expected) = first_status {
+                down(first_status_message(expected, code), Some(FailureKind::Status))
+            } else if code != monitor.expected_status {
+                down(format!("Expected {}, got {}", monitor.expected_status, code), Some(FailureKind::Status))
+            } else if let Some(msg) = final_url_error(&final_url, &monitor.expected_final_url_prefix) {
+                down(msg, None)
+            } else if monitor.body_contains.is_some() || monitor.body_not_contains.is_some()
+                || monitor.min_body_bytes.is_some() || monitor.max_body_bytes.is_some()
+            {
+                match body {
+                    Some(Ok(body)) => {
+                        let failure = body_size_error(&body, monitor.min_body_bytes, monitor.max_body_bytes)
+                            .or_else(|| body_assertion_error(&body.text, &monitor.body_contains, &monitor.body_not_contains));
+                        match failure {
+                            Some(msg) => down(msg, Some(FailureKind::Body)),
+                            None => rt_threshold(),
+                        }
+                    }
+                    Some(Err(e)) => down(format!("Body read error: {}", e), Some(FailureKind::Body)),
+                    None => down("Body read error: body not read".to_string(), Some(FailureKind::Body)),
+                }
+            } else {
+                rt_threshold()
+            };
+            (status, status_code, error_message, failure_kind, elapsed_ms)
+        }
+        HttpFetch::Failed { error, kind, elapsed_ms } => ("down".to_string(), None, Some(error), kind, elapsed_ms),
+    };
+
+    CheckResult {
+        status,
+        response_time_ms: elapsed_ms,
+        status_code,
+        error_message,
+        failure_kind,
+        extra_sse_data: None,
+    }
+}
+
+/// With `expected_first_status` on a monitor that follows redirects, fetch the first hop on
+/// its own and fail the check if its status differs. This costs one extra request; monitors
+/// that don't follow redirects have their first hop asserted in `execute_http_check` instead.
+async fn first_hop_failure(clients: &CheckerClients, monitor: &MonitorCheck, default_headers: &[(String, String)]) -> Option<CheckResult> {
+    let expected = monitor.expected_first_status.filter(|_| monitor.follow_redirects)?;
+    let (status_code, error, failure_kind, elapsed_ms) = match fetch_http(&clients.first_hop(monitor), monitor, default_headers, false).await {
+        HttpFetch::Response { code, .. } if code == expected => return None,
+        HttpFetch::Response { code, elapsed_ms, .. } => (Some(code), first_status_message(expected, code), Some(FailureKind::Status), elapsed_ms),
+        HttpFetch::Failed { error, kind, elapsed_ms } => (None, error, kind, elapsed_ms),
+    };
+    Some(CheckResult {
+        status: "down".to_string(),
+        response_time_ms: elapsed_ms,
+        status_code,
+        error_message: Some(error),
+        failure_kind,
+        extra_sse_data: None,
+    })
+}
+
+fn first_status_message(expected: u16, got: u16) -> String {
+    format!("First response: expected {}, got {}", expected, got)
+}
+
+/// Outcome of an HTTP check request, before a monitor's assertions are applied.
+#[derive(Debug, Clone)]
+enum HttpFetch {
+    Response {
+        code: u16,
+        final_url: String,
+        /// Read only when asked for
+        body: Option<Result<CappedBody, String>>,
+        elapsed_ms: u32,
+    },
+    Failed { error: String, kind: Option<FailureKind>, elapsed_ms: u32 },
+}
+
+/// Send a monitor's HTTP request (method, default and own headers, stored credentials).
+async fn fetch_http(client: &reqwest::Client, monitor: &MonitorCheck, default_headers: &[(String, String)], read_body: bool) -> HttpFetch {
     let start = std::time::Instant::now();
 
     // Build request
@@ -268,62 +1622,361 @@ async fn execute_http_check(client: &reqwest::Client, monitor: &MonitorCheck) ->
 
     req = req.timeout(Duration::from_millis(monitor.timeout_ms as u64));
 
-    // Add custom headers
-    if let Some(ref headers_json) = monitor.headers {
-        if let Ok(headers) = serde_json::from_str::<serde_json::Value>(headers_json) {
-            if let Some(obj) = headers.as_object() {
-                for (k, v) in obj {
-                    if let Some(val) = v.as_str() {
-                        req = req.header(k.as_str(), val);
-                    }
-                }
+    // Add global default headers, then the monitor's own (which win)
+    let own_headers = monitor.headers.as_deref()
+        .and_then(|h| serde_json::from_str::<serde_json::Value>(h).ok());
+    let own = own_headers.as_ref().and_then(|h| h.as_object());
+    req = with_default_headers(req, default_headers, own);
+    if let Some(obj) = own {
+        for (k, v) in obj {
+            if let Some(val) = v.as_str() {
+                req = req.header(k.as_str(), val);
             }
         }
     }
 
+    // Stored credentials
+    match monitor.auth_type.as_deref() {
+        Some("basic") => {
+            req = req.basic_auth(monitor.auth_username.as_deref().unwrap_or_default(), monitor.auth_password.as_deref());
+        }
+        Some("bearer") => {
+            req = req.bearer_auth(monitor.auth_token.as_deref().unwrap_or_default());
+        }
+        _ => {}
+    }
+
     // Execute
     let result = req.send().await;
     let elapsed_ms = start.elapsed().as_millis() as u32;
-    let rt_threshold = monitor.response_time_threshold_ms;
 
-    let (status, status_code, error_message) = match result {
+    match result {
         Ok(resp) => {
             let code = resp.status().as_u16();
-            if code != monitor.expected_status {
-                ("down".to_string(), Some(code), Some(format!("Expected {}, got {}", monitor.expected_status, code)))
-            } else if let Some(ref expected_body) = monitor.body_contains {
-                match resp.text().await {
-                    Ok(body) if body.contains(expected_body) => {
-                        check_rt_threshold(rt_threshold, elapsed_ms, code)
-                    }
-                    Ok(_) => ("down".to_string(), Some(code), Some("Body match failed".to_string())),
-                    Err(e) => ("down".to_string(), Some(code), Some(format!("Body read error: {}", e))),
-                }
+            let final_url = resp.url().to_string();
+            let body = if read_body {
+                Some(read_body_capped(resp, max_body_read_bytes()).await.map_err(|e| e.to_string()))
             } else {
-                check_rt_threshold(rt_threshold, elapsed_ms, code)
-            }
+                None
+            };
+            HttpFetch::Response { code, final_url, body, elapsed_ms }
         }
         Err(e) => {
-            let msg = if e.is_timeout() {
-                "Request timed out".to_string()
-            } else if e.is_connect() {
-                "Connection refused".to_string()
+            let (error, kind) = describe_request_error(&e, monitor.max_redirects);
+            HttpFetch::Failed { error, kind, elapsed_ms }
+        }
+    }
+}
+
+// ─── Result Cache ───────────────────────────────────────────────────────────
+
+/// How long an HTTP check response may be shared with other monitors making the same request.
+/// Configurable via CHECK_RESULT_CACHE_SECS env var. Default: 0 (off). Max: 60.
+fn check_result_cache_ttl() -> Duration {
+    let secs: u64 = std::env::var("CHECK_RESULT_CACHE_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    Duration::from_secs(secs.min(60))
+}
+
+/// Short-lived, opt-in sharing of HTTP check responses. Concurrent identical checks wait on
+/// the first one's fetch; each monitor still evaluates the response and records its own heartbeat.
+#[derive(Default)]
+pub struct ResultCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (std::time::Instant, Arc<tokio::sync::OnceCell<HttpFetch>>)>>,
+}
+
+impl ResultCache {
+    pub fn new(ttl: Duration) -> Self {
+        ResultCache { ttl, entries: Mutex::default() }
+    }
+
+    fn enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    async fn get_or_fetch<F, Fut>(&self, key: String, fetch: F) -> HttpFetch
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = HttpFetch>,
+    {
+        let cell = {
+            let mut entries = self.entries.lock().unwrap();
+            let now = std::time::Instant::now();
+            entries.retain(|_, (created, _)| now.duration_since(*created) < self.ttl);
+            entries.entry(key).or_insert_with(|| (now, Arc::default())).1.clone()
+        };
+        cell.get_or_init(fetch).await.clone()
+    }
+}
+
+/// Everything that shapes an HTTP check's request and response. Hashed, so credentials
+/// are never held in the key itself.
+fn http_cache_key(monitor: &MonitorCheck, default_headers: &[(String, String)]) -> String {
+    let mut hasher = Sha256::new();
+    let timeout = monitor.timeout_ms.to_string();
+    let connect_timeout = monitor.connect_timeout().as_millis().to_string();
+    let max_redirects = monitor.max_redirects.to_string();
+    let parts = [
+        monitor.method.as_str(),
+        monitor.url.as_str(),
+        monitor.headers.as_deref().unwrap_or_default(),
+        monitor.auth_type.as_deref().unwrap_or_default(),
+        monitor.auth_username.as_deref().unwrap_or_default(),
+        monitor.auth_password.as_deref().unwrap_or_default(),
+        monitor.auth_token.as_deref().unwrap_or_default(),
+        monitor.client_cert_pem.as_deref().unwrap_or_default(),
+        monitor.client_key_pem.as_deref().unwrap_or_default(),
+        if monitor.insecure_skip_tls_verify { "insecure" } else { "verify" },
+        monitor.ca_bundle_pem.as_deref().unwrap_or_default(),
+        monitor.proxy_url.as_deref().unwrap_or_default(),
+        if monitor.follow_redirects { "follow" } else { "no-follow" },
+        max_redirects.as_str(),
+        timeout.as_str(),
+        connect_timeout.as_str(),
+    ];
+    for part in parts.into_iter().chain(default_headers.iter().flat_map(|(k, v)| [k.as_str(), v.as_str()])) {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Execute a multistep HTTP check: run each step in order, stopping at the
+/// first failure. Values extracted from earlier steps are substituted into
+/// later steps' URLs and header values wherever `{{name}}` appears.
+async fn execute_multistep_check(client: &reqwest::Client, policy: &TargetPolicy, monitor: &MonitorCheck, default_headers: &[(String, String)]) -> CheckResult {
+    let start = std::time::Instant::now();
+    let steps: Vec<CheckStep> = monitor.steps.as_deref()
+        .and_then(|s| serde_json::from_str(s).ok())
+        .unwrap_or_default();
+    let mut vars: HashMap<String, String> = HashMap::new();
+    let mut last_code = None;
+
+    let fail = |step: usize, code: Option<u16>, msg: String, kind: Option<FailureKind>| CheckResult {
+        status: "down".to_string(),
+        response_time_ms: start.elapsed().as_millis() as u32,
+        status_code: code,
+        error_message: Some(format!("Step {} failed: {}", step, msg)),
+        failure_kind: kind,
+        extra_sse_data: Some(serde_json::json!({"monitor_type": "multistep", "failed_step": step})),
+    };
+
+    if steps.is_empty() {
+        return fail(1, None, "no steps configured".to_string(), None);
+    }
+
+    for (i, step) in steps.iter().enumerate() {
+        let n = i + 1;
+        let url = substitute_vars(&step.url, &vars);
+        // Extracted values can change the host, so each step's final URL is vetted again
+        if let Some((host, port)) = check_target("http", &url) {
+            if let Some(reason) = policy.blocked_reason(&host, port).await {
+                return fail(n, None, format!("Blocked by target policy: {}", reason), None);
+            }
+        }
+        let method = reqwest::Method::from_bytes(step.method.to_uppercase().as_bytes())
+            .unwrap_or(reqwest::Method::GET);
+        let mut req = client.request(method, &url)
+            .timeout(Duration::from_millis(monitor.timeout_ms as u64));
+        req = with_default_headers(req, default_headers, step.headers.as_ref().and_then(|h| h.as_object()));
+        if let Some(obj) = step.headers.as_ref().and_then(|h| h.as_object()) {
+            for (k, v) in obj {
+                if let Some(val) = v.as_str() {
+                    req = req.header(k.as_str(), substitute_vars(val, &vars));
+                }
+            }
+        }
+        if let Some(ref body) = step.body {
+            req = req.body(substitute_vars(body, &vars));
+        }
+
+        let resp = match req.send().await {
+            Ok(r) => r,
+            Err(e) => {
+                let (msg, kind) = describe_request_error(&e, monitor.max_redirects);
+                return fail(n, None, msg, kind);
+            }
+        };
+        let code = resp.status().as_u16();
+        last_code = Some(code);
+        if code != step.expected_status {
+            return fail(n, Some(code), format!("Expected {}, got {}", step.expected_status, code), Some(FailureKind::Status));
+        }
+
+        if let Some(ref ex) = step.extract {
+            let value = if let Some(ref header) = ex.header {
+                resp.headers().get(header.as_str()).and_then(|v| v.to_str().ok()).map(|v| v.to_string())
+            } else if let Some(ref cookie) = ex.cookie {
+                find_cookie(resp.headers(), cookie)
             } else {
-                format!("Request failed: {}", e)
+                None
             };
-            ("down".to_string(), None, Some(msg))
+            match value {
+                Some(v) => { vars.insert(ex.name.clone(), v); }
+                None => {
+                    let what = ex.header.as_deref().map(|h| format!("header '{}'", h))
+                        .or_else(|| ex.cookie.as_deref().map(|c| format!("cookie '{}'", c)))
+                        .unwrap_or_default();
+                    return fail(n, Some(code), format!("{} not found in response", what), Some(FailureKind::Body));
+                }
+            }
         }
-    };
+    }
 
+    let elapsed_ms = start.elapsed().as_millis() as u32;
+    let (status, status_code, error_message) =
+        check_rt_threshold(monitor.response_time_threshold_ms, elapsed_ms, last_code.unwrap_or(0));
     CheckResult {
         status,
         response_time_ms: elapsed_ms,
         status_code,
         error_message,
-        extra_sse_data: None,
+        failure_kind: None,
+        extra_sse_data: Some(serde_json::json!({"monitor_type": "multistep", "steps_completed": steps.len()})),
+    }
+}
+
+/// Replace `{{name}}` placeholders with extracted step values.
+fn substitute_vars(input: &str, vars: &HashMap<String, String>) -> String {
+    let mut out = input.to_string();
+    for (k, v) in vars {
+        out = out.replace(&format!("{{{{{}}}}}", k), v);
+    }
+    out
+}
+
+/// Find a cookie's value in a response's Set-Cookie headers.
+fn find_cookie(headers: &reqwest::header::HeaderMap, name: &str) -> Option<String> {
+    headers.get_all(reqwest::header::SET_COOKIE).iter()
+        .filter_map(|v| v.to_str().ok())
+        .filter_map(|v| v.split(';').next()?.split_once('='))
+        .find(|(k, _)| k.trim() == name)
+        .map(|(_, v)| v.trim().to_string())
+}
+
+/// Response body read up to the read cap.
+#[derive(Debug, Clone)]
+struct CappedBody {
+    /// Body text (lossy UTF-8), truncated at the cap
+    text: String,
+    /// Body size in bytes: exact when known, otherwise the cap
+    size: u64,
+    /// False when the body ran past the cap without a Content-Length
+    size_exact: bool,
+}
+
+/// Read a response body, buffering at most `cap` bytes. Bodies that run past
+/// the cap are truncated rather than failing the check.
+async fn read_body_capped(mut resp: reqwest::Response, cap: usize) -> Result<CappedBody, reqwest::Error> {
+    let content_length = resp.content_length();
+    let mut buf: Vec<u8> = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = resp.chunk().await? {
+        let room = cap - buf.len();
+        if chunk.len() > room {
+            buf.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        buf.extend_from_slice(&chunk);
+    }
+    let (size, size_exact) = match (truncated, content_length) {
+        (false, _) => (buf.len() as u64, true),
+        (true, Some(len)) => (len, true),
+        (true, None) => (buf.len() as u64, false),
+    };
+    Ok(CappedBody { text: String::from_utf8_lossy(&buf).into_owned(), size, size_exact })
+}
+
+/// Helper: check the body size against `min_body_bytes` / `max_body_bytes`.
+fn body_size_error(body: &CappedBody, min: Option<u32>, max: Option<u32>) -> Option<String> {
+    if let Some(min) = min {
+        if body.size_exact && body.size < min as u64 {
+            return Some(format!("Body too small: {} bytes (min {})", body.size, min));
+        }
+    }
+    if let Some(max) = max {
+        // An inexact size is a lower bound: the real body is larger than the cap
+        if body.size > max as u64 || (!body.size_exact && body.size >= max as u64) {
+            let size = if body.size_exact { body.size.to_string() } else { format!("over {}", body.size) };
+            return Some(format!("Body too large: {} bytes (max {})", size, max));
+        }
+    }
+    None
+}
+
+/// Helper: check the body against `body_contains` / `body_not_contains`.
+/// Returns the failure message, or None if both assertions pass.
+fn body_assertion_error(body: &str, contains: &Option<String>, not_contains: &Option<String>) -> Option<String> {
+    if let Some(expected) = contains {
+        if !body.contains(expected.as_str()) {
+            return Some("Body match failed".to_string());
+        }
+    }
+    if let Some(forbidden) = not_contains {
+        if body.contains(forbidden.as_str()) {
+            return Some(format!("Body contains forbidden text '{}'", forbidden));
+        }
+    }
+    None
+}
+
+/// Helper: check the final (post-redirect) URL against `expected_final_url_prefix`.
+fn final_url_error(final_url: &str, prefix: &Option<String>) -> Option<String> {
+    match prefix {
+        Some(p) if !final_url.starts_with(p.as_str()) => {
+            Some(format!("Final URL '{}' does not start with '{}'", final_url, p))
+        }
+        _ => None,
     }
 }
 
+/// Message and failure kind for a request that got no response. Connect errors are
+/// told apart by their source chain: hyper's "dns error", a refused socket, or
+/// rustls rejecting the handshake (surfaced as InvalidData).
+fn describe_request_error(e: &reqwest::Error, max_redirects: u32) -> (String, Option<FailureKind>) {
+    use std::error::Error;
+    let mut source = e.source();
+    while let Some(err) = source {
+        if let Some(blocked) = err.downcast_ref::<TargetBlocked>() {
+            return (blocked.to_string(), None);
+        }
+        source = err.source();
+    }
+    if e.is_timeout() && e.is_connect() {
+        return ("Connect timed out".to_string(), Some(FailureKind::ConnectTimeout));
+    }
+    if e.is_timeout() {
+        return ("Request timed out".to_string(), Some(FailureKind::Timeout));
+    }
+    if e.is_redirect() {
+        return (format!("Too many redirects (max {})", max_redirects), None);
+    }
+    if !e.is_connect() {
+        return (format!("Request failed: {}", e), None);
+    }
+    let mut source = e.source();
+    let mut innermost = None;
+    while let Some(err) = source {
+        if err.to_string() == "dns error" {
+            return ("DNS lookup failed".to_string(), Some(FailureKind::Dns));
+        }
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            match io.kind() {
+                std::io::ErrorKind::ConnectionRefused => return ("Connection refused".to_string(), Some(FailureKind::ConnectionRefused)),
+                std::io::ErrorKind::InvalidData => return (format!("TLS handshake failed: {}", io), Some(FailureKind::Tls)),
+                _ => {}
+            }
+        }
+        innermost = Some(err);
+        source = err.source();
+    }
+    (format!("Connection failed: {}", innermost.map(|err| err.to_string()).unwrap_or_else(|| e.to_string())), None)
+}
+
 /// Helper: check response time against optional threshold.
 fn check_rt_threshold(threshold: Option<u32>, elapsed_ms: u32, code: u16) -> (String, Option<u16>, Option<String>) {
     if let Some(t) = threshold {
@@ -342,54 +1995,99 @@ async fn execute_tcp_check(monitor: &MonitorCheck) -> CheckResult {
     use tokio::net::TcpStream;
 
     let start = std::time::Instant::now();
+    let timeout = Duration::from_millis(monitor.timeout_ms as u64);
 
     // Parse host:port, stripping optional tcp:// prefix
     let addr_str = monitor.url.strip_prefix("tcp://").unwrap_or(&monitor.url);
 
-    let result = tokio::time::timeout(
-        Duration::from_millis(monitor.timeout_ms as u64),
-        TcpStream::connect(addr_str),
-    ).await;
+    let result = tokio::time::timeout(monitor.connect_timeout(), TcpStream::connect(addr_str)).await;
 
-    let elapsed_ms = start.elapsed().as_millis() as u32;
-    let rt_threshold = monitor.response_time_threshold_ms;
-
-    let (status, error_message) = match result {
-        Ok(Ok(_stream)) => {
-            if let Some(threshold) = rt_threshold {
-                if elapsed_ms > threshold {
-                    ("degraded".to_string(), Some(format!("TCP connect time {}ms exceeds {}ms threshold", elapsed_ms, threshold)))
-                } else {
-                    ("up".to_string(), None)
-                }
+    let probed = monitor.tcp_send.is_some() || monitor.tcp_expect.is_some();
+    let (status, error_message, failure_kind) = match result {
+        Ok(Ok(stream)) => {
+            let probe_error = if probed {
+                tcp_probe(stream, monitor.tcp_send.as_deref(), monitor.tcp_expect.as_deref(), timeout.saturating_sub(start.elapsed())).await.err()
             } else {
-                ("up".to_string(), None)
+                None
+            };
+            let elapsed_ms = start.elapsed().as_millis() as u32;
+            match (probe_error, monitor.response_time_threshold_ms) {
+                (Some((msg, kind)), _) => ("down".to_string(), Some(msg), kind),
+                (None, Some(threshold)) if elapsed_ms > threshold => {
+                    let what = if probed { "TCP response time" } else { "TCP connect time" };
+                    ("degraded".to_string(), Some(format!("{} {}ms exceeds {}ms threshold", what, elapsed_ms, threshold)), None)
+                }
+                (None, _) => ("up".to_string(), None, None),
             }
         }
         Ok(Err(e)) => {
-            let msg = if e.kind() == std::io::ErrorKind::ConnectionRefused {
-                "Connection refused".to_string()
+            let (msg, kind) = if e.kind() == std::io::ErrorKind::ConnectionRefused {
+                ("Connection refused".to_string(), Some(FailureKind::ConnectionRefused))
+            } else if e.to_string().contains("failed to lookup address") {
+                (format!("TCP connect failed: {}", e), Some(FailureKind::Dns))
             } else {
-                format!("TCP connect failed: {}", e)
+                (format!("TCP connect failed: {}", e), None)
             };
-            ("down".to_string(), Some(msg))
+            ("down".to_string(), Some(msg), kind)
         }
         Err(_) => {
-            ("down".to_string(), Some("TCP connect timed out".to_string()))
+            ("down".to_string(), Some("TCP connect timed out".to_string()), Some(FailureKind::ConnectTimeout))
         }
     };
+    let elapsed_ms = start.elapsed().as_millis() as u32;
 
     CheckResult {
         status,
         response_time_ms: elapsed_ms,
         status_code: None,
         error_message,
+        failure_kind,
         extra_sse_data: Some(serde_json::json!({"monitor_type": "tcp"})),
     }
 }
 
-/// Execute a DNS resolution check.
-async fn execute_dns_check(monitor: &MonitorCheck) -> CheckResult {
+/// Most bytes read from a TCP peer while waiting for `tcp_expect`.
+const TCP_PROBE_READ_CAP: usize = 64 * 1024;
+
+/// Write `send` (if any), then read until the response contains `expect`,
+/// the peer closes, or `timeout` runs out. Returns the failure message and kind.
+async fn tcp_probe(mut stream: tokio::net::TcpStream, send: Option<&str>, expect: Option<&str>, timeout: Duration) -> Result<(), (String, Option<FailureKind>)> {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let exchange = async {
+        if let Some(send) = send {
+            stream.write_all(send.as_bytes()).await.map_err(|e| (format!("TCP send failed: {}", e), None))?;
+        }
+        let Some(expect) = expect else { return Ok(()) };
+        let mut buf = Vec::new();
+        let mut chunk = [0u8; 4096];
+        loop {
+            if String::from_utf8_lossy(&buf).contains(expect) {
+                return Ok(());
+            }
+            if buf.len() >= TCP_PROBE_READ_CAP {
+                break;
+            }
+            match stream.read(&mut chunk).await {
+                Ok(0) => break,
+                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+                Err(e) => return Err((format!("TCP read failed: {}", e), None)),
+            }
+        }
+        let got: String = String::from_utf8_lossy(&buf).chars().take(200).collect();
+        Err((format!("Expected '{}' in response, got: '{}'", expect, got.trim_end()), Some(FailureKind::Body)))
+    };
+    match tokio::time::timeout(timeout, exchange).await {
+        Ok(result) => result,
+        Err(_) => Err((match expect {
+            Some(expect) => format!("Timed out waiting for '{}'", expect),
+            None => "TCP send timed out".to_string(),
+        }, Some(FailureKind::Timeout))),
+    }
+}
+
+/// Execute a DNS resolution check, over plain DNS or the monitor's DoH endpoint.
+async fn execute_dns_check(http_client: &reqwest::Client, monitor: &MonitorCheck) -> CheckResult {
     use hickory_resolver::TokioAsyncResolver;
     use hickory_resolver::config::{ResolverConfig, ResolverOpts};
 
@@ -398,80 +2096,110 @@ async fn execute_dns_check(monitor: &MonitorCheck) -> CheckResult {
     // Strip optional dns:// prefix
     let hostname = monitor.url.strip_prefix("dns://").unwrap_or(&monitor.url);
 
-    let resolver = TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
-
     let record_type = monitor.dns_record_type.to_uppercase();
-    let result = tokio::time::timeout(
-        Duration::from_millis(monitor.timeout_ms as u64),
-        dns_lookup(&resolver, hostname, &record_type),
-    ).await;
+    let timeout = Duration::from_millis(monitor.timeout_ms as u64);
+    let result = match doh_endpoint(monitor.dns_resolver.as_deref()) {
+        Some(endpoint) => tokio::time::timeout(timeout, doh_lookup(http_client, endpoint, hostname, &record_type)).await,
+        None => {
+            let resolver = TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default());
+            tokio::time::timeout(timeout, dns_lookup(&resolver, hostname, &record_type)).await
+        }
+    };
 
     let elapsed_ms = start.elapsed().as_millis() as u32;
-    let rt_threshold = monitor.response_time_threshold_ms;
-
-    let (status, error_message, resolved_values) = match result {
-        Ok(Ok(values)) => {
-            if values.is_empty() {
-                ("down".to_string(), Some(format!("No {} records found for {}", record_type, hostname)), None)
-            } else if let Some(ref expected) = monitor.dns_expected {
-                let expected_lower = expected.to_lowercase();
-                let matched = values.iter().any(|v| {
-                    v.to_lowercase() == expected_lower
-                        || v.to_lowercase().trim_end_matches('.') == expected_lower.trim_end_matches('.')
-                });
-                if matched {
-                    if let Some(threshold) = rt_threshold {
-                        if elapsed_ms > threshold {
-                            ("degraded".to_string(), Some(format!("DNS resolution time {}ms exceeds {}ms threshold", elapsed_ms, threshold)), Some(values))
-                        } else {
-                            ("up".to_string(), None, Some(values))
-                        }
-                    } else {
-                        ("up".to_string(), None, Some(values))
-                    }
-                } else {
-                    ("down".to_string(), Some(format!("Expected '{}', got: {}", expected, values.join(", "))), Some(values))
-                }
-            } else {
-                // No expected value — just check that resolution succeeds
-                if let Some(threshold) = rt_threshold {
-                    if elapsed_ms > threshold {
-                        ("degraded".to_string(), Some(format!("DNS resolution time {}ms exceeds {}ms threshold", elapsed_ms, threshold)), Some(values))
-                    } else {
-                        ("up".to_string(), None, Some(values))
-                    }
-                } else {
-                    ("up".to_string(), None, Some(values))
-                }
-            }
+
+    // Every down answer from a DNS check (no records, wrong records) counts as a DNS failure
+    let (status, error_message, failure_kind, resolved_values) = match result {
+        Ok(Ok(answer)) => {
+            let (status, error_message) = evaluate_dns_answer(monitor, hostname, &record_type, &answer, elapsed_ms);
+            let kind = (status == "down").then_some(FailureKind::Dns);
+            (status, error_message, kind, Some(answer.values))
         }
         Ok(Err(e)) => {
-            ("down".to_string(), Some(format!("DNS lookup failed: {}", e)), None)
+            ("down".to_string(), Some(format!("DNS lookup failed: {}", e)), Some(FailureKind::Dns), None)
         }
         Err(_) => {
-            ("down".to_string(), Some("DNS lookup timed out".to_string()), None)
+            ("down".to_string(), Some("DNS lookup timed out".to_string()), Some(FailureKind::Timeout), None)
+        }
+    };
+
+    CheckResult {
+        status,
+        response_time_ms: elapsed_ms,
+        status_code: None,
+        error_message,
+        failure_kind,
+        extra_sse_data: Some(serde_json::json!({
+            "monitor_type": "dns",
+            "dns_record_type": monitor.dns_record_type,
+            "dns_resolver": monitor.dns_resolver.as_deref().unwrap_or("system"),
+            "resolved_values": resolved_values.unwrap_or_default(),
+        })),
+    }
+}
+
+/// Judge a successful DNS answer against the monitor's assertions. Missing
+/// records, too few records, or an unexpected value are down; a TTL below
+/// `dns_min_ttl` or a slow lookup is degraded.
+fn evaluate_dns_answer(monitor: &MonitorCheck, hostname: &str, record_type: &str, answer: &DnsAnswer, elapsed_ms: u32) -> (String, Option<String>) {
+    let values = &answer.values;
+    if values.is_empty() {
+        return ("down".to_string(), Some(format!("No {} records found for {}", record_type, hostname)));
+    }
+    if let Some(min) = monitor.dns_min_records {
+        if (values.len() as u32) < min {
+            return ("down".to_string(), Some(format!("Expected at least {} {} records, got {}", min, record_type, values.len())));
+        }
+    }
+    if let Some(ref expected) = monitor.dns_expected {
+        let expected_lower = expected.to_lowercase();
+        let matched = values.iter().any(|v| {
+            v.to_lowercase() == expected_lower
+                || v.to_lowercase().trim_end_matches('.') == expected_lower.trim_end_matches('.')
+        });
+        if !matched {
+            return ("down".to_string(), Some(format!("Expected '{}', got: {}", expected, values.join(", "))));
+        }
+    }
+    if let (Some(floor), Some(ttl)) = (monitor.dns_min_ttl, answer.min_ttl) {
+        if ttl < floor {
+            return ("degraded".to_string(), Some(format!("{} record TTL {}s is below the {}s minimum", record_type, ttl, floor)));
+        }
+    }
+    if let Some(threshold) = monitor.response_time_threshold_ms {
+        if elapsed_ms > threshold {
+            return ("degraded".to_string(), Some(format!("DNS resolution time {}ms exceeds {}ms threshold", elapsed_ms, threshold)));
         }
-    };
+    }
+    ("up".to_string(), None)
+}
 
-    CheckResult {
-        status,
-        response_time_ms: elapsed_ms,
-        status_code: None,
-        error_message,
-        extra_sse_data: Some(serde_json::json!({
-            "monitor_type": "dns",
-            "dns_record_type": monitor.dns_record_type,
-            "resolved_values": resolved_values.unwrap_or_default(),
-        })),
+/// Records returned by a DNS lookup.
+struct DnsAnswer {
+    values: Vec<String>,
+    /// Lowest TTL among the answer records of the queried type. This is the
+    /// TTL as the resolver returned it, so a caching resolver reports the
+    /// time remaining rather than the zone's configured value.
+    min_ttl: Option<u32>,
+}
+
+impl DnsAnswer {
+    fn new(values: Vec<String>, lookup: &hickory_resolver::lookup::Lookup, rtype: hickory_resolver::proto::rr::RecordType) -> Self {
+        let min_ttl = lookup.records().iter()
+            .filter(|r| r.record_type() == rtype)
+            .map(|r| r.ttl())
+            .min();
+        DnsAnswer { values, min_ttl }
     }
 }
 
-/// Perform DNS lookup for a specific record type, returning resolved values as strings.
+/// Perform DNS lookup for a specific record type, returning resolved values as
+/// strings along with their TTL.
 async fn dns_lookup(
     resolver: &hickory_resolver::TokioAsyncResolver,
     hostname: &str,
     record_type: &str,
-) -> Result<Vec<String>, String> {
+) -> Result<DnsAnswer, String> {
     use hickory_resolver::proto::rr::RecordType;
     use hickory_resolver::Name;
 
@@ -480,27 +2208,28 @@ async fn dns_lookup(
     match record_type {
         "A" => {
             let response = resolver.ipv4_lookup(name.clone()).await.map_err(|e| e.to_string())?;
-            Ok(response.iter().map(|ip| ip.to_string()).collect())
+            Ok(DnsAnswer::new(response.iter().map(|ip| ip.to_string()).collect(), response.as_lookup(), RecordType::A))
         }
         "AAAA" => {
             let response = resolver.ipv6_lookup(name.clone()).await.map_err(|e| e.to_string())?;
-            Ok(response.iter().map(|ip| ip.to_string()).collect())
+            Ok(DnsAnswer::new(response.iter().map(|ip| ip.to_string()).collect(), response.as_lookup(), RecordType::AAAA))
         }
         "MX" => {
             let response = resolver.mx_lookup(name.clone()).await.map_err(|e| e.to_string())?;
-            Ok(response.iter().map(|mx| format!("{} {}", mx.preference(), mx.exchange())).collect())
+            Ok(DnsAnswer::new(response.iter().map(|mx| format!("{} {}", mx.preference(), mx.exchange())).collect(), response.as_lookup(), RecordType::MX))
         }
         "TXT" => {
             let response = resolver.txt_lookup(name.clone()).await.map_err(|e| e.to_string())?;
-            Ok(response.iter().map(|txt| txt.to_string()).collect())
+            Ok(DnsAnswer::new(response.iter().map(|txt| txt.to_string()).collect(), response.as_lookup(), RecordType::TXT))
         }
         "NS" => {
             let response = resolver.ns_lookup(name.clone()).await.map_err(|e| e.to_string())?;
-            Ok(response.iter().map(|ns| ns.to_string()).collect())
+            Ok(DnsAnswer::new(response.iter().map(|ns| ns.to_string()).collect(), response.as_lookup(), RecordType::NS))
         }
         "SOA" => {
             let response = resolver.soa_lookup(name.clone()).await.map_err(|e| e.to_string())?;
-            Ok(response.iter().map(|soa| format!("{} {} {} {} {} {} {}", soa.mname(), soa.rname(), soa.serial(), soa.refresh(), soa.retry(), soa.expire(), soa.minimum())).collect())
+            let values = response.iter().map(|soa| format!("{} {} {} {} {} {} {}", soa.mname(), soa.rname(), soa.serial(), soa.refresh(), soa.retry(), soa.expire(), soa.minimum())).collect();
+            Ok(DnsAnswer::new(values, response.as_lookup(), RecordType::SOA))
         }
         "CNAME" | "PTR" | "SRV" | "CAA" => {
             let rtype = match record_type {
@@ -511,15 +2240,70 @@ async fn dns_lookup(
                 _ => unreachable!(),
             };
             let response = resolver.lookup(name, rtype).await.map_err(|e| e.to_string())?;
-            Ok(response.iter().map(|r| r.to_string()).collect())
+            Ok(DnsAnswer::new(response.iter().map(|r| r.to_string()).collect(), &response, rtype))
         }
         _ => Err(format!("Unsupported record type: {}", record_type)),
     }
 }
 
+/// DoH endpoint for a monitor's `dns_resolver`: presets map to their URL,
+/// None means the system resolver.
+fn doh_endpoint(dns_resolver: Option<&str>) -> Option<&str> {
+    let resolver = dns_resolver?;
+    let preset = crate::routes::DOH_PRESETS.iter().find(|(name, _)| *name == resolver);
+    Some(preset.map_or(resolver, |(_, url)| *url))
+}
+
+/// Resolve over DNS-over-HTTPS using the JSON API (`application/dns-json`)
+/// that Cloudflare, Google and most public DoH providers serve.
+async fn doh_lookup(client: &reqwest::Client, endpoint: &str, hostname: &str, record_type: &str) -> Result<DnsAnswer, String> {
+    let mut url = reqwest::Url::parse(endpoint).map_err(|e| format!("Invalid DoH endpoint: {}", e))?;
+    url.query_pairs_mut().append_pair("name", hostname).append_pair("type", record_type);
+    let resp = client.get(url)
+        .header(reqwest::header::ACCEPT, "application/dns-json")
+        .send().await
+        .map_err(|e| format!("DoH request failed: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("DoH endpoint returned HTTP {}", resp.status().as_u16()));
+    }
+    let body: serde_json::Value = resp.json().await.map_err(|e| format!("Invalid DoH response: {}", e))?;
+    parse_doh_answer(&body, record_type)
+}
+
+/// Turn a DoH JSON response into a `DnsAnswer`, keeping only records of the
+/// queried type (a CNAME chain is also listed in `Answer`).
+fn parse_doh_answer(body: &serde_json::Value, record_type: &str) -> Result<DnsAnswer, String> {
+    use hickory_resolver::proto::rr::RecordType;
+    use std::str::FromStr;
+
+    let rtype = RecordType::from_str(record_type).map_err(|_| format!("Unsupported record type: {}", record_type))?;
+    match body["Status"].as_u64() {
+        Some(0) => {}
+        Some(3) => return Err("NXDOMAIN".to_string()),
+        Some(code) => return Err(format!("DoH lookup failed with rcode {}", code)),
+        None => return Err("Invalid DoH response: missing Status".to_string()),
+    }
+    let records: Vec<(String, u32)> = body["Answer"].as_array().map(|answers| {
+        answers.iter()
+            .filter(|a| a["type"].as_u64() == Some(u16::from(rtype) as u64))
+            .filter_map(|a| {
+                let data = a["data"].as_str()?;
+                // TXT data is quoted in the JSON API; plain lookups return it bare
+                let data = if rtype == RecordType::TXT { data.trim_matches('"') } else { data };
+                Some((data.to_string(), a["TTL"].as_u64().unwrap_or(0) as u32))
+            })
+            .collect()
+    }).unwrap_or_default();
+    Ok(DnsAnswer {
+        min_ttl: records.iter().map(|(_, ttl)| *ttl).min(),
+        values: records.into_iter().map(|(data, _)| data).collect(),
+    })
+}
+
 // ─── Heartbeat-Only Processing (for consensus-enabled monitors) ─────────────
 //
-// Writes the heartbeat and updates last_checked_at + consecutive_failures,
+// Updates last_checked_at + the consecutive counters (the heartbeat itself goes
+// through the caller's `HeartbeatBuffer`),
 // but does NOT process incident lifecycle or update current_status.
 // The consensus evaluator handles status transitions separately.
 
@@ -528,32 +2312,23 @@ async fn process_check_result_heartbeat_only(
     broadcaster: &EventBroadcaster,
     monitor: &MonitorCheck,
     result: CheckResult,
-) {
-    {
+) -> (u32, u32) {
+    let (new_failures, new_successes) = {
         let conn = db.conn();
 
-        // Write heartbeat
-        let hb_id = uuid::Uuid::new_v4().to_string();
-        let hb_seq: i64 = conn
-            .query_row("SELECT COALESCE(MAX(seq), 0) + 1 FROM heartbeats", [], |r| r.get(0))
-            .unwrap_or(1);
-        let _ = conn.execute(
-            "INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, status_code, error_message, seq) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![hb_id, monitor.id, result.status, result.response_time_ms, result.status_code, result.error_message, hb_seq],
-        );
-
-        // Update consecutive failures + last_checked_at (but NOT current_status — consensus handles that)
-        let new_consecutive = if result.status == "down" {
-            monitor.consecutive_failures + 1
+        // Update consecutive counters + last_checked_at (but NOT current_status — consensus handles that)
+        let (new_failures, new_successes) = if result.status == "down" {
+            (monitor.consecutive_failures + 1, 0)
         } else {
-            0
+            (0, monitor.consecutive_successes + 1)
         };
 
         let _ = conn.execute(
-            "UPDATE monitors SET last_checked_at = datetime('now'), consecutive_failures = ?1, updated_at = datetime('now') WHERE id = ?2",
-            params![new_consecutive, monitor.id],
+            "UPDATE monitors SET last_checked_at = datetime('now'), consecutive_failures = ?1, consecutive_successes = ?2, updated_at = datetime('now') WHERE id = ?3",
+            params![new_failures, new_successes, monitor.id],
         );
-    }
+        (new_failures, new_successes)
+    };
 
     // Emit check.completed SSE event
     let mut sse_data = serde_json::json!({
@@ -563,6 +2338,9 @@ async fn process_check_result_heartbeat_only(
     if let Some(code) = result.status_code {
         sse_data["status_code"] = serde_json::json!(code);
     }
+    if let Some(kind) = result.failure_kind {
+        sse_data["failure_kind"] = kind.as_str().into();
+    }
     if let Some(extra) = result.extra_sse_data {
         if let (Some(base), Some(ext)) = (sse_data.as_object_mut(), extra.as_object()) {
             for (k, v) in ext {
@@ -575,12 +2353,15 @@ async fn process_check_result_heartbeat_only(
         monitor_id: monitor.id.clone(),
         data: sse_data,
     });
+    (new_failures, new_successes)
 }
 
 // ─── Shared Check Result Processing ─────────────────────────────────────────
 //
-// This is the single place where heartbeats, incident lifecycle, status
-// transitions, and notification dispatch happen — regardless of check type.
+// This is the single place where incident lifecycle, status transitions, and
+// notification dispatch happen — regardless of check type. Heartbeats are
+// buffered by the caller and written in batches. Returns the updated
+// (consecutive failures, consecutive successes).
 
 async fn process_check_result(
     db: &Db,
@@ -588,37 +2369,35 @@ async fn process_check_result(
     http_client: &reqwest::Client,
     monitor: &MonitorCheck,
     result: CheckResult,
-) {
+) -> (u32, u32) {
     // Check maintenance window status BEFORE acquiring DB lock
     let in_maintenance = is_in_maintenance(db, &monitor.id);
 
     let webhook_event: Option<WebhookPayload>;
+    let counters: (u32, u32);
 
     {
         // ── Scoped DB lock ──────────────────────────────────────────────
         let conn = db.conn();
 
-        // Write heartbeat
-        let hb_id = uuid::Uuid::new_v4().to_string();
-        let hb_seq: i64 = conn
-            .query_row("SELECT COALESCE(MAX(seq), 0) + 1 FROM heartbeats", [], |r| r.get(0))
-            .unwrap_or(1);
-        let _ = conn.execute(
-            "INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, status_code, error_message, seq) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-            params![hb_id, monitor.id, result.status, result.response_time_ms, result.status_code, result.error_message, hb_seq],
-        );
-
-        // Update consecutive failures and determine effective status
-        let (new_consecutive, mut effective_status) = if result.status == "down" {
+        // Update the consecutive counters and determine effective status.
+        // Going down and coming back up are confirmed separately.
+        let (new_failures, new_successes, mut effective_status) = if result.status == "down" {
             let new_count = monitor.consecutive_failures + 1;
-            if new_count >= monitor.confirmation_threshold {
-                (new_count, "down".to_string())
+            if new_count >= monitor.down_confirmation_threshold {
+                (new_count, 0, "down".to_string())
             } else {
                 // Not yet confirmed — keep previous status
-                (new_count, monitor.current_status.clone())
+                (new_count, 0, monitor.current_status.clone())
             }
         } else {
-            (0, result.status.clone())
+            let new_count = monitor.consecutive_successes + 1;
+            if monitor.current_status == "down" && new_count < monitor.up_confirmation_threshold {
+                // Recovery not yet confirmed — stay down
+                (0, new_count, monitor.current_status.clone())
+            } else {
+                (0, new_count, result.status.clone())
+            }
         };
 
         // If in maintenance window and would be "down", set to "maintenance" instead
@@ -626,70 +2405,102 @@ async fn process_check_result(
             effective_status = "maintenance".to_string();
         }
 
-        // ── Dependency chain suppression ────────────────────────────────
-        // Check if any upstream dependency is currently down.
-        // If so, suppress incident creation but still record the heartbeat
-        // and update status honestly.
-        let deps_suppressed = if effective_status == "down" {
-            crate::routes::has_dependency_down(&conn, &monitor.id)
+        // ── Dependency rollup ───────────────────────────────────────────
+        // If an upstream dependency (direct or transitive) is down, this
+        // outage is attributed to it rather than treated as independent.
+        let down_dependency = if effective_status == "down" {
+            crate::routes::find_down_dependency(&conn, &monitor.id)
         } else {
-            false
+            None
         };
 
-        // Persist status + failure counter
+        // Persist status + counters
         let _ = conn.execute(
-            "UPDATE monitors SET current_status = ?1, last_checked_at = datetime('now'), consecutive_failures = ?2, updated_at = datetime('now') WHERE id = ?3",
-            params![effective_status, new_consecutive, monitor.id],
+            "UPDATE monitors SET current_status = ?1, last_checked_at = datetime('now'), consecutive_failures = ?2, consecutive_successes = ?3, updated_at = datetime('now') WHERE id = ?4",
+            params![effective_status, new_failures, new_successes, monitor.id],
         );
+        counters = (new_failures, new_successes);
 
         // ── Incident lifecycle ──────────────────────────────────────────
         let prev = &monitor.current_status;
         let now_str = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-
-        if deps_suppressed {
-            // Dependency is down — suppress incident creation and notifications.
-            // Heartbeat is already recorded; status is updated honestly.
+        let open_incident = open_incident_attribution(&conn, &monitor.id);
+        let suppress = suppress_dependent_alerts();
+        // A dependency-attributed incident was never announced when
+        // suppression is on, so don't announce its resolution either.
+        let dependency_caused = matches!(open_incident, Some((_, Some(_))));
+
+        // ── Recovery cooldown ───────────────────────────────────────────
+        // With a cooldown, a recovery is held back: the monitor shows up,
+        // but its incident stays open (and nobody is notified) until it has
+        // stayed up for the whole cooldown. Going down again in the meantime
+        // just continues the same incident.
+        let cooldown = monitor.recovery_cooldown_seconds.filter(|&s| s > 0);
+        let pending_since = monitor.recovery_pending_since.as_deref();
+
+        if pending_since.is_some() && effective_status == "down" {
+            set_recovery_pending(&conn, &monitor.id, false);
             webhook_event = None;
-        } else {
-            // Check for delayed incident: monitor was previously down with
-            // suppressed deps, now deps recovered but monitor is still down.
-            // If there's no open incident, create one now.
-            let need_delayed_incident = prev == "down" && effective_status == "down"
-                && !crate::routes::has_open_incident(&conn, &monitor.id);
-
-            if need_delayed_incident {
-                // Create the incident now that dependencies are healthy
-                let inc_id = uuid::Uuid::new_v4().to_string();
-                let cause = result.error_message.clone()
-                    .unwrap_or_else(|| "Monitor is down (dependency recovered)".to_string());
-                let inc_seq: i64 = conn
-                    .query_row("SELECT COALESCE(MAX(seq), 0) + 1 FROM incidents", [], |r| r.get(0))
-                    .unwrap_or(1);
-                let _ = conn.execute(
-                    "INSERT INTO incidents (id, monitor_id, cause, seq) VALUES (?1, ?2, ?3, ?4)",
-                    params![inc_id, monitor.id, cause, inc_seq],
-                );
-                webhook_event = Some(WebhookPayload {
-                    event: "incident.created".to_string(),
-                    monitor: WebhookMonitor {
-                        id: monitor.id.clone(),
-                        name: monitor.name.clone(),
-                        url: monitor.url.clone(),
-                        current_status: "down".to_string(),
-                    },
-                    incident: Some(WebhookIncident {
-                        id: inc_id,
-                        cause,
-                        started_at: now_str.clone(),
-                        resolved_at: None,
-                    }),
-                    timestamp: now_str,
-                });
+        } else if let Some(since) = pending_since.filter(|since| {
+            result.status != "down"
+                && seconds_since(since) >= cooldown.map_or(0, i64::from)
+        }) {
+            set_recovery_pending(&conn, &monitor.id, false);
+            let event = resolve_recovered_incidents(&conn, monitor, &effective_status, Some(since), &now_str);
+            webhook_event = event.filter(|_| !(suppress && dependency_caused));
+        } else if cooldown.is_some() && pending_since.is_none() && prev == "down"
+            && effective_status == "up" && open_incident.is_some()
+        {
+            set_recovery_pending(&conn, &monitor.id, true);
+            webhook_event = None;
+        } else if let Some((dep_id, dep_name)) = down_dependency {
+            // Open a dependency-attributed incident if there isn't one already.
+            if open_incident.is_none() {
+                let cause = incident_cause(&conn, &Cause::Dependency { name: &dep_name });
+                let inc_id = insert_incident(&conn, &monitor.id, &cause, Some(&dep_id));
+                let payload = incident_created_payload(monitor, inc_id, cause, now_str);
+                webhook_event = if suppress { None } else { Some(payload) };
             } else {
-                webhook_event = resolve_transition(
-                    &conn, monitor, prev, &effective_status, &result.error_message, &now_str,
-                );
+                webhook_event = None;
+            }
+        } else if prev == "down" && effective_status == "down" {
+            match open_incident {
+                // Dependency recovered but this monitor is still down: the
+                // outage is now independent. Re-attribute it and alert if
+                // the original notification was suppressed.
+                Some((inc_id, Some(_))) => {
+                    let cause = incident_cause(&conn, &Cause::Check {
+                        error: Some(result.error_message.as_deref().unwrap_or("Monitor is down (dependency recovered)")),
+                        status_code: result.status_code,
+                        kind: result.failure_kind,
+                    });
+                    let _ = conn.execute(
+                        "UPDATE incidents SET cause = ?1, caused_by_monitor_id = NULL WHERE id = ?2",
+                        params![cause, inc_id],
+                    );
+                    webhook_event = suppress.then(|| incident_created_payload(monitor, inc_id, cause, now_str));
+                }
+                // Still down with no open incident (e.g. closed manually): open one now.
+                // An unconfirmed recovery check doesn't count as still down.
+                None if result.status == "down" => {
+                    let cause = incident_cause(&conn, &Cause::Check {
+                        error: Some(result.error_message.as_deref().unwrap_or("Monitor is down (dependency recovered)")),
+                        status_code: result.status_code,
+                        kind: result.failure_kind,
+                    });
+                    let inc_id = insert_incident(&conn, &monitor.id, &cause, None);
+                    webhook_event = Some(incident_created_payload(monitor, inc_id, cause, now_str));
+                }
+                _ => webhook_event = None,
             }
+        } else {
+            let event = resolve_transition(
+                &conn, monitor, prev, &effective_status, &result, &now_str,
+            );
+            webhook_event = match event {
+                Some(p) if suppress && dependency_caused && p.event == "incident.resolved" => None,
+                other => other,
+            };
         }
     } // DB lock released
 
@@ -703,17 +2514,22 @@ async fn process_check_result(
         });
 
         // Webhooks
-        let channels = notifications::get_webhook_channels(db, &monitor.id);
+        let severity = notifications::payload_severity(db, payload);
+        let channels = notifications::get_webhook_channels(db, &monitor.id, severity);
         if !channels.is_empty() {
-            notifications::fire_webhooks(db, http_client, &monitor.id, &channels, payload).await;
+            notifications::fire_webhooks(db, &monitor.id, &channels, payload).await;
         }
+        notifications::fire_integrations(db, http_client, &monitor.id, payload).await;
 
         // Emails
-        let emails = notifications::get_email_addresses(db, &monitor.id);
+        let emails = notifications::get_email_addresses(db, &monitor.id, severity);
         if !emails.is_empty() {
             notifications::fire_emails(&emails, payload).await;
         }
 
+        // Status page subscribers
+        notifications::notify_page_subscribers(db, &monitor.id, payload).await;
+
         // Log to alert_log
         log_alert(db, &monitor.id, payload, "initial");
     }
@@ -721,6 +2537,15 @@ async fn process_check_result(
     // ── Repeat notifications (alert rules) ───────────────────────────────
     process_repeat_notifications(db, monitor, http_client, broadcaster).await;
 
+    // ── Latency alert rule ───────────────────────────────────────────────
+    process_latency_alert(db, monitor, http_client, broadcaster).await;
+
+    // ── SLA alert rule ───────────────────────────────────────────────────
+    process_sla_alert(db, monitor, http_client, broadcaster).await;
+
+    // ── Burn-rate alert rule ─────────────────────────────────────────────
+    process_burn_rate_alert(db, monitor, http_client, broadcaster).await;
+
     // Always emit check.completed SSE event
     let mut sse_data = serde_json::json!({
         "status": result.status,
@@ -731,6 +2556,9 @@ async fn process_check_result(
     if let Some(code) = result.status_code {
         sse_data["status_code"] = serde_json::json!(code);
     }
+    if let Some(kind) = result.failure_kind {
+        sse_data["failure_kind"] = kind.as_str().into();
+    }
 
     // Merge type-specific extra data
     if let Some(extra) = result.extra_sse_data {
@@ -746,6 +2574,60 @@ async fn process_check_result(
         monitor_id: monitor.id.clone(),
         data: sse_data,
     });
+    counters
+}
+
+/// Whether notifications for outages attributed to a down dependency are suppressed.
+/// Configurable via DEPENDENCY_SUPPRESS_ALERTS env var. Default: true.
+fn suppress_dependent_alerts() -> bool {
+    std::env::var("DEPENDENCY_SUPPRESS_ALERTS")
+        .map(|v| !matches!(v.to_lowercase().as_str(), "0" | "false" | "no" | "off"))
+        .unwrap_or(true)
+}
+
+/// The monitor's open check-driven incident, if any, with the dependency it's
+/// attributed to. Manual incidents are tracked independently of check results.
+fn open_incident_attribution(conn: &rusqlite::Connection, monitor_id: &str) -> Option<(String, Option<String>)> {
+    conn.query_row(
+        "SELECT id, caused_by_monitor_id FROM incidents WHERE monitor_id = ?1 AND resolved_at IS NULL AND source = 'check'
+         ORDER BY started_at DESC LIMIT 1",
+        params![monitor_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).ok()
+}
+
+/// Insert a new open incident and return its id.
+fn insert_incident(conn: &rusqlite::Connection, monitor_id: &str, cause: &str, caused_by: Option<&str>) -> String {
+    let inc_id = uuid::Uuid::new_v4().to_string();
+    let inc_seq: i64 = conn
+        .query_row("SELECT COALESCE(MAX(seq), 0) + 1 FROM incidents", [], |r| r.get(0))
+        .unwrap_or(1);
+    let _ = conn.execute(
+        "INSERT INTO incidents (id, monitor_id, cause, seq, caused_by_monitor_id) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![inc_id, monitor_id, cause, inc_seq, caused_by],
+    );
+    inc_id
+}
+
+fn incident_created_payload(monitor: &MonitorCheck, inc_id: String, cause: String, now_str: String) -> WebhookPayload {
+    WebhookPayload {
+        event: "incident.created".to_string(),
+        monitor: WebhookMonitor {
+            id: monitor.id.clone(),
+            name: monitor.name.clone(),
+            url: monitor.url.clone(),
+            current_status: "down".to_string(),
+            runbook_url: monitor.runbook_url.clone(),
+        },
+        incident: Some(WebhookIncident {
+            id: inc_id,
+            cause,
+            started_at: now_str.clone(),
+            resolved_at: None,
+        }),
+        maintenance: None,
+        timestamp: now_str,
+    }
 }
 
 /// Determine which status transition occurred and produce the appropriate
@@ -757,7 +2639,7 @@ fn resolve_transition(
     monitor: &MonitorCheck,
     prev: &str,
     effective: &str,
-    error_message: &Option<String>,
+    result: &CheckResult,
     now_str: &str,
 ) -> Option<WebhookPayload> {
     let mk_monitor = |status: &str| WebhookMonitor {
@@ -765,19 +2647,17 @@ fn resolve_transition(
         name: monitor.name.clone(),
         url: monitor.url.clone(),
         current_status: status.to_string(),
+        runbook_url: monitor.runbook_url.clone(),
     };
 
     // Transition: → down (new incident)
     if prev != "down" && prev != "maintenance" && effective == "down" {
-        let inc_id = uuid::Uuid::new_v4().to_string();
-        let cause = error_message.clone().unwrap_or_else(|| "Monitor is down".to_string());
-        let inc_seq: i64 = conn
-            .query_row("SELECT COALESCE(MAX(seq), 0) + 1 FROM incidents", [], |r| r.get(0))
-            .unwrap_or(1);
-        let _ = conn.execute(
-            "INSERT INTO incidents (id, monitor_id, cause, seq) VALUES (?1, ?2, ?3, ?4)",
-            params![inc_id, monitor.id, cause, inc_seq],
-        );
+        let cause = incident_cause(conn, &Cause::Check {
+            error: result.error_message.as_deref(),
+            status_code: result.status_code,
+            kind: result.failure_kind,
+        });
+        let inc_id = insert_incident(conn, &monitor.id, &cause, None);
         return Some(WebhookPayload {
             event: "incident.created".to_string(),
             monitor: mk_monitor("down"),
@@ -787,6 +2667,7 @@ fn resolve_transition(
                 started_at: now_str.to_string(),
                 resolved_at: None,
             }),
+            maintenance: None,
             timestamp: now_str.to_string(),
         });
     }
@@ -797,6 +2678,7 @@ fn resolve_transition(
             event: "maintenance.started".to_string(),
             monitor: mk_monitor("maintenance"),
             incident: None,
+            maintenance: None,
             timestamp: now_str.to_string(),
         });
     }
@@ -807,6 +2689,7 @@ fn resolve_transition(
             event: "monitor.degraded".to_string(),
             monitor: mk_monitor("degraded"),
             incident: None,
+            maintenance: None,
             timestamp: now_str.to_string(),
         });
     }
@@ -817,6 +2700,7 @@ fn resolve_transition(
             event: "monitor.recovered".to_string(),
             monitor: mk_monitor("up"),
             incident: None,
+            maintenance: None,
             timestamp: now_str.to_string(),
         });
     }
@@ -827,55 +2711,91 @@ fn resolve_transition(
             event: "maintenance.ended".to_string(),
             monitor: mk_monitor("up"),
             incident: None,
+            maintenance: None,
             timestamp: now_str.to_string(),
         });
     }
 
     // Transition: down → recovered (resolve open incidents)
     if prev == "down" && effective != "down" && effective != "maintenance" {
-        let resolved_count = conn.execute(
-            "UPDATE incidents SET resolved_at = datetime('now') WHERE monitor_id = ?1 AND resolved_at IS NULL",
-            params![monitor.id],
-        ).unwrap_or(0);
-
-        // Only emit resolved event if there was actually an open incident.
-        // If deps were suppressed, there may be no open incident to resolve.
-        if resolved_count > 0 {
-            let incident_info: Option<(String, String, String)> = conn
-                .query_row(
-                    "SELECT id, cause, started_at FROM incidents WHERE monitor_id = ?1 ORDER BY started_at DESC LIMIT 1",
-                    params![monitor.id],
-                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-                )
-                .ok();
-            return Some(WebhookPayload {
-                event: "incident.resolved".to_string(),
-                monitor: mk_monitor(effective),
-                incident: incident_info.map(|(id, cause, started_at)| WebhookIncident {
-                    id,
-                    cause,
-                    started_at,
-                    resolved_at: Some(now_str.to_string()),
-                }),
-                timestamp: now_str.to_string(),
-            });
-        }
-        // If no open incident (was suppressed by deps), silently recover
-        return None;
+        return resolve_recovered_incidents(conn, monitor, effective, None, now_str);
     }
 
     None
 }
 
+/// Resolve the monitor's auto-resolving incidents as of `recovered_at` (a
+/// `datetime('now')`-style timestamp; None = now) and build the
+/// `incident.resolved` payload.
+///
+/// Returns `None` if nothing was open — e.g. the outage was suppressed by a
+/// dependency — so the monitor recovers silently.
+fn resolve_recovered_incidents(
+    conn: &rusqlite::Connection,
+    monitor: &MonitorCheck,
+    status: &str,
+    recovered_at: Option<&str>,
+    now_str: &str,
+) -> Option<WebhookPayload> {
+    let resolved_count = conn.execute(
+        "UPDATE incidents SET resolved_at = COALESCE(?2, datetime('now')) WHERE monitor_id = ?1 AND resolved_at IS NULL AND auto_resolve = 1",
+        params![monitor.id, recovered_at],
+    ).unwrap_or(0);
+    if resolved_count == 0 {
+        return None;
+    }
+
+    let resolved_at = recovered_at
+        .and_then(|t| chrono::NaiveDateTime::parse_from_str(t, "%Y-%m-%d %H:%M:%S").ok())
+        .map(|t| t.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|| now_str.to_string());
+    let incident_info: Option<(String, String, String)> = conn
+        .query_row(
+            "SELECT id, cause, started_at FROM incidents WHERE monitor_id = ?1 AND source = 'check' ORDER BY started_at DESC LIMIT 1",
+            params![monitor.id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .ok();
+    Some(WebhookPayload {
+        event: "incident.resolved".to_string(),
+        monitor: WebhookMonitor {
+            id: monitor.id.clone(),
+            name: monitor.name.clone(),
+            url: monitor.url.clone(),
+            current_status: status.to_string(),
+            runbook_url: monitor.runbook_url.clone(),
+        },
+        incident: incident_info.map(|(id, cause, started_at)| WebhookIncident {
+            id,
+            cause,
+            started_at,
+            resolved_at: Some(resolved_at),
+        }),
+        maintenance: None,
+        timestamp: now_str.to_string(),
+    })
+}
+
+/// Start (`pending = true`) or clear a recovery held back by the cooldown.
+fn set_recovery_pending(conn: &rusqlite::Connection, monitor_id: &str, pending: bool) {
+    let _ = conn.execute(
+        "UPDATE monitors SET recovery_pending_since = CASE WHEN ?1 THEN datetime('now') END WHERE id = ?2",
+        params![pending, monitor_id],
+    );
+}
+
 /// Log a notification to the alert_log table.
 fn log_alert(db: &Db, monitor_id: &str, payload: &WebhookPayload, alert_type: &str) {
     let conn = db.conn();
     let id = uuid::Uuid::new_v4().to_string();
     let incident_id = payload.incident.as_ref().map(|i| i.id.clone());
+    let seq: i64 = conn
+        .query_row("SELECT COALESCE(MAX(seq), 0) + 1 FROM alert_log", [], |r| r.get(0))
+        .unwrap_or(1);
     let _ = conn.execute(
-        "INSERT INTO alert_log (id, monitor_id, incident_id, alert_type, event, sent_at)
-         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'))",
-        params![id, monitor_id, incident_id, alert_type, payload.event],
+        "INSERT INTO alert_log (id, monitor_id, incident_id, alert_type, event, sent_at, seq)
+         VALUES (?1, ?2, ?3, ?4, ?5, datetime('now'), ?6)",
+        params![id, monitor_id, incident_id, alert_type, payload.event, seq],
     );
 }
 
@@ -911,8 +2831,9 @@ async fn process_repeat_notifications(
         match conn.query_row(
             "SELECT id, cause, started_at, CASE WHEN acknowledgement IS NOT NULL THEN 1 ELSE 0 END
              FROM incidents WHERE monitor_id = ?1 AND resolved_at IS NULL
+               AND (caused_by_monitor_id IS NULL OR ?2 = 0)
              ORDER BY started_at DESC LIMIT 1",
-            params![monitor.id],
+            params![monitor.id, suppress_dependent_alerts()],
             |row| Ok((
                 row.get::<_, String>(0)?,
                 row.get::<_, String>(1)?,
@@ -921,7 +2842,7 @@ async fn process_repeat_notifications(
             )),
         ) {
             Ok(r) => r,
-            Err(_) => return, // No open incident
+            Err(_) => return, // No open incident (or only a suppressed dependency-caused one)
         }
     };
 
@@ -962,6 +2883,7 @@ async fn process_repeat_notifications(
                         name: monitor.name.clone(),
                         url: monitor.url.clone(),
                         current_status: "down".to_string(),
+                        runbook_url: monitor.runbook_url.clone(),
                     },
                     incident: Some(WebhookIncident {
                         id: incident_id.clone(),
@@ -969,6 +2891,7 @@ async fn process_repeat_notifications(
                         started_at: incident_started.clone(),
                         resolved_at: None,
                     }),
+                    maintenance: None,
                     timestamp: now_str.clone(),
                 };
 
@@ -980,13 +2903,15 @@ async fn process_repeat_notifications(
                 });
 
                 // Webhooks
-                let channels = notifications::get_webhook_channels(db, &monitor.id);
+                let severity = notifications::payload_severity(db, &payload);
+                let channels = notifications::get_webhook_channels(db, &monitor.id, severity);
                 if !channels.is_empty() {
-                    notifications::fire_webhooks(db, http_client, &monitor.id, &channels, &payload).await;
+                    notifications::fire_webhooks(db, &monitor.id, &channels, &payload).await;
                 }
+                notifications::fire_integrations(db, http_client, &monitor.id, &payload).await;
 
                 // Emails
-                let emails = notifications::get_email_addresses(db, &monitor.id);
+                let emails = notifications::get_email_addresses(db, &monitor.id, severity);
                 if !emails.is_empty() {
                     notifications::fire_emails(&emails, &payload).await;
                 }
@@ -1021,6 +2946,7 @@ async fn process_repeat_notifications(
                         name: monitor.name.clone(),
                         url: monitor.url.clone(),
                         current_status: "down".to_string(),
+                        runbook_url: monitor.runbook_url.clone(),
                     },
                     incident: Some(WebhookIncident {
                         id: incident_id.clone(),
@@ -1028,6 +2954,7 @@ async fn process_repeat_notifications(
                         started_at: incident_started.clone(),
                         resolved_at: None,
                     }),
+                    maintenance: None,
                     timestamp: now_str.clone(),
                 };
 
@@ -1039,11 +2966,13 @@ async fn process_repeat_notifications(
                 });
 
                 // Fire to ALL channels (escalation = notify everything)
-                let channels = notifications::get_webhook_channels(db, &monitor.id);
+                let severity = notifications::payload_severity(db, &payload);
+                let channels = notifications::get_webhook_channels(db, &monitor.id, severity);
                 if !channels.is_empty() {
-                    notifications::fire_webhooks(db, http_client, &monitor.id, &channels, &payload).await;
+                    notifications::fire_webhooks(db, &monitor.id, &channels, &payload).await;
                 }
-                let emails = notifications::get_email_addresses(db, &monitor.id);
+                notifications::fire_integrations(db, http_client, &monitor.id, &payload).await;
+                let emails = notifications::get_email_addresses(db, &monitor.id, severity);
                 if !emails.is_empty() {
                     notifications::fire_emails(&emails, &payload).await;
                 }
@@ -1054,15 +2983,669 @@ async fn process_repeat_notifications(
     }
 }
 
+/// Announce an incident opened or closed outside the checker (manual incidents)
+/// the same way as a check-driven one: SSE, every channel, status page subscribers,
+/// and the alert log.
+pub async fn announce_incident(db: &Db, clients: &CheckerClients, broadcaster: &EventBroadcaster, payload: &WebhookPayload) {
+    dispatch_alert(db, &clients.webhook, broadcaster, payload, "initial").await;
+    notifications::notify_page_subscribers(db, &payload.monitor.id, payload).await;
+}
+
+/// Send a rule-driven notification (SSE, webhooks, emails) and record it in
+/// the alert log under `alert_type`.
+async fn dispatch_alert(
+    db: &Db,
+    http_client: &reqwest::Client,
+    broadcaster: &EventBroadcaster,
+    payload: &WebhookPayload,
+    alert_type: &str,
+) {
+    let monitor_id = &payload.monitor.id;
+    broadcaster.send(SseEvent {
+        event_type: payload.event.clone(),
+        monitor_id: monitor_id.clone(),
+        data: serde_json::to_value(payload).unwrap_or_default(),
+    });
+
+    let severity = notifications::payload_severity(db, payload);
+    let channels = notifications::get_webhook_channels(db, monitor_id, severity);
+    if !channels.is_empty() {
+        notifications::fire_webhooks(db, monitor_id, &channels, payload).await;
+    }
+    notifications::fire_integrations(db, http_client, monitor_id, payload).await;
+
+    let emails = notifications::get_email_addresses(db, monitor_id, severity);
+    if !emails.is_empty() {
+        notifications::fire_emails(&emails, payload).await;
+    }
+
+    log_alert(db, monitor_id, payload, alert_type);
+}
+
+/// Whether any of the monitor's alert rules are evaluated over stored heartbeats.
+fn has_heartbeat_window_rules(db: &Db, monitor_id: &str) -> bool {
+    db.read().query_row(
+        "SELECT EXISTS(SELECT 1 FROM alert_rules WHERE monitor_id = ?1
+             AND (latency_threshold_ms IS NOT NULL OR sla_breach_alert = 1 OR burn_rate_threshold IS NOT NULL))",
+        params![monitor_id],
+        |row| row.get(0),
+    ).unwrap_or(false)
+}
+
+/// Evaluate the monitor's latency rule, if any: fire `monitor.latency_high`
+/// once the average response time of successful checks over the rule's window
+/// exceeds the threshold, and `monitor.latency_recovered` once it drops back.
+/// The latest 'latency' alert-log entry holds the state, so each crossing
+/// notifies exactly once.
+async fn process_latency_alert(
+    db: &Db,
+    monitor: &MonitorCheck,
+    http_client: &reqwest::Client,
+    broadcaster: &EventBroadcaster,
+) {
+    let (avg_ms, window_covered, firing) = {
+        let conn = db.conn();
+        let (threshold, window) = match conn.query_row(
+            "SELECT latency_threshold_ms, latency_window_minutes FROM alert_rules
+             WHERE monitor_id = ?1 AND latency_threshold_ms IS NOT NULL",
+            params![monitor.id],
+            |row| Ok((row.get::<_, u32>(0)?, row.get::<_, u32>(1)?)),
+        ) {
+            Ok(r) => r,
+            Err(_) => return, // No latency rule
+        };
+        let since = format!("-{} minutes", window);
+        let avg: Option<f64> = conn.query_row(
+            "SELECT AVG(response_time_ms) FROM heartbeats
+             WHERE monitor_id = ?1 AND status IN ('up', 'degraded') AND checked_at >= datetime('now', ?2)",
+            params![monitor.id, since],
+            |row| row.get(0),
+        ).unwrap_or(None);
+        // Only judge a full window, so a single slow first check can't fire
+        let covered: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM heartbeats WHERE monitor_id = ?1 AND checked_at <= datetime('now', ?2))",
+            params![monitor.id, since],
+            |row| row.get(0),
+        ).unwrap_or(false);
+        let firing = last_rule_event(&conn, &monitor.id, "latency").as_deref() == Some("monitor.latency_high");
+        (avg.map(|a| (a, threshold)), covered, firing)
+    };
+
+    let event = match avg_ms {
+        Some((avg, threshold)) if !firing && window_covered && avg > threshold as f64 => "monitor.latency_high",
+        Some((avg, threshold)) if firing && avg <= threshold as f64 => "monitor.latency_recovered",
+        _ => return,
+    };
+
+    dispatch_alert(db, http_client, broadcaster, &rule_payload(monitor, event), "latency").await;
+}
+
+/// Evaluate the monitor's SLA rule, if enabled: fire `monitor.sla_breached`
+/// once rolling 24h uptime falls below `sla_target`, and `monitor.sla_recovered`
+/// once it is back at or above it. Uses the same uptime math as the SLA endpoint.
+async fn process_sla_alert(
+    db: &Db,
+    monitor: &MonitorCheck,
+    http_client: &reqwest::Client,
+    broadcaster: &EventBroadcaster,
+) {
+    let (uptime_pct, target, firing) = {
+        let conn = db.conn();
+        let target: f64 = match conn.query_row(
+            "SELECT m.sla_target FROM alert_rules r INNER JOIN monitors m ON m.id = r.monitor_id
+             WHERE r.monitor_id = ?1 AND r.sla_breach_alert = 1 AND m.sla_target IS NOT NULL",
+            params![monitor.id],
+            |row| row.get(0),
+        ) {
+            Ok(t) => t,
+            Err(_) => return, // Rule disabled or no SLA target
+        };
+        let pct = match crate::routes::sla_uptime(&conn, &monitor.id, "-24 hours") {
+            Ok((total, _, pct)) if total > 0 => pct,
+            _ => return,
+        };
+        let firing = last_rule_event(&conn, &monitor.id, "sla").as_deref() == Some("monitor.sla_breached");
+        (pct, target, firing)
+    };
+
+    let event = if !firing && uptime_pct < target {
+        "monitor.sla_breached"
+    } else if firing && uptime_pct >= target {
+        "monitor.sla_recovered"
+    } else {
+        return;
+    };
+    dispatch_alert(db, http_client, broadcaster, &rule_payload(monitor, event), "sla").await;
+}
+
+/// Evaluate the monitor's burn-rate rule, if any: fire `monitor.burn_rate_high`
+/// once both the 1h and 24h error budget burn rates reach the threshold, and
+/// `monitor.burn_rate_recovered` once the 1h rate drops back below it. Needing
+/// both windows keeps a short blip from paging; the short window alone clears it.
+async fn process_burn_rate_alert(
+    db: &Db,
+    monitor: &MonitorCheck,
+    http_client: &reqwest::Client,
+    broadcaster: &EventBroadcaster,
+) {
+    let (short, long, threshold, firing) = {
+        let conn = db.conn();
+        let (threshold, target): (f64, f64) = match conn.query_row(
+            "SELECT r.burn_rate_threshold, m.sla_target FROM alert_rules r INNER JOIN monitors m ON m.id = r.monitor_id
+             WHERE r.monitor_id = ?1 AND r.burn_rate_threshold IS NOT NULL AND m.sla_target IS NOT NULL",
+            params![monitor.id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ) {
+            Ok(r) => r,
+            Err(_) => return, // No burn-rate rule or no SLA target
+        };
+        let rate = |offset: &str| crate::routes::burn_rate(&conn, &monitor.id, offset, target).ok().flatten();
+        let firing = last_rule_event(&conn, &monitor.id, "burn_rate").as_deref() == Some("monitor.burn_rate_high");
+        (rate("-1 hours"), rate("-24 hours"), threshold, firing)
+    };
+
+    let event = match (short, long) {
+        (Some(s), Some(l)) if !firing && s >= threshold && l >= threshold => "monitor.burn_rate_high",
+        (Some(s), _) if firing && s < threshold => "monitor.burn_rate_recovered",
+        _ => return,
+    };
+    dispatch_alert(db, http_client, broadcaster, &rule_payload(monitor, event), "burn_rate").await;
+}
+
+/// Most recent event logged for a rule-driven alert type, which tells whether
+/// the rule is currently firing.
+fn last_rule_event(conn: &rusqlite::Connection, monitor_id: &str, alert_type: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT event FROM alert_log WHERE monitor_id = ?1 AND alert_type = ?2
+         ORDER BY sent_at DESC, rowid DESC LIMIT 1",
+        params![monitor_id, alert_type],
+        |row| row.get(0),
+    ).ok()
+}
+
+fn rule_payload(monitor: &MonitorCheck, event: &str) -> WebhookPayload {
+    WebhookPayload {
+        event: event.to_string(),
+        monitor: WebhookMonitor {
+            id: monitor.id.clone(),
+            name: monitor.name.clone(),
+            url: monitor.url.clone(),
+            current_status: monitor.current_status.clone(),
+            runbook_url: monitor.runbook_url.clone(),
+        },
+        incident: None,
+        maintenance: None,
+        timestamp: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+    }
+}
+
 /// Calculate minutes elapsed since a datetime string.
 fn minutes_since(datetime_str: &str) -> i64 {
+    seconds_since(datetime_str) / 60
+}
+
+/// Calculate seconds elapsed since a datetime string.
+fn seconds_since(datetime_str: &str) -> i64 {
     use chrono::{NaiveDateTime, Utc};
     let formats = ["%Y-%m-%dT%H:%M:%SZ", "%Y-%m-%d %H:%M:%S"];
     for fmt in &formats {
         if let Ok(dt) = NaiveDateTime::parse_from_str(datetime_str, fmt) {
             let then = dt.and_utc();
-            return (Utc::now() - then).num_minutes();
+            return (Utc::now() - then).num_seconds();
         }
     }
     0 // fallback: treat as "just now"
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_next_due_without_jitter() {
+        let last = at("2026-01-01 00:00:00");
+        assert_eq!(next_due_at(last, "mon-a", 600, 0), at("2026-01-01 00:10:00"));
+    }
+
+    #[test]
+    fn test_jitter_offset_within_bounds() {
+        let last = at("2026-01-01 00:00:00");
+        for i in 0..500 {
+            let id = format!("mon-{}", i);
+            let offset = jitter_offset_secs(&id, 600, 10);
+            assert!(offset <= 60, "offset {} exceeds 10% of 600s", offset);
+            let due = next_due_at(last, &id, 600, 10);
+            assert!(due >= at("2026-01-01 00:10:00") && due <= at("2026-01-01 00:11:00"));
+        }
+        // Percentages above 50 are capped
+        assert!((0..500).all(|i| jitter_offset_secs(&format!("mon-{}", i), 600, 100) <= 300));
+    }
+
+    #[rocket::async_test]
+    async fn test_supervise_respawns_panicking_task() {
+        let health = Arc::new(CheckerHealth::default());
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let tx = Arc::new(Mutex::new(Some(tx)));
+
+        let spawn = {
+            let attempts = attempts.clone();
+            move || {
+                let (attempts, tx) = (attempts.clone(), tx.clone());
+                async move {
+                    // Panic twice, then stay up and report in
+                    if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                        panic!("checker crashed");
+                    }
+                    if let Some(tx) = tx.lock().unwrap().take() {
+                        let _ = tx.send(());
+                    }
+                    std::future::pending::<()>().await;
+                }
+            }
+        };
+        let supervisor = tokio::spawn({
+            let health = health.clone();
+            async move {
+                supervise("test", spawn, health, Duration::from_millis(1), std::future::pending::<()>()).await;
+            }
+        });
+
+        time::timeout(Duration::from_secs(5), rx).await.expect("task was not respawned").unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert_eq!(health.restarts(), 2);
+        supervisor.abort();
+    }
+
+    #[rocket::async_test]
+    async fn test_supervise_stops_on_shutdown() {
+        let health = Arc::new(CheckerHealth::default());
+        let shutdown = std::future::ready(());
+        supervise("test", || async {}, health.clone(), Duration::from_millis(1), shutdown).await;
+        assert_eq!(health.restarts(), 0);
+    }
+
+    #[rocket::async_test]
+    async fn test_shutdown_drains_in_flight_checks() {
+        let path = format!("/tmp/watchpost_drain_{}.db", uuid::Uuid::new_v4());
+        let db = Arc::new(Db::new(&path).unwrap());
+        db.conn().execute_batch(
+            "INSERT INTO monitors (id, name, url, manage_key_hash) VALUES ('fast', 'fast', 'https://example.com', 'x');
+             INSERT INTO monitors (id, name, url, manage_key_hash) VALUES ('slow', 'slow', 'https://example.com', 'x');",
+        ).unwrap();
+        let pool = CheckPool::new(4, Arc::default());
+        let heartbeats = Arc::new(HeartbeatBuffer::default());
+        let result = CheckResult { status: "up".to_string(), response_time_ms: 42, status_code: Some(200), error_message: None, failure_kind: None, extra_sse_data: None };
+
+        // One check finishes within the grace period, one never would
+        for (id, delay) in [("fast", 50), ("slow", 10_000)] {
+            let (heartbeats, hb) = (heartbeats.clone(), PendingHeartbeat::from_result(id, &result));
+            let permit = pool.acquire().await;
+            pool.spawn(id.to_string(), permit, async move {
+                time::sleep(Duration::from_millis(delay)).await;
+                heartbeats.push(hb);
+            });
+        }
+
+        drain_checks(&pool, &heartbeats, &db, Duration::from_millis(500)).await;
+
+        // Give an aborted check the chance to misbehave
+        time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(heartbeats.len(), 0);
+        let rows: Vec<(String, String, u32, Option<u16>)> = {
+            let conn = db.conn();
+            let mut stmt = conn.prepare("SELECT monitor_id, status, response_time_ms, status_code FROM heartbeats").unwrap();
+            stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?))).unwrap().map(|r| r.unwrap()).collect()
+        };
+        assert_eq!(rows, vec![("fast".to_string(), "up".to_string(), 42, Some(200))]);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[rocket::async_test]
+    async fn test_manual_claim_waits_for_scheduled_check() {
+        let in_flight = Arc::new(InFlight::default());
+        let pool = CheckPool::new(2, in_flight.clone());
+        let permit = pool.acquire().await;
+        pool.spawn("mon".into(), permit, time::sleep(Duration::from_millis(200)));
+
+        let start = std::time::Instant::now();
+        let claim = in_flight.claim_when_idle("mon").await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        assert!(in_flight.snapshot().contains("mon"));
+
+        // The scheduler can't start the monitor while the manual check holds it
+        let ran = Arc::new(AtomicUsize::new(0));
+        let (permit, counter) = (pool.acquire().await, ran.clone());
+        pool.spawn("mon".into(), permit, async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(ran.load(Ordering::SeqCst), 0);
+
+        drop(claim);
+        assert!(in_flight.snapshot().is_empty());
+    }
+
+    #[rocket::async_test]
+    async fn test_check_pool_limits_concurrency() {
+        let pool = CheckPool::new(3, Arc::default());
+        for i in 0..12 {
+            let permit = pool.acquire().await;
+            pool.spawn(format!("mon-{}", i), permit, async {
+                time::sleep(Duration::from_millis(20)).await;
+            });
+            assert!(pool.active.load(Ordering::SeqCst) <= 3);
+        }
+        // Wait for everything to drain
+        let _all = pool.semaphore.acquire_many(3).await.unwrap();
+        assert_eq!(pool.peak.load(Ordering::SeqCst), 3);
+        assert!(pool.in_flight().is_empty());
+    }
+
+    #[rocket::async_test]
+    async fn test_check_pool_slow_check_does_not_block_others() {
+        let pool = CheckPool::new(2, Arc::default());
+        let done = Arc::new(AtomicUsize::new(0));
+        let permit = pool.acquire().await;
+        pool.spawn("slow".into(), permit, time::sleep(Duration::from_secs(5)));
+        assert!(pool.in_flight().contains("slow"));
+
+        let start = std::time::Instant::now();
+        for i in 0..5 {
+            let permit = pool.acquire().await;
+            let done = done.clone();
+            pool.spawn(format!("fast-{}", i), permit, async move {
+                time::sleep(Duration::from_millis(20)).await;
+                done.fetch_add(1, Ordering::SeqCst);
+            });
+        }
+        while done.load(Ordering::SeqCst) < 5 {
+            time::sleep(Duration::from_millis(5)).await;
+        }
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(pool.peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_heartbeat_batch_preserves_seq() {
+        let path = format!("/tmp/watchpost_hb_batch_{}.db", uuid::Uuid::new_v4());
+        let db = Db::new(&path).unwrap();
+        {
+            let conn = db.conn();
+            conn.execute(
+                "INSERT INTO monitors (id, name, url, manage_key_hash) VALUES ('m1', 'm1', 'https://example.com', 'x')",
+                [],
+            ).unwrap();
+            // An existing heartbeat (e.g. from a probe) that the batch must continue after
+            conn.execute(
+                "INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, seq) VALUES ('existing', 'm1', 'up', 5, 7)",
+                [],
+            ).unwrap();
+        }
+
+        let buffer = HeartbeatBuffer::default();
+        let mut ids = Vec::new();
+        for i in 0..25 {
+            let result = CheckResult {
+                status: if i % 2 == 0 { "up".into() } else { "down".into() },
+                response_time_ms: i,
+                status_code: Some(200),
+                error_message: None,
+                failure_kind: None,
+                extra_sse_data: None,
+            };
+            let hb = PendingHeartbeat::from_result("m1", &result);
+            ids.push(hb.id.clone());
+            buffer.push(hb);
+        }
+        assert_eq!(buffer.flush(&db), 25);
+        assert_eq!(buffer.len(), 0);
+        assert_eq!(buffer.flush(&db), 0);
+
+        let conn = db.conn();
+        let mut stmt = conn.prepare("SELECT id, seq FROM heartbeats WHERE id != 'existing' ORDER BY seq").unwrap();
+        let rows: Vec<(String, i64)> = stmt.query_map([], |r| Ok((r.get(0)?, r.get(1)?))).unwrap().flatten().collect();
+        assert_eq!(rows.len(), 25);
+        // Buffer order is kept and seq continues from the existing maximum without gaps
+        assert_eq!(rows.iter().map(|(id, _)| id.clone()).collect::<Vec<_>>(), ids);
+        assert_eq!(rows.iter().map(|(_, seq)| *seq).collect::<Vec<_>>(), (8..33).collect::<Vec<i64>>());
+        drop(stmt);
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_archived_monitor_not_scheduled() {
+        let path = format!("/tmp/watchpost_archived_{}.db", uuid::Uuid::new_v4());
+        let db = Db::new(&path).unwrap();
+        db.conn().execute_batch(
+            "INSERT INTO monitors (id, name, url, manage_key_hash, is_archived) VALUES ('live', 'live', 'https://example.com', 'x', 0);
+             INSERT INTO monitors (id, name, url, manage_key_hash, is_archived) VALUES ('archived', 'archived', 'https://example.com', 'x', 1);",
+        ).unwrap();
+
+        let none = HashSet::new();
+        assert_eq!(next_due_monitor(&db, 0, &none).map(|m| m.id).as_deref(), Some("live"));
+        let in_flight: HashSet<String> = ["live".to_string()].into();
+        assert!(next_due_monitor(&db, 0, &in_flight).is_none());
+
+        // Nothing is due once every monitor is archived, so no heartbeats get written
+        db.conn().execute("UPDATE monitors SET is_archived = 1", []).unwrap();
+        assert!(next_due_monitor(&db, 0, &none).is_none());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_auto_close_after_sustained_recovery() {
+        let path = format!("/tmp/watchpost_autoclose_{}.db", uuid::Uuid::new_v4());
+        let db = Db::new(&path).unwrap();
+        db.conn().execute_batch(
+            "INSERT INTO monitors (id, name, url, manage_key_hash, current_status) VALUES ('m', 'm', 'https://example.com', 'x', 'up');
+             INSERT INTO incidents (id, monitor_id, started_at, cause, seq) VALUES ('inc', 'm', datetime('now', '-1 hour'), 'HTTP 500', 1);
+             INSERT INTO incidents (id, monitor_id, started_at, cause, seq, source, auto_resolve) VALUES ('man', 'm', datetime('now', '-1 hour'), 'Provider outage', 2, 'manual', 0);
+             INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, checked_at, seq) VALUES ('hb0', 'm', 'down', 0, datetime('now', '-50 minutes'), 1);",
+        ).unwrap();
+        let flood = |count: usize| {
+            let heartbeats = HeartbeatBuffer::default();
+            let result = CheckResult { status: "up".to_string(), response_time_ms: 20, status_code: Some(200), error_message: None, failure_kind: None, extra_sse_data: None };
+            for _ in 0..count {
+                heartbeats.push(PendingHeartbeat::from_result("m", &result));
+            }
+            heartbeats.flush(&db);
+        };
+        let resolved = || db.conn().query_row(
+            "SELECT resolved_at IS NOT NULL, resolution_note FROM incidents WHERE id = 'inc'", [],
+            |r| Ok((r.get::<_, bool>(0)?, r.get::<_, Option<String>>(1)?)),
+        ).unwrap();
+
+        // Not enough up checks yet: the latest window still includes the down heartbeat
+        flood(4);
+        assert_eq!(auto_close_recovered_incidents(&db, 5), 0);
+        assert_eq!(auto_close_recovered_incidents(&db, 0), 0);
+        assert!(!resolved().0);
+
+        flood(1);
+        assert_eq!(auto_close_recovered_incidents(&db, 5), 1);
+        assert_eq!(resolved(), (true, Some("auto-closed after sustained recovery".to_string())));
+        // Manual incidents without auto_resolve are left for a human to close
+        let manual_open: bool = db.conn().query_row(
+            "SELECT resolved_at IS NULL FROM incidents WHERE id = 'man'", [], |r| r.get(0),
+        ).unwrap();
+        assert!(manual_open);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    /// Load monitor `id` and run a check result with `status` through the state machine.
+    async fn run_check_result(db: &Db, id: &str, status: &str) {
+        let monitor = db.conn().query_row(
+            &format!("SELECT {MONITOR_CHECK_COLUMNS} FROM monitors WHERE id = ?1"), params![id], row_to_monitor_check,
+        ).unwrap();
+        let result = CheckResult { status: status.to_string(), response_time_ms: 20, status_code: None, error_message: None, failure_kind: None, extra_sse_data: None };
+        process_check_result(db, &EventBroadcaster::new(16), &reqwest::Client::new(), &monitor, result).await;
+    }
+
+    #[tokio::test]
+    async fn test_asymmetric_confirmation_thresholds() {
+        let path = format!("/tmp/watchpost_asym_{}.db", uuid::Uuid::new_v4());
+        let db = Db::new(&path).unwrap();
+        // 'slow-down': 3 failures to alert, 1 success to recover.
+        // 'slow-up': 1 failure to alert, 3 successes to recover (confirmation_threshold is the down fallback).
+        db.conn().execute_batch(
+            "INSERT INTO monitors (id, name, url, manage_key_hash, current_status, confirmation_threshold, down_confirmation_threshold)
+                 VALUES ('slow-down', 'slow-down', 'https://example.com', 'x', 'up', 1, 3);
+             INSERT INTO monitors (id, name, url, manage_key_hash, current_status, confirmation_threshold, up_confirmation_threshold)
+                 VALUES ('slow-up', 'slow-up', 'https://example.com', 'x', 'up', 1, 3);",
+        ).unwrap();
+        let state = |id: &str| db.conn().query_row(
+            "SELECT current_status, (SELECT COUNT(*) FROM incidents WHERE monitor_id = ?1 AND resolved_at IS NULL) FROM monitors WHERE id = ?1",
+            params![id],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, i64>(1)?)),
+        ).unwrap();
+
+        run_check_result(&db, "slow-down", "down").await;
+        run_check_result(&db, "slow-down", "down").await;
+        assert_eq!(state("slow-down"), ("up".to_string(), 0));
+        run_check_result(&db, "slow-down", "down").await;
+        assert_eq!(state("slow-down"), ("down".to_string(), 1));
+        run_check_result(&db, "slow-down", "up").await;
+        assert_eq!(state("slow-down"), ("up".to_string(), 0));
+
+        run_check_result(&db, "slow-up", "down").await;
+        assert_eq!(state("slow-up"), ("down".to_string(), 1));
+        run_check_result(&db, "slow-up", "up").await;
+        run_check_result(&db, "slow-up", "up").await;
+        assert_eq!(state("slow-up"), ("down".to_string(), 1));
+        // A failure restarts the recovery count without opening a second incident
+        run_check_result(&db, "slow-up", "down").await;
+        run_check_result(&db, "slow-up", "up").await;
+        run_check_result(&db, "slow-up", "up").await;
+        assert_eq!(state("slow-up"), ("down".to_string(), 1));
+        run_check_result(&db, "slow-up", "up").await;
+        assert_eq!(state("slow-up"), ("up".to_string(), 0));
+        let total: i64 = db.conn().query_row("SELECT COUNT(*) FROM incidents WHERE monitor_id = 'slow-up'", [], |r| r.get(0)).unwrap();
+        assert_eq!(total, 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_recovery_cooldown_suppresses_flap() {
+        let path = format!("/tmp/watchpost_cooldown_{}.db", uuid::Uuid::new_v4());
+        let db = Db::new(&path).unwrap();
+        db.conn().execute_batch(
+            "INSERT INTO monitors (id, name, url, manage_key_hash, current_status, confirmation_threshold, consecutive_failures, recovery_cooldown_seconds)
+                 VALUES ('m', 'm', 'https://example.com', 'x', 'down', 1, 1, 600);
+             INSERT INTO incidents (id, monitor_id, started_at, cause, seq) VALUES ('inc', 'm', datetime('now', '-1 hour'), 'HTTP 500', 1);",
+        ).unwrap();
+        let state = || db.conn().query_row(
+            "SELECT m.current_status, m.recovery_pending_since IS NOT NULL, i.resolved_at IS NOT NULL,
+                    (SELECT COUNT(*) FROM incidents), (SELECT COUNT(*) FROM alert_log)
+             FROM monitors m JOIN incidents i ON i.id = 'inc' WHERE m.id = 'm'", [],
+            |r| Ok((r.get::<_, String>(0)?, r.get::<_, bool>(1)?, r.get::<_, bool>(2)?, r.get::<_, i64>(3)?, r.get::<_, i64>(4)?)),
+        ).unwrap();
+
+        // Back up: the recovery is held, the incident stays open, nothing is sent
+        run_check_result(&db, "m", "up").await;
+        assert_eq!(state(), ("up".to_string(), true, false, 1, 0));
+
+        // Flap inside the cooldown: same incident continues, no new alert either way
+        run_check_result(&db, "m", "down").await;
+        assert_eq!(state(), ("down".to_string(), false, false, 1, 0));
+
+        // Up again and stays up past the cooldown: resolved as of the recovery, alerted once
+        run_check_result(&db, "m", "up").await;
+        db.conn().execute("UPDATE monitors SET recovery_pending_since = datetime('now', '-601 seconds')", []).unwrap();
+        run_check_result(&db, "m", "up").await;
+        assert_eq!(state(), ("up".to_string(), false, true, 1, 1));
+        let (event, resolved_early): (String, bool) = db.conn().query_row(
+            "SELECT a.event, i.resolved_at <= datetime('now', '-600 seconds') FROM alert_log a JOIN incidents i ON i.id = a.incident_id", [],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        ).unwrap();
+        assert_eq!(event, "incident.resolved");
+        assert!(resolved_early);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_maintenance_reminder_due_within_lead_time() {
+        let at = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%SZ").unwrap();
+        let starts = "2026-03-01T14:00:00Z";
+        // 30 minute lead time: due from 13:30 until the window starts
+        assert!(!maintenance_reminder_due(at("2026-03-01T13:29:59Z"), starts, 30));
+        assert!(maintenance_reminder_due(at("2026-03-01T13:30:00Z"), starts, 30));
+        assert!(maintenance_reminder_due(at("2026-03-01T13:59:59Z"), starts, 30));
+        assert!(!maintenance_reminder_due(at("2026-03-01T14:00:00Z"), starts, 30));
+        // Stored without the trailing Z
+        assert!(maintenance_reminder_due(at("2026-03-01T13:45:00Z"), "2026-03-01T14:00:00", 30));
+        assert!(!maintenance_reminder_due(at("2026-03-01T13:45:00Z"), "not a time", 30));
+    }
+
+    #[test]
+    fn test_jitter_offset_deterministic_and_spread() {
+        assert_eq!(jitter_offset_secs("mon-a", 600, 20), jitter_offset_secs("mon-a", 600, 20));
+        let offsets: std::collections::HashSet<u32> = (0..50)
+            .map(|i| jitter_offset_secs(&format!("mon-{}", i), 600, 20))
+            .collect();
+        assert!(offsets.len() > 10, "expected offsets to spread, got {:?}", offsets);
+    }
+
+    fn dns_answer(values: &[&str], min_ttl: Option<u32>) -> DnsAnswer {
+        DnsAnswer { values: values.iter().map(|v| v.to_string()).collect(), min_ttl }
+    }
+
+    #[test]
+    fn test_dns_min_records() {
+        let monitor = MonitorCheck { dns_min_records: Some(2), ..Default::default() };
+        let (status, msg) = evaluate_dns_answer(&monitor, "example.com", "A", &dns_answer(&["192.0.2.1"], Some(300)), 5);
+        assert_eq!(status, "down");
+        assert_eq!(msg.unwrap(), "Expected at least 2 A records, got 1");
+
+        let answer = dns_answer(&["192.0.2.1", "192.0.2.2"], Some(300));
+        assert_eq!(evaluate_dns_answer(&monitor, "example.com", "A", &answer, 5), ("up".to_string(), None));
+    }
+
+    #[test]
+    fn test_dns_min_ttl() {
+        let monitor = MonitorCheck { dns_min_ttl: Some(300), ..Default::default() };
+        let (status, msg) = evaluate_dns_answer(&monitor, "example.com", "A", &dns_answer(&["192.0.2.1"], Some(60)), 5);
+        assert_eq!(status, "degraded");
+        assert_eq!(msg.unwrap(), "A record TTL 60s is below the 300s minimum");
+
+        let answer = dns_answer(&["192.0.2.1"], Some(300));
+        assert_eq!(evaluate_dns_answer(&monitor, "example.com", "A", &answer, 5).0, "up");
+
+        // Down conditions take precedence over a low TTL
+        let monitor = MonitorCheck { dns_min_ttl: Some(300), dns_expected: Some("192.0.2.9".into()), ..Default::default() };
+        assert_eq!(evaluate_dns_answer(&monitor, "example.com", "A", &dns_answer(&["192.0.2.1"], Some(60)), 5).0, "down");
+    }
+
+    #[test]
+    fn test_parse_doh_answer() {
+        let body = serde_json::json!({
+            "Status": 0,
+            "Answer": [
+                {"name": "www.example.com.", "type": 5, "TTL": 3600, "data": "example.com."},
+                {"name": "example.com.", "type": 1, "TTL": 300, "data": "192.0.2.1"},
+                {"name": "example.com.", "type": 1, "TTL": 120, "data": "192.0.2.2"}
+            ]
+        });
+        let answer = parse_doh_answer(&body, "A").unwrap();
+        assert_eq!(answer.values, vec!["192.0.2.1", "192.0.2.2"]);
+        assert_eq!(answer.min_ttl, Some(120));
+
+        let txt = serde_json::json!({"Status": 0, "Answer": [{"type": 16, "TTL": 60, "data": "\"v=spf1 -all\""}]});
+        assert_eq!(parse_doh_answer(&txt, "TXT").unwrap().values, vec!["v=spf1 -all"]);
+
+        assert_eq!(parse_doh_answer(&serde_json::json!({"Status": 3}), "A").err().unwrap(), "NXDOMAIN");
+        assert!(parse_doh_answer(&serde_json::json!({"Status": 0}), "A").unwrap().values.is_empty());
+    }
+
+    #[test]
+    fn test_doh_endpoint_presets() {
+        assert_eq!(doh_endpoint(None), None);
+        assert_eq!(doh_endpoint(Some("cloudflare")), Some("https://cloudflare-dns.com/dns-query"));
+        assert_eq!(doh_endpoint(Some("https://doh.example.net/dns-query")), Some("https://doh.example.net/dns-query"));
+    }
+}
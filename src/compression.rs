@@ -0,0 +1,76 @@
+use flate2::{write::GzEncoder, Compression};
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::{Request, Response};
+use std::io::{Cursor, Write};
+
+const DEFAULT_MIN_BYTES: usize = 1024;
+
+/// Gzips API responses of at least `min_bytes` for clients that accept it.
+/// Streaming bodies (SSE, WebSocket upgrades) have no preset size and pass through untouched.
+pub struct ResponseCompression {
+    min_bytes: usize,
+}
+
+impl ResponseCompression {
+    pub fn new(min_bytes: usize) -> Self {
+        ResponseCompression { min_bytes }
+    }
+
+    /// Threshold from COMPRESSION_MIN_BYTES (default 1024); 0 disables compression.
+    pub fn from_env() -> Option<Self> {
+        let min_bytes = std::env::var("COMPRESSION_MIN_BYTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_MIN_BYTES);
+        (min_bytes > 0).then(|| Self::new(min_bytes))
+    }
+}
+
+/// Whether an `Accept-Encoding` header allows gzip (explicitly or via `*`) with a non-zero q.
+fn accepts_gzip(accept_encoding: &str) -> bool {
+    accept_encoding.split(',').any(|entry| {
+        let mut parts = entry.split(';');
+        let coding = parts.next().unwrap_or_default().trim();
+        let q = parts
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .find_map(|q| q.trim().parse::<f32>().ok())
+            .unwrap_or(1.0);
+        (coding.eq_ignore_ascii_case("gzip") || coding == "*") && q > 0.0
+    })
+}
+
+#[rocket::async_trait]
+impl Fairing for ResponseCompression {
+    fn info(&self) -> Info {
+        Info { name: "Response compression", kind: Kind::Response }
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        if !request.uri().path().starts_with("/api/") || response.headers().contains("Content-Encoding") {
+            return;
+        }
+        match response.body().preset_size() {
+            Some(size) if size >= self.min_bytes => {}
+            _ => return,
+        }
+        response.adjoin_header(Header::new("Vary", "Accept-Encoding"));
+        if !request.headers().get("Accept-Encoding").any(accepts_gzip) {
+            return;
+        }
+
+        let body = match response.body_mut().to_bytes().await {
+            Ok(body) => body,
+            Err(_) => return,
+        };
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        let compressed = encoder.write_all(&body).and_then(|_| encoder.finish());
+        match compressed {
+            Ok(gz) => {
+                response.set_header(Header::new("Content-Encoding", "gzip"));
+                response.set_sized_body(gz.len(), Cursor::new(gz));
+            }
+            Err(_) => response.set_sized_body(body.len(), Cursor::new(body)),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::causes::{incident_cause, Cause};
 use crate::db::Db;
 use crate::notifications::{self, WebhookPayload, WebhookMonitor, WebhookIncident};
 use crate::sse::{EventBroadcaster, SseEvent};
@@ -7,11 +8,88 @@ use rusqlite::params;
 #[allow(dead_code)]
 struct LocationHeartbeat {
     location_id: Option<String>,
+    /// Location name, or "local" for the server's own checks
+    location_name: String,
     status: String,
     response_time_ms: u32,
     checked_at: String,
 }
 
+/// How many down locations it takes for consensus to call a monitor down.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsensusRule {
+    /// At least N locations (`consensus_threshold`)
+    Count(u32),
+    /// More than half of reporting locations
+    Majority,
+    /// At least N percent of reporting locations (`consensus_threshold` as 1–100)
+    Percent(u32),
+}
+
+pub const CONSENSUS_MODES: &[&str] = &["count", "majority", "percent"];
+
+impl ConsensusRule {
+    /// Build the rule from a monitor's stored `consensus_mode` + `consensus_threshold`.
+    /// Returns None when consensus isn't enabled (no threshold, and not majority mode).
+    pub fn from_config(mode: Option<&str>, threshold: Option<u32>) -> Option<Self> {
+        match mode.unwrap_or("count") {
+            "count" => threshold.map(ConsensusRule::Count),
+            "majority" => Some(ConsensusRule::Majority),
+            "percent" => threshold.map(ConsensusRule::Percent),
+            _ => None,
+        }
+    }
+
+    /// Validate a mode/threshold combination before storing it.
+    pub fn validate(mode: Option<&str>, threshold: Option<u32>) -> Result<(), String> {
+        match mode.unwrap_or("count") {
+            "count" => match threshold {
+                Some(0) => Err("consensus_threshold must be at least 1".into()),
+                _ => Ok(()),
+            },
+            "majority" => Ok(()),
+            "percent" => match threshold {
+                Some(1..=100) => Ok(()),
+                _ => Err("consensus_mode 'percent' requires consensus_threshold between 1 and 100".into()),
+            },
+            _ => Err(format!("consensus_mode must be one of: {}", CONSENSUS_MODES.join(", "))),
+        }
+    }
+
+    /// Whether `count` of `total` reporting locations satisfies the rule.
+    pub fn is_met(&self, count: u32, total: u32) -> bool {
+        match *self {
+            ConsensusRule::Count(n) => count >= n,
+            ConsensusRule::Majority => count * 2 > total,
+            ConsensusRule::Percent(pct) => total > 0 && count * 100 >= pct * total,
+        }
+    }
+
+    /// Human-readable form used in incident causes.
+    fn describe(&self) -> String {
+        match *self {
+            ConsensusRule::Count(n) => format!("threshold: {}", n),
+            ConsensusRule::Majority => "majority".to_string(),
+            ConsensusRule::Percent(pct) => format!("threshold: {}%", pct),
+        }
+    }
+}
+
+/// Decide a monitor's effective status from per-location status counts.
+fn consensus_status(rule: ConsensusRule, up: u32, down: u32, degraded: u32, total: u32) -> String {
+    if rule.is_met(down, total) {
+        "down".to_string()
+    } else if degraded > 0 && rule.is_met(down + degraded, total) {
+        "degraded".to_string()
+    } else if up > 0 {
+        "up".to_string()
+    } else if degraded > 0 {
+        "degraded".to_string()
+    } else {
+        "unknown".to_string()
+    }
+}
+
 /// Result of consensus evaluation.
 #[allow(dead_code)]
 pub struct ConsensusResult {
@@ -26,7 +104,7 @@ pub struct ConsensusResult {
 /// Evaluate multi-region consensus for a monitor and update its status + incident lifecycle.
 ///
 /// Call this after storing heartbeats (from local checker or probe submission)
-/// when the monitor has `consensus_threshold` set.
+/// when the monitor has consensus configured (see `ConsensusRule::from_config`).
 ///
 /// Returns the consensus result, or None if the monitor has no consensus configured
 /// or no heartbeat data exists.
@@ -43,37 +121,42 @@ pub async fn evaluate_and_apply(
         let conn = db.conn();
 
         // Get monitor info (returns None if monitor doesn't exist or has no consensus configured)
-        let monitor_info: Option<(String, String, u32, String)> = conn.query_row(
-            "SELECT id, name, url, consensus_threshold, current_status FROM monitors WHERE id = ?1",
+        let monitor_info: Option<(String, String, ConsensusRule, String, Option<String>)> = conn.query_row(
+            "SELECT id, name, url, consensus_threshold, current_status, consensus_mode, runbook_url FROM monitors WHERE id = ?1",
             params![monitor_id],
             |row| {
                 let ct: Option<u32> = row.get(3)?;
-                match ct {
-                    Some(threshold) => Ok(Some((
+                let mode: Option<String> = row.get(5)?;
+                match ConsensusRule::from_config(mode.as_deref(), ct) {
+                    Some(rule) => Ok(Some((
                         row.get::<_, String>(1)?,
                         row.get::<_, String>(2)?,
-                        threshold,
+                        rule,
                         row.get::<_, String>(4)?,
+                        row.get::<_, Option<String>>(6)?,
                     ))),
                     None => Ok(None),
                 }
             },
         ).ok()?;
 
-        let (name, url, threshold, current_status) = monitor_info?;
+        let (name, url, rule, current_status, runbook_url) = monitor_info?;
 
-        // Get latest heartbeat per location (including local where location_id IS NULL)
+        // Get latest heartbeat per location (including local where location_id IS NULL).
+        // Inactive locations are excluded so a deactivated probe can't sway the result.
         // Uses a window function to get the most recent heartbeat for each location
         // Order by seq DESC (not checked_at) to handle same-second heartbeats correctly
         let mut stmt = conn.prepare(
-            "SELECT location_id, status, response_time_ms, checked_at
+            "SELECT h.location_id, h.status, h.response_time_ms, h.checked_at, COALESCE(l.name, 'local')
              FROM (
                  SELECT location_id, status, response_time_ms, checked_at,
                         ROW_NUMBER() OVER (PARTITION BY COALESCE(location_id, '__local__') ORDER BY seq DESC) as rn
                  FROM heartbeats
                  WHERE monitor_id = ?1
-             )
-             WHERE rn = 1"
+                   AND (location_id IS NULL OR location_id IN (SELECT id FROM check_locations WHERE is_active = 1))
+             ) h
+             LEFT JOIN check_locations l ON l.id = h.location_id
+             WHERE h.rn = 1"
         ).ok()?;
 
         let heartbeats: Vec<LocationHeartbeat> = stmt.query_map(params![monitor_id], |row| {
@@ -82,6 +165,7 @@ pub async fn evaluate_and_apply(
                 status: row.get(1)?,
                 response_time_ms: row.get(2)?,
                 checked_at: row.get(3)?,
+                location_name: row.get(4)?,
             })
         }).ok()?
         .filter_map(|r| r.ok())
@@ -108,18 +192,8 @@ pub async fn evaluate_and_apply(
 
         let total_locations = heartbeats.len() as u32;
 
-        // Determine effective status based on consensus threshold
-        let effective_status = if down_count >= threshold {
-            "down".to_string()
-        } else if degraded_count > 0 && (down_count + degraded_count) >= threshold {
-            "degraded".to_string()
-        } else if up_count > 0 {
-            "up".to_string()
-        } else if degraded_count > 0 {
-            "degraded".to_string()
-        } else {
-            "unknown".to_string()
-        };
+        // Determine effective status based on the consensus rule
+        let effective_status = consensus_status(rule, up_count, down_count, degraded_count, total_locations);
 
         consensus = ConsensusResult {
             effective_status: effective_status.clone(),
@@ -148,15 +222,22 @@ pub async fn evaluate_and_apply(
             name: name.clone(),
             url: url.clone(),
             current_status: status.to_string(),
+            runbook_url: runbook_url.clone(),
         };
 
         webhook_event = if current_status != "down" && current_status != "maintenance" && effective_status == "down" {
             // → down: create incident
             let inc_id = uuid::Uuid::new_v4().to_string();
-            let cause = format!(
-                "Consensus: {}/{} locations report down (threshold: {})",
-                down_count, total_locations, threshold
-            );
+            let down_locations: Vec<String> = heartbeats.iter()
+                .filter(|hb| hb.status == "down")
+                .map(|hb| hb.location_name.clone())
+                .collect();
+            let cause = incident_cause(&conn, &Cause::Consensus {
+                down: down_count,
+                total: total_locations,
+                rule: &rule.describe(),
+                locations: &down_locations,
+            });
             let inc_seq: i64 = conn
                 .query_row("SELECT COALESCE(MAX(seq), 0) + 1 FROM incidents", [], |r| r.get(0))
                 .unwrap_or(1);
@@ -173,12 +254,13 @@ pub async fn evaluate_and_apply(
                     started_at: now_str.clone(),
                     resolved_at: None,
                 }),
+                maintenance: None,
                 timestamp: now_str,
             })
         } else if current_status == "down" && effective_status != "down" && effective_status != "maintenance" {
             // down → recovered: resolve incidents
             let _ = conn.execute(
-                "UPDATE incidents SET resolved_at = datetime('now') WHERE monitor_id = ?1 AND resolved_at IS NULL",
+                "UPDATE incidents SET resolved_at = datetime('now') WHERE monitor_id = ?1 AND resolved_at IS NULL AND auto_resolve = 1",
                 params![monitor_id],
             );
             let incident_info: Option<(String, String, String)> = conn
@@ -197,6 +279,7 @@ pub async fn evaluate_and_apply(
                     started_at,
                     resolved_at: Some(now_str.clone()),
                 }),
+                maintenance: None,
                 timestamp: now_str,
             })
         } else if current_status != "degraded" && effective_status == "degraded" {
@@ -204,6 +287,7 @@ pub async fn evaluate_and_apply(
                 event: "monitor.degraded".to_string(),
                 monitor: mk_monitor("degraded"),
                 incident: None,
+                maintenance: None,
                 timestamp: now_str,
             })
         } else if current_status == "degraded" && effective_status == "up" {
@@ -211,6 +295,7 @@ pub async fn evaluate_and_apply(
                 event: "monitor.recovered".to_string(),
                 monitor: mk_monitor("up"),
                 incident: None,
+                maintenance: None,
                 timestamp: now_str,
             })
         } else {
@@ -226,15 +311,19 @@ pub async fn evaluate_and_apply(
             data: serde_json::to_value(payload).unwrap_or_default(),
         });
 
-        let channels = notifications::get_webhook_channels(db, monitor_id);
+        let severity = notifications::payload_severity(db, payload);
+        let channels = notifications::get_webhook_channels(db, monitor_id, severity);
         if !channels.is_empty() {
-            notifications::fire_webhooks(db, http_client, monitor_id, &channels, payload).await;
+            notifications::fire_webhooks(db, monitor_id, &channels, payload).await;
         }
+        notifications::fire_integrations(db, http_client, monitor_id, payload).await;
 
-        let emails = notifications::get_email_addresses(db, monitor_id);
+        let emails = notifications::get_email_addresses(db, monitor_id, severity);
         if !emails.is_empty() {
             notifications::fire_emails(&emails, payload).await;
         }
+
+        notifications::notify_page_subscribers(db, monitor_id, payload).await;
     }
 
     Some(consensus)
@@ -248,12 +337,13 @@ pub fn get_consensus_status(
 ) -> Option<crate::models::ConsensusStatus> {
     let conn = db.conn();
 
-    // Get monitor consensus_threshold
-    let threshold: u32 = conn.query_row(
-        "SELECT consensus_threshold FROM monitors WHERE id = ?1",
+    // Get monitor consensus configuration
+    let (threshold, mode): (Option<u32>, Option<String>) = conn.query_row(
+        "SELECT consensus_threshold, consensus_mode FROM monitors WHERE id = ?1",
         params![monitor_id],
-        |row| row.get::<_, Option<u32>>(0),
-    ).ok()??;
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).ok()?;
+    let rule = ConsensusRule::from_config(mode.as_deref(), threshold)?;
 
     // Get latest heartbeat per location (order by seq for correct ordering)
     let mut stmt = conn.prepare(
@@ -263,6 +353,7 @@ pub fn get_consensus_status(
                     ROW_NUMBER() OVER (PARTITION BY COALESCE(location_id, '__local__') ORDER BY seq DESC) as rn
              FROM heartbeats
              WHERE monitor_id = ?1
+               AND (location_id IS NULL OR location_id IN (SELECT id FROM check_locations WHERE is_active = 1))
          ) h
          LEFT JOIN check_locations cl ON cl.id = h.location_id
          WHERE h.rn = 1
@@ -297,20 +388,11 @@ pub fn get_consensus_status(
     }
 
     let total_locations = locations.len() as u32;
-    let effective_status = if down_count >= threshold {
-        "down".to_string()
-    } else if degraded_count > 0 && (down_count + degraded_count) >= threshold {
-        "degraded".to_string()
-    } else if up_count > 0 {
-        "up".to_string()
-    } else if degraded_count > 0 {
-        "degraded".to_string()
-    } else {
-        "unknown".to_string()
-    };
+    let effective_status = consensus_status(rule, up_count, down_count, degraded_count, total_locations);
 
     Some(crate::models::ConsensusStatus {
         monitor_id: monitor_id.to_string(),
+        consensus_mode: mode.unwrap_or_else(|| "count".to_string()),
         consensus_threshold: threshold,
         total_locations,
         up_count,
@@ -1,25 +1,153 @@
-use rusqlite::{Connection, Result, params};
-use std::sync::Mutex;
-
+use crate::models::DbStats;
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::{Connection, OpenFlags, Result, params};
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
+
+/// SQLite access: one writer connection plus an r2d2 pool of read-only
+/// connections. With WAL, readers don't block on (or get blocked by) the
+/// writer, so read-heavy endpoints no longer queue behind the checker.
 pub struct Db {
     pub conn: Mutex<Connection>,
+    readers: Option<r2d2::Pool<SqliteConnectionManager>>,
+}
+
+/// A connection from `Db::read()`: a pooled reader, or the writer when there is no pool.
+pub enum ReadConn<'a> {
+    Pooled(r2d2::PooledConnection<SqliteConnectionManager>),
+    Writer(MutexGuard<'a, Connection>),
+}
+
+impl Deref for ReadConn<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            ReadConn::Pooled(conn) => conn,
+            ReadConn::Writer(conn) => conn,
+        }
+    }
+}
+
+/// Most read-only connections pooled alongside the writer.
+/// Configurable via DB_READ_POOL_SIZE env var. Default: 4 (0 routes reads to the writer).
+fn read_pool_size() -> usize {
+    std::env::var("DB_READ_POOL_SIZE")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(4)
+}
+
+/// Connection pragmas, from env with safe fallbacks:
+/// SQLITE_JOURNAL_MODE (default WAL), SQLITE_SYNCHRONOUS (default NORMAL),
+/// SQLITE_BUSY_TIMEOUT_MS (default 5000).
+struct Pragmas {
+    journal_mode: String,
+    synchronous: String,
+    busy_timeout_ms: u32,
+}
+
+impl Pragmas {
+    fn from_env() -> Self {
+        // Values are interpolated into PRAGMA statements, so only known keywords pass
+        let keyword = |var: &str, allowed: &[&str], default: &str| {
+            std::env::var(var)
+                .ok()
+                .map(|v| v.trim().to_uppercase())
+                .filter(|v| allowed.contains(&v.as_str()))
+                .unwrap_or_else(|| default.to_string())
+        };
+        Pragmas {
+            journal_mode: keyword("SQLITE_JOURNAL_MODE", &["WAL", "DELETE", "TRUNCATE", "PERSIST", "MEMORY", "OFF"], "WAL"),
+            synchronous: keyword("SQLITE_SYNCHRONOUS", &["OFF", "NORMAL", "FULL", "EXTRA"], "NORMAL"),
+            busy_timeout_ms: std::env::var("SQLITE_BUSY_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5000),
+        }
+    }
 }
 
 impl Db {
     /// Get a database connection with mutex poison recovery.
     /// If a previous request panicked while holding the lock, this recovers
     /// gracefully instead of propagating the panic to all subsequent requests.
-    pub fn conn(&self) -> std::sync::MutexGuard<'_, Connection> {
+    pub fn conn(&self) -> MutexGuard<'_, Connection> {
         self.conn.lock().unwrap_or_else(|e| e.into_inner())
     }
+
+    /// Get a read-only connection for queries that never write, waiting for one
+    /// if the pool is busy. Falls back to the writer when there is no read pool
+    /// (e.g. in-memory databases) or no reader can be had.
+    pub fn read(&self) -> ReadConn<'_> {
+        match self.readers.as_ref().map(|pool| pool.get()) {
+            Some(Ok(conn)) => ReadConn::Pooled(conn),
+            Some(Err(e)) => {
+                tracing::warn!(error = %e, "No pooled reader available, reading through the writer");
+                ReadConn::Writer(self.conn())
+            }
+            None => ReadConn::Writer(self.conn()),
+        }
+    }
+
+    /// Rebuild the database file to drop free pages, then checkpoint the WAL so the
+    /// space goes back to the filesystem. Holds the writer for the duration.
+    /// Returns the size in bytes before and after.
+    pub fn vacuum(&self) -> Result<(u64, u64)> {
+        let conn = self.conn();
+        let before = size_bytes(&conn)?;
+        conn.execute_batch("VACUUM;")?;
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(())).ok();
+        Ok((before, size_bytes(&conn)?))
+    }
+
+    /// Row counts for the largest tables plus the on-disk size. Counts include
+    /// soft-deleted monitors, since their rows still take up space.
+    pub fn stats(&self) -> Result<DbStats> {
+        let conn = self.read();
+        let count = |table: &str| -> Result<u64> {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))
+        };
+        Ok(DbStats {
+            monitors: count("monitors")?,
+            heartbeats: count("heartbeats")?,
+            incidents: count("incidents")?,
+            webhook_deliveries: count("webhook_deliveries")?,
+            db_size_bytes: size_bytes(&conn)?,
+            oldest_heartbeat_at: conn.query_row("SELECT MIN(checked_at) FROM heartbeats", [], |r| r.get(0))?,
+        })
+    }
+}
+
+/// Database size as SQLite sees it (page_count × page_size).
+fn size_bytes(conn: &Connection) -> Result<u64> {
+    let page_count: u64 = conn.query_row("PRAGMA page_count", [], |r| r.get(0))?;
+    let page_size: u64 = conn.query_row("PRAGMA page_size", [], |r| r.get(0))?;
+    Ok(page_count * page_size)
 }
 
 impl Db {
     pub fn new(path: &str) -> Result<Self> {
+        let pragmas = Pragmas::from_env();
         let conn = Connection::open(path)?;
-        conn.execute_batch("PRAGMA journal_mode=WAL; PRAGMA foreign_keys=ON; PRAGMA busy_timeout=5000;")?;
-        let db = Db { conn: Mutex::new(conn) };
+        conn.execute_batch(&format!(
+            "PRAGMA journal_mode={}; PRAGMA synchronous={}; PRAGMA foreign_keys=ON; PRAGMA busy_timeout={};",
+            pragmas.journal_mode, pragmas.synchronous, pragmas.busy_timeout_ms
+        ))?;
+        let mut db = Db { conn: Mutex::new(conn), readers: None };
         db.migrate()?;
+
+        // Readers see the migrated schema; an in-memory database can't be shared this way
+        let pool_size = read_pool_size();
+        if path != ":memory:" && !path.is_empty() && pool_size > 0 {
+            let busy_timeout = format!("PRAGMA busy_timeout={};", pragmas.busy_timeout_ms);
+            let manager = SqliteConnectionManager::file(path)
+                .with_flags(OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX | OpenFlags::SQLITE_OPEN_URI)
+                .with_init(move |c| c.execute_batch(&busy_timeout));
+            // Open one reader up front so a bad path or flag fails startup like the writer would
+            r2d2::ManageConnection::connect(&manager)?;
+            db.readers = Some(r2d2::Pool::builder().max_size(pool_size as u32).build_unchecked(manager));
+        }
         Ok(db)
     }
 
@@ -72,7 +200,7 @@ impl Db {
 
             CREATE TABLE IF NOT EXISTS notification_channels (
                 id TEXT PRIMARY KEY,
-                monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+                monitor_id TEXT REFERENCES monitors(id) ON DELETE CASCADE,
                 name TEXT NOT NULL,
                 channel_type TEXT NOT NULL,
                 config TEXT NOT NULL,
@@ -123,9 +251,9 @@ impl Db {
             );
         ").ok();
 
-        // Auto-generate admin key if not set
+        // Auto-generate admin key on first run; afterwards it changes only via POST /admin/rotate-key
         let has_admin_key: bool = conn
-            .query_row("SELECT COUNT(*) FROM settings WHERE key = 'admin_key'", [], |r| r.get::<_, i64>(0))
+            .query_row("SELECT COUNT(*) FROM settings WHERE key = 'admin_key_hash'", [], |r| r.get::<_, i64>(0))
             .map(|c| c > 0)
             .unwrap_or(false);
         if !has_admin_key {
@@ -275,6 +403,245 @@ impl Db {
             CREATE INDEX IF NOT EXISTS idx_deps_depends_on ON monitor_dependencies(depends_on_id);
         ").ok();
 
+        // Allow global notification channels (monitor_id NULL) — rebuild older tables
+        // where monitor_id was declared NOT NULL (SQLite can't drop a constraint in place)
+        let channel_monitor_notnull: i64 = conn
+            .query_row("SELECT \"notnull\" FROM pragma_table_info('notification_channels') WHERE name = 'monitor_id'", [], |r| r.get(0))
+            .unwrap_or(0);
+        if channel_monitor_notnull != 0 {
+            conn.execute_batch("
+                PRAGMA foreign_keys=OFF;
+                BEGIN;
+                CREATE TABLE notification_channels_new (
+                    id TEXT PRIMARY KEY,
+                    monitor_id TEXT REFERENCES monitors(id) ON DELETE CASCADE,
+                    name TEXT NOT NULL,
+                    channel_type TEXT NOT NULL,
+                    config TEXT NOT NULL,
+                    is_enabled INTEGER NOT NULL DEFAULT 1,
+                    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+                );
+                INSERT INTO notification_channels_new (id, monitor_id, name, channel_type, config, is_enabled, created_at)
+                    SELECT id, monitor_id, name, channel_type, config, is_enabled, created_at FROM notification_channels;
+                DROP TABLE notification_channels;
+                ALTER TABLE notification_channels_new RENAME TO notification_channels;
+                CREATE INDEX IF NOT EXISTS idx_notifications_monitor ON notification_channels(monitor_id);
+                COMMIT;
+                PRAGMA foreign_keys=ON;
+            ")?;
+        }
+
+        // Add routing filter to notification channels (JSON: min_severity, tags)
+        conn.execute_batch("ALTER TABLE notification_channels ADD COLUMN filter TEXT;").ok();
+
+        // Attribute incidents to an upstream dependency that was down when they opened
+        conn.execute_batch("ALTER TABLE incidents ADD COLUMN caused_by_monitor_id TEXT;").ok();
+
+        // Why an incident was resolved, when it wasn't a regular recovery transition
+        conn.execute_batch("ALTER TABLE incidents ADD COLUMN resolution_note TEXT;").ok();
+
+        // Manually opened incidents (source = 'manual') with a severity; they are
+        // only closed by a recovery transition when auto_resolve is set
+        conn.execute_batch("ALTER TABLE incidents ADD COLUMN source TEXT NOT NULL DEFAULT 'check';").ok();
+        conn.execute_batch("ALTER TABLE incidents ADD COLUMN severity TEXT;").ok();
+        conn.execute_batch("ALTER TABLE incidents ADD COLUMN auto_resolve INTEGER NOT NULL DEFAULT 1;").ok();
+
+        // Upcoming-maintenance reminders: lead time, and when the reminder went out
+        conn.execute_batch("ALTER TABLE maintenance_windows ADD COLUMN remind_minutes_before INTEGER;").ok();
+        conn.execute_batch("ALTER TABLE maintenance_windows ADD COLUMN reminder_sent_at TEXT;").ok();
+
+        // Consensus quorum mode: count (default when NULL), majority, or percent
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN consensus_mode TEXT;").ok();
+
+        // Ordered request steps for multistep monitors (JSON array)
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN steps TEXT;").ok();
+
+        // Keyword-absence assertion: check is down if the body contains this string
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN body_not_contains TEXT;").ok();
+
+        // Per-monitor redirect cap and optional final-URL assertion
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN max_redirects INTEGER NOT NULL DEFAULT 10;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN expected_final_url_prefix TEXT;").ok();
+
+        // Response body size assertions (bytes)
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN min_body_bytes INTEGER;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN max_body_bytes INTEGER;").ok();
+
+        // HTTP credentials (auth_type NULL = none); kept out of headers so exports can omit them
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN auth_type TEXT;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN auth_username TEXT;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN auth_password TEXT;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN auth_token TEXT;").ok();
+
+        // Per-monitor outbound proxy for HTTP checks
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN proxy_url TEXT;").ok();
+
+        // DNS answer assertions: minimum record count and minimum TTL (seconds)
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN dns_min_records INTEGER;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN dns_min_ttl INTEGER;").ok();
+
+        // DNS resolver for DNS monitors: NULL = system, else a DoH preset name or endpoint URL
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN dns_resolver TEXT;").ok();
+
+        // TCP probe: bytes written after connecting and a substring the response must contain
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN tcp_send TEXT;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN tcp_expect TEXT;").ok();
+
+        // Soft delete: deleted monitors stay restorable until purged after the grace period
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN deleted_at TEXT;").ok();
+
+        // Archived monitors keep their history but are no longer checked or edited
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0;").ok();
+
+        // Recovery cooldown: the monitor must stay up this long before its incident resolves;
+        // recovery_pending_since marks when a deferred recovery began
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN recovery_cooldown_seconds INTEGER;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN recovery_pending_since TEXT;").ok();
+
+        // Per-direction confirmation: failures to go down (NULL = confirmation_threshold),
+        // successes to come back up (NULL = 1), and the running success count
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN down_confirmation_threshold INTEGER;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN up_confirmation_threshold INTEGER;").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN consecutive_successes INTEGER NOT NULL DEFAULT 0;").ok();
+
+        // Caller-chosen key for idempotent provisioning (PUT /monitors/by-external-id/<key>);
+        // unique among live monitors
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN external_id TEXT;").ok();
+        conn.execute_batch("CREATE UNIQUE INDEX IF NOT EXISTS idx_monitors_external_id ON monitors(external_id) WHERE external_id IS NOT NULL AND deleted_at IS NULL;").ok();
+
+        // Named status page sections (e.g. "API", "Infra"), separate from the monitor's group_name
+        conn.execute_batch("ALTER TABLE status_page_monitors ADD COLUMN section TEXT;").ok();
+
+        // Daily uptime rollups (completed UTC days; today is always computed live)
+        conn.execute_batch("
+            CREATE TABLE IF NOT EXISTS daily_uptime (
+                monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+                day TEXT NOT NULL,
+                total_checks INTEGER NOT NULL,
+                up_checks INTEGER NOT NULL,
+                down_checks INTEGER NOT NULL,
+                up_rt_sum INTEGER NOT NULL,
+                up_rt_count INTEGER NOT NULL,
+                PRIMARY KEY (monitor_id, day)
+            );
+            CREATE INDEX IF NOT EXISTS idx_daily_uptime_day ON daily_uptime(day);
+        ").ok();
+
+        // Hourly aggregates of downsampled (deleted) raw heartbeats
+        conn.execute_batch("
+            CREATE TABLE IF NOT EXISTS heartbeat_hourly (
+                monitor_id TEXT NOT NULL REFERENCES monitors(id) ON DELETE CASCADE,
+                hour TEXT NOT NULL,
+                total_checks INTEGER NOT NULL,
+                up_checks INTEGER NOT NULL,
+                down_checks INTEGER NOT NULL,
+                up_rt_sum INTEGER NOT NULL,
+                up_rt_count INTEGER NOT NULL,
+                min_response_ms INTEGER,
+                max_response_ms INTEGER,
+                PRIMARY KEY (monitor_id, hour)
+            );
+        ").ok();
+
+        // Latency alert rule: avg response over threshold for a window
+        conn.execute_batch("ALTER TABLE alert_rules ADD COLUMN latency_threshold_ms INTEGER").ok();
+        conn.execute_batch("ALTER TABLE alert_rules ADD COLUMN latency_window_minutes INTEGER NOT NULL DEFAULT 5").ok();
+
+        // SLA alert rule: notify when rolling 24h uptime drops below sla_target
+        conn.execute_batch("ALTER TABLE alert_rules ADD COLUMN sla_breach_alert INTEGER NOT NULL DEFAULT 0").ok();
+
+        // Burn-rate alert rule: notify when both the 1h and 24h burn rates reach this multiple
+        conn.execute_batch("ALTER TABLE alert_rules ADD COLUMN burn_rate_threshold REAL").ok();
+
+        // Alert log seq cursor
+        conn.execute_batch("ALTER TABLE alert_log ADD COLUMN seq INTEGER;").ok();
+        conn.execute_batch("CREATE INDEX IF NOT EXISTS idx_alert_log_seq ON alert_log(seq);").ok();
+
+        // Email subscribers of status pages (double opt-in via token)
+        conn.execute_batch("
+            CREATE TABLE IF NOT EXISTS status_page_subscribers (
+                id TEXT PRIMARY KEY,
+                status_page_id TEXT NOT NULL REFERENCES status_pages(id) ON DELETE CASCADE,
+                email TEXT NOT NULL,
+                token TEXT NOT NULL UNIQUE,
+                confirmed INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                confirmed_at TEXT,
+                UNIQUE (status_page_id, email)
+            );
+        ").ok();
+        // Only token hashes are kept: `token` holds the emailed confirm token's hash and
+        // `unsubscribe_hash` the hash of the link token derived in alert emails
+        conn.execute(
+            "INSERT OR IGNORE INTO settings (key, value, updated_at) VALUES ('subscription_link_key', ?1, datetime('now'))",
+            rusqlite::params![crate::auth::generate_key()],
+        ).ok();
+        if conn.execute_batch("ALTER TABLE status_page_subscribers ADD COLUMN unsubscribe_hash TEXT").is_ok() {
+            let mut stmt = conn.prepare("SELECT id, token FROM status_page_subscribers")?;
+            let rows: Vec<(String, String)> = stmt
+                .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+                .filter_map(|r| r.ok())
+                .collect();
+            drop(stmt);
+            for (id, token) in rows {
+                let unsubscribe = crate::notifications::unsubscribe_token(&conn, &id);
+                conn.execute(
+                    "UPDATE status_page_subscribers SET token = ?1, unsubscribe_hash = ?2 WHERE id = ?3",
+                    rusqlite::params![crate::auth::hash_key(&token), crate::auth::hash_key(&unsubscribe), id],
+                ).ok();
+            }
+        }
+        conn.execute_batch("CREATE UNIQUE INDEX IF NOT EXISTS idx_subscribers_unsubscribe_hash ON status_page_subscribers(unsubscribe_hash);").ok();
+
+        // Account-level API keys with scopes (read, write, admin), created by the admin
+        conn.execute_batch("
+            CREATE TABLE IF NOT EXISTS api_keys (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                key_hash TEXT NOT NULL UNIQUE,
+                scopes TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+        ").ok();
+
+        // Audit trail of management actions. monitor_id is not a foreign key so entries outlive the monitor.
+        conn.execute_batch("
+            CREATE TABLE IF NOT EXISTS audit_log (
+                seq INTEGER PRIMARY KEY AUTOINCREMENT,
+                action TEXT NOT NULL,
+                monitor_id TEXT,
+                actor TEXT NOT NULL,
+                changes TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX IF NOT EXISTS idx_audit_log_monitor ON audit_log(monitor_id, seq);
+        ").ok();
+
+        // Why a check failed (timeout, connect_timeout, dns, connection_refused, tls, status, body); null when up or unclassified
+        conn.execute_batch("ALTER TABLE heartbeats ADD COLUMN failure_kind TEXT").ok();
+
+        // Separate limit for DNS + connect + TLS handshake; NULL = half of timeout_ms
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN connect_timeout_ms INTEGER").ok();
+
+        // Operator notes (markdown) shown with the monitor and on status pages
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN description TEXT").ok();
+
+        // Link to the on-call runbook, included in notifications and incident detail
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN runbook_url TEXT").ok();
+
+        // Status required of the response before redirects are followed (e.g. a 301)
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN expected_first_status INTEGER").ok();
+
+        // mTLS client certificate and key (PEM) for HTTP checks; the key is never returned
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN client_cert_pem TEXT").ok();
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN client_key_pem TEXT").ok();
+
+        // Opt-out of TLS certificate verification for self-signed internal endpoints
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN insecure_skip_tls_verify INTEGER NOT NULL DEFAULT 0").ok();
+
+        // Extra CA certificates trusted by a monitor's HTTP checks (private CAs)
+        conn.execute_batch("ALTER TABLE monitors ADD COLUMN ca_bundle_pem TEXT").ok();
+
         // Backfill seq for existing heartbeats
         let needs_hb_backfill: i64 = conn
             .query_row("SELECT COUNT(*) FROM heartbeats WHERE seq IS NULL", [], |r| r.get(0))
@@ -301,6 +668,19 @@ impl Db {
             }
         }
 
+        // Backfill seq for existing alert log entries
+        let needs_alert_backfill: i64 = conn
+            .query_row("SELECT COUNT(*) FROM alert_log WHERE seq IS NULL", [], |r| r.get(0))
+            .unwrap_or(0);
+        if needs_alert_backfill > 0 {
+            let mut stmt = conn.prepare("SELECT id FROM alert_log WHERE seq IS NULL ORDER BY sent_at ASC, rowid ASC").unwrap();
+            let ids: Vec<String> = stmt.query_map([], |row| row.get(0)).unwrap().filter_map(|r| r.ok()).collect();
+            let max_seq: i64 = conn.query_row("SELECT COALESCE(MAX(seq), 0) FROM alert_log", [], |r| r.get(0)).unwrap_or(0);
+            for (i, id) in ids.iter().enumerate() {
+                conn.execute("UPDATE alert_log SET seq = ?1 WHERE id = ?2", params![max_seq + (i as i64) + 1, &id]).ok();
+            }
+        }
+
         Ok(())
     }
 }
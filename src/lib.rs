@@ -4,6 +4,10 @@ pub mod auth;
 pub mod routes;
 pub mod checker;
 pub mod consensus;
+pub mod blackbox;
+pub mod causes;
 pub mod notifications;
 pub mod sse;
+pub mod compression;
+pub mod logging;
 pub mod catchers;
@@ -0,0 +1,120 @@
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::{Data, Request, Response};
+use std::time::Instant;
+use tracing::{Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::fmt;
+use tracing_subscriber::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    /// LOG_FORMAT=json for one JSON object per line; anything else is human-readable text.
+    pub fn from_env() -> Self {
+        match std::env::var("LOG_FORMAT") {
+            Ok(v) if v.trim().eq_ignore_ascii_case("json") => LogFormat::Json,
+            _ => LogFormat::Text,
+        }
+    }
+}
+
+/// Info-level subscriber in the given format. Rocket's own per-request chatter is held to
+/// warnings; `RequestLogger` emits one structured line per request instead.
+pub fn subscriber(format: LogFormat) -> Box<dyn Subscriber + Send + Sync> {
+    let filter = Targets::new()
+        .with_default(Level::INFO)
+        .with_target("rocket", Level::WARN);
+    let registry = tracing_subscriber::registry().with(filter);
+    match format {
+        LogFormat::Json => Box::new(registry.with(fmt::layer().json().flatten_event(true))),
+        LogFormat::Text => Box::new(registry.with(fmt::layer().with_target(false))),
+    }
+}
+
+/// Install the global subscriber (also capturing `log` records from Rocket and its dependencies).
+/// Returns an error if a subscriber was already installed.
+pub fn init(format: LogFormat) -> Result<(), String> {
+    use tracing_subscriber::util::SubscriberInitExt;
+    subscriber(format).try_init().map_err(|e| e.to_string())
+}
+
+/// Assigns every request an id (the caller's `X-Request-Id` if it sent a sane one) and echoes
+/// it on the response, so a client-reported id can be matched to the server's log line.
+pub struct RequestIds;
+
+struct RequestId(String);
+
+/// Longest caller-supplied id that is echoed rather than replaced.
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The id assigned to this request; generated on first use if `RequestIds` isn't attached.
+pub fn request_id<'r>(request: &'r Request<'_>) -> &'r str {
+    &request.local_cache(|| RequestId(uuid::Uuid::new_v4().to_string())).0
+}
+
+#[rocket::async_trait]
+impl Fairing for RequestIds {
+    fn info(&self) -> Info {
+        Info { name: "Request ids", kind: Kind::Request | Kind::Response }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        let provided = request.headers().get_one("X-Request-Id")
+            .map(str::trim)
+            .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic()))
+            .map(str::to_string);
+        let id = provided.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        request.local_cache(|| RequestId(id));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        response.set_header(Header::new("X-Request-Id", request_id(request).to_string()));
+    }
+}
+
+/// Logs method, path, status, handling time, and request id for every request.
+pub struct RequestLogger;
+
+struct RequestStart(Instant);
+
+#[rocket::async_trait]
+impl Fairing for RequestLogger {
+    fn info(&self) -> Info {
+        Info { name: "Request logger", kind: Kind::Request | Kind::Response }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        request.local_cache(|| RequestStart(Instant::now()));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let started = request.local_cache(|| RequestStart(Instant::now()));
+        tracing::info!(
+            request_id = request_id(request),
+            method = %request.method(),
+            path = %request.uri().path(),
+            status = response.status().code,
+            duration_ms = started.0.elapsed().as_millis() as u64,
+            "request handled"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriber_initializes_for_both_formats() {
+        for format in [LogFormat::Text, LogFormat::Json] {
+            tracing::subscriber::with_default(subscriber(format), || {
+                tracing::info!(monitor_id = "m-1", status = "up", duration_ms = 12u64, "check completed");
+            });
+        }
+    }
+}
@@ -6,8 +6,12 @@ mod auth;
 mod routes;
 mod checker;
 mod consensus;
+mod blackbox;
+mod causes;
 mod notifications;
 mod sse;
+mod compression;
+mod logging;
 mod catchers;
 
 use std::path::PathBuf;
@@ -19,6 +23,8 @@ use rocket_cors::{AllowedOrigins, CorsOptions};
 #[launch]
 fn rocket() -> _ {
     dotenvy::dotenv().ok();
+    // Only fails if a subscriber is already installed, which then keeps receiving our events
+    logging::init(logging::LogFormat::from_env()).ok();
 
     let db_path = std::env::var("DATABASE_PATH").unwrap_or_else(|_| "watchpost.db".into());
     let database = Arc::new(Db::new(&db_path).expect("Failed to initialize database"));
@@ -31,8 +37,18 @@ fn rocket() -> _ {
     let rate_limiter = routes::RateLimiter::new(rate_limit, 3600);
     let broadcaster = Arc::new(sse::EventBroadcaster::new(256));
 
+    // Shared by the scheduled checker and on-demand checks (check-now)
+    let clients = Arc::new(checker::CheckerClients::new().expect("Failed to build checker HTTP clients"));
+
     let checker_db = database.clone();
     let checker_broadcaster = broadcaster.clone();
+    let checker_clients = clients.clone();
+    let checker_health = Arc::new(checker::CheckerHealth::default());
+    let check_states = Arc::new(checker::CheckStates::default());
+    let checker_states = check_states.clone();
+    let graphql_schema = routes::build_graphql_schema(database.clone());
+    let checker_task: Arc<std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>> = Arc::default();
+    let checker_task_on_shutdown = checker_task.clone();
 
     let cors = CorsOptions::default()
         .allowed_origins(AllowedOrigins::all())
@@ -58,10 +74,19 @@ fn rocket() -> _ {
         .manage(database)
         .manage(rate_limiter)
         .manage(broadcaster)
+        .manage(clients)
+        .manage(checker_health.clone())
+        .manage(check_states)
+        .manage(graphql_schema)
         .mount("/api/v1", routes![
             routes::health,
             routes::create_monitor,
+            routes::upsert_monitor_by_external_id,
             routes::bulk_create_monitors,
+            routes::import_blackbox_targets,
+            routes::bulk_monitor_action,
+            routes::pause_all_monitors,
+            routes::resume_all_monitors,
             routes::export_monitor,
             routes::list_monitors,
             routes::get_monitor,
@@ -69,15 +94,34 @@ fn rocket() -> _ {
             routes::delete_monitor,
             routes::pause_monitor,
             routes::resume_monitor,
+            routes::rotate_monitor_key,
+            routes::restore_monitor,
+            routes::check_monitor_now,
+            routes::get_check_state,
+            routes::archive_monitor,
+            routes::unarchive_monitor,
             routes::get_heartbeats,
             routes::get_uptime,
+            routes::get_latency_histogram,
+            routes::graphql_query,
             routes::get_incidents,
+            routes::list_incidents,
+            routes::create_manual_incident,
+            routes::resolve_incident,
             routes::get_incident,
             routes::acknowledge_incident,
             routes::create_incident_note,
             routes::list_incident_notes,
             routes::dashboard,
             routes::admin_verify,
+            routes::admin_stats,
+            routes::admin_prune,
+            routes::admin_vacuum,
+            routes::rotate_admin_key,
+            routes::create_api_key,
+            routes::list_api_keys,
+            routes::delete_api_key,
+            routes::list_audit_log,
             routes::uptime_history,
             routes::monitor_uptime_history,
             routes::status_page,
@@ -85,8 +129,12 @@ fn rocket() -> _ {
             routes::list_notifications,
             routes::delete_notification,
             routes::update_notification,
+            routes::create_global_notification,
+            routes::list_global_notifications,
             routes::list_tags,
+            routes::rename_tag,
             routes::list_groups,
+            routes::get_group_uptime,
             routes::get_settings,
             routes::update_settings,
             routes::create_maintenance_window,
@@ -95,29 +143,41 @@ fn rocket() -> _ {
             routes::llms_txt,
             routes::openapi_spec,
             routes::monitor_uptime_badge,
+            routes::monitor_uptime_badge_json,
             routes::monitor_status_badge,
+            routes::monitor_response_badge,
             routes::monitor_sla,
+            routes::monitor_sla_report,
             routes::global_events,
             routes::monitor_events,
+            routes::ws_events,
             routes::create_location,
             routes::list_locations,
             routes::get_location,
+            routes::update_location,
             routes::delete_location,
             routes::submit_probe,
             routes::monitor_location_status,
+            routes::monitor_location_uptime,
             routes::monitor_consensus,
             routes::create_status_page,
             routes::list_status_pages,
             routes::get_status_page_detail,
+            routes::get_status_page_by_slug,
+            routes::status_page_html,
             routes::update_status_page,
             routes::delete_status_page,
             routes::add_page_monitors,
             routes::remove_page_monitor,
             routes::list_page_monitors,
+            routes::subscribe_to_page,
+            routes::confirm_subscription,
+            routes::unsubscribe_from_page,
             routes::set_alert_rules,
             routes::get_alert_rules,
             routes::delete_alert_rules,
             routes::get_alert_log,
+            routes::list_alert_log,
             routes::list_webhook_deliveries,
             routes::add_dependency,
             routes::list_dependencies,
@@ -137,18 +197,34 @@ fn rocket() -> _ {
         .attach(rocket::fairing::AdHoc::on_liftoff("Checker", move |rocket| {
             Box::pin(async move {
                 let shutdown = rocket.shutdown();
-                println!("🚀 Spawning checker task...");
-                let handle = tokio::spawn(checker::run_checker(checker_db, checker_broadcaster, shutdown));
-                // Monitor the checker task for unexpected exits
-                tokio::spawn(async move {
-                    match handle.await {
-                        Ok(()) => eprintln!("⚠️  Checker task exited normally (unexpected)"),
-                        Err(e) => eprintln!("❌ Checker task failed: {e}"),
+                tracing::info!("Spawning checker task");
+                // Respawn the checker if it ever exits or panics before shutdown
+                let spawn_checker = {
+                    let (health, shutdown) = (checker_health.clone(), shutdown.clone());
+                    move || checker::run_checker(checker_db.clone(), checker_broadcaster.clone(), checker_clients.clone(), health.clone(), checker_states.clone(), shutdown.clone())
+                };
+                let supervisor = tokio::spawn(checker::supervise("Checker", spawn_checker, checker_health, std::time::Duration::from_secs(1), shutdown));
+                *checker_task.lock().unwrap() = Some(supervisor);
+            })
+        }))
+        .attach(rocket::fairing::AdHoc::on_shutdown("Checker drain", move |_| {
+            Box::pin(async move {
+                // Hold the runtime open while the checker finishes in-flight checks
+                let supervisor = checker_task_on_shutdown.lock().unwrap().take();
+                if let Some(supervisor) = supervisor {
+                    let grace = checker::shutdown_grace() + std::time::Duration::from_secs(1);
+                    if tokio::time::timeout(grace, supervisor).await.is_err() {
+                        tracing::warn!(grace_secs = grace.as_secs(), "Checker did not stop within the shutdown grace period");
                     }
-                });
+                }
             })
         }));
 
+    build = build.attach(logging::RequestIds).attach(logging::RequestLogger);
+    if let Some(compression) = compression::ResponseCompression::from_env() {
+        build = build.attach(compression);
+    }
+
     // Well-known skills discovery (mounted at root, outside /api/v1)
     build = build.mount("/", routes![
         routes::skill_md,
@@ -159,15 +235,12 @@ fn rocket() -> _ {
 
     // Serve frontend static files if the directory exists
     if static_dir.is_dir() {
-        println!("📦 Serving frontend from: {}", static_dir.display());
+        tracing::info!(dir = %static_dir.display(), "Serving frontend");
         build = build
             .mount("/", FileServer::new(&static_dir, Options::Index))
             .mount("/", routes![routes::spa_fallback]);
     } else {
-        println!(
-            "⚠️  Frontend directory not found: {} (API-only mode)",
-            static_dir.display()
-        );
+        tracing::warn!(dir = %static_dir.display(), "Frontend directory not found (API-only mode)");
     }
 
     build
@@ -73,101 +73,299 @@ where
 pub struct Monitor {
     pub id: String,
     pub name: String,
+    /// Operator notes in markdown (context, runbook links)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Linked from every notification and the incident detail
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runbook_url: Option<String>,
     pub url: String,
     pub monitor_type: String,
     pub method: String,
     pub interval_seconds: u32,
     pub timeout_ms: u32,
+    /// Limit on DNS, TCP connect, and TLS handshake; unset means half of `timeout_ms`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_ms: Option<u32>,
     pub expected_status: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body_contains: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_not_contains: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_body_bytes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_body_bytes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<serde_json::Value>,
     pub is_public: bool,
     pub is_paused: bool,
+    pub is_archived: bool,
     pub current_status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_checked_at: Option<String>,
+    /// When the checker is scheduled to check next (interval plus any jitter); absent while paused or unchecked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_check_at: Option<String>,
     pub confirmation_threshold: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub down_confirmation_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub up_confirmation_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_cooldown_seconds: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub response_time_threshold_ms: Option<u32>,
     pub follow_redirects: bool,
+    pub max_redirects: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_final_url_prefix: Option<String>,
+    /// Status the response must have before any redirect is followed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_first_status: Option<u16>,
+    /// `basic` or `bearer`; absent when the monitor sends no credentials.
+    /// Passwords and tokens are never returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_username: Option<String>,
+    /// mTLS client certificate (PEM); its private key is never returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert_pem: Option<String>,
+    /// Extra CA certificates (PEM) trusted for this monitor's checks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle_pem: Option<String>,
+    /// TLS certificate verification is off for this monitor's checks
+    pub insecure_skip_tls_verify: bool,
+    /// Risky settings in effect, spelled out so they aren't missed
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// HTTP(S) proxy for checks; any password in it is masked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_url: Option<String>,
     pub dns_record_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dns_expected: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_min_records: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_min_ttl: Option<u32>,
+    /// `cloudflare`, `google`, or a DoH endpoint URL; absent for the system resolver
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_resolver: Option<String>,
+    /// Masked as `****` unless the caller has read access
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcp_send: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcp_expect: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sla_target: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sla_period_days: Option<u32>,
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group_name: Option<String>,
+    /// Caller-chosen provisioning key (see `PUT /monitors/by-external-id/<key>`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consensus_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<CheckStep>>,
     pub created_at: String,
     pub updated_at: String,
 }
 
+/// One request in a `multistep` monitor's sequence.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CheckStep {
+    #[serde(default = "default_method")]
+    pub method: String,
+    /// May reference values extracted by earlier steps as `{{name}}`
+    pub url: String,
+    #[serde(default = "default_step_status")]
+    pub expected_status: u16,
+    /// Header values may reference extracted values as `{{name}}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extract: Option<StepExtract>,
+}
+
+/// Capture a response header or cookie from a step for use by later steps.
+/// Exactly one of `header` / `cookie` must be set.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StepExtract {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cookie: Option<String>,
+    #[serde(rename = "as")]
+    pub name: String,
+}
+
+fn default_step_status() -> u16 { 200 }
+
 #[derive(Debug, Deserialize)]
 pub struct CreateMonitor {
     pub name: String,
+    /// Markdown notes; blank means none
+    pub description: Option<String>,
+    /// http(s) URL; blank means none
+    pub runbook_url: Option<String>,
     pub url: String,
     #[serde(default = "default_monitor_type")]
     pub monitor_type: Option<String>,
     #[serde(default = "default_method")]
     pub method: String,
-    #[serde(default = "default_interval")]
+    /// Omitted → the admin-configured default (see `monitor_defaults` in settings)
     pub interval_seconds: Option<u32>,
-    #[serde(default = "default_timeout")]
     pub timeout_ms: Option<u32>,
+    /// Omitted → half of `timeout_ms`
+    pub connect_timeout_ms: Option<u32>,
     #[serde(default = "default_status")]
     pub expected_status: Option<u16>,
     pub body_contains: Option<String>,
+    pub body_not_contains: Option<String>,
+    pub min_body_bytes: Option<u32>,
+    pub max_body_bytes: Option<u32>,
     pub headers: Option<serde_json::Value>,
     #[serde(default)]
     pub is_public: bool,
     pub confirmation_threshold: Option<u32>,
+    /// Consecutive failures before going down (default: `confirmation_threshold`)
+    pub down_confirmation_threshold: Option<u32>,
+    /// Consecutive successes before a down monitor recovers (default: 1)
+    pub up_confirmation_threshold: Option<u32>,
+    /// Seconds the monitor must stay up before its incident resolves and the recovery alert is sent
+    pub recovery_cooldown_seconds: Option<u32>,
     pub response_time_threshold_ms: Option<u32>,
     #[serde(default = "default_follow_redirects")]
     pub follow_redirects: Option<bool>,
+    pub max_redirects: Option<u32>,
+    pub expected_final_url_prefix: Option<String>,
+    /// 100-599; checked before redirects are followed
+    pub expected_first_status: Option<u16>,
+    pub auth_type: Option<String>,
+    pub auth_username: Option<String>,
+    pub auth_password: Option<String>,
+    pub auth_token: Option<String>,
+    /// mTLS client certificate and private key (PEM); both or neither
+    pub client_cert_pem: Option<String>,
+    pub client_key_pem: Option<String>,
+    /// Extra CA certificates (PEM) to trust, e.g. a private CA
+    pub ca_bundle_pem: Option<String>,
+    /// Skip TLS certificate verification (self-signed internal endpoints)
+    #[serde(default)]
+    pub insecure_skip_tls_verify: bool,
+    pub proxy_url: Option<String>,
     #[serde(default = "default_dns_record_type")]
     pub dns_record_type: Option<String>,
     pub dns_expected: Option<String>,
+    /// DNS check is down with fewer answer records than this
+    pub dns_min_records: Option<u32>,
+    /// DNS check is degraded if any answer's TTL (seconds) is below this
+    pub dns_min_ttl: Option<u32>,
+    /// `system` (default), `cloudflare`, `google`, or an https:// DoH JSON endpoint
+    pub dns_resolver: Option<String>,
+    /// TCP monitors: written after connecting (e.g. "PING\r\n")
+    pub tcp_send: Option<String>,
+    /// TCP monitors: the response must contain this, else the check is down
+    pub tcp_expect: Option<String>,
     pub sla_target: Option<f64>,
     pub sla_period_days: Option<u32>,
     #[serde(default, deserialize_with = "deserialize_flexible_tags")]
     pub tags: Vec<String>,
     pub group_name: Option<String>,
+    /// Unique caller-chosen key, e.g. a Terraform resource address
+    pub external_id: Option<String>,
     pub consensus_threshold: Option<u32>,
+    pub consensus_mode: Option<String>,
+    pub steps: Option<Vec<CheckStep>>,
 }
 
 fn default_follow_redirects() -> Option<bool> { Some(true) }
 fn default_monitor_type() -> Option<String> { Some("http".into()) }
 fn default_dns_record_type() -> Option<String> { Some("A".into()) }
 fn default_method() -> String { "GET".into() }
-fn default_interval() -> Option<u32> { Some(600) }
-fn default_timeout() -> Option<u32> { Some(10000) }
 fn default_status() -> Option<u16> { Some(200) }
 
 #[derive(Debug, Deserialize)]
 pub struct UpdateMonitor {
     pub name: Option<String>,
+    /// "" or null clears
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub description: Option<Option<String>>,
+    /// "" or null clears
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub runbook_url: Option<Option<String>>,
     pub url: Option<String>,
     pub monitor_type: Option<String>,
     pub method: Option<String>,
     pub interval_seconds: Option<u32>,
     pub timeout_ms: Option<u32>,
+    /// null → back to half of `timeout_ms`
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub connect_timeout_ms: Option<Option<u32>>,
     pub expected_status: Option<u16>,
     pub body_contains: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub body_not_contains: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub min_body_bytes: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub max_body_bytes: Option<Option<u32>>,
     pub headers: Option<serde_json::Value>,
     pub is_public: Option<bool>,
     pub confirmation_threshold: Option<u32>,
     #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub down_confirmation_threshold: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub up_confirmation_threshold: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub recovery_cooldown_seconds: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
     pub response_time_threshold_ms: Option<Option<u32>>,
     pub follow_redirects: Option<bool>,
+    pub max_redirects: Option<u32>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub expected_final_url_prefix: Option<Option<String>>,
+    /// null clears
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub expected_first_status: Option<Option<u16>>,
+    /// Set to `none` to remove stored credentials
+    pub auth_type: Option<String>,
+    pub auth_username: Option<String>,
+    pub auth_password: Option<String>,
+    pub auth_token: Option<String>,
+    /// Set both to "" to remove the client certificate
+    pub client_cert_pem: Option<String>,
+    pub client_key_pem: Option<String>,
+    /// null or empty string removes the CA bundle
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub ca_bundle_pem: Option<Option<String>>,
+    pub insecure_skip_tls_verify: Option<bool>,
+    /// null or empty string removes the proxy
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub proxy_url: Option<Option<String>>,
     pub dns_record_type: Option<String>,
     pub dns_expected: Option<String>,
     #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub dns_min_records: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub dns_min_ttl: Option<Option<u32>>,
+    /// null, empty string, or `system` switches back to the system resolver
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub dns_resolver: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub tcp_send: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub tcp_expect: Option<Option<String>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
     pub sla_target: Option<Option<f64>>,
     #[serde(default, deserialize_with = "deserialize_optional_nullable")]
     pub sla_period_days: Option<Option<u32>>,
@@ -176,6 +374,9 @@ pub struct UpdateMonitor {
     pub group_name: Option<String>,
     #[serde(default, deserialize_with = "deserialize_optional_nullable")]
     pub consensus_threshold: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub consensus_mode: Option<Option<String>>,
+    pub steps: Option<Vec<CheckStep>>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -188,12 +389,39 @@ pub struct Heartbeat {
     pub status_code: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+    /// timeout, connect_timeout, dns, connection_refused, tls, status, or body
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_kind: Option<String>,
     pub checked_at: String,
     pub seq: i64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub location_id: Option<String>,
 }
 
+/// What the checker last recorded for a monitor (kept in memory, not persisted).
+#[derive(Debug, Serialize, Clone)]
+pub struct MonitorCheckState {
+    /// Raw status of the last check, before confirmation
+    pub last_result: String,
+    pub consecutive_failures: u32,
+    pub consecutive_successes: u32,
+    pub down_confirmation_threshold: u32,
+    pub up_confirmation_threshold: u32,
+    /// Wall time of the last check, including DNS, connect, and TLS
+    pub last_check_duration_ms: u64,
+    pub last_checked_at: String,
+    pub next_due_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckStateResponse {
+    pub monitor_id: String,
+    pub current_status: String,
+    pub is_paused: bool,
+    /// None until the checker has run this monitor since the server started
+    pub state: Option<MonitorCheckState>,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct Incident {
     pub id: String,
@@ -208,9 +436,49 @@ pub struct Incident {
     pub acknowledged_by: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub acknowledged_at: Option<String>,
+    /// Upstream dependency this outage is attributed to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caused_by_monitor_id: Option<String>,
+    /// Set when the incident was closed by something other than a recovery transition
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resolution_note: Option<String>,
+    /// "check" when opened by the checker, "manual" when posted via the API
+    pub source: String,
+    /// Severity of a manual incident: "info", "warning", or "critical"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    /// Whether the next recovery transition closes this incident
+    pub auto_resolve: bool,
     pub seq: i64,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct CreateManualIncident {
+    pub cause: String,
+    /// "info", "warning", or "critical" (default)
+    #[serde(default)]
+    pub severity: Option<String>,
+    /// Let the checker close this incident on the next recovery (default: false)
+    #[serde(default)]
+    pub auto_resolve: bool,
+    #[serde(default = "default_actor")]
+    pub actor: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveIncident {
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default = "default_actor")]
+    pub actor: String,
+}
+
+impl Default for ResolveIncident {
+    fn default() -> Self {
+        ResolveIncident { note: None, actor: default_actor() }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AcknowledgeIncident {
     pub note: String,
@@ -229,6 +497,23 @@ pub struct CreateMonitorResponse {
     pub api_base: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct LatencyHistogram {
+    pub monitor_id: String,
+    pub period: String,
+    /// Successful checks counted across all buckets
+    pub total: u32,
+    pub buckets: Vec<LatencyBucket>,
+}
+
+/// Checks whose response time fell above the previous bucket's bound and at most `le_ms`.
+#[derive(Debug, Serialize)]
+pub struct LatencyBucket {
+    /// Upper bound in ms; null for the overflow bucket
+    pub le_ms: Option<u32>,
+    pub count: u32,
+}
+
 #[derive(Debug, Serialize)]
 pub struct UptimeStats {
     pub monitor_id: String,
@@ -241,6 +526,10 @@ pub struct UptimeStats {
     pub total_checks_30d: u32,
     pub total_checks_90d: u32,
     pub avg_response_ms_24h: Option<f64>,
+    /// Response-time percentiles of up checks in the last 24 hours (nearest rank)
+    pub p50_response_ms: Option<u32>,
+    pub p95_response_ms: Option<u32>,
+    pub p99_response_ms: Option<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -251,10 +540,12 @@ pub struct StatusOverview {
     pub branding: Option<StatusPageBranding>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Clone)]
 pub struct StatusMonitor {
     pub id: String,
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
     pub url: String,
     pub current_status: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -267,24 +558,61 @@ pub struct StatusMonitor {
     pub tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub group_name: Option<String>,
+    /// Status page section the monitor is listed under (status page views only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct NotificationChannel {
     pub id: String,
-    pub monitor_id: String,
+    /// None for global channels that receive alerts for every monitor
+    pub monitor_id: Option<String>,
     pub name: String,
     pub channel_type: String,
     pub config: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ChannelFilter>,
     pub is_enabled: bool,
     pub created_at: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RenameTag {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenameTagResponse {
+    pub from: String,
+    pub to: String,
+    /// Monitors whose tags changed
+    pub monitors_updated: usize,
+    /// Of those, monitors that already had `to`, so the two tags merged
+    pub merged: usize,
+    /// Notification channels whose tag filter changed
+    pub channels_updated: usize,
+}
+
+/// Routing filter for a notification channel. A channel with no filter fires for everything.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct ChannelFilter {
+    /// Minimum event severity: "info", "warning", or "critical"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_severity: Option<String>,
+    /// Only fire for monitors carrying at least one of these tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateNotification {
     pub name: String,
     pub channel_type: String,
     pub config: serde_json::Value,
+    #[serde(default)]
+    pub filter: Option<ChannelFilter>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -308,6 +636,37 @@ pub struct BulkError {
     pub code: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct BulkAction {
+    /// pause, resume, or delete
+    pub action: String,
+    /// Act on every monitor carrying this tag
+    pub tag: Option<String>,
+    /// Or on these monitors
+    pub ids: Option<Vec<String>>,
+    /// Manage key per monitor id, when not using an admin or API key
+    #[serde(default)]
+    pub manage_keys: std::collections::HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkActionResult {
+    pub id: String,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkActionResponse {
+    pub action: String,
+    pub results: Vec<BulkActionResult>,
+    pub succeeded: usize,
+    pub failed: usize,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct MaintenanceWindow {
     pub id: String,
@@ -316,6 +675,9 @@ pub struct MaintenanceWindow {
     pub starts_at: String,
     pub ends_at: String,
     pub active: bool,
+    /// Send a `maintenance.upcoming` notification this many minutes before `starts_at`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remind_minutes_before: Option<u32>,
     pub created_at: String,
 }
 
@@ -324,6 +686,20 @@ pub struct CreateMaintenanceWindow {
     pub title: String,
     pub starts_at: String,
     pub ends_at: String,
+    #[serde(default)]
+    pub remind_minutes_before: Option<u32>,
+}
+
+/// Storage footprint for GET /admin/stats.
+#[derive(Debug, Serialize)]
+pub struct DbStats {
+    pub monitors: u64,
+    pub heartbeats: u64,
+    pub incidents: u64,
+    pub webhook_deliveries: u64,
+    /// Database size in bytes (page_count × page_size, i.e. the file once the WAL is checkpointed)
+    pub db_size_bytes: u64,
+    pub oldest_heartbeat_at: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -331,6 +707,7 @@ pub struct DashboardOverview {
     pub total_monitors: u32,
     pub public_monitors: u32,
     pub paused_monitors: u32,
+    pub archived_monitors: u32,
     pub status_counts: StatusCounts,
     pub active_incidents: u32,
     pub avg_uptime_24h: f64,
@@ -372,25 +749,78 @@ pub struct SlowMonitor {
 #[derive(Debug, Serialize)]
 pub struct ExportedMonitor {
     pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runbook_url: Option<String>,
     pub url: String,
     pub monitor_type: String,
     pub method: String,
     pub interval_seconds: u32,
     pub timeout_ms: u32,
+    /// Limit on DNS, TCP connect, and TLS handshake; unset means half of `timeout_ms`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_ms: Option<u32>,
     pub expected_status: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub body_contains: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_not_contains: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_body_bytes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_body_bytes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub headers: Option<serde_json::Value>,
     pub is_public: bool,
     pub confirmation_threshold: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub down_confirmation_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub up_confirmation_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recovery_cooldown_seconds: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub response_time_threshold_ms: Option<u32>,
     pub follow_redirects: bool,
+    pub max_redirects: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_final_url_prefix: Option<String>,
+    /// Status the response must have before any redirect is followed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_first_status: Option<u16>,
+    /// `basic` or `bearer`; absent when the monitor sends no credentials.
+    /// Passwords and tokens are never returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth_username: Option<String>,
+    /// mTLS client certificate (PEM); its private key is never returned
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert_pem: Option<String>,
+    /// Extra CA certificates (PEM) trusted for this monitor's checks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle_pem: Option<String>,
+    /// TLS certificate verification is off for this monitor's checks
+    pub insecure_skip_tls_verify: bool,
+    /// HTTP(S) proxy for checks; any password in it is masked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy_url: Option<String>,
     pub dns_record_type: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dns_expected: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_min_records: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_min_ttl: Option<u32>,
+    /// `cloudflare`, `google`, or a DoH endpoint URL; absent for the system resolver
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_resolver: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcp_send: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tcp_expect: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub sla_target: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sla_period_days: Option<u32>,
@@ -399,6 +829,10 @@ pub struct ExportedMonitor {
     pub group_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub consensus_threshold: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<CheckStep>>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -416,6 +850,32 @@ pub struct UpdateSettings {
     pub title: Option<String>,
     pub description: Option<String>,
     pub logo_url: Option<String>,
+    /// Headers sent with every HTTP check; `{}` clears them
+    pub default_headers: Option<serde_json::Value>,
+    /// Incident cause wording by template name (down, timeout, status, dependency, consensus); `{}` restores the built-ins
+    pub incident_cause_templates: Option<serde_json::Value>,
+    /// Values used when a new monitor omits them; a null field resets it
+    pub monitor_defaults: Option<UpdateMonitorDefaults>,
+    /// How unknown monitors count toward status page overalls: unknown, operational, or excluded; "" resets
+    pub unknown_status_treatment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMonitorDefaults {
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub interval_seconds: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub timeout_ms: Option<Option<u32>>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub confirmation_threshold: Option<Option<u32>>,
+}
+
+/// Effective defaults for new monitors (settings overrides, else built-ins).
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct MonitorDefaults {
+    pub interval_seconds: u32,
+    pub timeout_ms: u32,
+    pub confirmation_threshold: u32,
 }
 
 #[derive(Debug, Serialize)]
@@ -423,6 +883,10 @@ pub struct SettingsResponse {
     pub title: Option<String>,
     pub description: Option<String>,
     pub logo_url: Option<String>,
+    pub default_headers: Option<serde_json::Value>,
+    pub incident_cause_templates: Option<serde_json::Value>,
+    pub monitor_defaults: MonitorDefaults,
+    pub unknown_status_treatment: String,
 }
 
 #[derive(Debug, Serialize)]
@@ -434,14 +898,57 @@ pub struct SlaStatus {
     pub total_checks: u32,
     pub successful_checks: u32,
     pub downtime_estimate_seconds: f64,
+    /// Planned downtime (maintenance checks) left out of the SLA
+    pub excluded_maintenance_seconds: f64,
     pub budget_total_seconds: f64,
     pub budget_remaining_seconds: f64,
     pub budget_used_pct: f64,
+    /// Error budget burn rate over the last hour (1.0 = on pace to use exactly the budget)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub burn_rate_1h: Option<f64>,
+    /// Error budget burn rate over the last 24 hours
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub burn_rate_24h: Option<f64>,
     pub status: String,
     pub period_start: String,
     pub period_end: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct SlaReport {
+    pub monitor_id: String,
+    /// week or month
+    pub period: String,
+    pub period_days: u32,
+    pub period_start: String,
+    pub period_end: String,
+    pub target_pct: f64,
+    pub total_checks: u32,
+    pub successful_checks: u32,
+    pub current_pct: f64,
+    /// met or breached
+    pub status: String,
+    pub days: Vec<SlaReportDay>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlaReportDay {
+    pub date: String,
+    pub total_checks: u32,
+    pub successful_checks: u32,
+    pub uptime_pct: f64,
+}
+
+/// Shields.io endpoint badge schema (https://shields.io/badges/endpoint-badge).
+#[derive(Debug, Serialize)]
+pub struct ShieldsEndpoint {
+    #[serde(rename = "schemaVersion")]
+    pub schema_version: u8,
+    pub label: String,
+    pub message: String,
+    pub color: String,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct IncidentNote {
     pub id: String,
@@ -458,6 +965,23 @@ pub struct CreateIncidentNote {
     pub author: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct GroupUptime {
+    pub group: String,
+    pub monitor_count: u32,
+    /// Pooled across the group's monitors: up checks / all checks
+    pub uptime_24h: f64,
+    pub uptime_7d: f64,
+    pub uptime_30d: f64,
+    pub total_checks_24h: u32,
+    pub total_checks_7d: u32,
+    pub total_checks_30d: u32,
+    /// Mean response time of up checks
+    pub avg_response_ms_24h: Option<f64>,
+    pub avg_response_ms_7d: Option<f64>,
+    pub avg_response_ms_30d: Option<f64>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct UptimeHistoryDay {
     pub date: String,
@@ -517,6 +1041,14 @@ pub struct CreateCheckLocation {
     pub region: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct UpdateCheckLocation {
+    pub name: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_optional_nullable")]
+    pub region: Option<Option<String>>,
+    pub is_active: Option<bool>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CreateCheckLocationResponse {
     pub location: CheckLocation,
@@ -563,10 +1095,27 @@ pub struct MonitorLocationStatus {
     pub last_checked_at: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct LocationUptimeStats {
+    pub monitor_id: String,
+    pub location_id: String,
+    pub location_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    pub uptime_24h: f64,
+    pub uptime_7d: f64,
+    pub total_checks_24h: u32,
+    pub total_checks_7d: u32,
+    pub avg_response_ms_24h: Option<f64>,
+    pub avg_response_ms_7d: Option<f64>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct ConsensusStatus {
     pub monitor_id: String,
-    pub consensus_threshold: u32,
+    pub consensus_mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consensus_threshold: Option<u32>,
     pub total_locations: u32,
     pub up_count: u32,
     pub down_count: u32,
@@ -648,14 +1197,31 @@ pub struct StatusPageDetail {
     pub custom_domain: Option<String>,
     pub is_public: bool,
     pub monitors: Vec<StatusMonitor>,
+    /// Monitors grouped by section, named sections first; unsectioned monitors come last under a null name
+    pub sections: Vec<StatusPageSection>,
+    /// Maintenance windows currently in effect for monitors on this page
+    pub maintenance: Vec<MaintenanceWindow>,
     pub overall: String,
     pub created_at: String,
     pub updated_at: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct StatusPageSection {
+    pub name: Option<String>,
+    pub monitors: Vec<StatusMonitor>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AddMonitorsToPage {
     pub monitor_ids: Vec<String>,
+    /// Section to list the monitors under; re-adding an assigned monitor moves it
+    pub section: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeToPage {
+    pub email: String,
 }
 
 // ── Alert Rules ──
@@ -666,6 +1232,12 @@ pub struct AlertRule {
     pub repeat_interval_minutes: u32,
     pub max_repeats: u32,
     pub escalation_after_minutes: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_threshold_ms: Option<u32>,
+    pub latency_window_minutes: u32,
+    pub sla_breach_alert: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub burn_rate_threshold: Option<f64>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -681,9 +1253,22 @@ pub struct CreateAlertRule {
     /// Minutes before escalation if incident not acknowledged. 0 = disabled.
     #[serde(default)]
     pub escalation_after_minutes: u32,
+    /// Alert when average response time exceeds this many ms. None = disabled.
+    #[serde(default)]
+    pub latency_threshold_ms: Option<u32>,
+    /// Window the latency average is taken over. Default 5.
+    #[serde(default = "default_latency_window_minutes")]
+    pub latency_window_minutes: u32,
+    /// Alert when rolling 24h uptime falls below the monitor's sla_target.
+    #[serde(default)]
+    pub sla_breach_alert: bool,
+    /// Alert when both the 1h and 24h error budget burn rates reach this multiple. None = disabled.
+    #[serde(default)]
+    pub burn_rate_threshold: Option<f64>,
 }
 
 fn default_max_repeats() -> u32 { 10 }
+fn default_latency_window_minutes() -> u32 { 5 }
 
 // ── Monitor Dependencies ──
 
@@ -713,4 +1298,43 @@ pub struct AlertLogEntry {
     pub alert_type: String,
     pub event: String,
     pub sent_at: String,
+    pub seq: Option<i64>,
+}
+
+// ── API Keys ──
+
+#[derive(Debug, Serialize)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKey {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    pub api_key: ApiKey,
+    /// Shown once; only its hash is stored
+    pub key: String,
+}
+
+// ── Audit Log ──
+
+#[derive(Debug, Serialize)]
+pub struct AuditLogEntry {
+    pub seq: i64,
+    pub action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor_id: Option<String>,
+    pub actor: String,
+    /// Per-field `{from, to}` for updates; a short summary for other actions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changes: Option<serde_json::Value>,
+    pub created_at: String,
 }
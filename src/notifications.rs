@@ -1,6 +1,9 @@
 use crate::db::Db;
 use rusqlite::params;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
 
 #[derive(Debug, Serialize, Clone)]
 pub struct WebhookPayload {
@@ -8,6 +11,9 @@ pub struct WebhookPayload {
     pub monitor: WebhookMonitor,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub incident: Option<WebhookIncident>,
+    /// The window a `maintenance.upcoming` reminder is about
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maintenance: Option<WebhookMaintenance>,
     pub timestamp: String,
 }
 
@@ -17,6 +23,8 @@ pub struct WebhookMonitor {
     pub name: String,
     pub url: String,
     pub current_status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runbook_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -28,6 +36,14 @@ pub struct WebhookIncident {
     pub resolved_at: Option<String>,
 }
 
+#[derive(Debug, Serialize, Clone)]
+pub struct WebhookMaintenance {
+    pub id: String,
+    pub title: String,
+    pub starts_at: String,
+    pub ends_at: String,
+}
+
 /// Payload format for webhook notifications.
 #[derive(Debug, Clone, PartialEq)]
 pub enum PayloadFormat {
@@ -43,27 +59,158 @@ pub enum PayloadFormat {
 pub struct WebhookChannel {
     pub url: String,
     pub payload_format: PayloadFormat,
+    /// JSON body version: 1 (default, unchanged) or 2 (adds monitor metadata)
+    pub payload_version: u8,
+}
+
+/// Monitor context included in v2 JSON webhook payloads.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MonitorMetadata {
+    pub tags: Vec<String>,
+    pub group_name: Option<String>,
+    pub uptime_24h: f64,
+    pub uptime_7d: f64,
+}
+
+/// Load tags, group, and rolling uptime for a monitor. Uptime is 100% with no
+/// checks in the window, matching the status page.
+pub fn load_monitor_metadata(db: &Db, monitor_id: &str) -> MonitorMetadata {
+    let conn = db.conn();
+    let (tags, group_name): (String, Option<String>) = conn.query_row(
+        "SELECT tags, group_name FROM monitors WHERE id = ?1",
+        params![monitor_id],
+        |row| Ok((row.get::<_, Option<String>>(0)?.unwrap_or_default(), row.get(1)?)),
+    ).unwrap_or_default();
+    let uptime = |window: &str| -> f64 {
+        let (total, up): (u32, u32) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END), 0)
+             FROM heartbeats WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2)",
+            params![monitor_id, window],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap_or((0, 0));
+        if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 }
+    };
+    MonitorMetadata {
+        tags: crate::routes::parse_tags(&tags),
+        group_name,
+        uptime_24h: uptime("-24 hours"),
+        uptime_7d: uptime("-7 days"),
+    }
+}
+
+/// Build the structured JSON body for a webhook. Version 1 is the payload as
+/// serialized; version 2 adds `payload_version`, a top-level `incident_id`,
+/// and the monitor's tags, group, and uptime under `monitor`.
+pub fn json_payload(payload: &WebhookPayload, version: u8, metadata: Option<&MonitorMetadata>) -> serde_json::Value {
+    let mut body = serde_json::to_value(payload).unwrap_or_default();
+    if version < 2 {
+        return body;
+    }
+    body["payload_version"] = serde_json::json!(2);
+    body["incident_id"] = serde_json::json!(payload.incident.as_ref().map(|i| &i.id));
+    let meta = metadata.cloned().unwrap_or_default();
+    body["monitor"]["tags"] = serde_json::json!(meta.tags);
+    body["monitor"]["group_name"] = serde_json::json!(meta.group_name);
+    body["monitor"]["uptime_24h"] = serde_json::json!(meta.uptime_24h);
+    body["monitor"]["uptime_7d"] = serde_json::json!(meta.uptime_7d);
+    body
+}
+
+/// Severity of a notification event, used for channel routing filters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn parse(s: &str) -> Option<Severity> {
+        match s.to_lowercase().as_str() {
+            "info" => Some(Severity::Info),
+            "warning" => Some(Severity::Warning),
+            "critical" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+}
+
+/// Map an event name to its severity. Outages are critical, degradation is a
+/// warning, and recoveries/maintenance are informational.
+pub fn event_severity(event: &str) -> Severity {
+    match event {
+        "incident.created" | "incident.reminder" | "incident.escalated" | "monitor.sla_breached"
+        | "monitor.burn_rate_high" => Severity::Critical,
+        "monitor.degraded" | "monitor.latency_high" => Severity::Warning,
+        _ => Severity::Info,
+    }
+}
+
+/// Severity a payload is routed with: the incident's own severity when it has one
+/// (manual incidents), otherwise the event's.
+pub fn payload_severity(db: &Db, payload: &WebhookPayload) -> Severity {
+    payload.incident.as_ref()
+        .and_then(|i| db.read().query_row(
+            "SELECT severity FROM incidents WHERE id = ?1",
+            params![i.id],
+            |row| row.get::<_, Option<String>>(0),
+        ).ok().flatten())
+        .and_then(|s| Severity::parse(&s))
+        .unwrap_or_else(|| event_severity(&payload.event))
+}
+
+/// Check whether a channel's routing filter (stored JSON) allows an event of
+/// `severity` for a monitor with the given comma-joined tags. No filter means always fire.
+pub fn filter_allows(filter: Option<&str>, severity: Severity, monitor_tags: &str) -> bool {
+    let filter: crate::models::ChannelFilter = match filter.and_then(|f| serde_json::from_str(f).ok()) {
+        Some(f) => f,
+        None => return true,
+    };
+    if let Some(min) = filter.min_severity.as_deref().and_then(Severity::parse) {
+        if severity < min {
+            return false;
+        }
+    }
+    if !filter.tags.is_empty() {
+        let tags: Vec<&str> = monitor_tags.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+        if !filter.tags.iter().any(|t| tags.contains(&t.as_str())) {
+            return false;
+        }
+    }
+    true
 }
 
-/// Fetch enabled webhook channels for a monitor.
-pub fn get_webhook_channels(db: &Db, monitor_id: &str) -> Vec<WebhookChannel> {
+/// Fetch enabled channel configs of one type for a monitor (its own plus global
+/// channels), keeping only those whose routing filter allows `severity`.
+fn get_channel_configs(db: &Db, monitor_id: &str, channel_type: &str, severity: Severity) -> Vec<String> {
     let conn = db.conn();
     let mut stmt = match conn.prepare(
-        "SELECT config FROM notification_channels WHERE monitor_id = ?1 AND channel_type = 'webhook' AND is_enabled = 1"
+        "SELECT nc.config, nc.filter, m.tags FROM notification_channels nc
+         JOIN monitors m ON m.id = ?1
+         WHERE (nc.monitor_id = ?1 OR nc.monitor_id IS NULL) AND nc.channel_type = ?2 AND nc.is_enabled = 1"
     ) {
         Ok(s) => s,
         Err(_) => return vec![],
     };
 
-    let rows: Vec<String> = match stmt.query_map(params![monitor_id], |row| {
-        let config_str: String = row.get(0)?;
-        Ok(config_str)
+    let rows: Vec<(String, Option<String>, String)> = match stmt.query_map(params![monitor_id, channel_type], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
     }) {
         Ok(mapped) => mapped.filter_map(|r| r.ok()).collect(),
         Err(_) => return vec![],
     };
 
     rows.into_iter()
+        .filter(|(_, filter, tags)| filter_allows(filter.as_deref(), severity, tags))
+        .map(|(config, _, _)| config)
+        .collect()
+}
+
+/// Fetch enabled webhook channels for a monitor, including global channels,
+/// that should receive events of `severity`.
+pub fn get_webhook_channels(db: &Db, monitor_id: &str, severity: Severity) -> Vec<WebhookChannel> {
+    get_channel_configs(db, monitor_id, "webhook", severity)
+        .into_iter()
         .filter_map(|config_str| {
             let v: serde_json::Value = serde_json::from_str(&config_str).ok()?;
             let url = v["url"].as_str()?.to_string();
@@ -71,11 +218,34 @@ pub fn get_webhook_channels(db: &Db, monitor_id: &str) -> Vec<WebhookChannel> {
                 Some("chat") => PayloadFormat::Chat,
                 _ => PayloadFormat::Json,
             };
-            Some(WebhookChannel { url, payload_format })
+            let payload_version = if v["payload_version"].as_u64() == Some(2) { 2 } else { 1 };
+            Some(WebhookChannel { url, payload_format, payload_version })
         })
         .collect()
 }
 
+/// Short human-readable label for an event, used in chat-style messages.
+fn event_label(event: &str) -> &str {
+    match event {
+        "incident.created" => "DOWN",
+        "incident.resolved" => "Recovered",
+        "monitor.degraded" => "Degraded",
+        "monitor.recovered" => "Recovered",
+        "maintenance.started" => "Maintenance started",
+        "maintenance.ended" => "Maintenance ended",
+        "maintenance.upcoming" => "Maintenance starting soon",
+        "incident.reminder" => "Still down",
+        "incident.escalated" => "ESCALATED",
+        "monitor.latency_high" => "Slow responses",
+        "monitor.latency_recovered" => "Response times back to normal",
+        "monitor.sla_breached" => "24h uptime below SLA target",
+        "monitor.sla_recovered" => "24h uptime back within SLA",
+        "monitor.burn_rate_high" => "Error budget burning fast",
+        "monitor.burn_rate_recovered" => "Error budget burn rate back to normal",
+        _ => event,
+    }
+}
+
 /// Format a webhook payload as a human-readable chat message.
 fn format_chat_message(payload: &WebhookPayload) -> String {
     let emoji = match payload.event.as_str() {
@@ -85,26 +255,21 @@ fn format_chat_message(payload: &WebhookPayload) -> String {
         "monitor.recovered" => "🟢",
         "maintenance.started" => "🔧",
         "maintenance.ended" => "✅",
+        "maintenance.upcoming" => "🗓️",
         "incident.reminder" => "🔔",
         "incident.escalated" => "🚨",
+        "monitor.latency_high" => "🐢",
+        "monitor.latency_recovered" => "🟢",
+        "monitor.sla_breached" => "📉",
+        "monitor.sla_recovered" => "🟢",
+        "monitor.burn_rate_high" => "🔥",
+        "monitor.burn_rate_recovered" => "🟢",
         _ => "ℹ️",
     };
 
-    let event_label = match payload.event.as_str() {
-        "incident.created" => "DOWN",
-        "incident.resolved" => "Recovered",
-        "monitor.degraded" => "Degraded",
-        "monitor.recovered" => "Recovered",
-        "maintenance.started" => "Maintenance started",
-        "maintenance.ended" => "Maintenance ended",
-        "incident.reminder" => "Still down",
-        "incident.escalated" => "ESCALATED",
-        _ => &payload.event,
-    };
-
     let mut msg = format!(
         "{} **{}** — {}",
-        emoji, payload.monitor.name, event_label
+        emoji, payload.monitor.name, event_label(&payload.event)
     );
 
     if let Some(ref incident) = payload.incident {
@@ -115,6 +280,12 @@ fn format_chat_message(payload: &WebhookPayload) -> String {
             msg.push_str(&format!("\nResolved: {}", resolved_at));
         }
     }
+    if let Some(ref window) = payload.maintenance {
+        msg.push_str(&format!("\n{}: {} – {}", window.title, window.starts_at, window.ends_at));
+    }
+    if let Some(ref runbook) = payload.monitor.runbook_url {
+        msg.push_str(&format!("\nRunbook: {}", runbook));
+    }
 
     msg
 }
@@ -130,18 +301,20 @@ const RETRY_BACKOFFS_MS: [u64; 2] = [2000, 4000];
 /// Each channel gets up to MAX_WEBHOOK_ATTEMPTS delivery attempts with exponential
 /// backoff. Every attempt is logged to the webhook_deliveries table for audit.
 /// Channels with `payload_format: Chat` receive a simple `{"content":"...","sender":"Watchpost"}`
-/// payload instead of the full structured JSON.
+/// payload instead of the full structured JSON; `payload_version: 2` channels
+/// get the JSON enriched with monitor metadata.
 pub async fn fire_webhooks(
     db: &Db,
-    client: &reqwest::Client,
     monitor_id: &str,
     channels: &[WebhookChannel],
     payload: &WebhookPayload,
 ) {
-    for channel in channels {
-        let url = &channel.url;
-        let delivery_group = uuid::Uuid::new_v4().to_string();
+    let metadata = channels
+        .iter()
+        .any(|c| c.payload_format == PayloadFormat::Json && c.payload_version >= 2)
+        .then(|| load_monitor_metadata(db, monitor_id));
 
+    for channel in channels {
         // Build the appropriate payload body based on format
         let body: serde_json::Value = match channel.payload_format {
             PayloadFormat::Chat => {
@@ -151,62 +324,180 @@ pub async fn fire_webhooks(
                     "sender": "Watchpost"
                 })
             }
-            PayloadFormat::Json => {
-                serde_json::to_value(payload).unwrap_or_default()
-            }
+            PayloadFormat::Json => json_payload(payload, channel.payload_version, metadata.as_ref()),
+        };
+
+        // The host may have been re-pointed at an internal address since the channel was created
+        let Some(client) = delivery_client_or_log(db, monitor_id, &payload.event, &channel.url).await else {
+            continue;
         };
+        deliver_with_retry(db, monitor_id, &payload.event, &channel.url, || client.post(&channel.url).json(&body)).await;
+    }
+}
+
+// ─── Webhook Target Safety ──────────────────────────────────────────────────
 
-        for attempt in 1..=MAX_WEBHOOK_ATTEMPTS {
-            // Wait before retry (not on first attempt)
-            if attempt > 1 {
-                let backoff = RETRY_BACKOFFS_MS[(attempt - 2) as usize];
-                tokio::time::sleep(std::time::Duration::from_millis(backoff)).await;
+/// ALLOW_PRIVATE_WEBHOOKS=1 lets webhooks target internal addresses, e.g. a receiver
+/// on the same private network as a self-hosted Watchpost.
+pub fn allow_private_webhooks() -> bool {
+    std::env::var("ALLOW_PRIVATE_WEBHOOKS")
+        .is_ok_and(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes"))
+}
+
+/// Loopback, private (RFC 1918 / unique-local), link-local (including cloud metadata at
+/// 169.254.169.254), carrier-grade NAT, unspecified, and broadcast addresses.
+pub fn is_internal_addr(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            v4.is_loopback() || v4.is_private() || v4.is_link_local() || v4.is_unspecified()
+                || v4.is_broadcast() || (a == 100 && (64..128).contains(&b))
+        }
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_internal_addr(IpAddr::V4(v4)),
+            None => {
+                let first = v6.segments()[0];
+                v6.is_loopback() || v6.is_unspecified() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
             }
+        },
+    }
+}
 
-            let start = std::time::Instant::now();
-            let result = client
-                .post(url)
-                .json(&body)
-                .timeout(std::time::Duration::from_secs(10))
-                .send()
-                .await;
-            let elapsed_ms = start.elapsed().as_millis() as i64;
-
-            match result {
-                Ok(resp) => {
-                    let status_code = resp.status().as_u16() as i64;
-                    if resp.status().is_success() {
-                        // Success — log and move to next URL
-                        log_webhook_delivery(db, &DeliveryLogEntry {
-                            delivery_group: &delivery_group, monitor_id, event: &payload.event,
-                            url, attempt, status: "success", status_code: Some(status_code),
-                            error_message: None, response_time_ms: elapsed_ms,
-                        });
-                        if attempt > 1 {
-                            println!("✅ Webhook delivered to {} after {} attempts", url, attempt);
-                        }
-                        break;
-                    } else {
-                        // HTTP error response
-                        let error_msg = format!("HTTP {}", status_code);
-                        log_webhook_delivery(db, &DeliveryLogEntry {
-                            delivery_group: &delivery_group, monitor_id, event: &payload.event,
-                            url, attempt, status: "failed", status_code: Some(status_code),
-                            error_message: Some(&error_msg), response_time_ms: elapsed_ms,
-                        });
-                        if attempt == MAX_WEBHOOK_ATTEMPTS {
-                            println!(
-                                "⚠️  Webhook delivery to {} exhausted after {} attempts (last: {})",
-                                url, MAX_WEBHOOK_ATTEMPTS, error_msg
-                            );
-                        }
+/// Reject webhook URLs whose host is, or currently resolves to, an internal address.
+/// Hosts that don't resolve are let through: delivery re-checks before every send.
+pub async fn check_webhook_target(url: &str) -> Result<(), String> {
+    resolve_webhook_target(url).await.map(|_| ())
+}
+
+/// The internal-address check behind `check_webhook_target`. Returns the host and the
+/// addresses it resolved to; no addresses for IP literals and when ALLOW_PRIVATE_WEBHOOKS
+/// is set, `None` when the host doesn't resolve.
+async fn resolve_webhook_target(url: &str) -> Result<Option<(String, Vec<SocketAddr>)>, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|_| "must be a valid http(s) URL".to_string())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("must be a valid http(s) URL".into());
+    }
+    let host = parsed.host_str().unwrap_or_default().trim_start_matches('[').trim_end_matches(']');
+    if allow_private_webhooks() {
+        return Ok(Some((host.to_string(), Vec::new())));
+    }
+    let blocked = || Err(format!("points to a private, loopback, or link-local address ({})", host));
+    if let Ok(ip) = host.parse::<IpAddr>() {
+        return if is_internal_addr(ip) { blocked() } else { Ok(Some((host.to_string(), Vec::new()))) };
+    }
+    let host_lower = host.to_lowercase();
+    if host_lower == "localhost" || host_lower.ends_with(".localhost") {
+        return blocked();
+    }
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    match tokio::net::lookup_host((host, port)).await {
+        Ok(addrs) => {
+            let addrs: Vec<SocketAddr> = addrs.collect();
+            if addrs.iter().any(|addr| is_internal_addr(addr.ip())) {
+                return blocked();
+            }
+            Ok(Some((host.to_string(), addrs)))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Most pinned delivery clients kept at once.
+const MAX_DELIVERY_CLIENTS: usize = 256;
+
+/// (host, pinned addresses) for a cached delivery client.
+type DeliveryClientKey = (String, Vec<SocketAddr>);
+
+/// Client for notification deliveries to `host`. Redirects are never followed, since a public
+/// receiver could bounce the request to an internal address, and when `addrs` is non-empty
+/// connections go only to those (the addresses that passed the internal-address check), so
+/// the host can't be re-pointed between the check and the send.
+///
+/// Clients are cached by (host, addrs), so repeat deliveries reuse pooled connections.
+pub fn pinned_delivery_client(host: &str, addrs: &[SocketAddr]) -> Result<reqwest::Client, String> {
+    static CLIENTS: OnceLock<Mutex<HashMap<DeliveryClientKey, reqwest::Client>>> = OnceLock::new();
+    let mut sorted = addrs.to_vec();
+    sorted.sort();
+    let key = (host.to_string(), sorted);
+    let mut clients = CLIENTS.get_or_init(Default::default).lock().unwrap();
+    if let Some(client) = clients.get(&key) {
+        return Ok(client.clone());
+    }
+
+    let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+    if !addrs.is_empty() {
+        builder = builder.resolve_to_addrs(host, addrs);
+    }
+    let client = builder.build().map_err(|e| e.to_string())?;
+    // Receivers behind DNS rotation keep minting new keys; in-flight deliveries hold their own handles
+    if clients.len() >= MAX_DELIVERY_CLIENTS {
+        clients.clear();
+    }
+    clients.insert(key, client.clone());
+    Ok(client)
+}
+
+/// Re-check `url` and build its pinned delivery client. A blocked or unresolvable target
+/// is logged as a failed delivery and yields `None`.
+async fn delivery_client_or_log(db: &Db, monitor_id: &str, event: &str, url: &str) -> Option<reqwest::Client> {
+    let client = match resolve_webhook_target(url).await {
+        Ok(Some((host, addrs))) => pinned_delivery_client(&host, &addrs),
+        Ok(None) => Err("host does not resolve".to_string()),
+        Err(e) => Err(format!("Blocked: URL {}", e)),
+    };
+    client.map_err(|error_msg| log_webhook_delivery(db, &DeliveryLogEntry {
+        delivery_group: &uuid::Uuid::new_v4().to_string(), monitor_id, event,
+        url, attempt: 1, status: "failed", status_code: None,
+        error_message: Some(&error_msg), response_time_ms: 0,
+    })).ok()
+}
+
+/// Send one notification request with retry, logging every attempt to the
+/// webhook_deliveries table. `build` is called once per attempt since a
+/// request builder is consumed on send.
+async fn deliver_with_retry(
+    db: &Db,
+    monitor_id: &str,
+    event: &str,
+    url: &str,
+    build: impl Fn() -> reqwest::RequestBuilder,
+) {
+    let delivery_group = uuid::Uuid::new_v4().to_string();
+
+    for attempt in 1..=MAX_WEBHOOK_ATTEMPTS {
+        // Wait before retry (not on first attempt)
+        if attempt > 1 {
+            let backoff = RETRY_BACKOFFS_MS[(attempt - 2) as usize];
+            tokio::time::sleep(std::time::Duration::from_millis(backoff)).await;
+        }
+
+        let start = std::time::Instant::now();
+        let result = build()
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await;
+        let elapsed_ms = start.elapsed().as_millis() as i64;
+
+        match result {
+            Ok(resp) => {
+                let status_code = resp.status().as_u16() as i64;
+                if resp.status().is_success() {
+                    // Success — log and stop retrying
+                    log_webhook_delivery(db, &DeliveryLogEntry {
+                        delivery_group: &delivery_group, monitor_id, event,
+                        url, attempt, status: "success", status_code: Some(status_code),
+                        error_message: None, response_time_ms: elapsed_ms,
+                    });
+                    if attempt > 1 {
+                        println!("✅ Webhook delivered to {} after {} attempts", url, attempt);
                     }
-                }
-                Err(e) => {
-                    let error_msg = format!("{}", e);
+                    return;
+                } else {
+                    // HTTP error response
+                    let error_msg = format!("HTTP {}", status_code);
                     log_webhook_delivery(db, &DeliveryLogEntry {
-                        delivery_group: &delivery_group, monitor_id, event: &payload.event,
-                        url, attempt, status: "failed", status_code: None,
+                        delivery_group: &delivery_group, monitor_id, event,
+                        url, attempt, status: "failed", status_code: Some(status_code),
                         error_message: Some(&error_msg), response_time_ms: elapsed_ms,
                     });
                     if attempt == MAX_WEBHOOK_ATTEMPTS {
@@ -217,6 +508,20 @@ pub async fn fire_webhooks(
                     }
                 }
             }
+            Err(e) => {
+                let error_msg = format!("{}", e);
+                log_webhook_delivery(db, &DeliveryLogEntry {
+                    delivery_group: &delivery_group, monitor_id, event,
+                    url, attempt, status: "failed", status_code: None,
+                    error_message: Some(&error_msg), response_time_ms: elapsed_ms,
+                });
+                if attempt == MAX_WEBHOOK_ATTEMPTS {
+                    println!(
+                        "⚠️  Webhook delivery to {} exhausted after {} attempts (last: {})",
+                        url, MAX_WEBHOOK_ATTEMPTS, error_msg
+                    );
+                }
+            }
         }
     }
 }
@@ -251,6 +556,309 @@ fn log_webhook_delivery(db: &Db, entry: &DeliveryLogEntry<'_>) {
     );
 }
 
+// ─── ntfy Notifications ─────────────────────────────────────────────────────
+
+/// Default ntfy server when a channel's config omits `server`.
+const NTFY_DEFAULT_SERVER: &str = "https://ntfy.sh";
+
+/// A resolved ntfy channel: messages are POSTed to `{server}/{topic}`.
+#[derive(Debug, Clone)]
+pub struct NtfyChannel {
+    pub server: String,
+    pub topic: String,
+}
+
+impl NtfyChannel {
+    pub fn url(&self) -> String {
+        format!("{}/{}", self.server.trim_end_matches('/'), self.topic)
+    }
+}
+
+/// Fetch enabled ntfy channels for a monitor, including global channels,
+/// that should receive events of `severity`.
+pub fn get_ntfy_channels(db: &Db, monitor_id: &str, severity: Severity) -> Vec<NtfyChannel> {
+    get_channel_configs(db, monitor_id, "ntfy", severity)
+        .into_iter()
+        .filter_map(|config_str| {
+            let v: serde_json::Value = serde_json::from_str(&config_str).ok()?;
+            let topic = v["topic"].as_str().map(str::trim).filter(|t| !t.is_empty())?.to_string();
+            let server = v["server"].as_str().unwrap_or(NTFY_DEFAULT_SERVER).to_string();
+            Some(NtfyChannel { server, topic })
+        })
+        .collect()
+}
+
+/// ntfy `Priority` and `Tags` header values for an event: outages are urgent,
+/// degradation is high, everything else uses the default priority.
+pub fn ntfy_headers(event: &str) -> (&'static str, &'static str) {
+    match event_severity(event) {
+        Severity::Critical => ("5", "rotating_light,watchpost"),
+        Severity::Warning => ("4", "warning,watchpost"),
+        Severity::Info => ("3", "information_source,watchpost"),
+    }
+}
+
+/// Deliver a plain-text message to each ntfy topic, with the same internal-address
+/// check, retry, and delivery logging as webhooks.
+pub async fn fire_ntfy(
+    db: &Db,
+    monitor_id: &str,
+    channels: &[NtfyChannel],
+    payload: &WebhookPayload,
+) {
+    for channel in channels {
+        // The server is user-supplied, so it gets the same guard as a webhook URL
+        if let Some(client) = delivery_client_or_log(db, monitor_id, &payload.event, &channel.url()).await {
+            send_ntfy(db, &client, monitor_id, channel, payload).await;
+        }
+    }
+}
+
+/// POST one ntfy message with retry and delivery logging, using `client` as given.
+pub async fn send_ntfy(
+    db: &Db,
+    client: &reqwest::Client,
+    monitor_id: &str,
+    channel: &NtfyChannel,
+    payload: &WebhookPayload,
+) {
+    // ntfy renders plain text by default, so drop the chat markdown
+    let message = format_chat_message(payload).replace("**", "");
+    let (priority, tags) = ntfy_headers(&payload.event);
+    let url = channel.url();
+    deliver_with_retry(db, monitor_id, &payload.event, &url, || {
+        client.post(&url)
+            .header("Priority", priority)
+            .header("Tags", tags)
+            .body(message.clone())
+    }).await;
+}
+
+// ─── Microsoft Teams Notifications ──────────────────────────────────────────
+
+/// Hosts that serve Teams incoming webhooks: legacy Office 365 connectors and
+/// Power Automate workflows.
+const TEAMS_WEBHOOK_HOST_SUFFIXES: [&str; 4] = [
+    ".webhook.office.com",
+    "outlook.office.com",
+    ".logic.azure.com",
+    ".powerplatform.com",
+];
+
+/// Whether `url` is an https URL on a Teams incoming-webhook host.
+pub fn is_teams_webhook_url(url: &str) -> bool {
+    match reqwest::Url::parse(url) {
+        Ok(u) if u.scheme() == "https" => u.host_str().is_some_and(|host| {
+            let host = host.to_lowercase();
+            TEAMS_WEBHOOK_HOST_SUFFIXES.iter().any(|suffix| host.ends_with(suffix))
+        }),
+        _ => false,
+    }
+}
+
+/// Fetch enabled Teams webhook URLs for a monitor, including global channels,
+/// that should receive events of `severity`.
+pub fn get_teams_urls(db: &Db, monitor_id: &str, severity: Severity) -> Vec<String> {
+    get_channel_configs(db, monitor_id, "teams", severity)
+        .into_iter()
+        .filter_map(|config_str| {
+            serde_json::from_str::<serde_json::Value>(&config_str)
+                .ok()
+                .and_then(|v| v["url"].as_str().map(|s| s.to_string()))
+        })
+        .collect()
+}
+
+/// Build a Teams message carrying an Adaptive Card: a colored header with the
+/// monitor name and event, then monitor and incident details as facts.
+pub fn teams_card(payload: &WebhookPayload) -> serde_json::Value {
+    let style = match event_severity(&payload.event) {
+        Severity::Critical => "attention",
+        Severity::Warning => "warning",
+        Severity::Info if payload.monitor.current_status == "up" => "good",
+        Severity::Info => "accent",
+    };
+
+    let mut facts = vec![
+        serde_json::json!({"title": "Monitor", "value": payload.monitor.name}),
+        serde_json::json!({"title": "URL", "value": payload.monitor.url}),
+        serde_json::json!({"title": "Status", "value": payload.monitor.current_status}),
+        serde_json::json!({"title": "Time", "value": payload.timestamp}),
+    ];
+    if let Some(ref incident) = payload.incident {
+        facts.push(serde_json::json!({"title": "Incident", "value": incident.id}));
+        if !incident.cause.is_empty() {
+            facts.push(serde_json::json!({"title": "Cause", "value": incident.cause}));
+        }
+        facts.push(serde_json::json!({"title": "Started", "value": incident.started_at}));
+        if let Some(ref resolved) = incident.resolved_at {
+            facts.push(serde_json::json!({"title": "Resolved", "value": resolved}));
+        }
+    }
+    if let Some(ref window) = payload.maintenance {
+        facts.push(serde_json::json!({"title": "Maintenance", "value": window.title}));
+        facts.push(serde_json::json!({"title": "Window", "value": format!("{} – {}", window.starts_at, window.ends_at)}));
+    }
+    if let Some(ref runbook) = payload.monitor.runbook_url {
+        facts.push(serde_json::json!({"title": "Runbook", "value": runbook}));
+    }
+
+    serde_json::json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "content": {
+                "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                "type": "AdaptiveCard",
+                "version": "1.4",
+                "body": [
+                    {
+                        "type": "Container",
+                        "style": style,
+                        "bleed": true,
+                        "items": [{
+                            "type": "TextBlock",
+                            "text": format!("{} — {}", payload.monitor.name, event_label(&payload.event)),
+                            "weight": "Bolder",
+                            "size": "Medium",
+                            "wrap": true
+                        }]
+                    },
+                    {"type": "FactSet", "facts": facts}
+                ]
+            }
+        }]
+    })
+}
+
+/// POST an Adaptive Card to each Teams webhook, with the same retry and
+/// delivery logging as webhooks.
+pub async fn fire_teams(
+    db: &Db,
+    client: &reqwest::Client,
+    monitor_id: &str,
+    urls: &[String],
+    payload: &WebhookPayload,
+) {
+    let card = teams_card(payload);
+    for url in urls {
+        deliver_with_retry(db, monitor_id, &payload.event, url, || client.post(url).json(&card)).await;
+    }
+}
+
+// ─── Opsgenie Alerts ────────────────────────────────────────────────────────
+
+/// A resolved Opsgenie channel: the regional API base URL and integration key.
+#[derive(Debug, Clone)]
+pub struct OpsgenieChannel {
+    pub api_url: String,
+    pub api_key: String,
+}
+
+/// Opsgenie API base URL for a `region` config value (`us` default, `eu`).
+pub fn opsgenie_api_url(region: Option<&str>) -> &'static str {
+    match region {
+        Some("eu") => "https://api.eu.opsgenie.com",
+        _ => "https://api.opsgenie.com",
+    }
+}
+
+/// Whether `key` looks like an Opsgenie API integration key (a hyphenated UUID).
+pub fn is_opsgenie_api_key(key: &str) -> bool {
+    key.len() == 36 && uuid::Uuid::parse_str(key).is_ok()
+}
+
+/// Fetch enabled Opsgenie channels for a monitor, including global channels,
+/// that should receive events of `severity`.
+pub fn get_opsgenie_channels(db: &Db, monitor_id: &str, severity: Severity) -> Vec<OpsgenieChannel> {
+    get_channel_configs(db, monitor_id, "opsgenie", severity)
+        .into_iter()
+        .filter_map(|config_str| {
+            let v: serde_json::Value = serde_json::from_str(&config_str).ok()?;
+            let api_key = v["api_key"].as_str()?.to_string();
+            let api_url = opsgenie_api_url(v["region"].as_str()).to_string();
+            Some(OpsgenieChannel { api_url, api_key })
+        })
+        .collect()
+}
+
+/// Map an event to an Opsgenie API call as (path, body). Outage events create
+/// an alert aliased by incident id — reminders and escalations hit the same
+/// alert, which Opsgenie deduplicates — and `incident.resolved` closes it.
+/// Events without an incident return None.
+pub fn opsgenie_request(payload: &WebhookPayload) -> Option<(String, serde_json::Value)> {
+    let incident = payload.incident.as_ref()?;
+    match payload.event.as_str() {
+        "incident.created" | "incident.reminder" | "incident.escalated" => {
+            let message: String = format!("{} is down: {}", payload.monitor.name, incident.cause)
+                .chars()
+                .take(130) // Opsgenie's message limit
+                .collect();
+            let priority = if payload.event == "incident.escalated" { "P1" } else { "P2" };
+            Some(("/v2/alerts".to_string(), serde_json::json!({
+                "message": message,
+                "alias": incident.id,
+                "description": incident.cause,
+                "priority": priority,
+                "source": "Watchpost",
+                "tags": ["watchpost"],
+                "details": {
+                    "monitor_id": payload.monitor.id,
+                    "monitor_url": payload.monitor.url,
+                    "runbook_url": payload.monitor.runbook_url,
+                    "started_at": incident.started_at,
+                },
+            })))
+        }
+        "incident.resolved" => Some((
+            format!("/v2/alerts/{}/close?identifierType=alias", incident.id),
+            serde_json::json!({
+                "source": "Watchpost",
+                "note": format!("{} recovered", payload.monitor.name),
+            }),
+        )),
+        _ => None,
+    }
+}
+
+/// Create or close the Opsgenie alert for an incident transition, with the
+/// same retry and delivery logging as webhooks. Other events are skipped.
+pub async fn send_opsgenie(
+    db: &Db,
+    client: &reqwest::Client,
+    monitor_id: &str,
+    channel: &OpsgenieChannel,
+    payload: &WebhookPayload,
+) {
+    let Some((path, body)) = opsgenie_request(payload) else {
+        return;
+    };
+    let url = format!("{}{}", channel.api_url.trim_end_matches('/'), path);
+    let auth = format!("GenieKey {}", channel.api_key);
+    deliver_with_retry(db, monitor_id, &payload.event, &url, || {
+        client.post(&url).header("Authorization", &auth).json(&body)
+    }).await;
+}
+
+/// Deliver an event to the monitor's third-party integration channels
+/// (ntfy, Teams, and Opsgenie). Webhooks and email are fired separately.
+pub async fn fire_integrations(db: &Db, client: &reqwest::Client, monitor_id: &str, payload: &WebhookPayload) {
+    let severity = payload_severity(db, payload);
+    let ntfy = get_ntfy_channels(db, monitor_id, severity);
+    if !ntfy.is_empty() {
+        fire_ntfy(db, monitor_id, &ntfy, payload).await;
+    }
+
+    let teams = get_teams_urls(db, monitor_id, severity);
+    if !teams.is_empty() {
+        fire_teams(db, client, monitor_id, &teams, payload).await;
+    }
+
+    for channel in get_opsgenie_channels(db, monitor_id, severity) {
+        send_opsgenie(db, client, monitor_id, &channel, payload).await;
+    }
+}
+
 // ─── Email Notifications ────────────────────────────────────────────────────
 
 use lettre::message::header::ContentType;
@@ -309,25 +917,11 @@ pub fn get_smtp_config() -> &'static Option<SmtpConfig> {
     })
 }
 
-/// Fetch enabled email addresses for a monitor.
-pub fn get_email_addresses(db: &Db, monitor_id: &str) -> Vec<String> {
-    let conn = db.conn();
-    let mut stmt = match conn.prepare(
-        "SELECT config FROM notification_channels WHERE monitor_id = ?1 AND channel_type = 'email' AND is_enabled = 1"
-    ) {
-        Ok(s) => s,
-        Err(_) => return vec![],
-    };
-
-    let rows: Vec<String> = match stmt.query_map(params![monitor_id], |row| {
-        let config_str: String = row.get(0)?;
-        Ok(config_str)
-    }) {
-        Ok(mapped) => mapped.filter_map(|r| r.ok()).collect(),
-        Err(_) => return vec![],
-    };
-
-    rows.into_iter()
+/// Fetch enabled email addresses for a monitor, including global channels,
+/// that should receive events of `severity`.
+pub fn get_email_addresses(db: &Db, monitor_id: &str, severity: Severity) -> Vec<String> {
+    get_channel_configs(db, monitor_id, "email", severity)
+        .into_iter()
         .filter_map(|config_str| {
             serde_json::from_str::<serde_json::Value>(&config_str)
                 .ok()
@@ -345,6 +939,13 @@ fn email_subject(payload: &WebhookPayload) -> String {
         "monitor.recovered" => "🟢",
         "maintenance.started" => "🔧",
         "maintenance.ended" => "✅",
+        "maintenance.upcoming" => "🗓️",
+        "monitor.latency_high" => "🐢",
+        "monitor.latency_recovered" => "🟢",
+        "monitor.sla_breached" => "📉",
+        "monitor.sla_recovered" => "🟢",
+        "monitor.burn_rate_high" => "🔥",
+        "monitor.burn_rate_recovered" => "🟢",
         _ => "ℹ️",
     };
 
@@ -355,6 +956,13 @@ fn email_subject(payload: &WebhookPayload) -> String {
         "monitor.recovered" => "RECOVERED",
         "maintenance.started" => "MAINTENANCE",
         "maintenance.ended" => "MAINTENANCE ENDED",
+        "maintenance.upcoming" => "UPCOMING MAINTENANCE",
+        "monitor.latency_high" => "SLOW",
+        "monitor.latency_recovered" => "LATENCY RECOVERED",
+        "monitor.sla_breached" => "SLA BREACHED",
+        "monitor.sla_recovered" => "SLA RECOVERED",
+        "monitor.burn_rate_high" => "BURN RATE HIGH",
+        "monitor.burn_rate_recovered" => "BURN RATE RECOVERED",
         _ => &payload.event,
     };
 
@@ -365,7 +973,7 @@ fn email_subject(payload: &WebhookPayload) -> String {
 }
 
 /// Build email body (plain text) from a webhook payload.
-fn email_body_text(payload: &WebhookPayload) -> String {
+fn email_body_text(payload: &WebhookPayload, unsubscribe_url: Option<&str>) -> String {
     let mut body = String::new();
 
     body.push_str(&format!("Monitor: {}\n", payload.monitor.name));
@@ -373,6 +981,10 @@ fn email_body_text(payload: &WebhookPayload) -> String {
     body.push_str(&format!("Status: {}\n", payload.monitor.current_status));
     body.push_str(&format!("Event: {}\n", payload.event));
     body.push_str(&format!("Time: {}\n", payload.timestamp));
+    // Runbooks are for the operators' on-call, not status page subscribers
+    if let (Some(runbook), None) = (&payload.monitor.runbook_url, unsubscribe_url) {
+        body.push_str(&format!("Runbook: {}\n", runbook));
+    }
 
     if let Some(ref incident) = payload.incident {
         body.push_str("\n--- Incident ---\n");
@@ -383,18 +995,27 @@ fn email_body_text(payload: &WebhookPayload) -> String {
             body.push_str(&format!("Resolved: {}\n", resolved));
         }
     }
+    if let Some(ref window) = payload.maintenance {
+        body.push_str("\n--- Maintenance ---\n");
+        body.push_str(&format!("Title: {}\n", window.title));
+        body.push_str(&format!("Starts: {}\n", window.starts_at));
+        body.push_str(&format!("Ends: {}\n", window.ends_at));
+    }
 
     body.push_str("\n--\nSent by Watchpost\n");
+    if let Some(url) = unsubscribe_url {
+        body.push_str(&format!("Unsubscribe: {}\n", url));
+    }
     body
 }
 
 /// Build email body (HTML) from a webhook payload.
-fn email_body_html(payload: &WebhookPayload) -> String {
+fn email_body_html(payload: &WebhookPayload, unsubscribe_url: Option<&str>) -> String {
     let status_color = match payload.event.as_str() {
         "incident.created" => "#e74c3c",
         "incident.resolved" | "monitor.recovered" | "maintenance.ended" => "#2ecc71",
         "monitor.degraded" => "#f39c12",
-        "maintenance.started" => "#3498db",
+        "maintenance.started" | "maintenance.upcoming" => "#3498db",
         _ => "#95a5a6",
     };
 
@@ -405,6 +1026,7 @@ fn email_body_html(payload: &WebhookPayload) -> String {
         "monitor.recovered" => "RECOVERED",
         "maintenance.started" => "MAINTENANCE",
         "maintenance.ended" => "MAINTENANCE ENDED",
+        "maintenance.upcoming" => "UPCOMING MAINTENANCE",
         _ => &payload.event,
     };
 
@@ -421,7 +1043,7 @@ fn email_body_html(payload: &WebhookPayload) -> String {
       <tr><td style="padding: 6px 0; color: #8899aa;">Monitor</td><td style="padding: 6px 0;">{name}</td></tr>
       <tr><td style="padding: 6px 0; color: #8899aa;">URL</td><td style="padding: 6px 0;"><a href="{url}" style="color: #5dade2;">{url}</a></td></tr>
       <tr><td style="padding: 6px 0; color: #8899aa;">Status</td><td style="padding: 6px 0; font-weight: 600; color: {color};">{status}</td></tr>
-      <tr><td style="padding: 6px 0; color: #8899aa;">Time</td><td style="padding: 6px 0;">{time}</td></tr>
+      <tr><td style="padding: 6px 0; color: #8899aa;">Time</td><td style="padding: 6px 0;">{time}</td></tr>{runbook}
     </table>"#,
         color = status_color,
         label = event_label,
@@ -429,6 +1051,14 @@ fn email_body_html(payload: &WebhookPayload) -> String {
         url = html_escape(&payload.monitor.url),
         status = payload.monitor.current_status,
         time = payload.timestamp,
+        runbook = match (&payload.monitor.runbook_url, unsubscribe_url) {
+            (Some(runbook), None) => format!(
+                r#"
+      <tr><td style="padding: 6px 0; color: #8899aa;">Runbook</td><td style="padding: 6px 0;"><a href="{url}" style="color: #5dade2;">{url}</a></td></tr>"#,
+                url = html_escape(runbook),
+            ),
+            _ => String::new(),
+        },
     );
 
     if let Some(ref incident) = payload.incident {
@@ -454,12 +1084,35 @@ fn email_body_html(payload: &WebhookPayload) -> String {
 
         html.push_str("    </table>");
     }
+    if let Some(ref window) = payload.maintenance {
+        html.push_str(&format!(
+            r#"
+    <hr style="border: none; border-top: 1px solid #0f3460; margin: 16px 0;">
+    <table style="width: 100%; border-collapse: collapse; color: #e0e0e0;">
+      <tr><td style="padding: 6px 0; color: #8899aa;">Maintenance</td><td style="padding: 6px 0;">{title}</td></tr>
+      <tr><td style="padding: 6px 0; color: #8899aa;">Starts</td><td style="padding: 6px 0;">{starts}</td></tr>
+      <tr><td style="padding: 6px 0; color: #8899aa;">Ends</td><td style="padding: 6px 0;">{ends}</td></tr>
+    </table>"#,
+            title = html_escape(&window.title),
+            starts = window.starts_at,
+            ends = window.ends_at,
+        ));
+    }
 
     html.push_str(
         r#"
   </div>
   <div style="text-align: center; margin-top: 16px; color: #555; font-size: 12px;">
-    Sent by Watchpost · Agent-Native Monitoring
+    Sent by Watchpost · Agent-Native Monitoring"#,
+    );
+    if let Some(url) = unsubscribe_url {
+        html.push_str(&format!(
+            r#" · <a href="{}" style="color: #555;">Unsubscribe</a>"#,
+            html_escape(url)
+        ));
+    }
+    html.push_str(
+        r#"
   </div>
 </div>
 </body>
@@ -469,8 +1122,8 @@ fn email_body_html(payload: &WebhookPayload) -> String {
     html
 }
 
-/// Minimal HTML escaping for email body.
-fn html_escape(s: &str) -> String {
+/// Minimal HTML escaping for email bodies and the HTML status page.
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -488,8 +1141,8 @@ pub async fn fire_emails(addresses: &[String], payload: &WebhookPayload) {
     };
 
     let subject = email_subject(payload);
-    let text_body = email_body_text(payload);
-    let html_body = email_body_html(payload);
+    let text_body = email_body_text(payload, None);
+    let html_body = email_body_html(payload, None);
 
     // Build SMTP transport
     let transport = match build_transport(config) {
@@ -501,45 +1154,179 @@ pub async fn fire_emails(addresses: &[String], payload: &WebhookPayload) {
     };
 
     for address in addresses {
-        let email = match Message::builder()
-            .from(config.from_address.parse().unwrap_or_else(|_| {
-                "watchpost@localhost".parse().unwrap()
-            }))
-            .to(match address.parse() {
-                Ok(a) => a,
-                Err(e) => {
-                    println!("⚠️  Skipping invalid email address '{}': {}", address, e);
-                    continue;
-                }
-            })
-            .subject(&subject)
-            .multipart(
-                lettre::message::MultiPart::alternative()
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_PLAIN)
-                            .body(text_body.clone()),
-                    )
-                    .singlepart(
-                        lettre::message::SinglePart::builder()
-                            .header(ContentType::TEXT_HTML)
-                            .body(html_body.clone()),
-                    ),
-            ) {
-            Ok(m) => m,
-            Err(e) => {
-                println!("⚠️  Failed to build email to '{}': {}", address, e);
-                continue;
-            }
-        };
+        match send_email(&transport, config, address, &subject, &text_body, &html_body).await {
+            Ok(()) => println!("📧 Email sent to {} for {}", address, payload.event),
+            Err(e) => println!("⚠️  {}", e),
+        }
+    }
+}
+
+/// Send one multipart (plain text + HTML) email.
+async fn send_email(
+    transport: &AsyncSmtpTransport<Tokio1Executor>,
+    config: &SmtpConfig,
+    address: &str,
+    subject: &str,
+    text_body: &str,
+    html_body: &str,
+) -> Result<(), String> {
+    let to = address.parse()
+        .map_err(|e| format!("Skipping invalid email address '{}': {}", address, e))?;
+    let email = Message::builder()
+        .from(config.from_address.parse().unwrap_or_else(|_| {
+            "watchpost@localhost".parse().unwrap()
+        }))
+        .to(to)
+        .subject(subject)
+        .multipart(
+            lettre::message::MultiPart::alternative()
+                .singlepart(
+                    lettre::message::SinglePart::builder()
+                        .header(ContentType::TEXT_PLAIN)
+                        .body(text_body.to_string()),
+                )
+                .singlepart(
+                    lettre::message::SinglePart::builder()
+                        .header(ContentType::TEXT_HTML)
+                        .body(html_body.to_string()),
+                ),
+        )
+        .map_err(|e| format!("Failed to build email to '{}': {}", address, e))?;
+
+    transport.send(email).await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to send email to '{}': {}", address, e))
+}
+
+// ── Status page subscribers ──
+
+/// Base URL for links in outgoing emails. Links stay relative when
+/// PUBLIC_BASE_URL is unset.
+pub fn public_base_url() -> String {
+    std::env::var("PUBLIC_BASE_URL")
+        .map(|u| u.trim_end_matches('/').to_string())
+        .unwrap_or_default()
+}
+
+/// A confirmed subscriber; `token` goes into their unsubscribe link.
+#[derive(Debug, Clone)]
+pub struct PageSubscriber {
+    pub email: String,
+    pub token: String,
+}
+
+/// Unsubscribe link token for a subscriber's alert emails, keyed by the per-install
+/// `subscription_link_key` so it can be rebuilt for every email without being stored.
+pub fn unsubscribe_token(conn: &rusqlite::Connection, subscriber_id: &str) -> String {
+    let key: String = conn
+        .query_row("SELECT value FROM settings WHERE key = 'subscription_link_key'", [], |r| r.get(0))
+        .unwrap_or_default();
+    format!("wp_{}", &crate::auth::hash_key(&format!("{}:{}", key, subscriber_id))[..32])
+}
+
+/// Confirmed subscribers of every status page that includes `monitor_id`,
+/// one entry per address.
+pub fn get_page_subscribers(db: &Db, monitor_id: &str) -> Vec<PageSubscriber> {
+    let conn = db.conn();
+    let mut stmt = match conn.prepare(
+        "SELECT s.email, MIN(s.id) FROM status_page_subscribers s
+         INNER JOIN status_page_monitors spm ON spm.status_page_id = s.status_page_id
+         WHERE spm.monitor_id = ?1 AND s.confirmed = 1
+         GROUP BY s.email
+         ORDER BY s.email",
+    ) {
+        Ok(s) => s,
+        Err(_) => return vec![],
+    };
+    let rows: Vec<(String, String)> = stmt
+        .query_map(params![monitor_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map(|rows| rows.filter_map(|r| r.ok()).collect())
+        .unwrap_or_default();
+    rows.into_iter()
+        .map(|(email, id)| PageSubscriber { token: unsubscribe_token(&conn, &id), email })
+        .collect()
+}
+
+/// Email status-page subscribers when a monitor on their page goes down or
+/// comes back up. Other events stay with the monitor's own channels.
+pub async fn notify_page_subscribers(db: &Db, monitor_id: &str, payload: &WebhookPayload) {
+    if !matches!(payload.event.as_str(), "incident.created" | "incident.resolved") {
+        return;
+    }
+    let subscribers = get_page_subscribers(db, monitor_id);
+    if subscribers.is_empty() {
+        return;
+    }
+    let config = match get_smtp_config() {
+        Some(c) => c,
+        None => {
+            println!("⚠️  Subscriber notification skipped: SMTP not configured (set SMTP_HOST)");
+            return;
+        }
+    };
+    let transport = match build_transport(config) {
+        Ok(t) => t,
+        Err(e) => {
+            println!("⚠️  Subscriber notification failed: could not build SMTP transport: {}", e);
+            return;
+        }
+    };
 
-        match transport.send(email).await {
-            Ok(_) => println!("📧 Email sent to {} for {}", address, payload.event),
-            Err(e) => println!("⚠️  Failed to send email to '{}': {}", address, e),
+    let subject = email_subject(payload);
+    let base = public_base_url();
+    for sub in &subscribers {
+        let unsubscribe_url = format!("{}/api/v1/subscriptions/{}/unsubscribe", base, sub.token);
+        let text_body = email_body_text(payload, Some(&unsubscribe_url));
+        let html_body = email_body_html(payload, Some(&unsubscribe_url));
+        match send_email(&transport, config, &sub.email, &subject, &text_body, &html_body).await {
+            Ok(()) => println!("📧 Subscriber email sent to {} for {}", sub.email, payload.event),
+            Err(e) => println!("⚠️  {}", e),
         }
     }
 }
 
+/// Send the double opt-in email for a new status page subscription.
+pub async fn send_subscription_confirmation(address: &str, page_title: &str, token: &str) {
+    let config = match get_smtp_config() {
+        Some(c) => c,
+        None => {
+            println!("⚠️  Subscription confirmation skipped: SMTP not configured (set SMTP_HOST)");
+            return;
+        }
+    };
+    let transport = match build_transport(config) {
+        Ok(t) => t,
+        Err(e) => {
+            println!("⚠️  Subscription confirmation failed: could not build SMTP transport: {}", e);
+            return;
+        }
+    };
+
+    let confirm_url = format!("{}/api/v1/subscriptions/{}/confirm", public_base_url(), token);
+    let subject = format!("[Watchpost] Confirm your subscription to {}", page_title);
+    let text_body = format!(
+        "You asked to receive status updates for {}.\n\nConfirm: {}\n\nIf this wasn't you, ignore this email.\n\n--\nSent by Watchpost\n",
+        page_title, confirm_url
+    );
+    let html_body = format!(
+        r#"<!DOCTYPE html>
+<html>
+<body style="font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', sans-serif; padding: 24px;">
+<p>You asked to receive status updates for <strong>{title}</strong>.</p>
+<p><a href="{url}">Confirm subscription</a></p>
+<p style="color: #555; font-size: 12px;">If this wasn't you, ignore this email.</p>
+</body>
+</html>"#,
+        title = html_escape(page_title),
+        url = html_escape(&confirm_url),
+    );
+
+    match send_email(&transport, config, address, &subject, &text_body, &html_body).await {
+        Ok(()) => println!("📧 Subscription confirmation sent to {}", address),
+        Err(e) => println!("⚠️  {}", e),
+    }
+}
+
 /// Build an async SMTP transport from config.
 fn build_transport(config: &SmtpConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
     let creds = if !config.username.is_empty() {
@@ -593,6 +1380,7 @@ mod tests {
                 } else {
                     "down".to_string()
                 },
+                runbook_url: None,
             },
             incident: if cause.is_empty() {
                 None
@@ -608,6 +1396,7 @@ mod tests {
                     },
                 })
             },
+            maintenance: None,
             timestamp: "2026-02-17T03:00:00Z".to_string(),
         }
     }
@@ -686,4 +1475,150 @@ mod tests {
         };
         assert_eq!(fmt, PayloadFormat::Json);
     }
+
+    #[test]
+    fn test_json_payload_v2_adds_monitor_metadata() {
+        let payload = make_payload("incident.created", "API", "HTTP 503");
+        let meta = MonitorMetadata {
+            tags: vec!["prod".to_string(), "api".to_string()],
+            group_name: Some("Backend".to_string()),
+            uptime_24h: 98.5,
+            uptime_7d: 99.9,
+        };
+
+        // v1 is exactly the serialized payload, with or without metadata on hand
+        let v1 = json_payload(&payload, 1, Some(&meta));
+        assert_eq!(v1, serde_json::to_value(&payload).unwrap());
+        assert!(v1.get("payload_version").is_none());
+        assert!(v1["monitor"].get("tags").is_none());
+
+        let v2 = json_payload(&payload, 2, Some(&meta));
+        assert_eq!(v2["payload_version"], 2);
+        assert_eq!(v2["incident_id"], v2["incident"]["id"]);
+        assert_eq!(v2["monitor"]["tags"], serde_json::json!(["prod", "api"]));
+        assert_eq!(v2["monitor"]["group_name"], "Backend");
+        assert_eq!(v2["monitor"]["uptime_24h"], 98.5);
+        assert_eq!(v2["monitor"]["uptime_7d"], 99.9);
+        assert_eq!(v2["monitor"]["name"], "API");
+        assert_eq!(v2["event"], "incident.created");
+    }
+
+    #[test]
+    fn test_runbook_url_in_payloads() {
+        let mut payload = make_payload("incident.created", "API", "HTTP 503");
+        assert!(json_payload(&payload, 1, None)["monitor"].get("runbook_url").is_none());
+        assert!(!format_chat_message(&payload).contains("Runbook"));
+
+        payload.monitor.runbook_url = Some("https://wiki.example.com/runbooks/api".to_string());
+        assert_eq!(json_payload(&payload, 1, None)["monitor"]["runbook_url"], "https://wiki.example.com/runbooks/api");
+        assert_eq!(json_payload(&payload, 2, None)["monitor"]["runbook_url"], "https://wiki.example.com/runbooks/api");
+        assert!(format_chat_message(&payload).ends_with("\nRunbook: https://wiki.example.com/runbooks/api"));
+        assert!(teams_card(&payload).to_string().contains("https://wiki.example.com/runbooks/api"));
+        let (_, alert) = opsgenie_request(&payload).unwrap();
+        assert_eq!(alert["details"]["runbook_url"], "https://wiki.example.com/runbooks/api");
+
+        // Operator emails link it; status page subscribers don't see it
+        assert!(email_body_text(&payload, None).contains("Runbook: https://wiki.example.com/runbooks/api"));
+        assert!(!email_body_text(&payload, Some("https://status.example.com/unsub")).contains("Runbook"));
+        assert!(email_body_html(&payload, None).contains("https://wiki.example.com/runbooks/api"));
+    }
+
+    #[test]
+    fn test_load_monitor_metadata() {
+        let path = format!("/tmp/watchpost_meta_{}.db", uuid::Uuid::new_v4());
+        let db = Db::new(&path).unwrap();
+        db.conn().execute_batch(
+            "INSERT INTO monitors (id, name, url, manage_key_hash, tags, group_name) VALUES ('m', 'm', 'https://example.com', 'x', 'prod,api', 'Backend');
+             INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, checked_at, seq) VALUES ('h1', 'm', 'up', 10, datetime('now', '-1 hour'), 1);
+             INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, checked_at, seq) VALUES ('h2', 'm', 'up', 10, datetime('now', '-1 hour'), 2);
+             INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, checked_at, seq) VALUES ('h3', 'm', 'up', 10, datetime('now', '-1 hour'), 3);
+             INSERT INTO heartbeats (id, monitor_id, status, response_time_ms, checked_at, seq) VALUES ('h4', 'm', 'down', 0, datetime('now', '-2 days'), 4);",
+        ).unwrap();
+        let meta = load_monitor_metadata(&db, "m");
+        assert_eq!(meta.tags, vec!["prod".to_string(), "api".to_string()]);
+        assert_eq!(meta.group_name.as_deref(), Some("Backend"));
+        assert_eq!(meta.uptime_24h, 100.0);
+        assert_eq!(meta.uptime_7d, 75.0);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_ntfy_headers_and_url() {
+        assert_eq!(ntfy_headers("incident.created"), ("5", "rotating_light,watchpost"));
+        assert_eq!(ntfy_headers("monitor.degraded"), ("4", "warning,watchpost"));
+        assert_eq!(ntfy_headers("incident.resolved"), ("3", "information_source,watchpost"));
+        let channel = NtfyChannel { server: "https://ntfy.example.com/".to_string(), topic: "ops".to_string() };
+        assert_eq!(channel.url(), "https://ntfy.example.com/ops");
+    }
+
+    #[test]
+    fn test_teams_card_contains_monitor_and_incident() {
+        let payload = make_payload("incident.created", "Checkout API", "HTTP 503");
+        let card = teams_card(&payload);
+        assert_eq!(card["attachments"][0]["contentType"], "application/vnd.microsoft.card.adaptive");
+        let content = &card["attachments"][0]["content"];
+        assert_eq!(content["body"][0]["style"], "attention");
+        assert_eq!(content["body"][0]["items"][0]["text"], "Checkout API — DOWN");
+        let facts = content["body"][1]["facts"].as_array().unwrap();
+        assert!(facts.contains(&serde_json::json!({"title": "Monitor", "value": "Checkout API"})));
+        assert!(facts.contains(&serde_json::json!({"title": "Cause", "value": "HTTP 503"})));
+
+        let resolved = teams_card(&make_payload("incident.resolved", "Checkout API", "HTTP 503"));
+        assert_eq!(resolved["attachments"][0]["content"]["body"][0]["style"], "good");
+    }
+
+    #[test]
+    fn test_teams_webhook_url_hosts() {
+        assert!(is_teams_webhook_url("https://contoso.webhook.office.com/webhookb2/abc"));
+        assert!(is_teams_webhook_url("https://outlook.office.com/webhook/abc"));
+        assert!(is_teams_webhook_url("https://prod-12.westus.logic.azure.com:443/workflows/abc"));
+        assert!(!is_teams_webhook_url("http://contoso.webhook.office.com/webhookb2/abc"));
+        assert!(!is_teams_webhook_url("https://webhook.office.com.evil.example/abc"));
+        assert!(!is_teams_webhook_url("not a url"));
+    }
+
+    #[test]
+    fn test_opsgenie_request_create_and_close() {
+        let (path, body) = opsgenie_request(&make_payload("incident.created", "API", "HTTP 503")).unwrap();
+        assert_eq!(path, "/v2/alerts");
+        assert_eq!(body["alias"], "inc_456");
+        assert_eq!(body["message"], "API is down: HTTP 503");
+        assert_eq!(body["priority"], "P2");
+
+        let (_, body) = opsgenie_request(&make_payload("incident.escalated", "API", "HTTP 503")).unwrap();
+        assert_eq!(body["alias"], "inc_456");
+        assert_eq!(body["priority"], "P1");
+
+        let (path, _) = opsgenie_request(&make_payload("incident.resolved", "API", "HTTP 503")).unwrap();
+        assert_eq!(path, "/v2/alerts/inc_456/close?identifierType=alias");
+
+        assert!(opsgenie_request(&make_payload("monitor.degraded", "API", "")).is_none());
+    }
+
+    #[test]
+    fn test_opsgenie_api_key_format() {
+        assert!(is_opsgenie_api_key("eb243592-faa2-4ba2-a551-1afdf565c889"));
+        assert!(!is_opsgenie_api_key("eb243592faa24ba2a5511afdf565c889"));
+        assert!(!is_opsgenie_api_key("not-a-key"));
+        assert_eq!(opsgenie_api_url(Some("eu")), "https://api.eu.opsgenie.com");
+        assert_eq!(opsgenie_api_url(None), "https://api.opsgenie.com");
+    }
+
+    #[test]
+    fn test_filter_min_severity() {
+        let f = Some(r#"{"min_severity":"critical"}"#);
+        assert!(filter_allows(f, event_severity("incident.created"), ""));
+        assert!(filter_allows(f, event_severity("incident.escalated"), ""));
+        assert!(!filter_allows(f, event_severity("monitor.degraded"), ""));
+        assert!(!filter_allows(f, event_severity("incident.resolved"), ""));
+        assert!(filter_allows(None, event_severity("monitor.degraded"), ""));
+    }
+
+    #[test]
+    fn test_filter_tags_intersect() {
+        let f = Some(r#"{"tags":["prod","db"]}"#);
+        assert!(filter_allows(f, Severity::Critical, "api,prod"));
+        assert!(!filter_allows(f, Severity::Critical, "staging"));
+        assert!(!filter_allows(f, Severity::Critical, ""));
+    }
 }
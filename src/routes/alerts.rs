@@ -2,12 +2,34 @@ use rocket::{get, put, delete, serde::json::Json, State, http::Status};
 use crate::db::Db;
 use crate::models::{AlertRule, CreateAlertRule, AlertLogEntry};
 use crate::auth::ManageToken;
-use super::verify_manage_key;
+use super::{verify_manage_key, verify_read_access, verify_admin_key};
 use rusqlite::params;
 use std::sync::Arc;
 
 // ── Alert Rules ──
 
+fn load_alert_rule(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<AlertRule> {
+    conn.query_row(
+        "SELECT monitor_id, repeat_interval_minutes, max_repeats, escalation_after_minutes,
+                latency_threshold_ms, latency_window_minutes, sla_breach_alert, created_at, updated_at,
+                burn_rate_threshold
+         FROM alert_rules WHERE monitor_id = ?1",
+        params![id],
+        |row| Ok(AlertRule {
+            monitor_id: row.get(0)?,
+            repeat_interval_minutes: row.get(1)?,
+            max_repeats: row.get(2)?,
+            escalation_after_minutes: row.get(3)?,
+            latency_threshold_ms: row.get(4)?,
+            latency_window_minutes: row.get(5)?,
+            sla_breach_alert: row.get(6)?,
+            burn_rate_threshold: row.get(9)?,
+            created_at: row.get(7)?,
+            updated_at: row.get(8)?,
+        }),
+    )
+}
+
 #[put("/monitors/<id>/alert-rules", format = "json", data = "<input>")]
 pub fn set_alert_rules(
     id: &str,
@@ -40,30 +62,73 @@ pub fn set_alert_rules(
         }))));
     }
 
+    if let Some(ms) = data.latency_threshold_ms {
+        if ms == 0 || ms > 300_000 {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "latency_threshold_ms must be between 1 and 300000",
+                "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+    if data.latency_window_minutes == 0 || data.latency_window_minutes > 1440 {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "latency_window_minutes must be between 1 and 1440",
+            "code": "VALIDATION_ERROR"
+        }))));
+    }
+
+    if data.sla_breach_alert {
+        let has_target: bool = conn.query_row(
+            "SELECT sla_target IS NOT NULL FROM monitors WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        ).unwrap_or(false);
+        if !has_target {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "sla_breach_alert requires the monitor to have an sla_target",
+                "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+
+    if let Some(threshold) = data.burn_rate_threshold {
+        if !(threshold > 0.0 && threshold <= 1000.0) {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "burn_rate_threshold must be greater than 0 and at most 1000",
+                "code": "VALIDATION_ERROR"
+            }))));
+        }
+        let target: Option<f64> = conn.query_row(
+            "SELECT sla_target FROM monitors WHERE id = ?1",
+            params![id],
+            |row| row.get(0),
+        ).unwrap_or(None);
+        if !target.is_some_and(|t| t < 100.0) {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "burn_rate_threshold requires the monitor to have an sla_target below 100",
+                "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+
     conn.execute(
-        "INSERT INTO alert_rules (monitor_id, repeat_interval_minutes, max_repeats, escalation_after_minutes, updated_at)
-         VALUES (?1, ?2, ?3, ?4, datetime('now'))
+        "INSERT INTO alert_rules (monitor_id, repeat_interval_minutes, max_repeats, escalation_after_minutes,
+                                  latency_threshold_ms, latency_window_minutes, sla_breach_alert, burn_rate_threshold, updated_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, datetime('now'))
          ON CONFLICT(monitor_id) DO UPDATE SET
            repeat_interval_minutes = excluded.repeat_interval_minutes,
            max_repeats = excluded.max_repeats,
            escalation_after_minutes = excluded.escalation_after_minutes,
+           latency_threshold_ms = excluded.latency_threshold_ms,
+           latency_window_minutes = excluded.latency_window_minutes,
+           sla_breach_alert = excluded.sla_breach_alert,
+           burn_rate_threshold = excluded.burn_rate_threshold,
            updated_at = datetime('now')",
-        params![id, data.repeat_interval_minutes, data.max_repeats, data.escalation_after_minutes],
+        params![id, data.repeat_interval_minutes, data.max_repeats, data.escalation_after_minutes,
+                data.latency_threshold_ms, data.latency_window_minutes, data.sla_breach_alert, data.burn_rate_threshold],
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
-    let rule = conn.query_row(
-        "SELECT monitor_id, repeat_interval_minutes, max_repeats, escalation_after_minutes, created_at, updated_at
-         FROM alert_rules WHERE monitor_id = ?1",
-        params![id],
-        |row| Ok(AlertRule {
-            monitor_id: row.get(0)?,
-            repeat_interval_minutes: row.get(1)?,
-            max_repeats: row.get(2)?,
-            escalation_after_minutes: row.get(3)?,
-            created_at: row.get(4)?,
-            updated_at: row.get(5)?,
-        }),
-    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    let rule = load_alert_rule(&conn, id).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
     Ok(Json(rule))
 }
@@ -75,21 +140,9 @@ pub fn get_alert_rules(
     db: &State<Arc<Db>>,
 ) -> Result<Json<AlertRule>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
-    verify_manage_key(&conn, id, &token.0)?;
+    verify_read_access(&conn, id, &token.0)?;
 
-    let rule = conn.query_row(
-        "SELECT monitor_id, repeat_interval_minutes, max_repeats, escalation_after_minutes, created_at, updated_at
-         FROM alert_rules WHERE monitor_id = ?1",
-        params![id],
-        |row| Ok(AlertRule {
-            monitor_id: row.get(0)?,
-            repeat_interval_minutes: row.get(1)?,
-            max_repeats: row.get(2)?,
-            escalation_after_minutes: row.get(3)?,
-            created_at: row.get(4)?,
-            updated_at: row.get(5)?,
-        }),
-    ).map_err(|_| (Status::NotFound, Json(serde_json::json!({
+    let rule = load_alert_rule(&conn, id).map_err(|_| (Status::NotFound, Json(serde_json::json!({
         "error": "No alert rules configured for this monitor",
         "code": "NOT_FOUND"
     }))))?;
@@ -123,52 +176,148 @@ pub fn delete_alert_rules(
 
 // ── Alert Log ──
 
-#[get("/monitors/<id>/alert-log?<limit>&<after>")]
+/// Normalize a caller-supplied timestamp (ISO 8601 or SQLite format) so it
+/// compares correctly against `sent_at`.
+pub(super) fn parse_log_time(conn: &rusqlite::Connection, name: &str, value: &str) -> Result<String, (Status, Json<serde_json::Value>)> {
+    conn.query_row("SELECT datetime(?1)", params![value], |row| row.get::<_, Option<String>>(0))
+        .ok()
+        .flatten()
+        .ok_or_else(|| (Status::BadRequest, Json(serde_json::json!({
+            "error": format!("{} must be a timestamp like 2026-01-01T12:00:00Z", name),
+            "code": "VALIDATION_ERROR"
+        }))))
+}
+
+/// Filters shared by the per-monitor and global alert-log routes.
+struct AlertLogFilter<'a> {
+    monitor_id: Option<&'a str>,
+    alert_type: Option<&'a str>,
+    since: Option<&'a str>,
+    until: Option<&'a str>,
+}
+
+/// Ordering/cursor for an alert-log query: newest first by default, or
+/// oldest-first after a `seq` cursor (same as heartbeats).
+enum AlertLogCursor<'a> {
+    Latest,
+    AfterSentAt(&'a str),
+    AfterSeq(i64),
+}
+
+fn query_alert_log(
+    conn: &rusqlite::Connection,
+    filter: &AlertLogFilter,
+    cursor: AlertLogCursor,
+    limit: u32,
+) -> Result<Vec<AlertLogEntry>, (Status, Json<serde_json::Value>)> {
+    let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
+
+    let mut where_parts: Vec<String> = Vec::new();
+    let mut bind_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if let Some(m) = filter.monitor_id {
+        bind_values.push(Box::new(m.to_string()));
+        where_parts.push(format!("monitor_id = ?{}", bind_values.len()));
+    }
+    if let Some(t) = filter.alert_type {
+        bind_values.push(Box::new(t.to_string()));
+        where_parts.push(format!("alert_type = ?{}", bind_values.len()));
+    }
+    if let Some(since) = filter.since {
+        bind_values.push(Box::new(parse_log_time(conn, "since", since)?));
+        where_parts.push(format!("datetime(sent_at) >= ?{}", bind_values.len()));
+    }
+    if let Some(until) = filter.until {
+        bind_values.push(Box::new(parse_log_time(conn, "until", until)?));
+        where_parts.push(format!("datetime(sent_at) < ?{}", bind_values.len()));
+    }
+    let order = match cursor {
+        AlertLogCursor::Latest => "sent_at DESC, seq DESC",
+        AlertLogCursor::AfterSentAt(ts) => {
+            bind_values.push(Box::new(ts.to_string()));
+            where_parts.push(format!("sent_at > ?{}", bind_values.len()));
+            "sent_at DESC, seq DESC"
+        }
+        AlertLogCursor::AfterSeq(seq) => {
+            bind_values.push(Box::new(seq));
+            where_parts.push(format!("seq > ?{}", bind_values.len()));
+            "seq ASC"
+        }
+    };
+    bind_values.push(Box::new(limit));
+
+    let where_clause = if where_parts.is_empty() { "1 = 1".to_string() } else { where_parts.join(" AND ") };
+    let sql = format!(
+        "SELECT id, monitor_id, incident_id, channel_id, alert_type, event, sent_at, seq
+         FROM alert_log WHERE {} ORDER BY {} LIMIT ?{}",
+        where_clause, order, bind_values.len()
+    );
+    let mut stmt = conn.prepare(&sql).map_err(err_map)?;
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
+    let rows = stmt.query_map(params_ref.as_slice(), |row| Ok(AlertLogEntry {
+        id: row.get(0)?,
+        monitor_id: row.get(1)?,
+        incident_id: row.get(2)?,
+        channel_id: row.get(3)?,
+        alert_type: row.get(4)?,
+        event: row.get(5)?,
+        sent_at: row.get(6)?,
+        seq: row.get(7)?,
+    })).map_err(err_map)?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+#[get("/monitors/<id>/alert-log?<limit>&<after>&<alert_type>&<since>&<until>")]
+#[allow(clippy::too_many_arguments)]
 pub fn get_alert_log(
     id: &str,
     limit: Option<u32>,
     after: Option<String>,
+    alert_type: Option<&str>,
+    since: Option<&str>,
+    until: Option<&str>,
     token: ManageToken,
     db: &State<Arc<Db>>,
 ) -> Result<Json<Vec<AlertLogEntry>>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     verify_manage_key(&conn, id, &token.0)?;
 
-    let limit = limit.unwrap_or(50).min(200);
-
-    let entries: Vec<AlertLogEntry> = if let Some(after_ts) = after {
-        let mut stmt = conn.prepare(
-            "SELECT id, monitor_id, incident_id, channel_id, alert_type, event, sent_at
-             FROM alert_log WHERE monitor_id = ?1 AND sent_at > ?2 ORDER BY sent_at DESC LIMIT ?3"
-        ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
-
-        let rows = stmt.query_map(params![id, after_ts, limit], |row| Ok(AlertLogEntry {
-            id: row.get(0)?,
-            monitor_id: row.get(1)?,
-            incident_id: row.get(2)?,
-            channel_id: row.get(3)?,
-            alert_type: row.get(4)?,
-            event: row.get(5)?,
-            sent_at: row.get(6)?,
-        })).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
-        rows.filter_map(|r| r.ok()).collect()
-    } else {
-        let mut stmt = conn.prepare(
-            "SELECT id, monitor_id, incident_id, channel_id, alert_type, event, sent_at
-             FROM alert_log WHERE monitor_id = ?1 ORDER BY sent_at DESC LIMIT ?2"
-        ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
-
-        let rows = stmt.query_map(params![id, limit], |row| Ok(AlertLogEntry {
-            id: row.get(0)?,
-            monitor_id: row.get(1)?,
-            incident_id: row.get(2)?,
-            channel_id: row.get(3)?,
-            alert_type: row.get(4)?,
-            event: row.get(5)?,
-            sent_at: row.get(6)?,
-        })).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
-        rows.filter_map(|r| r.ok()).collect()
+    let filter = AlertLogFilter { monitor_id: Some(id), alert_type, since, until };
+    let cursor = match after.as_deref() {
+        Some(ts) => AlertLogCursor::AfterSentAt(ts),
+        None => AlertLogCursor::Latest,
     };
+    query_alert_log(&conn, &filter, cursor, limit.unwrap_or(50).min(200)).map(Json)
+}
+
+/// Alert log across monitors. Requires the admin key, or the monitor's manage
+/// key when filtering by `monitor_id`.
+#[get("/alert-log?<monitor_id>&<alert_type>&<since>&<until>&<limit>&<after>")]
+#[allow(clippy::too_many_arguments)]
+pub fn list_alert_log(
+    monitor_id: Option<&str>,
+    alert_type: Option<&str>,
+    since: Option<&str>,
+    until: Option<&str>,
+    limit: Option<u32>,
+    after: Option<i64>,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<Vec<AlertLogEntry>>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    let authorized = verify_admin_key(&conn, &token.0).is_ok()
+        || monitor_id.is_some_and(|m| verify_manage_key(&conn, m, &token.0).is_ok());
+    if !authorized {
+        return Err((Status::Forbidden, Json(serde_json::json!({
+            "error": "Admin key (or the monitor's manage key with monitor_id) required",
+            "code": "FORBIDDEN"
+        }))));
+    }
 
-    Ok(Json(entries))
+    let filter = AlertLogFilter { monitor_id, alert_type, since, until };
+    let cursor = match after {
+        Some(seq) => AlertLogCursor::AfterSeq(seq),
+        None => AlertLogCursor::Latest,
+    };
+    query_alert_log(&conn, &filter, cursor, limit.unwrap_or(50).min(200)).map(Json)
 }
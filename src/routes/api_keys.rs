@@ -0,0 +1,108 @@
+use rocket::{get, post, delete, serde::json::Json, State, http::Status};
+use crate::db::Db;
+use crate::models::{ApiKey, CreateApiKey, CreateApiKeyResponse};
+use crate::auth::{ManageToken, Scope, generate_key, hash_key};
+use super::verify_admin_key;
+use rusqlite::params;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn row_to_api_key(row: &rusqlite::Row) -> rusqlite::Result<ApiKey> {
+    let scopes: String = row.get(2)?;
+    Ok(ApiKey {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        scopes: scopes.split(',').filter(|s| !s.is_empty()).map(String::from).collect(),
+        created_at: row.get(3)?,
+    })
+}
+
+/// POST /api/v1/api-keys — Create a scoped API key (admin key required)
+#[post("/api-keys", data = "<body>")]
+pub fn create_api_key(
+    body: Json<CreateApiKey>,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<CreateApiKeyResponse>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    let name = body.name.trim();
+    if name.is_empty() || name.len() > 200 {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "Name must be 1-200 characters", "code": "VALIDATION_ERROR"
+        }))));
+    }
+
+    let mut scopes = Vec::new();
+    for raw in &body.scopes {
+        let scope = Scope::parse(raw.trim()).ok_or_else(|| (Status::BadRequest, Json(serde_json::json!({
+            "error": format!("Unknown scope '{}'. Must be one of: read, write, admin", raw), "code": "VALIDATION_ERROR"
+        }))))?;
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    if scopes.is_empty() {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "At least one scope is required", "code": "VALIDATION_ERROR"
+        }))));
+    }
+    scopes.sort();
+    let scopes: Vec<String> = scopes.iter().map(|s| s.as_str().to_string()).collect();
+
+    let id = Uuid::new_v4().to_string();
+    let key = generate_key();
+    conn.execute(
+        "INSERT INTO api_keys (id, name, key_hash, scopes, created_at) VALUES (?1, ?2, ?3, ?4, datetime('now'))",
+        params![id, name, hash_key(&key), scopes.join(",")],
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+
+    let api_key = conn.query_row(
+        "SELECT id, name, scopes, created_at FROM api_keys WHERE id = ?1",
+        params![id],
+        row_to_api_key,
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+
+    Ok(Json(CreateApiKeyResponse { api_key, key }))
+}
+
+/// GET /api/v1/api-keys — List API keys, without the keys themselves (admin key required)
+#[get("/api-keys")]
+pub fn list_api_keys(
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<Vec<ApiKey>>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, name, scopes, created_at FROM api_keys ORDER BY created_at ASC, id ASC"
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    let keys = stmt.query_map([], row_to_api_key)
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
+        .filter_map(|r| r.ok())
+        .collect();
+
+    Ok(Json(keys))
+}
+
+/// DELETE /api/v1/api-keys/<id> — Revoke an API key (admin key required)
+#[delete("/api-keys/<id>")]
+pub fn delete_api_key(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    let deleted = conn.execute("DELETE FROM api_keys WHERE id = ?1", params![id]).unwrap_or(0);
+    if deleted == 0 {
+        return Err((Status::NotFound, Json(serde_json::json!({
+            "error": "API key not found", "code": "NOT_FOUND"
+        }))));
+    }
+
+    Ok(Json(serde_json::json!({ "deleted": true })))
+}
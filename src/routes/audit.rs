@@ -0,0 +1,104 @@
+use rocket::{get, serde::json::Json, State, http::Status};
+use crate::db::Db;
+use crate::models::{AuditLogEntry, Monitor};
+use crate::auth::{ManageToken, hash_key};
+use super::verify_admin_key;
+use rusqlite::params;
+use std::sync::Arc;
+
+// ── Audit Helpers ──
+
+/// Describe who is acting with `token`: the admin key, a named API key, or a monitor's manage key.
+pub(crate) fn audit_actor(conn: &rusqlite::Connection, token: &str) -> String {
+    let hash = hash_key(token);
+    let is_admin = conn.query_row(
+        "SELECT COUNT(*) FROM settings WHERE key = 'admin_key_hash' AND value = ?1",
+        params![hash],
+        |r| r.get::<_, i64>(0),
+    ).unwrap_or(0) > 0;
+    if is_admin {
+        return "admin".to_string();
+    }
+    match conn.query_row("SELECT name FROM api_keys WHERE key_hash = ?1", params![hash], |r| r.get::<_, String>(0)) {
+        Ok(name) => format!("api_key:{}", name),
+        Err(_) => "manage_key".to_string(),
+    }
+}
+
+/// Append an entry to the audit log. Failures are ignored: the action itself already happened.
+pub(crate) fn record_audit(
+    conn: &rusqlite::Connection,
+    action: &str,
+    monitor_id: Option<&str>,
+    actor: &str,
+    changes: Option<serde_json::Value>,
+) {
+    conn.execute(
+        "INSERT INTO audit_log (action, monitor_id, actor, changes, created_at) VALUES (?1, ?2, ?3, ?4, datetime('now'))",
+        params![action, monitor_id, actor, changes.map(|c| c.to_string())],
+    ).ok();
+}
+
+/// Fields that differ between two versions of a monitor, as `{field: {from, to}}`.
+pub(crate) fn monitor_diff(before: &Monitor, after: &Monitor) -> serde_json::Value {
+    let before = serde_json::to_value(before).unwrap_or_default();
+    let after = serde_json::to_value(after).unwrap_or_default();
+    let (Some(before), Some(after)) = (before.as_object(), after.as_object()) else {
+        return serde_json::json!({});
+    };
+    let mut fields: Vec<&String> = before.keys().chain(after.keys()).filter(|k| k.as_str() != "updated_at").collect();
+    fields.sort();
+    fields.dedup();
+
+    let mut diff = serde_json::Map::new();
+    for field in fields {
+        let from = before.get(field).cloned().unwrap_or(serde_json::Value::Null);
+        let to = after.get(field).cloned().unwrap_or(serde_json::Value::Null);
+        if from != to {
+            diff.insert(field.clone(), serde_json::json!({"from": from, "to": to}));
+        }
+    }
+    serde_json::Value::Object(diff)
+}
+
+// ── Audit Log Endpoint ──
+
+/// GET /api/v1/audit-log — Management actions, newest first (admin key required).
+/// `after` is a `seq` cursor: entries after it are returned oldest first.
+#[get("/audit-log?<monitor_id>&<action>&<limit>&<after>")]
+pub fn list_audit_log(
+    monitor_id: Option<&str>,
+    action: Option<&str>,
+    limit: Option<u32>,
+    after: Option<i64>,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<Vec<AuditLogEntry>>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    let limit = limit.unwrap_or(50).min(200);
+    let order = if after.is_some() { "ASC" } else { "DESC" };
+    let sql = format!(
+        "SELECT seq, action, monitor_id, actor, changes, created_at FROM audit_log
+         WHERE (?1 IS NULL OR monitor_id = ?1) AND (?2 IS NULL OR action = ?2) AND (?3 IS NULL OR seq > ?3)
+         ORDER BY seq {order} LIMIT ?4"
+    );
+    let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
+    let mut stmt = conn.prepare(&sql).map_err(err_map)?;
+    let entries = stmt.query_map(params![monitor_id, action, after, limit], |row| {
+        let changes: Option<String> = row.get(4)?;
+        Ok(AuditLogEntry {
+            seq: row.get(0)?,
+            action: row.get(1)?,
+            monitor_id: row.get(2)?,
+            actor: row.get(3)?,
+            changes: changes.and_then(|c| serde_json::from_str(&c).ok()),
+            created_at: row.get(5)?,
+        })
+    }).map_err(err_map)?
+    .filter_map(|r| r.ok())
+    .collect();
+
+    Ok(Json(entries))
+}
@@ -1,11 +1,66 @@
-use rocket::{get, serde::json::Json, State, http::{Status, ContentType}};
+use rocket::{get, serde::json::Json, State, http::{Header, Status, ContentType}};
+use rocket::request::{FromRequest, Outcome, Request};
 use crate::db::Db;
+use crate::models::ShieldsEndpoint;
 use super::get_monitor_from_db;
 use rusqlite::params;
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
 // ── Status Badges ──
 
+/// README embeds refetch badges constantly; let clients and proxies hold them briefly.
+const BADGE_CACHE_CONTROL: &str = "public, max-age=60";
+
+/// The `If-None-Match` header, if present.
+pub struct IfNoneMatch(pub Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfNoneMatch {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfNoneMatch(request.headers().get_one("If-None-Match").map(str::to_string)))
+    }
+}
+
+impl IfNoneMatch {
+    fn matches(&self, etag: &str) -> bool {
+        self.0.as_deref().is_some_and(|h| {
+            h.split(',').map(|t| t.trim()).any(|t| t == "*" || t.trim_start_matches("W/") == etag)
+        })
+    }
+}
+
+/// A badge with its caching headers; a bare 304 when the client's
+/// `If-None-Match` already matches.
+#[derive(rocket::Responder)]
+pub struct BadgeResponse {
+    body: (Status, String),
+    content_type: ContentType,
+    etag: Header<'static>,
+    cache_control: Header<'static>,
+}
+
+/// Build the badge response, skipping the SVG render when the client already
+/// holds it. The ETag covers everything drawn (label, value, color) plus the
+/// monitor's current status.
+fn badge_response(kind: &str, status: &str, label: &str, value: &str, color: &str, if_none_match: &IfNoneMatch) -> BadgeResponse {
+    let digest = Sha256::digest(format!("{}\n{}\n{}\n{}\n{}", kind, status, label, value, color).as_bytes());
+    let etag = format!("\"{}\"", &hex::encode(digest)[..16]);
+    let body = if if_none_match.matches(&etag) {
+        (Status::NotModified, String::new())
+    } else {
+        (Status::Ok, render_badge(label, value, color))
+    };
+    BadgeResponse {
+        body,
+        content_type: ContentType::new("image", "svg+xml"),
+        etag: Header::new("ETag", etag),
+        cache_control: Header::new("Cache-Control", BADGE_CACHE_CONTROL),
+    }
+}
+
 /// Generate a shields.io-style SVG badge
 fn render_badge(label: &str, value: &str, color: &str) -> String {
     let label_width = (label.len() as f64 * 6.5 + 10.0) as u32;
@@ -60,35 +115,76 @@ fn uptime_color(pct: f64) -> &'static str {
     else { "#e05d44" }
 }
 
-fn status_color(status: &str) -> &'static str {
-    match status {
-        "up" => "#4c1",
-        "degraded" => "#dfb317",
-        "maintenance" => "#9f9f9f",
-        "paused" => "#9f9f9f",
-        "down" => "#e05d44",
-        _ => "#9f9f9f",
+/// Three-tier coloring once a team sets its own thresholds: green at or above
+/// `warn`, amber at or above `crit`, red below. Unset thresholds default to 99 and 90.
+fn threshold_color(pct: f64, warn: Option<f64>, crit: Option<f64>) -> &'static str {
+    if pct >= warn.unwrap_or(99.0) { "#4c1" }
+    else if pct >= crit.unwrap_or(90.0) { "#dfb317" }
+    else { "#e05d44" }
+}
+
+fn badge_error(message: &str) -> (Status, Json<serde_json::Value>) {
+    (Status::BadRequest, Json(serde_json::json!({"error": message, "code": "VALIDATION_ERROR"})))
+}
+
+/// Accept a 3- or 6-digit hex color, with or without the leading `#`.
+/// Anything else is rejected since it ends up inside the SVG markup.
+fn parse_badge_color(color: &str) -> Result<String, (Status, Json<serde_json::Value>)> {
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    if (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(format!("#{}", hex))
+    } else {
+        Err(badge_error("color must be a hex color like #007ec6 or 007ec6"))
     }
 }
 
-#[get("/monitors/<id>/badge/uptime?<period>&<label>")]
-pub fn monitor_uptime_badge(
-    id: &str,
-    period: Option<&str>,
-    label: Option<&str>,
-    db: &State<Arc<Db>>,
-) -> Result<(ContentType, String), (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
-    get_monitor_from_db(&conn, id)
-        .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
+/// Latency bands in ms; with `warn`/`crit` set, the same three-tier scale as
+/// the uptime badge (green below `warn`, amber below `crit`, red above).
+fn response_color(ms: f64, warn: Option<f64>, crit: Option<f64>) -> &'static str {
+    if warn.is_some() || crit.is_some() {
+        if ms < warn.unwrap_or(500.0) { "#4c1" }
+        else if ms < crit.unwrap_or(1000.0) { "#dfb317" }
+        else { "#e05d44" }
+    } else if ms < 200.0 { "#4c1" }
+    else if ms < 500.0 { "#97ca00" }
+    else if ms < 1000.0 { "#dfb317" }
+    else if ms < 2000.0 { "#fe7d37" }
+    else { "#e05d44" }
+}
 
-    let hours = match period.unwrap_or("24h") {
+fn period_hours(period: Option<&str>) -> u32 {
+    match period.unwrap_or("24h") {
         "7d" => 168,
         "30d" => 720,
         "90d" => 2160,
         _ => 24,
-    };
+    }
+}
+
+fn check_uptime_thresholds(warn: Option<f64>, crit: Option<f64>) -> Result<(), (Status, Json<serde_json::Value>)> {
+    for t in [warn, crit].into_iter().flatten() {
+        if !(0.0..=100.0).contains(&t) {
+            return Err(badge_error("warn and crit must be between 0 and 100"));
+        }
+    }
+    if let (Some(w), Some(c)) = (warn, crit) {
+        if c > w {
+            return Err(badge_error("crit must not be above warn"));
+        }
+    }
+    Ok(())
+}
 
+fn uptime_badge_color(pct: f64, warn: Option<f64>, crit: Option<f64>) -> &'static str {
+    if warn.is_none() && crit.is_none() {
+        uptime_color(pct)
+    } else {
+        threshold_color(pct, warn, crit)
+    }
+}
+
+/// Share of `up` heartbeats over the last `hours`; 100% when there are none yet.
+fn uptime_percent(conn: &rusqlite::Connection, id: &str, hours: u32) -> f64 {
     let total: u32 = conn.query_row(
         "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2)",
         params![id, format!("-{} hours", hours)],
@@ -101,33 +197,154 @@ pub fn monitor_uptime_badge(
         |row| row.get(0),
     ).unwrap_or(0);
 
-    let pct = if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 };
+    if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 }
+}
+
+/// Shields.io named color for one of our badge hex colors; custom colors pass
+/// through as bare hex, which Shields also accepts.
+fn shields_color(hex: &str) -> String {
+    match hex {
+        "#4c1" => "brightgreen".to_string(),
+        "#97ca00" => "green".to_string(),
+        "#dfb317" => "yellow".to_string(),
+        "#fe7d37" => "orange".to_string(),
+        "#e05d44" => "red".to_string(),
+        "#9f9f9f" => "lightgrey".to_string(),
+        other => other.trim_start_matches('#').to_string(),
+    }
+}
+
+fn status_color(status: &str) -> &'static str {
+    match status {
+        "up" => "#4c1",
+        "degraded" => "#dfb317",
+        "maintenance" => "#9f9f9f",
+        "paused" => "#9f9f9f",
+        "down" => "#e05d44",
+        _ => "#9f9f9f",
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[get("/monitors/<id>/badge/uptime?<period>&<label>&<warn>&<crit>&<color>")]
+pub fn monitor_uptime_badge(
+    id: &str,
+    period: Option<&str>,
+    label: Option<&str>,
+    warn: Option<f64>,
+    crit: Option<f64>,
+    color: Option<&str>,
+    if_none_match: IfNoneMatch,
+    db: &State<Arc<Db>>,
+) -> Result<BadgeResponse, (Status, Json<serde_json::Value>)> {
+    check_uptime_thresholds(warn, crit)?;
+    let color_override = color.map(parse_badge_color).transpose()?;
+
+    let conn = db.read();
+    let monitor = get_monitor_from_db(&conn, id)
+        .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
+
+    let pct = uptime_percent(&conn, id, period_hours(period));
     let value = format!("{:.1}%", pct);
-    let color = uptime_color(pct);
+    let color = color_override.as_deref().unwrap_or_else(|| uptime_badge_color(pct, warn, crit));
     let period_str = period.unwrap_or("24h");
     let default_label = format!("uptime {}", period_str);
     let badge_label = label.unwrap_or(&default_label);
 
-    let svg = render_badge(badge_label, &value, color);
-    let ct = ContentType::new("image", "svg+xml");
-    Ok((ct, svg))
+    Ok(badge_response("uptime", &monitor.current_status, badge_label, &value, color, &if_none_match))
+}
+
+/// Shields.io endpoint schema for the uptime badge, for users who render via
+/// `https://img.shields.io/endpoint?url=...` instead of our SVG.
+#[get("/monitors/<id>/badge/uptime.json?<period>&<label>&<warn>&<crit>&<color>")]
+pub fn monitor_uptime_badge_json(
+    id: &str,
+    period: Option<&str>,
+    label: Option<&str>,
+    warn: Option<f64>,
+    crit: Option<f64>,
+    color: Option<&str>,
+    db: &State<Arc<Db>>,
+) -> Result<Json<ShieldsEndpoint>, (Status, Json<serde_json::Value>)> {
+    check_uptime_thresholds(warn, crit)?;
+    let color_override = color.map(parse_badge_color).transpose()?;
+
+    let conn = db.read();
+    get_monitor_from_db(&conn, id)
+        .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
+
+    let pct = uptime_percent(&conn, id, period_hours(period));
+    let color = color_override.as_deref().unwrap_or_else(|| uptime_badge_color(pct, warn, crit));
+
+    Ok(Json(ShieldsEndpoint {
+        schema_version: 1,
+        label: label.map(str::to_string).unwrap_or_else(|| format!("uptime {}", period.unwrap_or("24h"))),
+        message: format!("{:.1}%", pct),
+        color: shields_color(color),
+    }))
 }
 
-#[get("/monitors/<id>/badge/status?<label>")]
+#[get("/monitors/<id>/badge/status?<label>&<color>")]
 pub fn monitor_status_badge(
     id: &str,
     label: Option<&str>,
+    color: Option<&str>,
+    if_none_match: IfNoneMatch,
     db: &State<Arc<Db>>,
-) -> Result<(ContentType, String), (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+) -> Result<BadgeResponse, (Status, Json<serde_json::Value>)> {
+    let color_override = color.map(parse_badge_color).transpose()?;
+    let conn = db.read();
     let monitor = get_monitor_from_db(&conn, id)
         .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
 
     let status = &monitor.current_status;
-    let color = status_color(status);
+    let color = color_override.as_deref().unwrap_or_else(|| status_color(status));
     let badge_label = label.unwrap_or("status");
 
-    let svg = render_badge(badge_label, status, color);
-    let ct = ContentType::new("image", "svg+xml");
-    Ok((ct, svg))
+    Ok(badge_response("status", status, badge_label, status, color, &if_none_match))
+}
+
+#[allow(clippy::too_many_arguments)]
+#[get("/monitors/<id>/badge/response?<period>&<label>&<warn>&<crit>&<color>")]
+pub fn monitor_response_badge(
+    id: &str,
+    period: Option<&str>,
+    label: Option<&str>,
+    warn: Option<f64>,
+    crit: Option<f64>,
+    color: Option<&str>,
+    if_none_match: IfNoneMatch,
+    db: &State<Arc<Db>>,
+) -> Result<BadgeResponse, (Status, Json<serde_json::Value>)> {
+    if [warn, crit].into_iter().flatten().any(|t| t < 0.0) {
+        return Err(badge_error("warn and crit must not be negative"));
+    }
+    if let (Some(w), Some(c)) = (warn, crit) {
+        if c < w {
+            return Err(badge_error("crit must not be below warn"));
+        }
+    }
+    let color_override = color.map(parse_badge_color).transpose()?;
+
+    let conn = db.read();
+    let monitor = get_monitor_from_db(&conn, id)
+        .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
+
+    // Failed checks carry timeout-sized latencies, so only successful ones count.
+    let avg: Option<f64> = conn.query_row(
+        "SELECT AVG(response_time_ms) FROM heartbeats
+         WHERE monitor_id = ?1 AND status IN ('up', 'degraded') AND checked_at > datetime('now', ?2)",
+        params![id, format!("-{} hours", period_hours(period))],
+        |row| row.get(0),
+    ).unwrap_or(None);
+
+    let (value, default_color) = match avg {
+        Some(ms) => (format!("{:.0} ms", ms), response_color(ms, warn, crit)),
+        None => ("no data".to_string(), "#9f9f9f"),
+    };
+    let color = color_override.as_deref().unwrap_or(default_color);
+    let default_label = format!("response {}", period.unwrap_or("24h"));
+    let badge_label = label.unwrap_or(&default_label);
+
+    Ok(badge_response("response", &monitor.current_status, badge_label, &value, color, &if_none_match))
 }
@@ -1,34 +1,32 @@
-use rocket::{get, serde::json::Json, State, http::Status};
+use rocket::{get, post, serde::json::Json, State, http::Status};
+use crate::checker;
 use crate::db::Db;
-use crate::models::{DashboardOverview, StatusCounts, DashboardIncident, SlowMonitor};
-use crate::auth::{OptionalManageToken, hash_key};
+use crate::models::{DashboardOverview, StatusCounts, DashboardIncident, SlowMonitor, DbStats};
+use crate::auth::{ManageToken, OptionalManageToken, generate_key, hash_key};
+use super::{field_error, verify_admin_key};
 use rusqlite::params;
 use std::sync::Arc;
 
-/// Check if the provided token matches the admin key.
+/// Check if the provided token is the admin key (or an admin-scoped API key).
 fn is_admin(conn: &rusqlite::Connection, token: &Option<String>) -> bool {
-    let Some(ref key) = token else { return false };
-    let Ok(stored_hash) = conn.query_row(
-        "SELECT value FROM settings WHERE key = 'admin_key_hash'",
-        [],
-        |row| row.get::<_, String>(0),
-    ) else { return false };
-    hash_key(key) == stored_hash
+    token.as_ref().is_some_and(|key| verify_admin_key(conn, key).is_ok())
 }
 
 // ── Dashboard ──
 
 #[get("/dashboard")]
 pub fn dashboard(token: OptionalManageToken, db: &State<Arc<Db>>) -> Result<Json<DashboardOverview>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+    let conn = db.read();
     let admin = is_admin(&conn, &token.0);
 
-    let total_monitors: u32 = conn.query_row("SELECT COUNT(*) FROM monitors", [], |r| r.get(0)).unwrap_or(0);
-    let public_monitors: u32 = conn.query_row("SELECT COUNT(*) FROM monitors WHERE is_public = 1", [], |r| r.get(0)).unwrap_or(0);
-    let paused_monitors: u32 = conn.query_row("SELECT COUNT(*) FROM monitors WHERE is_paused = 1", [], |r| r.get(0)).unwrap_or(0);
+    let total_monitors: u32 = conn.query_row("SELECT COUNT(*) FROM monitors WHERE deleted_at IS NULL", [], |r| r.get(0)).unwrap_or(0);
+    let public_monitors: u32 = conn.query_row("SELECT COUNT(*) FROM monitors WHERE deleted_at IS NULL AND is_public = 1", [], |r| r.get(0)).unwrap_or(0);
+    let paused_monitors: u32 = conn.query_row("SELECT COUNT(*) FROM monitors WHERE deleted_at IS NULL AND is_paused = 1", [], |r| r.get(0)).unwrap_or(0);
+    let archived_monitors: u32 = conn.query_row("SELECT COUNT(*) FROM monitors WHERE deleted_at IS NULL AND is_archived = 1", [], |r| r.get(0)).unwrap_or(0);
 
+    // Archived monitors keep their last status, which no longer reflects anything live
     let count_status = |s: &str| -> u32 {
-        conn.query_row("SELECT COUNT(*) FROM monitors WHERE current_status = ?1", params![s], |r| r.get(0)).unwrap_or(0)
+        conn.query_row("SELECT COUNT(*) FROM monitors WHERE deleted_at IS NULL AND is_archived = 0 AND current_status = ?1", params![s], |r| r.get(0)).unwrap_or(0)
     };
     let status_counts = StatusCounts {
         up: count_status("up"),
@@ -67,7 +65,7 @@ pub fn dashboard(token: OptionalManageToken, db: &State<Arc<Db>>) -> Result<Json
     let recent_incidents: Vec<DashboardIncident> = if admin {
         let mut stmt = conn.prepare(
             "SELECT i.id, i.monitor_id, m.name, i.started_at, i.resolved_at, i.cause \
-             FROM incidents i JOIN monitors m ON i.monitor_id = m.id \
+             FROM incidents i JOIN monitors m ON i.monitor_id = m.id AND m.deleted_at IS NULL \
              ORDER BY i.started_at DESC LIMIT 10"
         ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
         let rows: Vec<DashboardIncident> = stmt.query_map([], |row| {
@@ -90,7 +88,7 @@ pub fn dashboard(token: OptionalManageToken, db: &State<Arc<Db>>) -> Result<Json
     let slowest_monitors: Vec<SlowMonitor> = if admin {
         let mut stmt = conn.prepare(
             "SELECT m.id, m.name, AVG(h.response_time_ms) as avg_ms, m.current_status \
-             FROM heartbeats h JOIN monitors m ON h.monitor_id = m.id \
+             FROM heartbeats h JOIN monitors m ON h.monitor_id = m.id AND m.deleted_at IS NULL \
              WHERE h.status = 'up' AND h.checked_at > datetime('now', '-24 hours') \
              GROUP BY m.id \
              ORDER BY avg_ms DESC LIMIT 5"
@@ -114,6 +112,7 @@ pub fn dashboard(token: OptionalManageToken, db: &State<Arc<Db>>) -> Result<Json
         total_monitors,
         public_monitors,
         paused_monitors,
+        archived_monitors,
         status_counts,
         active_incidents,
         avg_uptime_24h,
@@ -136,3 +135,65 @@ pub fn admin_verify(token: OptionalManageToken, db: &State<Arc<Db>>) -> Result<J
         Ok(Json(serde_json::json!({"valid": false})))
     }
 }
+
+// ── Admin Stats ──
+
+/// Table sizes and database footprint, for capacity planning and retention tuning.
+#[get("/admin/stats")]
+pub fn admin_stats(token: ManageToken, db: &State<Arc<Db>>) -> Result<Json<DbStats>, (Status, Json<serde_json::Value>)> {
+    verify_admin_key(&db.conn(), &token.0)?;
+    db.stats()
+        .map(Json)
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))
+}
+
+// ── Admin Maintenance ──
+
+/// Run heartbeat retention now instead of waiting for the checker's hourly pass.
+/// `days` defaults to HEARTBEAT_RETENTION_DAYS.
+#[post("/admin/prune?<days>")]
+pub fn admin_prune(days: Option<u32>, token: ManageToken, db: &State<Arc<Db>>) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    verify_admin_key(&db.conn(), &token.0)?;
+    let days = days.unwrap_or_else(checker::retention_days);
+    if days == 0 {
+        return Err(field_error("days", "days must be at least 1"));
+    }
+    let deleted = checker::prune_heartbeats(db, days);
+    Ok(Json(serde_json::json!({
+        "deleted_heartbeats": deleted,
+        "older_than_days": days,
+    })))
+}
+
+/// Compact the database file. Writes (including check results) wait until it finishes.
+#[post("/admin/vacuum")]
+pub fn admin_vacuum(token: ManageToken, db: &State<Arc<Db>>) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    verify_admin_key(&db.conn(), &token.0)?;
+    let (before, after) = db.vacuum()
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    Ok(Json(serde_json::json!({
+        "size_before_bytes": before,
+        "size_after_bytes": after,
+        "reclaimed_bytes": before.saturating_sub(after),
+    })))
+}
+
+// ── Admin Key Rotation ──
+
+/// Replace the admin key. The current key stops working as soon as this returns.
+#[post("/admin/rotate-key")]
+pub fn rotate_admin_key(token: ManageToken, db: &State<Arc<Db>>) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    let admin_key = generate_key();
+    conn.execute(
+        "UPDATE settings SET value = ?1, updated_at = datetime('now') WHERE key = 'admin_key_hash'",
+        params![hash_key(&admin_key)],
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Admin key rotated",
+        "admin_key": admin_key,
+    })))
+}
@@ -23,7 +23,7 @@ pub fn add_dependency(
 
     // Validate: monitor exists
     let monitor_exists: bool = conn
-        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1", params![id], |r| r.get::<_, i64>(0))
+        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL", params![id], |r| r.get::<_, i64>(0))
         .map(|c| c > 0)
         .unwrap_or(false);
     if !monitor_exists {
@@ -34,7 +34,7 @@ pub fn add_dependency(
 
     // Validate: dependency monitor exists
     let dep_exists: bool = conn
-        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1", params![depends_on_id], |r| r.get::<_, i64>(0))
+        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL", params![depends_on_id], |r| r.get::<_, i64>(0))
         .map(|c| c > 0)
         .unwrap_or(false);
     if !dep_exists {
@@ -50,11 +50,11 @@ pub fn add_dependency(
         }))));
     }
 
-    // Validate: no circular dependency
-    // Walk the dependency chain from depends_on_id to see if it eventually reaches id
+    // Validate: no cycle
+    // DFS from depends_on_id over existing edges to see if it eventually reaches id
     if has_circular_dependency(&conn, depends_on_id, id) {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "Adding this dependency would create a circular chain", "code": "CIRCULAR_DEPENDENCY"
+        return Err((Status::Conflict, Json(serde_json::json!({
+            "error": "Adding this dependency would create a cycle", "code": "DEPENDENCY_CYCLE"
         }))));
     }
 
@@ -100,7 +100,7 @@ pub fn list_dependencies(
 
     // Check monitor exists
     let exists: bool = conn
-        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1", params![id], |r| r.get::<_, i64>(0))
+        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL", params![id], |r| r.get::<_, i64>(0))
         .map(|c| c > 0)
         .unwrap_or(false);
     if !exists {
@@ -112,7 +112,7 @@ pub fn list_dependencies(
     let mut stmt = conn.prepare(
         "SELECT d.id, d.monitor_id, d.depends_on_id, m.name, m.current_status, d.created_at
          FROM monitor_dependencies d
-         JOIN monitors m ON m.id = d.depends_on_id
+         JOIN monitors m ON m.id = d.depends_on_id AND m.deleted_at IS NULL
          WHERE d.monitor_id = ?1
          ORDER BY d.created_at ASC"
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
@@ -165,7 +165,7 @@ pub fn list_dependents(
     let conn = db.conn();
 
     let exists: bool = conn
-        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1", params![id], |r| r.get::<_, i64>(0))
+        .query_row("SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL", params![id], |r| r.get::<_, i64>(0))
         .map(|c| c > 0)
         .unwrap_or(false);
     if !exists {
@@ -177,7 +177,7 @@ pub fn list_dependents(
     let mut stmt = conn.prepare(
         "SELECT d.id, d.monitor_id, d.depends_on_id, m.name, m.current_status, d.created_at
          FROM monitor_dependencies d
-         JOIN monitors m ON m.id = d.monitor_id
+         JOIN monitors m ON m.id = d.monitor_id AND m.deleted_at IS NULL
          WHERE d.depends_on_id = ?1
          ORDER BY d.created_at ASC"
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
@@ -245,25 +245,34 @@ fn get_dependency_row(conn: &rusqlite::Connection, dep_id: &str) -> rusqlite::Re
     )
 }
 
-/// Check if any of a monitor's dependencies are currently down.
-/// Used by the checker to suppress alerts when upstream is down.
-pub fn has_dependency_down(conn: &rusqlite::Connection, monitor_id: &str) -> bool {
-    let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM monitor_dependencies d
-         JOIN monitors m ON m.id = d.depends_on_id
-         WHERE d.monitor_id = ?1 AND m.current_status = 'down'",
-        params![monitor_id],
-        |r| r.get(0),
-    ).unwrap_or(0);
-    count > 0
-}
+/// Find the down dependency a monitor's outage should be attributed to.
+/// Walks the dependency graph (direct and transitive) and follows chains of
+/// down monitors to the furthest upstream one, so A → B → C with B and C down
+/// attributes A's outage to C. Returns (id, name). Cycle-safe.
+pub fn find_down_dependency(conn: &rusqlite::Connection, monitor_id: &str) -> Option<(String, String)> {
+    let down_deps = |id: &str| -> Vec<(String, String)> {
+        let mut stmt = match conn.prepare(
+            "SELECT m.id, m.name FROM monitor_dependencies d
+             JOIN monitors m ON m.id = d.depends_on_id AND m.deleted_at IS NULL
+             WHERE d.monitor_id = ?1 AND m.current_status = 'down'
+             ORDER BY d.created_at ASC"
+        ) {
+            Ok(s) => s,
+            Err(_) => return vec![],
+        };
+        stmt.query_map(params![id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map(|rows| rows.filter_map(|r| r.ok()).collect())
+            .unwrap_or_default()
+    };
 
-/// Check if a monitor has any open (unresolved) incidents.
-pub fn has_open_incident(conn: &rusqlite::Connection, monitor_id: &str) -> bool {
-    let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM incidents WHERE monitor_id = ?1 AND resolved_at IS NULL",
-        params![monitor_id],
-        |r| r.get(0),
-    ).unwrap_or(0);
-    count > 0
+    let mut visited = std::collections::HashSet::new();
+    visited.insert(monitor_id.to_string());
+    let mut current = down_deps(monitor_id).into_iter().next()?;
+    loop {
+        visited.insert(current.0.clone());
+        match down_deps(&current.0).into_iter().find(|(id, _)| !visited.contains(id)) {
+            Some(next) => current = next,
+            None => return Some(current),
+        }
+    }
 }
@@ -0,0 +1,144 @@
+use async_graphql::{Context, EmptyMutation, EmptySubscription, InputObject, Object, Schema};
+use rocket::{post, serde::json::Json, State};
+use crate::db::Db;
+use crate::models::{Incident, Monitor, UptimeStats};
+use super::get_monitor_from_db;
+use super::heartbeats::query_uptime_stats;
+use super::incidents::query_recent_incidents;
+use super::monitors::query_public_monitors;
+use std::sync::Arc;
+
+// ── GraphQL ──
+
+pub type GraphqlSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Read-only schema over the same queries as the REST endpoints.
+pub fn build_graphql_schema(db: Arc<Db>) -> GraphqlSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(db)
+        .limit_depth(8)
+        .limit_complexity(500)
+        .finish()
+}
+
+#[post("/graphql", format = "json", data = "<request>")]
+pub async fn graphql_query(
+    request: Json<async_graphql::Request>,
+    schema: &State<GraphqlSchema>,
+) -> Json<async_graphql::Response> {
+    Json(schema.execute(request.into_inner()).await)
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// A monitor by id; like GET /monitors/{id}, unlisted monitors resolve too
+    async fn monitor(&self, ctx: &Context<'_>, id: String) -> Option<MonitorNode> {
+        let db = ctx.data_unchecked::<Arc<Db>>();
+        let conn = db.read();
+        get_monitor_from_db(&conn, &id).ok().map(MonitorNode)
+    }
+
+    /// Public monitors, with the same filters as GET /monitors
+    async fn monitors(&self, ctx: &Context<'_>, filter: Option<MonitorFilter>) -> async_graphql::Result<Vec<MonitorNode>> {
+        let db = ctx.data_unchecked::<Arc<Db>>();
+        let filter = filter.unwrap_or_default();
+        let conn = db.read();
+        let monitors = query_public_monitors(
+            &conn,
+            filter.search.as_deref(),
+            filter.status.as_deref(),
+            filter.tag.as_deref(),
+            filter.group.as_deref(),
+        ).map_err(|_| async_graphql::Error::new("Internal server error"))?;
+        Ok(monitors.into_iter().map(MonitorNode).collect())
+    }
+}
+
+#[derive(InputObject, Default)]
+pub struct MonitorFilter {
+    /// Substring of the name or URL
+    search: Option<String>,
+    /// up, down, degraded, or unknown
+    status: Option<String>,
+    tag: Option<String>,
+    group: Option<String>,
+}
+
+pub struct MonitorNode(Monitor);
+
+#[Object(name = "Monitor")]
+impl MonitorNode {
+    async fn id(&self) -> &str { &self.0.id }
+    async fn name(&self) -> &str { &self.0.name }
+    /// Operator notes in markdown
+    async fn description(&self) -> Option<&str> { self.0.description.as_deref() }
+    async fn url(&self) -> &str { &self.0.url }
+    async fn monitor_type(&self) -> &str { &self.0.monitor_type }
+    async fn interval_seconds(&self) -> u32 { self.0.interval_seconds }
+    async fn current_status(&self) -> &str { &self.0.current_status }
+    async fn last_checked_at(&self) -> Option<&str> { self.0.last_checked_at.as_deref() }
+    async fn next_check_at(&self) -> Option<&str> { self.0.next_check_at.as_deref() }
+    async fn is_public(&self) -> bool { self.0.is_public }
+    async fn is_paused(&self) -> bool { self.0.is_paused }
+    async fn insecure_skip_tls_verify(&self) -> bool { self.0.insecure_skip_tls_verify }
+    async fn tags(&self) -> Vec<String> { self.0.tags.clone() }
+    async fn group_name(&self) -> Option<&str> { self.0.group_name.as_deref() }
+    async fn sla_target(&self) -> Option<f64> { self.0.sla_target }
+    async fn created_at(&self) -> &str { &self.0.created_at }
+
+    /// Most recent incidents, newest first
+    async fn incidents(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20)] limit: u32,
+    ) -> async_graphql::Result<Vec<IncidentNode>> {
+        let db = ctx.data_unchecked::<Arc<Db>>();
+        let conn = db.read();
+        let incidents = query_recent_incidents(&conn, &self.0.id, limit.min(100))
+            .map_err(|_| async_graphql::Error::new("Internal server error"))?;
+        Ok(incidents.into_iter().map(IncidentNode).collect())
+    }
+
+    /// Uptime over the last 24h/7d/30d/90d, as GET /monitors/{id}/uptime
+    async fn uptime(&self, ctx: &Context<'_>) -> UptimeNode {
+        let db = ctx.data_unchecked::<Arc<Db>>();
+        let conn = db.read();
+        UptimeNode(query_uptime_stats(&conn, &self.0.id))
+    }
+}
+
+pub struct IncidentNode(Incident);
+
+#[Object(name = "Incident")]
+impl IncidentNode {
+    async fn id(&self) -> &str { &self.0.id }
+    async fn started_at(&self) -> &str { &self.0.started_at }
+    async fn resolved_at(&self) -> Option<&str> { self.0.resolved_at.as_deref() }
+    async fn cause(&self) -> &str { &self.0.cause }
+    async fn source(&self) -> &str { &self.0.source }
+    async fn severity(&self) -> Option<&str> { self.0.severity.as_deref() }
+    async fn acknowledgement(&self) -> Option<&str> { self.0.acknowledgement.as_deref() }
+    async fn acknowledged_by(&self) -> Option<&str> { self.0.acknowledged_by.as_deref() }
+    async fn caused_by_monitor_id(&self) -> Option<&str> { self.0.caused_by_monitor_id.as_deref() }
+}
+
+pub struct UptimeNode(UptimeStats);
+
+#[Object(name = "Uptime")]
+impl UptimeNode {
+    #[graphql(name = "uptime24h")]
+    async fn uptime_24h(&self) -> f64 { self.0.uptime_24h }
+    #[graphql(name = "uptime7d")]
+    async fn uptime_7d(&self) -> f64 { self.0.uptime_7d }
+    #[graphql(name = "uptime30d")]
+    async fn uptime_30d(&self) -> f64 { self.0.uptime_30d }
+    #[graphql(name = "uptime90d")]
+    async fn uptime_90d(&self) -> f64 { self.0.uptime_90d }
+    #[graphql(name = "totalChecks24h")]
+    async fn total_checks_24h(&self) -> u32 { self.0.total_checks_24h }
+    #[graphql(name = "avgResponseMs24h")]
+    async fn avg_response_ms_24h(&self) -> Option<f64> { self.0.avg_response_ms_24h }
+    async fn p95_response_ms(&self) -> Option<u32> { self.0.p95_response_ms }
+}
@@ -1,7 +1,7 @@
 use rocket::{get, serde::json::Json, State, http::Status};
 use crate::db::Db;
-use crate::models::{Heartbeat, UptimeStats};
-use super::get_monitor_from_db;
+use crate::models::{Heartbeat, LatencyBucket, LatencyHistogram, UptimeStats};
+use super::{field_error, get_monitor_from_db};
 use rusqlite::params;
 use std::sync::Arc;
 
@@ -14,7 +14,7 @@ pub fn get_heartbeats(
     after: Option<i64>,
     db: &State<Arc<Db>>,
 ) -> Result<Json<Vec<Heartbeat>>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+    let conn = db.read();
     get_monitor_from_db(&conn, id)
         .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
 
@@ -32,12 +32,13 @@ pub fn get_heartbeats(
             checked_at: row.get(6)?,
             seq: row.get(7)?,
             location_id: row.get(8)?,
+            failure_kind: row.get(9)?,
         })
     };
 
     let heartbeats: Vec<Heartbeat> = if let Some(after_seq) = after {
         let mut stmt = conn.prepare(
-            "SELECT id, monitor_id, status, response_time_ms, status_code, error_message, checked_at, seq, location_id
+            "SELECT id, monitor_id, status, response_time_ms, status_code, error_message, checked_at, seq, location_id, failure_kind
              FROM heartbeats WHERE monitor_id = ?1 AND seq > ?2 ORDER BY seq ASC LIMIT ?3"
         ).map_err(err_map)?;
         let results: Vec<Heartbeat> = stmt.query_map(params![id, after_seq, limit], row_to_hb)
@@ -47,7 +48,7 @@ pub fn get_heartbeats(
         results
     } else {
         let mut stmt = conn.prepare(
-            "SELECT id, monitor_id, status, response_time_ms, status_code, error_message, checked_at, seq, location_id
+            "SELECT id, monitor_id, status, response_time_ms, status_code, error_message, checked_at, seq, location_id, failure_kind
              FROM heartbeats WHERE monitor_id = ?1 ORDER BY seq DESC LIMIT ?2"
         ).map_err(err_map)?;
         let results: Vec<Heartbeat> = stmt.query_map(params![id, limit], row_to_hb)
@@ -60,6 +61,81 @@ pub fn get_heartbeats(
     Ok(Json(heartbeats))
 }
 
+// ── Latency Histogram ──
+
+/// Bucket bounds (ms) used when the request doesn't give any.
+const DEFAULT_LATENCY_BUCKETS: [u32; 7] = [50, 100, 250, 500, 1000, 2500, 5000];
+const MAX_LATENCY_BUCKETS: usize = 50;
+
+/// Parse `buckets=50,100,250` into strictly increasing positive bounds.
+fn parse_latency_buckets(buckets: Option<&str>) -> Result<Vec<u32>, (Status, Json<serde_json::Value>)> {
+    let Some(buckets) = buckets else { return Ok(DEFAULT_LATENCY_BUCKETS.to_vec()) };
+    let bounds: Vec<u32> = buckets.split(',')
+        .map(|b| b.trim().parse::<u32>().ok().filter(|&b| b > 0))
+        .collect::<Option<_>>()
+        .ok_or_else(|| field_error("buckets", "buckets must be a comma-separated list of positive millisecond bounds"))?;
+    if bounds.len() > MAX_LATENCY_BUCKETS {
+        return Err(field_error("buckets", format!("At most {} buckets are allowed", MAX_LATENCY_BUCKETS)));
+    }
+    if bounds.windows(2).any(|w| w[0] >= w[1]) {
+        return Err(field_error("buckets", "buckets must be strictly increasing"));
+    }
+    Ok(bounds)
+}
+
+/// Response-time distribution of successful (up or degraded) checks over the period;
+/// failed checks are left out since their latency is mostly the timeout.
+#[get("/monitors/<id>/latency-histogram?<buckets>&<period>")]
+pub fn get_latency_histogram(
+    id: &str,
+    buckets: Option<&str>,
+    period: Option<&str>,
+    db: &State<Arc<Db>>,
+) -> Result<Json<LatencyHistogram>, (Status, Json<serde_json::Value>)> {
+    let bounds = parse_latency_buckets(buckets)?;
+    let period = period.unwrap_or("24h");
+    let hours = match period {
+        "24h" => 24,
+        "7d" => 168,
+        "30d" => 720,
+        "90d" => 2160,
+        _ => return Err(field_error("period", "period must be one of 24h, 7d, 30d, 90d")),
+    };
+
+    let conn = db.read();
+    get_monitor_from_db(&conn, id)
+        .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
+
+    // Bucket index per heartbeat; bounds are validated integers, so inlining them is safe
+    let cases: String = bounds.iter().enumerate()
+        .map(|(i, b)| format!("WHEN response_time_ms <= {} THEN {} ", b, i))
+        .collect();
+    let sql = format!(
+        "SELECT CASE {}ELSE {} END AS bucket, COUNT(*) FROM heartbeats
+         WHERE monitor_id = ?1 AND status IN ('up', 'degraded') AND checked_at > datetime('now', ?2)
+         GROUP BY bucket",
+        cases, bounds.len()
+    );
+    let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
+    let mut counts = vec![0u32; bounds.len() + 1];
+    let mut stmt = conn.prepare(&sql).map_err(err_map)?;
+    let rows = stmt.query_map(params![id, format!("-{} hours", hours)], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, u32>(1)?)))
+        .map_err(err_map)?;
+    for (bucket, count) in rows.filter_map(|r| r.ok()) {
+        counts[bucket as usize] = count;
+    }
+
+    Ok(Json(LatencyHistogram {
+        monitor_id: id.to_string(),
+        period: period.to_string(),
+        total: counts.iter().sum(),
+        buckets: bounds.iter().map(|&b| Some(b)).chain([None])
+            .zip(counts)
+            .map(|(le_ms, count)| LatencyBucket { le_ms, count })
+            .collect(),
+    }))
+}
+
 // ── Uptime Stats ──
 
 #[get("/monitors/<id>/uptime")]
@@ -67,10 +143,15 @@ pub fn get_uptime(
     id: &str,
     db: &State<Arc<Db>>,
 ) -> Result<Json<UptimeStats>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+    let conn = db.read();
     get_monitor_from_db(&conn, id)
         .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
 
+    Ok(Json(query_uptime_stats(&conn, id)))
+}
+
+/// Uptime percentages and 24h response-time stats for one monitor.
+pub(crate) fn query_uptime_stats(conn: &rusqlite::Connection, id: &str) -> UptimeStats {
     let calc_uptime = |hours: u32| -> (f64, u32) {
         let total: u32 = conn.query_row(
             "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2)",
@@ -97,7 +178,27 @@ pub fn get_uptime(
         |row| row.get(0),
     ).ok();
 
-    Ok(Json(UptimeStats {
+    // Nearest-rank percentiles: the ceil(p * n)-th smallest response time
+    let up_24h: u32 = conn.query_row(
+        "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND status = 'up' AND checked_at > datetime('now', '-24 hours')",
+        params![id],
+        |row| row.get(0),
+    ).unwrap_or(0);
+    let percentile = |p: f64| -> Option<u32> {
+        if up_24h == 0 {
+            return None;
+        }
+        let rank = ((p * up_24h as f64).ceil() as u32).max(1);
+        conn.query_row(
+            "SELECT response_time_ms FROM heartbeats
+             WHERE monitor_id = ?1 AND status = 'up' AND checked_at > datetime('now', '-24 hours')
+             ORDER BY response_time_ms ASC LIMIT 1 OFFSET ?2",
+            params![id, rank - 1],
+            |row| row.get(0),
+        ).ok()
+    };
+
+    UptimeStats {
         monitor_id: id.to_string(),
         uptime_24h: u24,
         uptime_7d: u7d,
@@ -108,5 +209,8 @@ pub fn get_uptime(
         total_checks_30d: t30d,
         total_checks_90d: t90d,
         avg_response_ms_24h: avg_ms,
-    }))
+        p50_response_ms: percentile(0.50),
+        p95_response_ms: percentile(0.95),
+        p99_response_ms: percentile(0.99),
+    }
 }
@@ -1,13 +1,38 @@
 use rocket::{get, post, serde::json::Json, State, http::Status};
 use crate::db::Db;
-use crate::models::{Incident, AcknowledgeIncident, IncidentNote, CreateIncidentNote};
-use crate::auth::ManageToken;
-use super::{get_monitor_from_db, verify_manage_key};
+use crate::models::{Incident, AcknowledgeIncident, CreateManualIncident, ResolveIncident, IncidentNote, CreateIncidentNote};
+use crate::auth::{ManageToken, OptionalManageToken};
+use super::{get_monitor_from_db, verify_manage_key, verify_admin_key, audit_actor, record_audit};
+use super::alerts::parse_log_time;
+use crate::checker::{announce_incident, CheckerClients};
+use crate::notifications::{Severity, WebhookIncident, WebhookMonitor, WebhookPayload};
+use crate::sse::EventBroadcaster;
 use rusqlite::params;
 use std::sync::Arc;
 
 // ── Incidents ──
 
+pub(crate) const INCIDENT_COLUMNS: &str = "id, monitor_id, started_at, resolved_at, cause, acknowledgement, acknowledged_by, acknowledged_at, seq, caused_by_monitor_id, resolution_note, source, severity, auto_resolve";
+
+pub(crate) fn row_to_incident(row: &rusqlite::Row) -> rusqlite::Result<Incident> {
+    Ok(Incident {
+        id: row.get(0)?,
+        monitor_id: row.get(1)?,
+        started_at: row.get(2)?,
+        resolved_at: row.get(3)?,
+        cause: row.get(4)?,
+        acknowledgement: row.get(5)?,
+        acknowledged_by: row.get(6)?,
+        acknowledged_at: row.get(7)?,
+        seq: row.get(8)?,
+        caused_by_monitor_id: row.get(9)?,
+        resolution_note: row.get(10)?,
+        source: row.get(11)?,
+        severity: row.get(12)?,
+        auto_resolve: row.get(13)?,
+    })
+}
+
 #[get("/monitors/<id>/incidents?<limit>&<after>")]
 pub fn get_incidents(
     id: &str,
@@ -22,45 +47,245 @@ pub fn get_incidents(
     let limit = limit.unwrap_or(20).min(100);
     let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
 
-    let row_to_inc = |row: &rusqlite::Row| -> rusqlite::Result<Incident> {
-        Ok(Incident {
-            id: row.get(0)?,
-            monitor_id: row.get(1)?,
-            started_at: row.get(2)?,
-            resolved_at: row.get(3)?,
-            cause: row.get(4)?,
-            acknowledgement: row.get(5)?,
-            acknowledged_by: row.get(6)?,
-            acknowledged_at: row.get(7)?,
-            seq: row.get(8)?,
-        })
-    };
-
     let incidents: Vec<Incident> = if let Some(after_seq) = after {
-        let mut stmt = conn.prepare(
-            "SELECT id, monitor_id, started_at, resolved_at, cause, acknowledgement, acknowledged_by, acknowledged_at, seq
-             FROM incidents WHERE monitor_id = ?1 AND seq > ?2 ORDER BY seq ASC LIMIT ?3"
-        ).map_err(err_map)?;
-        let results: Vec<Incident> = stmt.query_map(params![id, after_seq, limit], row_to_inc)
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {INCIDENT_COLUMNS} FROM incidents WHERE monitor_id = ?1 AND seq > ?2 ORDER BY seq ASC LIMIT ?3"
+        )).map_err(err_map)?;
+        let results: Vec<Incident> = stmt.query_map(params![id, after_seq, limit], row_to_incident)
             .map_err(err_map)?
             .filter_map(|r| r.ok())
             .collect();
         results
     } else {
-        let mut stmt = conn.prepare(
-            "SELECT id, monitor_id, started_at, resolved_at, cause, acknowledgement, acknowledged_by, acknowledged_at, seq
-             FROM incidents WHERE monitor_id = ?1 ORDER BY seq DESC LIMIT ?2"
-        ).map_err(err_map)?;
-        let results: Vec<Incident> = stmt.query_map(params![id, limit], row_to_inc)
-            .map_err(err_map)?
-            .filter_map(|r| r.ok())
-            .collect();
-        results
+        query_recent_incidents(&conn, id, limit).map_err(err_map)?
     };
 
     Ok(Json(incidents))
 }
 
+/// A monitor's most recent incidents, newest first.
+pub(crate) fn query_recent_incidents(conn: &rusqlite::Connection, monitor_id: &str, limit: u32) -> rusqlite::Result<Vec<Incident>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {INCIDENT_COLUMNS} FROM incidents WHERE monitor_id = ?1 ORDER BY seq DESC LIMIT ?2"
+    ))?;
+    let incidents = stmt.query_map(params![monitor_id, limit], row_to_incident)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(incidents)
+}
+
+/// Recent incidents across all monitors. Anonymous callers see incidents of
+/// public monitors only; the admin key includes private ones, as does the
+/// monitor's manage key when filtering by `monitor_id`.
+#[get("/incidents?<status>&<monitor_id>&<since>&<search>&<limit>&<after>")]
+#[allow(clippy::too_many_arguments)]
+pub fn list_incidents(
+    status: Option<&str>,
+    monitor_id: Option<&str>,
+    since: Option<&str>,
+    search: Option<&str>,
+    limit: Option<u32>,
+    after: Option<i64>,
+    token: OptionalManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<Vec<Incident>>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
+
+    let include_private = token.0.as_deref().is_some_and(|key| {
+        verify_admin_key(&conn, key).is_ok()
+            || monitor_id.is_some_and(|m| verify_manage_key(&conn, m, key).is_ok())
+    });
+
+    let mut where_parts: Vec<String> = vec!["m.deleted_at IS NULL".to_string()];
+    let mut bind_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
+
+    if !include_private {
+        where_parts.push("m.is_public = 1".to_string());
+    }
+    match status {
+        None => {}
+        Some("open") => where_parts.push("i.resolved_at IS NULL".to_string()),
+        Some("resolved") => where_parts.push("i.resolved_at IS NOT NULL".to_string()),
+        Some(_) => return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "status must be 'open' or 'resolved'",
+            "code": "VALIDATION_ERROR"
+        })))),
+    }
+    if let Some(m) = monitor_id {
+        bind_values.push(Box::new(m.to_string()));
+        where_parts.push(format!("i.monitor_id = ?{}", bind_values.len()));
+    }
+    if let Some(since) = since {
+        bind_values.push(Box::new(parse_log_time(&conn, "since", since)?));
+        where_parts.push(format!("datetime(i.started_at) >= ?{}", bind_values.len()));
+    }
+    if let Some(q) = search.map(str::trim).filter(|q| !q.is_empty()) {
+        bind_values.push(Box::new(format!("%{}%", q)));
+        where_parts.push(format!("i.cause LIKE ?{}", bind_values.len()));
+    }
+    // Newest first by default; oldest first after a `seq` cursor (same as per-monitor incidents)
+    let order = match after {
+        Some(seq) => {
+            bind_values.push(Box::new(seq));
+            where_parts.push(format!("i.seq > ?{}", bind_values.len()));
+            "i.seq ASC"
+        }
+        None => "i.seq DESC",
+    };
+    bind_values.push(Box::new(limit.unwrap_or(20).min(100)));
+
+    let columns = INCIDENT_COLUMNS.split(", ").map(|c| format!("i.{}", c)).collect::<Vec<_>>().join(", ");
+    let sql = format!(
+        "SELECT {} FROM incidents i JOIN monitors m ON m.id = i.monitor_id WHERE {} ORDER BY {} LIMIT ?{}",
+        columns, where_parts.join(" AND "), order, bind_values.len()
+    );
+    let mut stmt = conn.prepare(&sql).map_err(err_map)?;
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
+    let incidents = stmt.query_map(params_ref.as_slice(), row_to_incident)
+        .map_err(err_map)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(Json(incidents))
+}
+
+// ── Manual Incidents ──
+
+/// Send `event` for a manual incident in the background, so slow channels don't
+/// hold up the response. Routed by the incident's own severity.
+fn announce_manual_incident(
+    conn: &rusqlite::Connection,
+    event: &str,
+    incident: &Incident,
+    db: &State<Arc<Db>>,
+    clients: &State<Arc<CheckerClients>>,
+    broadcaster: &State<Arc<EventBroadcaster>>,
+) {
+    let Ok(monitor) = get_monitor_from_db(conn, &incident.monitor_id) else {
+        return;
+    };
+    let payload = WebhookPayload {
+        event: event.to_string(),
+        monitor: WebhookMonitor {
+            id: monitor.id,
+            name: monitor.name,
+            url: monitor.url,
+            current_status: monitor.current_status,
+            runbook_url: monitor.runbook_url,
+        },
+        incident: Some(WebhookIncident {
+            id: incident.id.clone(),
+            cause: incident.cause.clone(),
+            started_at: incident.started_at.clone(),
+            resolved_at: incident.resolved_at.clone(),
+        }),
+        maintenance: None,
+        timestamp: chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+    };
+    let (db, clients, broadcaster) = (db.inner().clone(), clients.inner().clone(), broadcaster.inner().clone());
+    rocket::tokio::spawn(async move {
+        announce_incident(&db, &clients, &broadcaster, &payload).await;
+    });
+}
+
+/// Open an incident by hand, e.g. when a provider reports an outage before
+/// checks notice. It stays open until resolved via the API unless
+/// `auto_resolve` lets the checker close it on recovery. Sends `incident.created`.
+#[post("/monitors/<id>/incidents", format = "json", data = "<input>")]
+pub fn create_manual_incident(
+    id: &str,
+    input: Json<CreateManualIncident>,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+    clients: &State<Arc<CheckerClients>>,
+    broadcaster: &State<Arc<EventBroadcaster>>,
+) -> Result<(Status, Json<Incident>), (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_manage_key(&conn, id, &token.0)?;
+
+    let data = input.into_inner();
+    let cause = data.cause.trim();
+    if cause.is_empty() || cause.len() > 1000 {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "cause must be 1-1000 characters",
+            "code": "VALIDATION_ERROR"
+        }))));
+    }
+    let severity = data.severity.as_deref().unwrap_or("critical").to_lowercase();
+    if Severity::parse(&severity).is_none() {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "severity must be 'info', 'warning', or 'critical'",
+            "code": "VALIDATION_ERROR"
+        }))));
+    }
+
+    let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
+    let inc_id = uuid::Uuid::new_v4().to_string();
+    conn.execute(
+        "INSERT INTO incidents (id, monitor_id, cause, seq, source, severity, auto_resolve)
+         VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(seq), 0) + 1 FROM incidents), 'manual', ?4, ?5)",
+        params![inc_id, id, cause, severity, data.auto_resolve],
+    ).map_err(err_map)?;
+    let actor = if data.actor == "anonymous" { audit_actor(&conn, &token.0) } else { data.actor.clone() };
+    record_audit(&conn, "incident.opened", Some(id), &actor, Some(serde_json::json!({
+        "incident_id": inc_id, "cause": cause, "severity": severity
+    })));
+
+    let incident = conn.query_row(
+        &format!("SELECT {INCIDENT_COLUMNS} FROM incidents WHERE id = ?1"),
+        params![inc_id],
+        row_to_incident,
+    ).map_err(err_map)?;
+    announce_manual_incident(&conn, "incident.created", &incident, db, clients, broadcaster);
+    Ok((Status::Created, Json(incident)))
+}
+
+/// Close an open incident. Works for check-driven incidents too; if the
+/// monitor is still down the checker opens a fresh one on its next failure.
+/// Sends `incident.resolved`.
+#[post("/incidents/<id>/resolve", data = "<input>")]
+pub fn resolve_incident(
+    id: &str,
+    input: Option<Json<ResolveIncident>>,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+    clients: &State<Arc<CheckerClients>>,
+    broadcaster: &State<Arc<EventBroadcaster>>,
+) -> Result<Json<Incident>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+
+    let (monitor_id, resolved): (String, bool) = conn.query_row(
+        "SELECT monitor_id, resolved_at IS NOT NULL FROM incidents WHERE id = ?1",
+        params![id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Incident not found", "code": "NOT_FOUND"}))))?;
+
+    verify_manage_key(&conn, &monitor_id, &token.0)?;
+    if resolved {
+        return Err((Status::Conflict, Json(serde_json::json!({
+            "error": "Incident is already resolved", "code": "ALREADY_RESOLVED"
+        }))));
+    }
+
+    let data = input.map(|j| j.into_inner()).unwrap_or_default();
+    let note = data.note.as_deref().map(str::trim).filter(|n| !n.is_empty()).unwrap_or("resolved manually");
+    let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
+    conn.execute(
+        "UPDATE incidents SET resolved_at = datetime('now'), resolution_note = ?1 WHERE id = ?2",
+        params![note, id],
+    ).map_err(err_map)?;
+    let actor = if data.actor == "anonymous" { audit_actor(&conn, &token.0) } else { data.actor.clone() };
+    record_audit(&conn, "incident.resolved", Some(&monitor_id), &actor, Some(serde_json::json!({"incident_id": id, "note": note})));
+
+    let incident = conn.query_row(
+        &format!("SELECT {INCIDENT_COLUMNS} FROM incidents WHERE id = ?1"),
+        params![id],
+        row_to_incident,
+    ).map_err(err_map)?;
+    announce_manual_incident(&conn, "incident.resolved", &incident, db, clients, broadcaster);
+    Ok(Json(incident))
+}
+
 // ── Acknowledge Incident ──
 
 #[post("/incidents/<id>/acknowledge", format = "json", data = "<input>")]
@@ -85,6 +310,9 @@ pub fn acknowledge_incident(
         "UPDATE incidents SET acknowledgement = ?1, acknowledged_by = ?2, acknowledged_at = datetime('now') WHERE id = ?3",
         params![data.note, data.actor, id],
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    // Prefer the self-reported actor; fall back to describing the key used
+    let actor = if data.actor == "anonymous" { audit_actor(&conn, &token.0) } else { data.actor.clone() };
+    record_audit(&conn, "incident.acknowledged", Some(&monitor_id), &actor, Some(serde_json::json!({"incident_id": id, "note": data.note})));
 
     Ok(Json(serde_json::json!({"message": "Incident acknowledged"})))
 }
@@ -99,20 +327,9 @@ pub fn get_incident(
     let conn = db.conn();
 
     let incident: Incident = conn.query_row(
-        "SELECT id, monitor_id, started_at, resolved_at, cause, acknowledgement, acknowledged_by, acknowledged_at, seq
-         FROM incidents WHERE id = ?1",
+        &format!("SELECT {INCIDENT_COLUMNS} FROM incidents WHERE id = ?1"),
         params![id],
-        |row| Ok(Incident {
-            id: row.get(0)?,
-            monitor_id: row.get(1)?,
-            started_at: row.get(2)?,
-            resolved_at: row.get(3)?,
-            cause: row.get(4)?,
-            acknowledgement: row.get(5)?,
-            acknowledged_by: row.get(6)?,
-            acknowledged_at: row.get(7)?,
-            seq: row.get(8)?,
-        }),
+        row_to_incident,
     ).map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Incident not found", "code": "NOT_FOUND"}))))?;
 
     // Include notes count
@@ -122,8 +339,17 @@ pub fn get_incident(
         |r| r.get(0),
     ).unwrap_or(0);
 
+    let runbook_url: Option<String> = conn.query_row(
+        "SELECT runbook_url FROM monitors WHERE id = ?1",
+        params![incident.monitor_id],
+        |r| r.get(0),
+    ).unwrap_or(None);
+
     let mut val = serde_json::to_value(&incident).unwrap_or_default();
     val["notes_count"] = serde_json::json!(notes_count);
+    if let Some(runbook_url) = runbook_url {
+        val["runbook_url"] = serde_json::json!(runbook_url);
+    }
 
     Ok(Json(val))
 }
@@ -1,15 +1,17 @@
-use rocket::{get, post, delete, http::Status, serde::json::Json, State};
+use rocket::{get, post, patch, delete, http::Status, serde::json::Json, State};
 use rusqlite::params;
 use uuid::Uuid;
 use std::sync::Arc;
 
 use crate::db::Db;
 use crate::auth::{ManageToken, hash_key, generate_key};
+use crate::consensus::ConsensusRule;
 use crate::sse::EventBroadcaster;
+use super::verify_admin_key;
 use crate::models::{
-    CheckLocation, CreateCheckLocation, CreateCheckLocationResponse,
+    CheckLocation, CreateCheckLocation, CreateCheckLocationResponse, UpdateCheckLocation,
     ProbeSubmission, ProbeSubmissionResponse, ProbeError,
-    MonitorLocationStatus, ConsensusStatus,
+    MonitorLocationStatus, LocationUptimeStats, ConsensusStatus,
 };
 
 fn stale_threshold_minutes() -> u32 {
@@ -19,37 +21,47 @@ fn stale_threshold_minutes() -> u32 {
         .unwrap_or(30)
 }
 
-// ── Verify admin key against settings table ──
-fn verify_admin_key(conn: &rusqlite::Connection, token: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
-    let stored_hash: String = conn.query_row(
-        "SELECT value FROM settings WHERE key = 'admin_key_hash'",
-        [],
-        |row| row.get(0),
-    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({
-        "error": "Admin key not configured", "code": "SERVER_ERROR"
-    }))))?;
-
-    if hash_key(token) != stored_hash {
-        return Err((Status::Forbidden, Json(serde_json::json!({
-            "error": "Invalid admin key", "code": "FORBIDDEN"
-        }))));
-    }
-    Ok(())
-}
-
 // ── Verify probe key against check_locations table ──
 fn verify_probe_key(conn: &rusqlite::Connection, token: &str) -> Result<String, (Status, Json<serde_json::Value>)> {
     let token_hash = hash_key(token);
-    let location_id: String = conn.query_row(
-        "SELECT id FROM check_locations WHERE probe_key_hash = ?1 AND is_active = 1",
+    let (location_id, is_active): (String, bool) = conn.query_row(
+        "SELECT id, is_active FROM check_locations WHERE probe_key_hash = ?1",
         params![token_hash],
-        |row| row.get(0),
+        |row| Ok((row.get(0)?, row.get::<_, i32>(1)? != 0)),
     ).map_err(|_| (Status::Unauthorized, Json(serde_json::json!({
-        "error": "Invalid or inactive probe key", "code": "UNAUTHORIZED"
+        "error": "Invalid probe key", "code": "UNAUTHORIZED"
     }))))?;
+    if !is_active {
+        return Err((Status::Forbidden, Json(serde_json::json!({
+            "error": "Check location is inactive. Reactivate it with PATCH /api/v1/locations/:id before submitting probes.",
+            "code": "LOCATION_INACTIVE"
+        }))));
+    }
     Ok(location_id)
 }
 
+fn get_location_row(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<CheckLocation> {
+    let stale_min = stale_threshold_minutes();
+    conn.query_row(
+        "SELECT id, name, region, is_active, last_seen_at, created_at FROM check_locations WHERE id = ?1",
+        params![id],
+        |row| {
+            let is_active = row.get::<_, i32>(3)? != 0;
+            let last_seen_at: Option<String> = row.get(4)?;
+            let health_status = CheckLocation::compute_health(is_active, &last_seen_at, stale_min);
+            Ok(CheckLocation {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                region: row.get(2)?,
+                is_active,
+                last_seen_at,
+                health_status,
+                created_at: row.get(5)?,
+            })
+        },
+    )
+}
+
 /// POST /api/v1/locations — Register a new check location (admin key required)
 #[post("/locations", data = "<body>")]
 pub fn create_location(
@@ -156,31 +168,71 @@ pub fn get_location(
     db: &State<Arc<Db>>,
 ) -> Result<Json<CheckLocation>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
-    let stale_min = stale_threshold_minutes();
-    let location = conn.query_row(
-        "SELECT id, name, region, is_active, last_seen_at, created_at FROM check_locations WHERE id = ?1",
-        params![id],
-        |row| {
-            let is_active = row.get::<_, i32>(3)? != 0;
-            let last_seen_at: Option<String> = row.get(4)?;
-            let health_status = CheckLocation::compute_health(is_active, &last_seen_at, stale_min);
-            Ok(CheckLocation {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                region: row.get(2)?,
-                is_active,
-                last_seen_at,
-                health_status,
-                created_at: row.get(5)?,
-            })
-        },
-    ).map_err(|_| (Status::NotFound, Json(serde_json::json!({
+    let location = get_location_row(&conn, id).map_err(|_| (Status::NotFound, Json(serde_json::json!({
         "error": "Check location not found", "code": "NOT_FOUND"
     }))))?;
 
     Ok(Json(location))
 }
 
+/// PATCH /api/v1/locations/<id> — Rename, change region, or (de)activate a check location (admin key required)
+#[patch("/locations/<id>", data = "<body>")]
+pub fn update_location(
+    id: &str,
+    body: Json<UpdateCheckLocation>,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<CheckLocation>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    get_location_row(&conn, id).map_err(|_| (Status::NotFound, Json(serde_json::json!({
+        "error": "Check location not found", "code": "NOT_FOUND"
+    }))))?;
+
+    if let Some(ref name) = body.name {
+        let name = name.trim();
+        if name.is_empty() || name.len() > 200 {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "Name must be 1-200 characters", "code": "VALIDATION_ERROR"
+            }))));
+        }
+        let taken: bool = conn.query_row(
+            "SELECT COUNT(*) FROM check_locations WHERE name = ?1 AND id != ?2",
+            params![name, id],
+            |r| r.get::<_, i64>(0),
+        ).unwrap_or(0) > 0;
+        if taken {
+            return Err((Status::Conflict, Json(serde_json::json!({
+                "error": "A check location with this name already exists", "code": "DUPLICATE_NAME"
+            }))));
+        }
+        let _ = conn.execute("UPDATE check_locations SET name = ?1 WHERE id = ?2", params![name, id]);
+    }
+
+    if let Some(ref region) = body.region {
+        if region.as_ref().is_some_and(|r| r.len() > 200) {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "Region must be at most 200 characters", "code": "VALIDATION_ERROR"
+            }))));
+        }
+        let _ = conn.execute("UPDATE check_locations SET region = ?1 WHERE id = ?2", params![region, id]);
+    }
+
+    if let Some(is_active) = body.is_active {
+        let _ = conn.execute(
+            "UPDATE check_locations SET is_active = ?1 WHERE id = ?2",
+            params![is_active as i32, id],
+        );
+    }
+
+    get_location_row(&conn, id)
+        .map(Json)
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({
+            "error": "Internal server error", "code": "SERVER_ERROR"
+        }))))
+}
+
 /// DELETE /api/v1/locations/<id> — Remove a check location (admin key required)
 #[delete("/locations/<id>")]
 pub fn delete_location(
@@ -247,10 +299,10 @@ pub async fn submit_probe(
             }
 
             // Verify monitor exists and check if consensus is configured
-            let monitor_info: Option<Option<u32>> = conn.query_row(
-                "SELECT consensus_threshold FROM monitors WHERE id = ?1",
+            let monitor_info: Option<Option<ConsensusRule>> = conn.query_row(
+                "SELECT consensus_threshold, consensus_mode FROM monitors WHERE id = ?1",
                 params![result.monitor_id],
-                |r| r.get(0),
+                |r| Ok(ConsensusRule::from_config(r.get::<_, Option<String>>(1)?.as_deref(), r.get(0)?)),
             ).ok();
 
             if monitor_info.is_none() {
@@ -263,7 +315,7 @@ pub async fn submit_probe(
             }
 
             // Track monitors with consensus for post-submission evaluation
-            if let Some(Some(_threshold)) = monitor_info {
+            if let Some(Some(_rule)) = monitor_info {
                 if !consensus_monitor_ids.contains(&result.monitor_id) {
                     consensus_monitor_ids.push(result.monitor_id.clone());
                 }
@@ -345,7 +397,7 @@ pub fn monitor_consensus(
 
     // Verify monitor exists
     let exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM monitors WHERE id = ?1",
+        "SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL",
         params![monitor_id],
         |r| r.get::<_, i64>(0),
     ).unwrap_or(0) > 0;
@@ -357,15 +409,15 @@ pub fn monitor_consensus(
     }
 
     // Check if consensus is configured
-    let threshold: Option<u32> = conn.query_row(
-        "SELECT consensus_threshold FROM monitors WHERE id = ?1",
+    let rule: Option<ConsensusRule> = conn.query_row(
+        "SELECT consensus_threshold, consensus_mode FROM monitors WHERE id = ?1",
         params![monitor_id],
-        |row| row.get(0),
+        |row| Ok(ConsensusRule::from_config(row.get::<_, Option<String>>(1)?.as_deref(), row.get(0)?)),
     ).unwrap_or(None);
 
-    if threshold.is_none() {
+    if rule.is_none() {
         return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "Consensus not configured for this monitor. Set consensus_threshold to enable.",
+            "error": "Consensus not configured for this monitor. Set consensus_threshold or consensus_mode to enable.",
             "code": "CONSENSUS_NOT_CONFIGURED"
         }))));
     }
@@ -389,7 +441,7 @@ pub fn monitor_location_status(
 
     // Verify monitor exists
     let exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM monitors WHERE id = ?1",
+        "SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL",
         params![monitor_id],
         |r| r.get::<_, i64>(0),
     ).unwrap_or(0) > 0;
@@ -432,3 +484,64 @@ pub fn monitor_location_status(
 
     Ok(Json(statuses))
 }
+
+/// GET /api/v1/monitors/<monitor_id>/locations/<location_id>/uptime — Uptime and latency
+/// for a monitor as seen from a single check location
+#[get("/monitors/<monitor_id>/locations/<location_id>/uptime")]
+pub fn monitor_location_uptime(
+    monitor_id: &str,
+    location_id: &str,
+    db: &State<Arc<Db>>,
+) -> Result<Json<LocationUptimeStats>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+
+    let exists: bool = conn.query_row(
+        "SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL",
+        params![monitor_id],
+        |r| r.get::<_, i64>(0),
+    ).unwrap_or(0) > 0;
+    if !exists {
+        return Err((Status::NotFound, Json(serde_json::json!({
+            "error": "Monitor not found", "code": "NOT_FOUND"
+        }))));
+    }
+
+    let (location_name, region): (String, Option<String>) = conn.query_row(
+        "SELECT name, region FROM check_locations WHERE id = ?1",
+        params![location_id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|_| (Status::NotFound, Json(serde_json::json!({
+        "error": "Check location not found", "code": "NOT_FOUND"
+    }))))?;
+
+    // (uptime %, total checks, avg response ms of up checks) over the window
+    let calc = |hours: u32| -> (f64, u32, Option<f64>) {
+        let (total, up, avg_ms): (u32, u32, Option<f64>) = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END), 0),
+                    AVG(CASE WHEN status = 'up' THEN response_time_ms END)
+             FROM heartbeats
+             WHERE monitor_id = ?1 AND location_id = ?2 AND checked_at > datetime('now', ?3)",
+            params![monitor_id, location_id, format!("-{} hours", hours)],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).unwrap_or((0, 0, None));
+        let pct = if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 };
+        (pct, total, avg_ms)
+    };
+
+    let (u24, t24, avg24) = calc(24);
+    let (u7d, t7d, avg7d) = calc(168);
+
+    Ok(Json(LocationUptimeStats {
+        monitor_id: monitor_id.to_string(),
+        location_id: location_id.to_string(),
+        location_name,
+        region,
+        uptime_24h: u24,
+        uptime_7d: u7d,
+        total_checks_24h: t24,
+        total_checks_7d: t7d,
+        avg_response_ms_24h: avg24,
+        avg_response_ms_7d: avg7d,
+    }))
+}
@@ -7,6 +7,9 @@ use std::sync::Arc;
 
 // ── Maintenance Windows ──
 
+/// Longest supported reminder lead time (one week)
+const MAX_REMIND_MINUTES: u32 = 7 * 24 * 60;
+
 #[post("/monitors/<monitor_id>/maintenance", format = "json", data = "<input>")]
 pub fn create_maintenance_window(
     monitor_id: &str,
@@ -40,13 +43,18 @@ pub fn create_maintenance_window(
             "error": "ends_at must be after starts_at", "code": "VALIDATION_ERROR"
         }))));
     }
+    if data.remind_minutes_before.is_some_and(|m| m == 0 || m > MAX_REMIND_MINUTES) {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": format!("remind_minutes_before must be between 1 and {}", MAX_REMIND_MINUTES), "code": "VALIDATION_ERROR"
+        }))));
+    }
 
     let id = uuid::Uuid::new_v4().to_string();
     let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
     conn.execute(
-        "INSERT INTO maintenance_windows (id, monitor_id, title, starts_at, ends_at) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![id, monitor_id, data.title.trim(), data.starts_at, data.ends_at],
+        "INSERT INTO maintenance_windows (id, monitor_id, title, starts_at, ends_at, remind_minutes_before) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![id, monitor_id, data.title.trim(), data.starts_at, data.ends_at, data.remind_minutes_before],
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({
         "error": "Internal server error", "code": "INTERNAL_ERROR"
     }))))?;
@@ -60,6 +68,7 @@ pub fn create_maintenance_window(
         starts_at: data.starts_at,
         ends_at: data.ends_at,
         active,
+        remind_minutes_before: data.remind_minutes_before,
         created_at: now,
     }))
 }
@@ -72,7 +81,7 @@ pub fn list_maintenance_windows(
     let conn = db.conn();
 
     let _: String = conn.query_row(
-        "SELECT id FROM monitors WHERE id = ?1", params![monitor_id], |r| r.get(0)
+        "SELECT id FROM monitors WHERE id = ?1 AND deleted_at IS NULL", params![monitor_id], |r| r.get(0)
     ).map_err(|_| (Status::NotFound, Json(serde_json::json!({
         "error": "Monitor not found", "code": "NOT_FOUND"
     }))))?;
@@ -80,7 +89,7 @@ pub fn list_maintenance_windows(
     let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
     let mut stmt = conn.prepare(
-        "SELECT id, monitor_id, title, starts_at, ends_at, created_at FROM maintenance_windows WHERE monitor_id = ?1 ORDER BY starts_at DESC"
+        "SELECT id, monitor_id, title, starts_at, ends_at, created_at, remind_minutes_before FROM maintenance_windows WHERE monitor_id = ?1 ORDER BY starts_at DESC"
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
     let windows: Vec<crate::models::MaintenanceWindow> = stmt.query_map(params![monitor_id], |row| {
@@ -93,6 +102,7 @@ pub fn list_maintenance_windows(
             starts_at: starts_at.clone(),
             ends_at: ends_at.clone(),
             active: is_time_in_window(&now, &starts_at, &ends_at),
+            remind_minutes_before: row.get(6)?,
             created_at: row.get(5)?,
         })
     }).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
@@ -134,12 +144,29 @@ fn is_time_in_window(now: &str, starts_at: &str, ends_at: &str) -> bool {
 /// Check if a monitor currently has an active maintenance window.
 /// Used by the checker to suppress incident creation.
 pub fn is_in_maintenance(db: &Db, monitor_id: &str) -> bool {
-    let conn = db.conn();
+    !active_maintenance_windows(&db.conn(), monitor_id).is_empty()
+}
+
+/// Windows of a monitor that are in effect right now, soonest-ending first
+pub(crate) fn active_maintenance_windows(conn: &rusqlite::Connection, monitor_id: &str) -> Vec<crate::models::MaintenanceWindow> {
     let now = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
-    let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM maintenance_windows WHERE monitor_id = ?1 AND starts_at <= ?2 AND ends_at > ?2",
-        params![monitor_id, now],
-        |r| r.get(0),
-    ).unwrap_or(0);
-    count > 0
+    let Ok(mut stmt) = conn.prepare(
+        "SELECT id, monitor_id, title, starts_at, ends_at, created_at, remind_minutes_before FROM maintenance_windows
+         WHERE monitor_id = ?1 AND starts_at <= ?2 AND ends_at > ?2 ORDER BY ends_at"
+    ) else {
+        return Vec::new();
+    };
+    stmt.query_map(params![monitor_id, now], |row| {
+        Ok(crate::models::MaintenanceWindow {
+            id: row.get(0)?,
+            monitor_id: row.get(1)?,
+            title: row.get(2)?,
+            starts_at: row.get(3)?,
+            ends_at: row.get(4)?,
+            active: true,
+            remind_minutes_before: row.get(6)?,
+            created_at: row.get(5)?,
+        })
+    }).map(|rows| rows.filter_map(|r| r.ok()).collect())
+    .unwrap_or_default()
 }
@@ -20,32 +20,41 @@ mod status_pages;
 mod alerts;
 mod webhook_deliveries;
 mod dependencies;
+mod api_keys;
+mod audit;
+mod graphql;
 
 // Re-export all route handlers so main.rs can use routes::* unchanged
-pub use monitors::{create_monitor, bulk_create_monitors, export_monitor, list_monitors, get_monitor, update_monitor, delete_monitor, pause_monitor, resume_monitor};
-pub use heartbeats::{get_heartbeats, get_uptime};
-pub use incidents::{get_incidents, get_incident, acknowledge_incident, create_incident_note, list_incident_notes};
+pub use monitors::{create_monitor, upsert_monitor_by_external_id, bulk_create_monitors, import_blackbox_targets, bulk_monitor_action, pause_all_monitors, resume_all_monitors, export_monitor, list_monitors, get_monitor, update_monitor, delete_monitor, pause_monitor, resume_monitor, rotate_monitor_key, restore_monitor, check_monitor_now, get_check_state, archive_monitor, unarchive_monitor};
+pub use heartbeats::{get_heartbeats, get_latency_histogram, get_uptime};
+pub use graphql::{graphql_query, build_graphql_schema, GraphqlSchema};
+pub use incidents::{get_incidents, list_incidents, create_manual_incident, resolve_incident, get_incident, acknowledge_incident, create_incident_note, list_incident_notes};
 pub use dashboard_route::dashboard;
-pub use dashboard_route::admin_verify;
+pub use dashboard_route::{admin_verify, admin_stats, admin_prune, admin_vacuum, rotate_admin_key};
 pub use uptime::{uptime_history, monitor_uptime_history};
 pub use status::status_page;
-pub use notifications::{create_notification, list_notifications, delete_notification, update_notification};
+pub use notifications::{create_notification, list_notifications, delete_notification, update_notification, create_global_notification, list_global_notifications};
 pub use maintenance::{create_maintenance_window, list_maintenance_windows, delete_maintenance_window, is_in_maintenance};
-pub use tags::{list_tags, list_groups};
-pub use settings::{get_settings, update_settings};
+pub(crate) use maintenance::active_maintenance_windows;
+pub use tags::{list_tags, list_groups, rename_tag, get_group_uptime};
+pub use settings::{get_settings, update_settings, load_default_headers};
+pub(crate) use settings::load_monitor_defaults;
 pub use system::{health, skill_md, llms_txt, root_llms_txt, openapi_spec, skills_index, skills_skill_md, api_skills_skill_md, spa_fallback};
-pub use badges::{monitor_uptime_badge, monitor_status_badge};
-pub use sla::monitor_sla;
-pub use stream::{global_events, monitor_events};
-pub use locations::{create_location, list_locations, get_location, delete_location, submit_probe, monitor_location_status, monitor_consensus};
-pub use status_pages::{create_status_page, list_status_pages, get_status_page_detail, update_status_page, delete_status_page, add_page_monitors, remove_page_monitor, list_page_monitors};
-pub use alerts::{set_alert_rules, get_alert_rules, delete_alert_rules, get_alert_log};
+pub use badges::{monitor_uptime_badge, monitor_uptime_badge_json, monitor_status_badge, monitor_response_badge};
+pub use sla::{monitor_sla, monitor_sla_report, sla_uptime, burn_rate};
+pub use stream::{global_events, monitor_events, ws_events};
+pub use locations::{create_location, list_locations, get_location, update_location, delete_location, submit_probe, monitor_location_status, monitor_location_uptime, monitor_consensus};
+pub use status_pages::{create_status_page, list_status_pages, get_status_page_detail, get_status_page_by_slug, status_page_html, update_status_page, delete_status_page, add_page_monitors, remove_page_monitor, list_page_monitors, subscribe_to_page, confirm_subscription, unsubscribe_from_page};
+pub use alerts::{set_alert_rules, get_alert_rules, delete_alert_rules, get_alert_log, list_alert_log};
 pub use webhook_deliveries::list_webhook_deliveries;
-pub use dependencies::{add_dependency, list_dependencies, remove_dependency, list_dependents, has_dependency_down, has_open_incident};
+pub use dependencies::{add_dependency, list_dependencies, remove_dependency, list_dependents, find_down_dependency};
+pub use api_keys::{create_api_key, list_api_keys, delete_api_key};
+pub use audit::list_audit_log;
+pub(crate) use audit::{audit_actor, record_audit, monitor_diff};
 
 use rocket::{http::Status, serde::json::Json};
 use crate::models::Monitor;
-use crate::auth::{hash_key};
+use crate::auth::{hash_key, api_key_allows, Scope};
 use rusqlite::params;
 use std::collections::HashMap;
 use std::sync::Mutex;
@@ -89,43 +98,102 @@ impl RateLimiter {
 /// Valid DNS record types for DNS monitors
 pub(crate) const VALID_DNS_RECORD_TYPES: &[&str] = &["A", "AAAA", "CNAME", "MX", "TXT", "NS", "SOA", "PTR", "SRV", "CAA"];
 
+/// Columns loaded into a `Monitor`, in `row_to_monitor` order.
+pub(crate) const MONITOR_COLUMNS: &str = "id, name, url, method, interval_seconds, timeout_ms, expected_status, body_contains, headers, is_public, is_paused, current_status, last_checked_at, confirmation_threshold, created_at, updated_at, tags, response_time_threshold_ms, follow_redirects, group_name, monitor_type, dns_record_type, dns_expected, sla_target, sla_period_days, consensus_threshold, consensus_mode, steps, body_not_contains, max_redirects, expected_final_url_prefix, min_body_bytes, max_body_bytes, auth_type, auth_username, proxy_url, dns_min_records, dns_min_ttl, dns_resolver, tcp_send, tcp_expect, is_archived, recovery_cooldown_seconds, down_confirmation_threshold, up_confirmation_threshold, external_id, connect_timeout_ms, description, runbook_url, expected_first_status, client_cert_pem, insecure_skip_tls_verify, ca_bundle_pem";
+
+/// Days a deleted monitor stays restorable before it is purged.
+/// Configurable via MONITOR_RESTORE_DAYS env var. Default: 7; 0 deletes immediately.
+pub fn monitor_restore_days() -> u32 {
+    std::env::var("MONITOR_RESTORE_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(7)
+}
+
 pub(crate) fn get_monitor_from_db(conn: &rusqlite::Connection, id: &str) -> rusqlite::Result<Monitor> {
     conn.query_row(
-        "SELECT id, name, url, method, interval_seconds, timeout_ms, expected_status, body_contains, headers, is_public, is_paused, current_status, last_checked_at, confirmation_threshold, created_at, updated_at, tags, response_time_threshold_ms, follow_redirects, group_name, monitor_type, dns_record_type, dns_expected, sla_target, sla_period_days, consensus_threshold
-         FROM monitors WHERE id = ?1",
+        &format!("SELECT {MONITOR_COLUMNS} FROM monitors WHERE id = ?1 AND deleted_at IS NULL"),
         params![id],
         |row| Ok(row_to_monitor(row)),
     )
 }
 
+/// Shown in a monitor's `warnings` while `insecure_skip_tls_verify` is on.
+pub(crate) const INSECURE_TLS_WARNING: &str =
+    "TLS certificate verification is disabled (insecure_skip_tls_verify): expired, self-signed, or spoofed certificates will not fail this check";
+
 pub(crate) fn row_to_monitor(row: &rusqlite::Row) -> Monitor {
     let headers_str: Option<String> = row.get(8).unwrap_or(None);
     let tags_str: String = row.get(16).unwrap_or_default();
+    let id: String = row.get(0).unwrap();
+    let interval_seconds: u32 = row.get(4).unwrap();
+    let is_paused = row.get::<_, i32>(10).unwrap() != 0;
+    let is_archived = row.get::<_, i32>(41).unwrap_or(0) != 0;
+    let last_checked_at: Option<String> = row.get(12).unwrap_or(None);
+    // Paused and archived monitors aren't scheduled; unchecked ones are due on the next tick
+    let next_check_at = last_checked_at.as_deref()
+        .filter(|_| !is_paused && !is_archived)
+        .and_then(|last| crate::checker::scheduled_next_check(last, &id, interval_seconds));
+    let insecure_skip_tls_verify = row.get::<_, i32>(51).unwrap_or(0) != 0;
+    let mut warnings = Vec::new();
+    if insecure_skip_tls_verify {
+        warnings.push(INSECURE_TLS_WARNING.to_string());
+    }
     Monitor {
-        id: row.get(0).unwrap(),
+        id,
         name: row.get(1).unwrap(),
+        description: row.get::<_, Option<String>>(47).unwrap_or(None),
+        runbook_url: row.get::<_, Option<String>>(48).unwrap_or(None),
         url: row.get(2).unwrap(),
         monitor_type: row.get::<_, String>(20).unwrap_or_else(|_| "http".to_string()),
         method: row.get(3).unwrap(),
-        interval_seconds: row.get(4).unwrap(),
+        interval_seconds,
         timeout_ms: row.get(5).unwrap(),
+        connect_timeout_ms: row.get::<_, Option<u32>>(46).unwrap_or(None),
         expected_status: row.get(6).unwrap(),
         body_contains: row.get(7).unwrap_or(None),
+        body_not_contains: row.get::<_, Option<String>>(28).unwrap_or(None),
+        min_body_bytes: row.get::<_, Option<u32>>(31).unwrap_or(None),
+        max_body_bytes: row.get::<_, Option<u32>>(32).unwrap_or(None),
+        auth_type: row.get::<_, Option<String>>(33).unwrap_or(None),
+        auth_username: row.get::<_, Option<String>>(34).unwrap_or(None),
+        client_cert_pem: row.get::<_, Option<String>>(50).unwrap_or(None),
+        ca_bundle_pem: row.get::<_, Option<String>>(52).unwrap_or(None),
+        insecure_skip_tls_verify,
+        warnings,
+        proxy_url: row.get::<_, Option<String>>(35).unwrap_or(None).map(|u| redact_url_password(&u)),
         headers: headers_str.and_then(|s| serde_json::from_str(&s).ok()),
         is_public: row.get::<_, i32>(9).unwrap() != 0,
-        is_paused: row.get::<_, i32>(10).unwrap() != 0,
+        is_paused,
+        is_archived,
         current_status: row.get(11).unwrap(),
-        last_checked_at: row.get(12).unwrap_or(None),
+        last_checked_at,
+        next_check_at,
         confirmation_threshold: row.get(13).unwrap(),
+        down_confirmation_threshold: row.get::<_, Option<u32>>(43).unwrap_or(None),
+        up_confirmation_threshold: row.get::<_, Option<u32>>(44).unwrap_or(None),
+        recovery_cooldown_seconds: row.get::<_, Option<u32>>(42).unwrap_or(None),
         response_time_threshold_ms: row.get::<_, Option<u32>>(17).unwrap_or(None),
         follow_redirects: row.get::<_, i32>(18).unwrap_or(1) != 0,
+        max_redirects: row.get::<_, u32>(29).unwrap_or(10),
+        expected_final_url_prefix: row.get::<_, Option<String>>(30).unwrap_or(None),
+        expected_first_status: row.get::<_, Option<u16>>(49).unwrap_or(None),
         dns_record_type: row.get::<_, String>(21).unwrap_or_else(|_| "A".to_string()),
         dns_expected: row.get::<_, Option<String>>(22).unwrap_or(None),
+        dns_min_records: row.get::<_, Option<u32>>(36).unwrap_or(None),
+        dns_min_ttl: row.get::<_, Option<u32>>(37).unwrap_or(None),
+        dns_resolver: row.get::<_, Option<String>>(38).unwrap_or(None),
+        // Probes often carry a credential (a Redis AUTH line); see monitor_tcp_send
+        tcp_send: row.get::<_, Option<String>>(39).unwrap_or(None).map(|_| REDACTED_TCP_SEND.to_string()),
+        tcp_expect: row.get::<_, Option<String>>(40).unwrap_or(None),
         sla_target: row.get::<_, Option<f64>>(23).unwrap_or(None),
         sla_period_days: row.get::<_, Option<u32>>(24).unwrap_or(None),
         tags: parse_tags(&tags_str),
         group_name: row.get::<_, Option<String>>(19).unwrap_or(None),
+        external_id: row.get::<_, Option<String>>(45).unwrap_or(None),
         consensus_threshold: row.get::<_, Option<u32>>(25).unwrap_or(None),
+        consensus_mode: row.get::<_, Option<String>>(26).unwrap_or(None),
+        steps: row.get::<_, Option<String>>(27).unwrap_or(None).and_then(|s| serde_json::from_str(&s).ok()),
         created_at: row.get(14).unwrap(),
         updated_at: row.get(15).unwrap(),
     }
@@ -147,16 +215,26 @@ pub(crate) fn tags_to_string(tags: &[String]) -> String {
         .join(",")
 }
 
+/// Allow changes to a monitor: its own manage key, or an API key with `write` scope.
 pub(crate) fn verify_manage_key(conn: &rusqlite::Connection, monitor_id: &str, token: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
+    verify_monitor_access(conn, monitor_id, token, Scope::Write)
+}
+
+/// Allow viewing a monitor's private config: its own manage key, or any API key.
+pub(crate) fn verify_read_access(conn: &rusqlite::Connection, monitor_id: &str, token: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
+    verify_monitor_access(conn, monitor_id, token, Scope::Read)
+}
+
+fn verify_monitor_access(conn: &rusqlite::Connection, monitor_id: &str, token: &str, needed: Scope) -> Result<(), (Status, Json<serde_json::Value>)> {
     let stored_hash: String = conn.query_row(
-        "SELECT manage_key_hash FROM monitors WHERE id = ?1",
+        "SELECT manage_key_hash FROM monitors WHERE id = ?1 AND deleted_at IS NULL",
         params![monitor_id],
         |row| row.get(0),
     ).map_err(|_| (Status::NotFound, Json(serde_json::json!({
         "error": "Monitor not found", "code": "NOT_FOUND"
     }))))?;
 
-    if hash_key(token) != stored_hash {
+    if hash_key(token) != stored_hash && !api_key_allows(conn, token, needed) {
         return Err((Status::Forbidden, Json(serde_json::json!({
             "error": "Invalid manage key", "code": "FORBIDDEN"
         }))));
@@ -164,24 +242,328 @@ pub(crate) fn verify_manage_key(conn: &rusqlite::Connection, monitor_id: &str, t
     Ok(())
 }
 
+/// Verify an admin key against the hash stored in the settings table
+pub(crate) fn verify_admin_key(conn: &rusqlite::Connection, token: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
+    let stored_hash: String = conn.query_row(
+        "SELECT value FROM settings WHERE key = 'admin_key_hash'",
+        [],
+        |row| row.get(0),
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({
+        "error": "Admin key not configured", "code": "SERVER_ERROR"
+    }))))?;
+
+    if hash_key(token) != stored_hash && !api_key_allows(conn, token, Scope::Admin) {
+        return Err((Status::Forbidden, Json(serde_json::json!({
+            "error": "Invalid admin key", "code": "FORBIDDEN"
+        }))));
+    }
+    Ok(())
+}
+
+/// Valid values for `monitor_type`
+pub(crate) const VALID_MONITOR_TYPES: &[&str] = &["http", "tcp", "dns", "multistep"];
+
+/// Maximum number of steps in a multistep monitor
+const MAX_CHECK_STEPS: usize = 10;
+
+/// Validate the step list of a multistep monitor.
+pub(crate) fn validate_steps(steps: &[crate::models::CheckStep]) -> Result<(), String> {
+    if steps.is_empty() {
+        return Err("multistep monitors require at least one step".into());
+    }
+    if steps.len() > MAX_CHECK_STEPS {
+        return Err(format!("multistep monitors support at most {} steps", MAX_CHECK_STEPS));
+    }
+    for (i, step) in steps.iter().enumerate() {
+        let n = i + 1;
+        let url = step.url.trim().to_lowercase();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("Step {}: URL must start with http:// or https://", n));
+        }
+        if !["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE"].contains(&step.method.to_uppercase().as_str()) {
+            return Err(format!("Step {}: method must be GET, HEAD, POST, PUT, PATCH, or DELETE", n));
+        }
+        if step.headers.as_ref().is_some_and(|h| !h.is_object()) {
+            return Err(format!("Step {}: headers must be a JSON object", n));
+        }
+        if let Some(ref ex) = step.extract {
+            if ex.header.is_some() == ex.cookie.is_some() {
+                return Err(format!("Step {}: extract needs exactly one of 'header' or 'cookie'", n));
+            }
+            if ex.name.trim().is_empty() {
+                return Err(format!("Step {}: extract 'as' name is required", n));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate the `min_body_bytes` / `max_body_bytes` pair.
+pub(crate) fn validate_body_size(min: Option<u32>, max: Option<u32>) -> Result<(), String> {
+    if let (Some(min), Some(max)) = (min, max) {
+        if min > max {
+            return Err("min_body_bytes must not exceed max_body_bytes".into());
+        }
+    }
+    Ok(())
+}
+
+/// Valid values for `auth_type`
+pub(crate) const VALID_AUTH_TYPES: &[&str] = &["none", "basic", "bearer"];
+
+/// Credentials a monitor sends with its HTTP checks, as stored.
+/// `auth_type` is None for `none`; fields that don't apply to the type are None.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct MonitorAuth {
+    pub auth_type: Option<String>,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub token: Option<String>,
+}
+
+/// Validate and normalize auth settings: `basic` needs a username,
+/// `bearer` needs a token.
+pub(crate) fn resolve_auth(
+    auth_type: Option<&str>,
+    username: Option<String>,
+    password: Option<String>,
+    token: Option<String>,
+) -> Result<MonitorAuth, String> {
+    let auth_type = auth_type.map(|t| t.trim().to_lowercase()).unwrap_or_else(|| "none".into());
+    if !VALID_AUTH_TYPES.contains(&auth_type.as_str()) {
+        return Err(format!("auth_type must be one of: {}", VALID_AUTH_TYPES.join(", ")));
+    }
+    let non_empty = |v: Option<String>| v.filter(|s| !s.is_empty());
+    match auth_type.as_str() {
+        "basic" => {
+            let username = non_empty(username).ok_or("auth_username is required for basic auth")?;
+            Ok(MonitorAuth { auth_type: Some(auth_type), username: Some(username), password, token: None })
+        }
+        "bearer" => {
+            let token = non_empty(token).ok_or("auth_token is required for bearer auth")?;
+            Ok(MonitorAuth { auth_type: Some(auth_type), username: None, password: None, token: Some(token) })
+        }
+        _ => Ok(MonitorAuth::default()),
+    }
+}
+
+/// Validate an mTLS client certificate and key: both or neither, and together they must
+/// load as a TLS client identity. Errors name the field to blame.
+pub(crate) fn resolve_client_cert(cert: Option<&str>, key: Option<&str>) -> Result<Option<(String, String)>, (&'static str, String)> {
+    let non_empty = |v: Option<&str>| v.map(str::trim).filter(|s| !s.is_empty());
+    match (non_empty(cert), non_empty(key)) {
+        (None, None) => Ok(None),
+        (Some(_), None) => Err(("client_key_pem", "client_key_pem is required with client_cert_pem".into())),
+        (None, Some(_)) => Err(("client_cert_pem", "client_cert_pem is required with client_key_pem".into())),
+        (Some(cert), Some(key)) => {
+            if !cert.contains("-----BEGIN CERTIFICATE-----") {
+                return Err(("client_cert_pem", "client_cert_pem must be a PEM certificate".into()));
+            }
+            if !key.contains("PRIVATE KEY-----") {
+                return Err(("client_key_pem", "client_key_pem must be a PEM private key".into()));
+            }
+            crate::checker::validate_client_identity(cert, key)
+                .map_err(|e| ("client_cert_pem", format!("client_cert_pem and client_key_pem are not a usable certificate and key: {}", e)))?;
+            Ok(Some((cert.to_string(), key.to_string())))
+        }
+    }
+}
+
+/// Validate a `ca_bundle_pem`: one or more PEM certificates that load as trust roots.
+/// Blank means none.
+pub(crate) fn normalize_ca_bundle(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(value) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    if !value.contains("-----BEGIN CERTIFICATE-----") {
+        return Err("ca_bundle_pem must contain at least one PEM certificate".into());
+    }
+    crate::checker::validate_ca_bundle(value)
+        .map_err(|e| format!("ca_bundle_pem is not a usable CA bundle: {}", e))?;
+    Ok(Some(value.to_string()))
+}
+
+/// Validate and normalize a `proxy_url`: http:// or https:// with a host.
+/// Blank means no proxy.
+pub(crate) fn normalize_proxy_url(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(url) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let lower = url.to_lowercase();
+    if !lower.starts_with("http://") && !lower.starts_with("https://") {
+        return Err("proxy_url must start with http:// or https://".into());
+    }
+    let authority = url.split_once("://").map(|(_, rest)| rest).unwrap_or_default()
+        .split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    if host.is_empty() || host.contains(' ') || reqwest::Proxy::all(url).is_err() {
+        return Err("proxy_url must be a valid URL with a host".into());
+    }
+    Ok(Some(url.to_string()))
+}
+
+/// Stands in for a monitor's `tcp_send` in responses to callers without read access.
+pub(crate) const REDACTED_TCP_SEND: &str = "****";
+
+/// A monitor's stored `tcp_send`, unmasked. Only for callers with read access.
+pub(crate) fn monitor_tcp_send(conn: &rusqlite::Connection, id: &str) -> Option<String> {
+    conn.query_row("SELECT tcp_send FROM monitors WHERE id = ?1", params![id], |row| row.get(0)).ok().flatten()
+}
+
+/// Mask the password in a URL's userinfo (`user:****@host`), so stored proxy
+/// credentials are never returned.
+pub(crate) fn redact_url_password(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let (authority, path) = rest.split_at(rest.find(['/', '?', '#']).unwrap_or(rest.len()));
+    match authority.rsplit_once('@').and_then(|(userinfo, host)| Some((userinfo.split_once(':')?.0, host))) {
+        Some((user, host)) => format!("{scheme}://{user}:****@{host}{path}"),
+        None => url.to_string(),
+    }
+}
+
+/// Longest accepted `tcp_send` / `tcp_expect`
+const MAX_TCP_PROBE_LEN: usize = 1024;
+
+/// Validate a TCP probe string (`tcp_send` / `tcp_expect`). Empty means unset.
+pub(crate) fn normalize_tcp_probe(field: &str, value: Option<&str>) -> Result<Option<String>, String> {
+    match value.filter(|v| !v.is_empty()) {
+        Some(v) if v.len() > MAX_TCP_PROBE_LEN => Err(format!("{} must be at most {} bytes", field, MAX_TCP_PROBE_LEN)),
+        v => Ok(v.map(|v| v.to_string())),
+    }
+}
+
+/// Longest accepted `recovery_cooldown_seconds` (one day)
+const MAX_RECOVERY_COOLDOWN_SECONDS: u32 = 86_400;
+
+/// Clamp a recovery cooldown to the accepted range; 0 means no cooldown.
+pub(crate) fn normalize_recovery_cooldown(seconds: Option<u32>) -> Option<u32> {
+    seconds.filter(|&s| s > 0).map(|s| s.min(MAX_RECOVERY_COOLDOWN_SECONDS))
+}
+
+/// Clamp a connect timeout to 100ms-60s. Unlike `timeout_ms` it may go below a second.
+pub(crate) fn normalize_connect_timeout(ms: Option<u32>) -> Option<u32> {
+    ms.map(|ms| ms.clamp(100, 60000))
+}
+
+/// Longest accepted monitor `description`
+const MAX_DESCRIPTION_LEN: usize = 5000;
+
+/// Trim a monitor description; blank means none.
+pub(crate) fn normalize_description(value: Option<&str>) -> Result<Option<String>, String> {
+    match value.map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        Some(v) if v.chars().count() > MAX_DESCRIPTION_LEN => Err(format!("description must be at most {} characters", MAX_DESCRIPTION_LEN)),
+        v => Ok(v.map(String::from)),
+    }
+}
+
+/// Validate a `runbook_url`: http:// or https:// with a host. Blank means none.
+pub(crate) fn normalize_runbook_url(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(value) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    match reqwest::Url::parse(value) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(|h| !h.is_empty()) => Ok(Some(value.to_string())),
+        _ => Err("runbook_url must be an http:// or https:// URL".into()),
+    }
+}
+
+/// An `expected_first_status` must be a real HTTP status code.
+pub(crate) fn validate_first_status(status: Option<u16>) -> Result<Option<u16>, String> {
+    match status {
+        Some(s) if !(100..=599).contains(&s) => Err("expected_first_status must be between 100 and 599".into()),
+        s => Ok(s),
+    }
+}
+
+/// Longest accepted `external_id`
+const MAX_EXTERNAL_ID_LEN: usize = 200;
+
+/// Trim an `external_id`; blank means none.
+pub(crate) fn normalize_external_id(value: Option<&str>) -> Result<Option<String>, String> {
+    match value.map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        Some(v) if v.len() > MAX_EXTERNAL_ID_LEN => Err(format!("external_id must be at most {} characters", MAX_EXTERNAL_ID_LEN)),
+        v => Ok(v.map(|v| v.to_string())),
+    }
+}
+
+/// Id of the live monitor provisioned under `external_id`, if any.
+pub(crate) fn monitor_id_by_external_id(conn: &rusqlite::Connection, external_id: &str) -> Option<String> {
+    conn.query_row(
+        "SELECT id FROM monitors WHERE external_id = ?1 AND deleted_at IS NULL",
+        params![external_id],
+        |row| row.get(0),
+    ).ok()
+}
+
+/// 400 VALIDATION_ERROR naming the request field at fault, so clients can attach it to a form input.
+pub(crate) fn field_error(field: &str, error: impl Into<String>) -> (Status, Json<serde_json::Value>) {
+    (Status::BadRequest, Json(serde_json::json!({
+        "error": error.into(), "code": "VALIDATION_ERROR", "field": field
+    })))
+}
+
+/// `field_error` for validators covering several fields. Their messages lead with the
+/// offending field's name; the first field is the fallback.
+pub(crate) fn fields_error(fields: &[&str], error: String) -> (Status, Json<serde_json::Value>) {
+    let field = fields.iter().copied().find(|f| error.starts_with(f)).unwrap_or(fields[0]);
+    field_error(field, error)
+}
+
 /// Validate TCP address format: host:port (port must be 1-65535)
 pub(crate) fn validate_tcp_address(addr: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
     let addr = addr.strip_prefix("tcp://").unwrap_or(addr);
     let parts: Vec<&str> = addr.rsplitn(2, ':').collect();
     if parts.len() != 2 || parts[1].is_empty() {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "TCP address must be in host:port format (e.g., 'example.com:443' or 'tcp://example.com:443')",
-            "code": "VALIDATION_ERROR"
-        }))));
+        return Err(field_error("url", "TCP address must be in host:port format (e.g., 'example.com:443' or 'tcp://example.com:443')"));
     }
     match parts[0].parse::<u16>() {
-        Ok(0) => Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "Port must be between 1 and 65535", "code": "VALIDATION_ERROR"
-        })))),
+        Ok(0) => Err(field_error("url", "Port must be between 1 and 65535")),
         Ok(_) => Ok(()),
-        Err(_) => Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "Invalid port number in TCP address", "code": "VALIDATION_ERROR"
-        })))),
+        Err(_) => Err(field_error("url", "Invalid port number in TCP address")),
+    }
+}
+
+/// Largest accepted `dns_min_records`
+const MAX_DNS_MIN_RECORDS: u32 = 100;
+/// Largest accepted `dns_min_ttl` (one week)
+const MAX_DNS_MIN_TTL: u32 = 604_800;
+
+/// Validate the DNS answer assertions `dns_min_records` / `dns_min_ttl`.
+pub(crate) fn validate_dns_assertions(min_records: Option<u32>, min_ttl: Option<u32>) -> Result<(), String> {
+    if min_records.is_some_and(|n| !(1..=MAX_DNS_MIN_RECORDS).contains(&n)) {
+        return Err(format!("dns_min_records must be between 1 and {}", MAX_DNS_MIN_RECORDS));
+    }
+    if min_ttl.is_some_and(|t| !(1..=MAX_DNS_MIN_TTL).contains(&t)) {
+        return Err(format!("dns_min_ttl must be between 1 and {} seconds", MAX_DNS_MIN_TTL));
+    }
+    Ok(())
+}
+
+/// DNS-over-HTTPS presets accepted as `dns_resolver`, with their JSON API endpoints
+pub(crate) const DOH_PRESETS: &[(&str, &str)] = &[
+    ("cloudflare", "https://cloudflare-dns.com/dns-query"),
+    ("google", "https://dns.google/resolve"),
+];
+
+/// Validate and normalize `dns_resolver`. Blank or `system` means the system
+/// resolver (stored as None); presets are stored by name; anything else must
+/// be an https:// URL with a host.
+pub(crate) fn normalize_dns_resolver(raw: Option<&str>) -> Result<Option<String>, String> {
+    let Some(value) = raw.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    let lower = value.to_lowercase();
+    if lower == "system" {
+        return Ok(None);
+    }
+    if DOH_PRESETS.iter().any(|(name, _)| *name == lower) {
+        return Ok(Some(lower));
+    }
+    match reqwest::Url::parse(value) {
+        Ok(url) if url.scheme() == "https" && url.host_str().is_some_and(|h| !h.is_empty()) => Ok(Some(value.to_string())),
+        _ => Err("dns_resolver must be 'system', 'cloudflare', 'google', or an https:// DoH endpoint URL".into()),
     }
 }
 
@@ -189,16 +571,10 @@ pub(crate) fn validate_tcp_address(addr: &str) -> Result<(), (Status, Json<serde
 pub(crate) fn validate_dns_hostname(host: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
     let host = host.strip_prefix("dns://").unwrap_or(host);
     if host.is_empty() {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "DNS hostname cannot be empty",
-            "code": "VALIDATION_ERROR"
-        }))));
+        return Err(field_error("url", "DNS hostname cannot be empty"));
     }
     if host.contains(' ') || host.contains("://") {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "DNS hostname must be a valid domain (e.g., 'example.com' or 'dns://example.com')",
-            "code": "VALIDATION_ERROR"
-        }))));
+        return Err(field_error("url", "DNS hostname must be a valid domain (e.g., 'example.com' or 'dns://example.com')"));
     }
     Ok(())
 }
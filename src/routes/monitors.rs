@@ -1,13 +1,21 @@
-use rocket::{get, post, patch, delete, serde::json::Json, State, http::Status};
+use rocket::{get, post, put, patch, delete, serde::json::Json, State, http::{Header, Status}};
+use rocket::request::{FromRequest, Outcome, Request};
 use crate::db::Db;
 use crate::models::{
-    Monitor, CreateMonitor, UpdateMonitor, CreateMonitorResponse,
+    Monitor, Heartbeat, CreateMonitor, UpdateMonitor, CreateMonitorResponse,
     BulkCreateMonitors, BulkCreateResponse, BulkError, ExportedMonitor,
+    BulkAction, BulkActionResult, BulkActionResponse, CheckStateResponse, MonitorDefaults,
 };
-use crate::auth::{ManageToken, ClientIp, generate_key, hash_key};
+use crate::auth::{ManageToken, OptionalManageToken, ClientIp, Scope, api_key_allows, generate_key, hash_key};
+use crate::consensus::ConsensusRule;
+use crate::checker::{check_monitor, CheckerClients, CheckStates};
+use crate::sse::EventBroadcaster;
 use super::{
-    RateLimiter, get_monitor_from_db, row_to_monitor, tags_to_string,
-    verify_manage_key, validate_tcp_address, validate_dns_hostname, VALID_DNS_RECORD_TYPES,
+    RateLimiter, MONITOR_COLUMNS, REDACTED_TCP_SEND, get_monitor_from_db, row_to_monitor, tags_to_string,
+    verify_manage_key, verify_read_access, validate_tcp_address, validate_dns_hostname, validate_steps, validate_body_size, resolve_auth, resolve_client_cert,
+    normalize_proxy_url, validate_dns_assertions, normalize_dns_resolver, normalize_tcp_probe, normalize_recovery_cooldown, normalize_connect_timeout, normalize_description, normalize_runbook_url, validate_first_status, normalize_ca_bundle, normalize_external_id, monitor_id_by_external_id, monitor_restore_days, monitor_tcp_send,
+    audit_actor, record_audit, monitor_diff, load_monitor_defaults, verify_admin_key, parse_tags, field_error, fields_error,
+    VALID_DNS_RECORD_TYPES, VALID_MONITOR_TYPES,
 };
 use rusqlite::params;
 use std::sync::Arc;
@@ -32,21 +40,20 @@ pub fn create_monitor(
 
     // Validate
     if data.name.trim().is_empty() {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "Name is required", "code": "VALIDATION_ERROR"
-        }))));
+        return Err(field_error("name", "Name is required"));
     }
     if data.url.trim().is_empty() {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "URL is required", "code": "VALIDATION_ERROR"
-        }))));
+        return Err(field_error("url", "URL is required"));
     }
 
     let monitor_type = data.monitor_type.as_deref().unwrap_or("http").to_lowercase();
-    if !["http", "tcp", "dns"].contains(&monitor_type.as_str()) {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "monitor_type must be 'http', 'tcp', or 'dns'", "code": "VALIDATION_ERROR"
-        }))));
+    if !VALID_MONITOR_TYPES.contains(&monitor_type.as_str()) {
+        return Err(field_error("monitor_type", "monitor_type must be 'http', 'tcp', 'dns', or 'multistep'"));
+    }
+    if monitor_type == "multistep" {
+        if let Err(e) = validate_steps(data.steps.as_deref().unwrap_or_default()) {
+            return Err(field_error("steps", e));
+        }
     }
 
     if monitor_type == "tcp" {
@@ -55,36 +62,28 @@ pub fn create_monitor(
         validate_dns_hostname(data.url.trim())?;
         let rt = data.dns_record_type.as_deref().unwrap_or("A").to_uppercase();
         if !VALID_DNS_RECORD_TYPES.contains(&rt.as_str()) {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": format!("dns_record_type must be one of: {}", VALID_DNS_RECORD_TYPES.join(", ")),
-                "code": "VALIDATION_ERROR"
-            }))));
+            return Err(field_error("dns_record_type", format!("dns_record_type must be one of: {}", VALID_DNS_RECORD_TYPES.join(", "))));
         }
     } else {
         let url_trimmed = data.url.trim().to_lowercase();
         if !url_trimmed.starts_with("http://") && !url_trimmed.starts_with("https://") {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": "URL must start with http:// or https://", "code": "VALIDATION_ERROR"
-            }))));
+            return Err(field_error("url", "URL must start with http:// or https://"));
         }
     }
     if let Some(ref headers) = data.headers {
         if !headers.is_object() {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": "Headers must be a JSON object", "code": "VALIDATION_ERROR"
-            }))));
+            return Err(field_error("headers", "Headers must be a JSON object"));
         }
     }
     let method = data.method.to_uppercase();
     if monitor_type == "http" && !["GET", "HEAD", "POST"].contains(&method.as_str()) {
-        return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "Method must be GET, HEAD, or POST", "code": "VALIDATION_ERROR"
-        }))));
+        return Err(field_error("method", "Method must be GET, HEAD, or POST"));
     }
-    let interval = data.interval_seconds.unwrap_or(600).max(600);
-    let timeout = data.timeout_ms.unwrap_or(10000).clamp(1000, 60000);
+    let defaults = load_monitor_defaults(&db.read());
+    let interval = data.interval_seconds.unwrap_or(defaults.interval_seconds).max(600);
+    let timeout = data.timeout_ms.unwrap_or(defaults.timeout_ms).clamp(1000, 60000);
     let expected_status = data.expected_status.unwrap_or(200);
-    let confirmation = data.confirmation_threshold.unwrap_or(2).clamp(1, 10);
+    let confirmation = data.confirmation_threshold.unwrap_or(defaults.confirmation_threshold).clamp(1, 10);
 
     let id = uuid::Uuid::new_v4().to_string();
     let manage_key = generate_key();
@@ -92,6 +91,8 @@ pub fn create_monitor(
     let tags_str = tags_to_string(&data.tags);
     let rt_threshold = data.response_time_threshold_ms.map(|v| v.max(100));
     let follow_redirects = data.follow_redirects.unwrap_or(true);
+    let max_redirects = data.max_redirects.unwrap_or(10).min(20);
+    let final_url_prefix = data.expected_final_url_prefix.as_deref().map(|s| s.trim()).filter(|s| !s.is_empty());
 
     let group_name = data.group_name.as_deref().map(|g| g.trim()).filter(|g| !g.is_empty()).map(|g| g.to_string());
     let dns_record_type = data.dns_record_type.as_deref().unwrap_or("A").to_uppercase();
@@ -101,27 +102,92 @@ pub fn create_monitor(
     let sla_target = data.sla_target;
     if let Some(target) = sla_target {
         if !(0.0..=100.0).contains(&target) {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": "sla_target must be between 0 and 100", "code": "VALIDATION_ERROR"
-            }))));
+            return Err(field_error("sla_target", "sla_target must be between 0 and 100"));
         }
     }
     let sla_period_days = data.sla_period_days.map(|d| d.clamp(1, 365));
 
-    // Validate consensus_threshold
+    // Validate consensus_threshold / consensus_mode
     let consensus_threshold = data.consensus_threshold;
-    if let Some(ct) = consensus_threshold {
-        if ct < 1 {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": "consensus_threshold must be at least 1", "code": "VALIDATION_ERROR"
-            }))));
-        }
+    let consensus_mode = data.consensus_mode.as_deref().map(|m| m.trim().to_lowercase());
+    if let Err(e) = ConsensusRule::validate(consensus_mode.as_deref(), consensus_threshold) {
+        return Err(fields_error(&["consensus_mode", "consensus_threshold"], e));
     }
 
+    if let Err(e) = validate_body_size(data.min_body_bytes, data.max_body_bytes) {
+        return Err(fields_error(&["min_body_bytes", "max_body_bytes"], e));
+    }
+
+    let auth = match resolve_auth(data.auth_type.as_deref(), data.auth_username.clone(), data.auth_password.clone(), data.auth_token.clone()) {
+        Ok(auth) => auth,
+        Err(e) => return Err(fields_error(&["auth_type", "auth_username", "auth_token"], e)),
+    };
+    let (client_cert_pem, client_key_pem) = match resolve_client_cert(data.client_cert_pem.as_deref(), data.client_key_pem.as_deref()) {
+        Ok(pair) => pair.unzip(),
+        Err((field, e)) => return Err(field_error(field, e)),
+    };
+    let ca_bundle_pem = match normalize_ca_bundle(data.ca_bundle_pem.as_deref()) {
+        Ok(pem) => pem,
+        Err(e) => return Err(field_error("ca_bundle_pem", e)),
+    };
+
+    if let Err(e) = validate_dns_assertions(data.dns_min_records, data.dns_min_ttl) {
+        return Err(fields_error(&["dns_min_records", "dns_min_ttl"], e));
+    }
+
+    let dns_resolver = match normalize_dns_resolver(data.dns_resolver.as_deref()) {
+        Ok(r) => r,
+        Err(e) => return Err(field_error("dns_resolver", e)),
+    };
+
+    let (tcp_send, tcp_expect) = match (
+        normalize_tcp_probe("tcp_send", data.tcp_send.as_deref()),
+        normalize_tcp_probe("tcp_expect", data.tcp_expect.as_deref()),
+    ) {
+        (Ok(send), Ok(expect)) => (send, expect),
+        (Err(e), _) | (_, Err(e)) => return Err(fields_error(&["tcp_send", "tcp_expect"], e)),
+    };
+
+    let proxy_url = match normalize_proxy_url(data.proxy_url.as_deref()) {
+        Ok(p) => p,
+        Err(e) => return Err(field_error("proxy_url", e)),
+    };
+
+    let steps_json = if monitor_type == "multistep" {
+        data.steps.as_ref().and_then(|st| serde_json::to_string(st).ok())
+    } else {
+        None
+    };
+
+    let external_id = match normalize_external_id(data.external_id.as_deref()) {
+        Ok(e) => e,
+        Err(e) => return Err(field_error("external_id", e)),
+    };
+
+    let description = match normalize_description(data.description.as_deref()) {
+        Ok(d) => d,
+        Err(e) => return Err(field_error("description", e)),
+    };
+
+    let runbook_url = match normalize_runbook_url(data.runbook_url.as_deref()) {
+        Ok(u) => u,
+        Err(e) => return Err(field_error("runbook_url", e)),
+    };
+
+    let expected_first_status = match validate_first_status(data.expected_first_status) {
+        Ok(s) => s,
+        Err(e) => return Err(field_error("expected_first_status", e)),
+    };
+
     let conn = db.conn();
+    if external_id.as_deref().is_some_and(|e| monitor_id_by_external_id(&conn, e).is_some()) {
+        return Err((Status::Conflict, Json(serde_json::json!({
+            "error": "A monitor with this external_id already exists", "code": "EXTERNAL_ID_CONFLICT"
+        }))));
+    }
     conn.execute(
-        "INSERT INTO monitors (id, name, url, monitor_type, method, interval_seconds, timeout_ms, expected_status, body_contains, headers, manage_key_hash, is_public, confirmation_threshold, tags, response_time_threshold_ms, follow_redirects, group_name, dns_record_type, dns_expected, sla_target, sla_period_days, consensus_threshold)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
+        "INSERT INTO monitors (id, name, url, monitor_type, method, interval_seconds, timeout_ms, expected_status, body_contains, headers, manage_key_hash, is_public, confirmation_threshold, tags, response_time_threshold_ms, follow_redirects, group_name, dns_record_type, dns_expected, sla_target, sla_period_days, consensus_threshold, consensus_mode, steps, body_not_contains, max_redirects, expected_final_url_prefix, min_body_bytes, max_body_bytes, auth_type, auth_username, auth_password, auth_token, proxy_url, dns_min_records, dns_min_ttl, dns_resolver, tcp_send, tcp_expect, recovery_cooldown_seconds, down_confirmation_threshold, up_confirmation_threshold, connect_timeout_ms, external_id, description, runbook_url, expected_first_status, client_cert_pem, client_key_pem, insecure_skip_tls_verify, ca_bundle_pem)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43, ?44, ?45, ?46, ?47, ?48, ?49, ?50, ?51)",
         params![
             id,
             data.name.trim(),
@@ -145,6 +211,35 @@ pub fn create_monitor(
             sla_target,
             sla_period_days,
             consensus_threshold,
+            consensus_mode,
+            steps_json,
+            data.body_not_contains.as_deref().filter(|s| !s.is_empty()),
+            max_redirects,
+            final_url_prefix,
+            data.min_body_bytes,
+            data.max_body_bytes,
+            auth.auth_type,
+            auth.username,
+            auth.password,
+            auth.token,
+            proxy_url,
+            data.dns_min_records,
+            data.dns_min_ttl,
+            dns_resolver,
+            tcp_send,
+            tcp_expect,
+            normalize_recovery_cooldown(data.recovery_cooldown_seconds),
+            data.down_confirmation_threshold.map(|v| v.clamp(1, 10)),
+            data.up_confirmation_threshold.map(|v| v.clamp(1, 10)),
+            normalize_connect_timeout(data.connect_timeout_ms),
+            external_id,
+            description,
+            runbook_url,
+            expected_first_status,
+            client_cert_pem,
+            client_key_pem,
+            data.insecure_skip_tls_verify as i32,
+            ca_bundle_pem,
         ],
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({
         "error": "Internal server error", "code": "INTERNAL_ERROR"
@@ -155,6 +250,7 @@ pub fn create_monitor(
             "error": "Internal server error", "code": "INTERNAL_ERROR"
         })))
     })?;
+    record_audit(&conn, "monitor.created", Some(&id), "anonymous", Some(serde_json::json!({"name": monitor.name, "url": monitor.url})));
 
     Ok(Json(CreateMonitorResponse {
         monitor,
@@ -165,6 +261,56 @@ pub fn create_monitor(
     }))
 }
 
+// ── Upsert by External ID ──
+
+/// Create or update the monitor provisioned under `key`, so repeated IaC applies converge.
+/// The first PUT creates it (201, with its manage_key); later PUTs need that key and apply
+/// the body as an update (200, no key).
+#[put("/monitors/by-external-id/<key>", format = "json", data = "<input>")]
+pub fn upsert_monitor_by_external_id(
+    key: &str,
+    input: Json<serde_json::Value>,
+    token: OptionalManageToken,
+    db: &State<Arc<Db>>,
+    rate_limiter: &State<RateLimiter>,
+    client_ip: ClientIp,
+) -> Result<(Status, Json<serde_json::Value>), (Status, Json<serde_json::Value>)> {
+    let external_id = normalize_external_id(Some(key))
+        .map_err(|e| (Status::BadRequest, Json(serde_json::json!({"error": e, "code": "VALIDATION_ERROR"}))))?
+        .ok_or_else(|| (Status::BadRequest, Json(serde_json::json!({
+            "error": "external_id is required", "code": "VALIDATION_ERROR"
+        }))))?;
+    let mut body = input.into_inner();
+    if !body.is_object() {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "Body must be a JSON object", "code": "VALIDATION_ERROR"
+        }))));
+    }
+    body["external_id"] = external_id.clone().into();
+
+    let existing = monitor_id_by_external_id(&db.conn(), &external_id);
+    let Some(id) = existing else {
+        let data: CreateMonitor = serde_json::from_value(body).map_err(|e| (Status::BadRequest, Json(serde_json::json!({
+            "error": format!("Invalid monitor: {}", e), "code": "VALIDATION_ERROR"
+        }))))?;
+        let created = create_monitor(Json(data), db, rate_limiter, client_ip)?.into_inner();
+        return Ok((Status::Created, Json(serde_json::to_value(created).unwrap_or_default())));
+    };
+
+    let token = token.0.ok_or_else(|| (Status::Unauthorized, Json(serde_json::json!({
+        "error": "A monitor with this external_id exists; its manage key is required to update it", "code": "UNAUTHORIZED"
+    }))))?;
+    let changes: UpdateMonitor = serde_json::from_value(body).map_err(|e| (Status::BadRequest, Json(serde_json::json!({
+        "error": format!("Invalid monitor: {}", e), "code": "VALIDATION_ERROR"
+    }))))?;
+    update_monitor(&id, Json(changes), ManageToken(token), db)?;
+
+    let monitor = get_monitor_from_db(&db.conn(), &id).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({
+        "error": "Internal server error", "code": "INTERNAL_ERROR"
+    }))))?;
+    Ok((Status::Ok, Json(serde_json::json!({"monitor": monitor}))))
+}
+
 // ── Bulk Create Monitors ──
 
 #[post("/monitors/bulk", format = "json", data = "<input>")]
@@ -173,7 +319,7 @@ pub fn bulk_create_monitors(
     db: &State<Arc<Db>>,
     rate_limiter: &State<RateLimiter>,
     client_ip: ClientIp,
-) -> Result<Json<BulkCreateResponse>, (Status, Json<serde_json::Value>)> {
+) -> Result<(Status, Json<BulkCreateResponse>), (Status, Json<serde_json::Value>)> {
     let data = input.into_inner();
 
     if data.monitors.is_empty() {
@@ -191,6 +337,7 @@ pub fn bulk_create_monitors(
     let mut created = Vec::new();
     let mut errors = Vec::new();
     let conn = db.conn();
+    let defaults = load_monitor_defaults(&conn);
 
     for (idx, monitor_data) in data.monitors.into_iter().enumerate() {
         if !rate_limiter.check(&client_ip.0) {
@@ -202,137 +349,262 @@ pub fn bulk_create_monitors(
             continue;
         }
 
-        if monitor_data.name.trim().is_empty() {
-            errors.push(BulkError { index: idx, error: "Name is required".into(), code: "VALIDATION_ERROR".into() });
-            continue;
+        match insert_bulk_monitor(&conn, &defaults, monitor_data) {
+            Ok(resp) => created.push(resp),
+            Err((error, code)) => errors.push(BulkError { index: idx, error, code: code.into() }),
         }
-        if monitor_data.url.trim().is_empty() {
-            errors.push(BulkError { index: idx, error: "URL is required".into(), code: "VALIDATION_ERROR".into() });
+    }
+
+    let succeeded = created.len();
+    let failed = errors.len();
+
+    // 207 so clients that only look at the status still notice partial failures
+    let status = if failed > 0 { Status::MultiStatus } else { Status::Ok };
+    Ok((status, Json(BulkCreateResponse { created, errors, total, succeeded, failed })))
+}
+
+/// Create monitors from a Prometheus blackbox_exporter targets file (YAML).
+/// Targets whose module can't be mapped to a monitor type land in `errors`.
+#[post("/monitors/import/blackbox", data = "<input>")]
+pub fn import_blackbox_targets(
+    input: String,
+    db: &State<Arc<Db>>,
+    rate_limiter: &State<RateLimiter>,
+    client_ip: ClientIp,
+) -> Result<Json<BulkCreateResponse>, (Status, Json<serde_json::Value>)> {
+    let entries = crate::blackbox::parse_targets(&input).map_err(|e| (Status::BadRequest, Json(serde_json::json!({
+        "error": e, "code": "VALIDATION_ERROR"
+    }))))?;
+
+    if entries.is_empty() {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "No targets found", "code": "VALIDATION_ERROR"
+        }))));
+    }
+    if entries.len() > 50 {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "Maximum 50 targets per import", "code": "VALIDATION_ERROR"
+        }))));
+    }
+
+    let total = entries.len();
+    let mut created = Vec::new();
+    let mut errors = Vec::new();
+    let conn = db.conn();
+    let defaults = load_monitor_defaults(&conn);
+
+    for (idx, entry) in entries.into_iter().enumerate() {
+        let monitor_data = match entry {
+            Ok(m) => m,
+            Err(error) => {
+                errors.push(BulkError { index: idx, error, code: "UNSUPPORTED_MODULE".into() });
+                continue;
+            }
+        };
+        if !rate_limiter.check(&client_ip.0) {
+            errors.push(BulkError {
+                index: idx,
+                error: "Rate limit exceeded".into(),
+                code: "RATE_LIMIT_EXCEEDED".into(),
+            });
             continue;
         }
-        let bulk_monitor_type = monitor_data.monitor_type.as_deref().unwrap_or("http").to_lowercase();
-        if !["http", "tcp", "dns"].contains(&bulk_monitor_type.as_str()) {
-            errors.push(BulkError { index: idx, error: "monitor_type must be 'http', 'tcp', or 'dns'".into(), code: "VALIDATION_ERROR".into() });
-            continue;
+
+        match insert_bulk_monitor(&conn, &defaults, monitor_data) {
+            Ok(resp) => created.push(resp),
+            Err((error, code)) => errors.push(BulkError { index: idx, error, code: code.into() }),
         }
-        if bulk_monitor_type == "tcp" {
-            if validate_tcp_address(monitor_data.url.trim()).is_err() {
-                errors.push(BulkError { index: idx, error: "TCP address must be in host:port format".into(), code: "VALIDATION_ERROR".into() });
-                continue;
-            }
-        } else if bulk_monitor_type == "dns" {
-            if validate_dns_hostname(monitor_data.url.trim()).is_err() {
-                errors.push(BulkError { index: idx, error: "DNS hostname must be a valid domain (e.g., 'example.com' or 'dns://example.com')".into(), code: "VALIDATION_ERROR".into() });
-                continue;
-            }
-            let rt = monitor_data.dns_record_type.as_deref().unwrap_or("A").to_uppercase();
-            if !VALID_DNS_RECORD_TYPES.contains(&rt.as_str()) {
-                errors.push(BulkError { index: idx, error: format!("dns_record_type must be one of: {}", VALID_DNS_RECORD_TYPES.join(", ")), code: "VALIDATION_ERROR".into() });
-                continue;
-            }
-        } else {
-            let url_trimmed = monitor_data.url.trim().to_lowercase();
-            if !url_trimmed.starts_with("http://") && !url_trimmed.starts_with("https://") {
-                errors.push(BulkError { index: idx, error: "URL must start with http:// or https://".into(), code: "VALIDATION_ERROR".into() });
-                continue;
-            }
+    }
+
+    let succeeded = created.len();
+    let failed = errors.len();
+
+    Ok(Json(BulkCreateResponse { created, errors, total, succeeded, failed }))
+}
+
+/// Validate and insert one monitor of a bulk request, returning the error
+/// message and code for the caller's error list on failure.
+fn insert_bulk_monitor(
+    conn: &rusqlite::Connection,
+    defaults: &MonitorDefaults,
+    monitor_data: CreateMonitor,
+) -> Result<CreateMonitorResponse, (String, &'static str)> {
+    if monitor_data.name.trim().is_empty() {
+        return Err(("Name is required".into(), "VALIDATION_ERROR"));
+    }
+    if monitor_data.url.trim().is_empty() {
+        return Err(("URL is required".into(), "VALIDATION_ERROR"));
+    }
+    let bulk_monitor_type = monitor_data.monitor_type.as_deref().unwrap_or("http").to_lowercase();
+    if !VALID_MONITOR_TYPES.contains(&bulk_monitor_type.as_str()) {
+        return Err(("monitor_type must be 'http', 'tcp', 'dns', or 'multistep'".into(), "VALIDATION_ERROR"));
+    }
+    if bulk_monitor_type == "multistep" {
+        validate_steps(monitor_data.steps.as_deref().unwrap_or_default()).map_err(|e| (e, "VALIDATION_ERROR"))?;
+    }
+    if bulk_monitor_type == "tcp" {
+        if validate_tcp_address(monitor_data.url.trim()).is_err() {
+            return Err(("TCP address must be in host:port format".into(), "VALIDATION_ERROR"));
         }
-        if let Some(ref headers) = monitor_data.headers {
-            if !headers.is_object() {
-                errors.push(BulkError { index: idx, error: "Headers must be a JSON object".into(), code: "VALIDATION_ERROR".into() });
-                continue;
-            }
+    } else if bulk_monitor_type == "dns" {
+        if validate_dns_hostname(monitor_data.url.trim()).is_err() {
+            return Err(("DNS hostname must be a valid domain (e.g., 'example.com' or 'dns://example.com')".into(), "VALIDATION_ERROR"));
         }
-        let method = monitor_data.method.to_uppercase();
-        if bulk_monitor_type == "http" && !["GET", "HEAD", "POST"].contains(&method.as_str()) {
-            errors.push(BulkError { index: idx, error: "Method must be GET, HEAD, or POST".into(), code: "VALIDATION_ERROR".into() });
-            continue;
+        let rt = monitor_data.dns_record_type.as_deref().unwrap_or("A").to_uppercase();
+        if !VALID_DNS_RECORD_TYPES.contains(&rt.as_str()) {
+            return Err((format!("dns_record_type must be one of: {}", VALID_DNS_RECORD_TYPES.join(", ")), "VALIDATION_ERROR"));
         }
-
-        let interval = monitor_data.interval_seconds.unwrap_or(600).max(600);
-        let timeout = monitor_data.timeout_ms.unwrap_or(10000).clamp(1000, 60000);
-        let expected_status = monitor_data.expected_status.unwrap_or(200);
-        let confirmation = monitor_data.confirmation_threshold.unwrap_or(2).clamp(1, 10);
-        let rt_threshold = monitor_data.response_time_threshold_ms.map(|v| v.max(100));
-
-        let id = uuid::Uuid::new_v4().to_string();
-        let manage_key = generate_key();
-        let key_hash = hash_key(&manage_key);
-        let tags_str = tags_to_string(&monitor_data.tags);
-        let follow_redirects = monitor_data.follow_redirects.unwrap_or(true);
-        let group_name = monitor_data.group_name.as_deref().map(|g| g.trim()).filter(|g| !g.is_empty()).map(|g| g.to_string());
-        let bulk_dns_record_type = monitor_data.dns_record_type.as_deref().unwrap_or("A").to_uppercase();
-        let bulk_dns_expected = monitor_data.dns_expected.as_deref().map(|s| s.trim()).filter(|s| !s.is_empty()).map(|s| s.to_string());
-        let bulk_sla_target = monitor_data.sla_target;
-        if let Some(target) = bulk_sla_target {
-            if !(0.0..=100.0).contains(&target) {
-                errors.push(BulkError { index: idx, error: "sla_target must be between 0 and 100".into(), code: "VALIDATION_ERROR".into() });
-                continue;
-            }
+    } else {
+        let url_trimmed = monitor_data.url.trim().to_lowercase();
+        if !url_trimmed.starts_with("http://") && !url_trimmed.starts_with("https://") {
+            return Err(("URL must start with http:// or https://".into(), "VALIDATION_ERROR"));
         }
-        let bulk_sla_period = monitor_data.sla_period_days.map(|d| d.clamp(1, 365));
-        let bulk_consensus = monitor_data.consensus_threshold;
-        if let Some(ct) = bulk_consensus {
-            if ct < 1 {
-                errors.push(BulkError { index: idx, error: "consensus_threshold must be at least 1".into(), code: "VALIDATION_ERROR".into() });
-                continue;
-            }
+    }
+    if let Some(ref headers) = monitor_data.headers {
+        if !headers.is_object() {
+            return Err(("Headers must be a JSON object".into(), "VALIDATION_ERROR"));
         }
+    }
+    let method = monitor_data.method.to_uppercase();
+    if bulk_monitor_type == "http" && !["GET", "HEAD", "POST"].contains(&method.as_str()) {
+        return Err(("Method must be GET, HEAD, or POST".into(), "VALIDATION_ERROR"));
+    }
 
-        match conn.execute(
-            "INSERT INTO monitors (id, name, url, monitor_type, method, interval_seconds, timeout_ms, expected_status, body_contains, headers, manage_key_hash, is_public, confirmation_threshold, tags, response_time_threshold_ms, follow_redirects, group_name, dns_record_type, dns_expected, sla_target, sla_period_days, consensus_threshold)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
-            params![
-                id,
-                monitor_data.name.trim(),
-                monitor_data.url.trim(),
-                bulk_monitor_type,
-                method,
-                interval,
-                timeout,
-                expected_status,
-                monitor_data.body_contains,
-                monitor_data.headers.map(|h| h.to_string()),
-                key_hash,
-                monitor_data.is_public as i32,
-                confirmation,
-                tags_str,
-                rt_threshold,
-                follow_redirects as i32,
-                group_name,
-                bulk_dns_record_type,
-                bulk_dns_expected,
-                bulk_sla_target,
-                bulk_sla_period,
-                bulk_consensus,
-            ],
-        ) {
-            Ok(_) => {
-                match get_monitor_from_db(&conn, &id) {
-                    Ok(monitor) => {
-                        created.push(CreateMonitorResponse {
-                            monitor,
-                            manage_key: manage_key.clone(),
-                            manage_url: format!("/monitor/{}?key={}", id, manage_key),
-                            view_url: format!("/monitor/{}", id),
-                            api_base: format!("/api/v1/monitors/{}", id),
-                        });
-                    }
-                    Err(_) => {
-                        errors.push(BulkError { index: idx, error: "Internal server error".into(), code: "INTERNAL_ERROR".into() });
-                    }
-                }
-            }
-            Err(_) => {
-                errors.push(BulkError { index: idx, error: "Internal server error".into(), code: "INTERNAL_ERROR".into() });
-            }
+    let interval = monitor_data.interval_seconds.unwrap_or(defaults.interval_seconds).max(600);
+    let timeout = monitor_data.timeout_ms.unwrap_or(defaults.timeout_ms).clamp(1000, 60000);
+    let expected_status = monitor_data.expected_status.unwrap_or(200);
+    let confirmation = monitor_data.confirmation_threshold.unwrap_or(defaults.confirmation_threshold).clamp(1, 10);
+    let rt_threshold = monitor_data.response_time_threshold_ms.map(|v| v.max(100));
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let manage_key = generate_key();
+    let key_hash = hash_key(&manage_key);
+    let tags_str = tags_to_string(&monitor_data.tags);
+    let follow_redirects = monitor_data.follow_redirects.unwrap_or(true);
+    let max_redirects = monitor_data.max_redirects.unwrap_or(10).min(20);
+    let final_url_prefix = monitor_data.expected_final_url_prefix.as_deref().map(|s| s.trim()).filter(|s| !s.is_empty());
+    let group_name = monitor_data.group_name.as_deref().map(|g| g.trim()).filter(|g| !g.is_empty()).map(|g| g.to_string());
+    let bulk_dns_record_type = monitor_data.dns_record_type.as_deref().unwrap_or("A").to_uppercase();
+    let bulk_dns_expected = monitor_data.dns_expected.as_deref().map(|s| s.trim()).filter(|s| !s.is_empty()).map(|s| s.to_string());
+    let bulk_sla_target = monitor_data.sla_target;
+    if let Some(target) = bulk_sla_target {
+        if !(0.0..=100.0).contains(&target) {
+            return Err(("sla_target must be between 0 and 100".into(), "VALIDATION_ERROR"));
         }
     }
+    let bulk_sla_period = monitor_data.sla_period_days.map(|d| d.clamp(1, 365));
+    let bulk_consensus = monitor_data.consensus_threshold;
+    let bulk_consensus_mode = monitor_data.consensus_mode.as_deref().map(|m| m.trim().to_lowercase());
+    ConsensusRule::validate(bulk_consensus_mode.as_deref(), bulk_consensus).map_err(|e| (e, "VALIDATION_ERROR"))?;
 
-    let succeeded = created.len();
-    let failed = errors.len();
+    validate_body_size(monitor_data.min_body_bytes, monitor_data.max_body_bytes).map_err(|e| (e, "VALIDATION_ERROR"))?;
 
-    Ok(Json(BulkCreateResponse { created, errors, total, succeeded, failed }))
+    let bulk_auth = resolve_auth(
+        monitor_data.auth_type.as_deref(),
+        monitor_data.auth_username.clone(),
+        monitor_data.auth_password.clone(),
+        monitor_data.auth_token.clone(),
+    ).map_err(|e| (e, "VALIDATION_ERROR"))?;
+
+    validate_dns_assertions(monitor_data.dns_min_records, monitor_data.dns_min_ttl).map_err(|e| (e, "VALIDATION_ERROR"))?;
+
+    let bulk_dns_resolver = normalize_dns_resolver(monitor_data.dns_resolver.as_deref()).map_err(|e| (e, "VALIDATION_ERROR"))?;
+
+    let (bulk_tcp_send, bulk_tcp_expect) = match (
+        normalize_tcp_probe("tcp_send", monitor_data.tcp_send.as_deref()),
+        normalize_tcp_probe("tcp_expect", monitor_data.tcp_expect.as_deref()),
+    ) {
+        (Ok(send), Ok(expect)) => (send, expect),
+        (Err(e), _) | (_, Err(e)) => return Err((e, "VALIDATION_ERROR")),
+    };
+
+    let bulk_proxy = normalize_proxy_url(monitor_data.proxy_url.as_deref()).map_err(|e| (e, "VALIDATION_ERROR"))?;
+
+    let bulk_steps = if bulk_monitor_type == "multistep" {
+        monitor_data.steps.as_ref().and_then(|st| serde_json::to_string(st).ok())
+    } else {
+        None
+    };
+
+    let bulk_external_id = normalize_external_id(monitor_data.external_id.as_deref()).map_err(|e| (e, "VALIDATION_ERROR"))?;
+    let bulk_description = normalize_description(monitor_data.description.as_deref()).map_err(|e| (e, "VALIDATION_ERROR"))?;
+    let bulk_runbook_url = normalize_runbook_url(monitor_data.runbook_url.as_deref()).map_err(|e| (e, "VALIDATION_ERROR"))?;
+    let bulk_first_status = validate_first_status(monitor_data.expected_first_status).map_err(|e| (e, "VALIDATION_ERROR"))?;
+    let (bulk_client_cert, bulk_client_key) = resolve_client_cert(monitor_data.client_cert_pem.as_deref(), monitor_data.client_key_pem.as_deref())
+        .map_err(|(_, e)| (e, "VALIDATION_ERROR"))?
+        .unzip();
+    let bulk_ca_bundle = normalize_ca_bundle(monitor_data.ca_bundle_pem.as_deref()).map_err(|e| (e, "VALIDATION_ERROR"))?;
+    if bulk_external_id.as_deref().is_some_and(|e| monitor_id_by_external_id(conn, e).is_some()) {
+        return Err(("A monitor with this external_id already exists".into(), "EXTERNAL_ID_CONFLICT"));
+    }
+
+    conn.execute(
+        "INSERT INTO monitors (id, name, url, monitor_type, method, interval_seconds, timeout_ms, expected_status, body_contains, headers, manage_key_hash, is_public, confirmation_threshold, tags, response_time_threshold_ms, follow_redirects, group_name, dns_record_type, dns_expected, sla_target, sla_period_days, consensus_threshold, consensus_mode, steps, body_not_contains, max_redirects, expected_final_url_prefix, min_body_bytes, max_body_bytes, auth_type, auth_username, auth_password, auth_token, proxy_url, dns_min_records, dns_min_ttl, dns_resolver, tcp_send, tcp_expect, recovery_cooldown_seconds, down_confirmation_threshold, up_confirmation_threshold, connect_timeout_ms, external_id, description, runbook_url, expected_first_status, client_cert_pem, client_key_pem, insecure_skip_tls_verify, ca_bundle_pem)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29, ?30, ?31, ?32, ?33, ?34, ?35, ?36, ?37, ?38, ?39, ?40, ?41, ?42, ?43, ?44, ?45, ?46, ?47, ?48, ?49, ?50, ?51)",
+        params![
+            id,
+            monitor_data.name.trim(),
+            monitor_data.url.trim(),
+            bulk_monitor_type,
+            method,
+            interval,
+            timeout,
+            expected_status,
+            monitor_data.body_contains,
+            monitor_data.headers.map(|h| h.to_string()),
+            key_hash,
+            monitor_data.is_public as i32,
+            confirmation,
+            tags_str,
+            rt_threshold,
+            follow_redirects as i32,
+            group_name,
+            bulk_dns_record_type,
+            bulk_dns_expected,
+            bulk_sla_target,
+            bulk_sla_period,
+            bulk_consensus,
+            bulk_consensus_mode,
+            bulk_steps,
+            monitor_data.body_not_contains.as_deref().filter(|s| !s.is_empty()),
+            max_redirects,
+            final_url_prefix,
+            monitor_data.min_body_bytes,
+            monitor_data.max_body_bytes,
+            bulk_auth.auth_type,
+            bulk_auth.username,
+            bulk_auth.password,
+            bulk_auth.token,
+            bulk_proxy,
+            monitor_data.dns_min_records,
+            monitor_data.dns_min_ttl,
+            bulk_dns_resolver,
+            bulk_tcp_send,
+            bulk_tcp_expect,
+            normalize_recovery_cooldown(monitor_data.recovery_cooldown_seconds),
+            monitor_data.down_confirmation_threshold.map(|v| v.clamp(1, 10)),
+            monitor_data.up_confirmation_threshold.map(|v| v.clamp(1, 10)),
+            normalize_connect_timeout(monitor_data.connect_timeout_ms),
+            bulk_external_id,
+            bulk_description,
+            bulk_runbook_url,
+            bulk_first_status,
+            bulk_client_cert,
+            bulk_client_key,
+            monitor_data.insecure_skip_tls_verify as i32,
+            bulk_ca_bundle,
+        ],
+    ).map_err(|_| ("Internal server error".to_string(), "INTERNAL_ERROR"))?;
+
+    let monitor = get_monitor_from_db(conn, &id).map_err(|_| ("Internal server error".to_string(), "INTERNAL_ERROR"))?;
+    record_audit(conn, "monitor.created", Some(&id), "anonymous", Some(serde_json::json!({"name": monitor.name, "url": monitor.url})));
+    Ok(CreateMonitorResponse {
+        monitor,
+        manage_key: manage_key.clone(),
+        manage_url: format!("/monitor/{}?key={}", id, manage_key),
+        view_url: format!("/monitor/{}", id),
+        api_base: format!("/api/v1/monitors/{}", id),
+    })
 }
 
 // ── Export Monitor Config ──
@@ -344,7 +616,7 @@ pub fn export_monitor(
     token: ManageToken,
 ) -> Result<Json<ExportedMonitor>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
-    verify_manage_key(&conn, id, &token.0)?;
+    verify_read_access(&conn, id, &token.0)?;
 
     let monitor = get_monitor_from_db(&conn, id).map_err(|_| {
         (Status::NotFound, Json(serde_json::json!({
@@ -354,38 +626,91 @@ pub fn export_monitor(
 
     Ok(Json(ExportedMonitor {
         name: monitor.name,
+        description: monitor.description,
+        runbook_url: monitor.runbook_url,
         url: monitor.url,
         monitor_type: monitor.monitor_type,
         method: monitor.method,
         interval_seconds: monitor.interval_seconds,
         timeout_ms: monitor.timeout_ms,
+        connect_timeout_ms: monitor.connect_timeout_ms,
         expected_status: monitor.expected_status,
         body_contains: monitor.body_contains,
+        body_not_contains: monitor.body_not_contains,
+        min_body_bytes: monitor.min_body_bytes,
+        max_body_bytes: monitor.max_body_bytes,
         headers: monitor.headers,
         is_public: monitor.is_public,
         confirmation_threshold: monitor.confirmation_threshold,
+        down_confirmation_threshold: monitor.down_confirmation_threshold,
+        up_confirmation_threshold: monitor.up_confirmation_threshold,
+        recovery_cooldown_seconds: monitor.recovery_cooldown_seconds,
         response_time_threshold_ms: monitor.response_time_threshold_ms,
         follow_redirects: monitor.follow_redirects,
+        max_redirects: monitor.max_redirects,
+        expected_final_url_prefix: monitor.expected_final_url_prefix,
+        expected_first_status: monitor.expected_first_status,
+        // Passwords and tokens stay server-side; re-supply them on import
+        auth_type: monitor.auth_type,
+        auth_username: monitor.auth_username,
+        // The client certificate is exported; its private key is not
+        client_cert_pem: monitor.client_cert_pem,
+        // CA certificates are public; exported as-is
+        ca_bundle_pem: monitor.ca_bundle_pem,
+        insecure_skip_tls_verify: monitor.insecure_skip_tls_verify,
+        // Likewise proxy credentials: a proxy_url with userinfo is left out
+        proxy_url: monitor.proxy_url.filter(|u| !u.contains('@')),
         dns_record_type: monitor.dns_record_type,
         dns_expected: monitor.dns_expected,
+        dns_min_records: monitor.dns_min_records,
+        dns_min_ttl: monitor.dns_min_ttl,
+        dns_resolver: monitor.dns_resolver,
+        tcp_send: monitor_tcp_send(&conn, id),
+        tcp_expect: monitor.tcp_expect,
         sla_target: monitor.sla_target,
         sla_period_days: monitor.sla_period_days,
         tags: monitor.tags,
         group_name: monitor.group_name,
         consensus_threshold: monitor.consensus_threshold,
+        consensus_mode: monitor.consensus_mode,
+        steps: monitor.steps,
     }))
 }
 
 // ── List Monitors (public only) ──
 
 #[get("/monitors?<search>&<status>&<tag>&<group>")]
-pub fn list_monitors(search: Option<&str>, status: Option<&str>, tag: Option<&str>, group: Option<&str>, db: &State<Arc<Db>>) -> Result<Json<Vec<Monitor>>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+pub fn list_monitors(
+    search: Option<&str>,
+    status: Option<&str>,
+    tag: Option<&str>,
+    group: Option<&str>,
+    if_modified_since: IfModifiedSince,
+    db: &State<Arc<Db>>,
+) -> Result<Conditional<Json<Vec<Monitor>>>, (Status, Json<serde_json::Value>)> {
+    let conn = db.read();
+    // Every edit, check, and (soft) delete bumps updated_at, so the newest one dates the whole list
+    let last_modified: Option<String> = conn.query_row("SELECT MAX(updated_at) FROM monitors", [], |r| r.get(0))
+        .unwrap_or(None);
+    let last_modified = last_modified.as_deref().and_then(parse_db_timestamp);
+    if let Some(not_modified) = if_modified_since.not_modified(last_modified) {
+        return Ok(not_modified);
+    }
+
+    let monitors = query_public_monitors(&conn, search, status, tag, group)
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    Ok(Conditional::modified(Json(monitors), last_modified))
+}
 
-    let mut sql = String::from(
-        "SELECT id, name, url, method, interval_seconds, timeout_ms, expected_status, body_contains, headers, is_public, is_paused, current_status, last_checked_at, confirmation_threshold, created_at, updated_at, tags, response_time_threshold_ms, follow_redirects, group_name, monitor_type, dns_record_type, dns_expected, sla_target, sla_period_days, consensus_threshold
-         FROM monitors WHERE is_public = 1"
-    );
+/// Public, non-deleted monitors matching the optional search/status/tag/group filters.
+pub(crate) fn query_public_monitors(
+    conn: &rusqlite::Connection,
+    search: Option<&str>,
+    status: Option<&str>,
+    tag: Option<&str>,
+    group: Option<&str>,
+) -> rusqlite::Result<Vec<Monitor>> {
+    let mut sql = format!("SELECT {MONITOR_COLUMNS} FROM monitors WHERE is_public = 1 AND deleted_at IS NULL");
     let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
 
     if let Some(q) = search {
@@ -425,29 +750,89 @@ pub fn list_monitors(search: Option<&str>, status: Option<&str>, tag: Option<&st
     }
     sql.push_str(" ORDER BY group_name NULLS LAST, name");
 
-    let mut stmt = conn.prepare(&sql)
-        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    let mut stmt = conn.prepare(&sql)?;
     let params_vec: Vec<&dyn rusqlite::types::ToSql> = param_values.iter().map(|v| v.as_ref()).collect();
 
     let monitors = stmt.query_map(params_vec.as_slice(), |row| {
         Ok(row_to_monitor(row))
-    }).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
+    })?
     .filter_map(|r| r.ok())
     .collect();
 
-    Ok(Json(monitors))
+    Ok(monitors)
 }
 
 // ── Get Monitor ──
 
 #[get("/monitors/<id>")]
-pub fn get_monitor(id: &str, db: &State<Arc<Db>>) -> Result<Json<Monitor>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
-    let monitor = get_monitor_from_db(&conn, id)
+pub fn get_monitor(id: &str, if_modified_since: IfModifiedSince, token: OptionalManageToken, db: &State<Arc<Db>>) -> Result<Conditional<Json<Monitor>>, (Status, Json<serde_json::Value>)> {
+    let conn = db.read();
+    let mut monitor = get_monitor_from_db(&conn, id)
         .map_err(|_| (Status::NotFound, Json(serde_json::json!({
             "error": "Monitor not found", "code": "NOT_FOUND"
         }))))?;
-    Ok(Json(monitor))
+    if monitor.tcp_send.is_some() && token.0.is_some_and(|key| verify_read_access(&conn, id, &key).is_ok()) {
+        monitor.tcp_send = monitor_tcp_send(&conn, id);
+    }
+    let last_modified = parse_db_timestamp(&monitor.updated_at);
+    if let Some(not_modified) = if_modified_since.not_modified(last_modified) {
+        return Ok(not_modified);
+    }
+    Ok(Conditional::modified(Json(monitor), last_modified))
+}
+
+// ── Conditional Reads ──
+
+/// The `If-Modified-Since` header, if present.
+pub struct IfModifiedSince(Option<String>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfModifiedSince {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        Outcome::Success(IfModifiedSince(request.headers().get_one("If-Modified-Since").map(str::to_string)))
+    }
+}
+
+impl IfModifiedSince {
+    /// A bare 304 when the client's copy is at least as new as `last_modified`.
+    /// Unparseable dates are ignored, as RFC 9110 requires.
+    fn not_modified<T>(&self, last_modified: Option<chrono::DateTime<chrono::Utc>>) -> Option<Conditional<T>> {
+        let since = self.0.as_deref().and_then(|h| chrono::DateTime::parse_from_rfc2822(h.trim()).ok())?
+            .with_timezone(&chrono::Utc);
+        let last_modified = last_modified?;
+        (last_modified <= since).then(|| Conditional::NotModified((), last_modified_header(last_modified)))
+    }
+}
+
+/// A monitor read with its `Last-Modified` date, or a bare 304.
+#[derive(rocket::Responder)]
+pub enum Conditional<T> {
+    Modified(T, Header<'static>),
+    Undated(T),
+    #[response(status = 304)]
+    NotModified((), Header<'static>),
+}
+
+impl<T> Conditional<T> {
+    fn modified(body: T, last_modified: Option<chrono::DateTime<chrono::Utc>>) -> Self {
+        match last_modified {
+            Some(at) => Conditional::Modified(body, last_modified_header(at)),
+            None => Conditional::Undated(body),
+        }
+    }
+}
+
+fn last_modified_header(at: chrono::DateTime<chrono::Utc>) -> Header<'static> {
+    Header::new("Last-Modified", at.format("%a, %d %b %Y %H:%M:%S GMT").to_string())
+}
+
+/// Parse a stored timestamp (`datetime('now')` or RFC 3339) as UTC.
+fn parse_db_timestamp(s: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").map(|t| t.and_utc())
+        .or_else(|_| chrono::DateTime::parse_from_rfc3339(s).map(|t| t.with_timezone(&chrono::Utc)))
+        .ok()
 }
 
 // ── Update Monitor ──
@@ -461,6 +846,13 @@ pub fn update_monitor(
 ) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     verify_manage_key(&conn, id, &token.0)?;
+    let archived: bool = conn.query_row("SELECT is_archived FROM monitors WHERE id = ?1", params![id], |r| r.get(0))
+        .unwrap_or(false);
+    if archived {
+        return Err((Status::Conflict, Json(serde_json::json!({
+            "error": "Monitor is archived; unarchive it before editing", "code": "MONITOR_ARCHIVED"
+        }))));
+    }
 
     let mut data = input.into_inner();
     let mut updates = Vec::new();
@@ -477,10 +869,8 @@ pub fn update_monitor(
 
     if let Some(ref mt) = data.monitor_type {
         let mt_lower = mt.trim().to_lowercase();
-        if !["http", "tcp", "dns"].contains(&mt_lower.as_str()) {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": "monitor_type must be 'http', 'tcp', or 'dns'", "code": "VALIDATION_ERROR"
-            }))));
+        if !VALID_MONITOR_TYPES.contains(&mt_lower.as_str()) {
+            return Err(field_error("monitor_type", "monitor_type must be 'http', 'tcp', 'dns', or 'multistep'"));
         }
     }
 
@@ -491,6 +881,27 @@ pub fn update_monitor(
     ).unwrap_or_else(|_| "http".to_string());
     let effective_type = data.monitor_type.as_deref().unwrap_or(&current_type).to_lowercase();
 
+    // Multistep monitors need steps, either in this patch or already stored
+    let steps_check = match data.steps {
+        Some(ref steps) => validate_steps(steps),
+        None if effective_type == "multistep" => {
+            let stored: Option<String> = conn.query_row(
+                "SELECT steps FROM monitors WHERE id = ?1",
+                params![id],
+                |row| row.get(0),
+            ).unwrap_or(None);
+            if stored.is_some() { Ok(()) } else { validate_steps(&[]) }
+        }
+        None => Ok(()),
+    };
+    if let Err(e) = steps_check {
+        return Err(field_error("steps", e));
+    }
+    if let Some(ref steps) = data.steps {
+        updates.push(format!("steps = ?{}", values.len() + 1));
+        values.push(Box::new(serde_json::to_string(steps).unwrap_or_default()));
+    }
+
     if let Some(ref url) = data.url {
         if effective_type == "tcp" {
             validate_tcp_address(url.trim())?;
@@ -499,26 +910,19 @@ pub fn update_monitor(
         } else {
             let url_lower = url.trim().to_lowercase();
             if !url_lower.starts_with("http://") && !url_lower.starts_with("https://") {
-                return Err((Status::BadRequest, Json(serde_json::json!({
-                    "error": "URL must start with http:// or https://", "code": "VALIDATION_ERROR"
-                }))));
+                return Err(field_error("url", "URL must start with http:// or https://"));
             }
         }
     }
     if let Some(ref rt) = data.dns_record_type {
         let rt_upper = rt.trim().to_uppercase();
         if !VALID_DNS_RECORD_TYPES.contains(&rt_upper.as_str()) {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": format!("dns_record_type must be one of: {}", VALID_DNS_RECORD_TYPES.join(", ")),
-                "code": "VALIDATION_ERROR"
-            }))));
+            return Err(field_error("dns_record_type", format!("dns_record_type must be one of: {}", VALID_DNS_RECORD_TYPES.join(", "))));
         }
     }
     if let Some(ref headers) = data.headers {
         if !headers.is_object() {
-            return Err((Status::BadRequest, Json(serde_json::json!({
-                "error": "Headers must be a JSON object", "code": "VALIDATION_ERROR"
-            }))));
+            return Err(field_error("headers", "Headers must be a JSON object"));
         }
     }
 
@@ -527,6 +931,18 @@ pub fn update_monitor(
     }
 
     add_update!(name, "name");
+    if let Some(ref description) = data.description {
+        let description = normalize_description(description.as_deref())
+            .map_err(|e| field_error("description", e))?;
+        updates.push(format!("description = ?{}", values.len() + 1));
+        values.push(Box::new(description));
+    }
+    if let Some(ref runbook_url) = data.runbook_url {
+        let runbook_url = normalize_runbook_url(runbook_url.as_deref())
+            .map_err(|e| field_error("runbook_url", e))?;
+        updates.push(format!("runbook_url = ?{}", values.len() + 1));
+        values.push(Box::new(runbook_url));
+    }
     add_update!(url, "url");
     if let Some(ref mt) = data.monitor_type {
         updates.push(format!("monitor_type = ?{}", values.len() + 1));
@@ -536,10 +952,31 @@ pub fn update_monitor(
     add_update!(method, "method");
     add_update!(interval_seconds, "interval_seconds");
     add_update!(timeout_ms, "timeout_ms");
+    if let Some(connect_timeout) = data.connect_timeout_ms {
+        updates.push(format!("connect_timeout_ms = ?{}", values.len() + 1));
+        values.push(Box::new(normalize_connect_timeout(connect_timeout)));
+    }
     add_update!(expected_status, "expected_status");
     add_update!(body_contains, "body_contains");
+    if let Some(ref bnc) = data.body_not_contains {
+        updates.push(format!("body_not_contains = ?{}", values.len() + 1));
+        values.push(Box::new(bnc.clone().filter(|s| !s.is_empty())));
+    }
     add_update!(is_public, "is_public");
     add_update!(confirmation_threshold, "confirmation_threshold");
+    for (field, value) in [
+        ("down_confirmation_threshold", data.down_confirmation_threshold),
+        ("up_confirmation_threshold", data.up_confirmation_threshold),
+    ] {
+        if let Some(threshold) = value {
+            updates.push(format!("{} = ?{}", field, values.len() + 1));
+            values.push(Box::new(threshold.map(|v| v.clamp(1, 10))));
+        }
+    }
+    if let Some(cooldown) = data.recovery_cooldown_seconds {
+        updates.push(format!("recovery_cooldown_seconds = ?{}", values.len() + 1));
+        values.push(Box::new(normalize_recovery_cooldown(cooldown)));
+    }
 
     if let Some(ref headers) = data.headers {
         updates.push(format!("headers = ?{}", values.len() + 1));
@@ -563,6 +1000,24 @@ pub fn update_monitor(
         updates.push(format!("follow_redirects = ?{}", values.len() + 1));
         values.push(Box::new(follow as i32));
     }
+    if let Some(insecure) = data.insecure_skip_tls_verify {
+        updates.push(format!("insecure_skip_tls_verify = ?{}", values.len() + 1));
+        values.push(Box::new(insecure as i32));
+    }
+    if let Some(max) = data.max_redirects {
+        updates.push(format!("max_redirects = ?{}", values.len() + 1));
+        values.push(Box::new(max.min(20)));
+    }
+    if let Some(ref prefix) = data.expected_final_url_prefix {
+        updates.push(format!("expected_final_url_prefix = ?{}", values.len() + 1));
+        values.push(Box::new(prefix.as_deref().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())));
+    }
+    if let Some(first_status) = data.expected_first_status {
+        let first_status = validate_first_status(first_status)
+            .map_err(|e| field_error("expected_first_status", e))?;
+        updates.push(format!("expected_first_status = ?{}", values.len() + 1));
+        values.push(Box::new(first_status));
+    }
 
     if let Some(ref gn) = data.group_name {
         updates.push(format!("group_name = ?{}", values.len() + 1));
@@ -593,9 +1048,7 @@ pub fn update_monitor(
         match sla_opt {
             Some(val) => {
                 if !(0.0..=100.0).contains(val) {
-                    return Err((Status::BadRequest, Json(serde_json::json!({
-                        "error": "sla_target must be between 0 and 100", "code": "VALIDATION_ERROR"
-                    }))));
+                    return Err(field_error("sla_target", "sla_target must be between 0 and 100"));
                 }
                 values.push(Box::new(Some(*val)));
             }
@@ -610,21 +1063,146 @@ pub fn update_monitor(
         }
     }
 
-    if let Some(ref ct_opt) = data.consensus_threshold {
-        updates.push(format!("consensus_threshold = ?{}", values.len() + 1));
-        match ct_opt {
-            Some(val) => {
-                if *val < 1 {
-                    return Err((Status::BadRequest, Json(serde_json::json!({
-                        "error": "consensus_threshold must be at least 1", "code": "VALIDATION_ERROR"
-                    }))));
-                }
-                values.push(Box::new(Some(*val)));
+    if data.consensus_threshold.is_some() || data.consensus_mode.is_some() {
+        // Validate the combination that will be stored, not just the patched fields
+        let (current_threshold, current_mode): (Option<u32>, Option<String>) = conn.query_row(
+            "SELECT consensus_threshold, consensus_mode FROM monitors WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap_or((None, None));
+        let new_mode = match data.consensus_mode {
+            Some(ref mode_opt) => mode_opt.as_deref().map(|m| m.trim().to_lowercase()),
+            None => current_mode,
+        };
+        let new_threshold = data.consensus_threshold.unwrap_or(current_threshold);
+        if let Err(e) = ConsensusRule::validate(new_mode.as_deref(), new_threshold) {
+            return Err(fields_error(&["consensus_mode", "consensus_threshold"], e));
+        }
+        if data.consensus_threshold.is_some() {
+            updates.push(format!("consensus_threshold = ?{}", values.len() + 1));
+            values.push(Box::new(new_threshold));
+        }
+        if data.consensus_mode.is_some() {
+            updates.push(format!("consensus_mode = ?{}", values.len() + 1));
+            values.push(Box::new(new_mode));
+        }
+    }
+
+    if data.min_body_bytes.is_some() || data.max_body_bytes.is_some() {
+        let (current_min, current_max): (Option<u32>, Option<u32>) = conn.query_row(
+            "SELECT min_body_bytes, max_body_bytes FROM monitors WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap_or((None, None));
+        let new_min = data.min_body_bytes.unwrap_or(current_min);
+        let new_max = data.max_body_bytes.unwrap_or(current_max);
+        if let Err(e) = validate_body_size(new_min, new_max) {
+            return Err(fields_error(&["min_body_bytes", "max_body_bytes"], e));
+        }
+        if data.min_body_bytes.is_some() {
+            updates.push(format!("min_body_bytes = ?{}", values.len() + 1));
+            values.push(Box::new(new_min));
+        }
+        if data.max_body_bytes.is_some() {
+            updates.push(format!("max_body_bytes = ?{}", values.len() + 1));
+            values.push(Box::new(new_max));
+        }
+    }
+
+    if data.auth_type.is_some() || data.auth_username.is_some() || data.auth_password.is_some() || data.auth_token.is_some() {
+        // Merge with stored credentials so e.g. rotating a password keeps the username
+        type StoredAuth = (Option<String>, Option<String>, Option<String>, Option<String>);
+        let (cur_type, cur_username, cur_password, cur_token): StoredAuth = conn.query_row(
+            "SELECT auth_type, auth_username, auth_password, auth_token FROM monitors WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        ).unwrap_or((None, None, None, None));
+        let auth = match resolve_auth(
+            data.auth_type.as_deref().or(cur_type.as_deref()),
+            data.auth_username.take().or(cur_username),
+            data.auth_password.take().or(cur_password),
+            data.auth_token.take().or(cur_token),
+        ) {
+            Ok(auth) => auth,
+            Err(e) => return Err(fields_error(&["auth_type", "auth_username", "auth_token"], e)),
+        };
+        updates.push(format!("auth_type = ?{}", values.len() + 1));
+        values.push(Box::new(auth.auth_type));
+        updates.push(format!("auth_username = ?{}", values.len() + 1));
+        values.push(Box::new(auth.username));
+        updates.push(format!("auth_password = ?{}", values.len() + 1));
+        values.push(Box::new(auth.password));
+        updates.push(format!("auth_token = ?{}", values.len() + 1));
+        values.push(Box::new(auth.token));
+    }
+
+    if data.client_cert_pem.is_some() || data.client_key_pem.is_some() {
+        // Merge with the stored pair so either half can be rotated on its own
+        let (cur_cert, cur_key): (Option<String>, Option<String>) = conn.query_row(
+            "SELECT client_cert_pem, client_key_pem FROM monitors WHERE id = ?1",
+            params![id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).unwrap_or((None, None));
+        let cert = data.client_cert_pem.take().or(cur_cert);
+        let key = data.client_key_pem.take().or(cur_key);
+        let (cert, key) = resolve_client_cert(cert.as_deref(), key.as_deref())
+            .map_err(|(field, e)| field_error(field, e))?
+            .unzip();
+        updates.push(format!("client_cert_pem = ?{}", values.len() + 1));
+        values.push(Box::new(cert));
+        updates.push(format!("client_key_pem = ?{}", values.len() + 1));
+        values.push(Box::new(key));
+    }
+    if let Some(ref ca_bundle) = data.ca_bundle_pem {
+        let ca_bundle = normalize_ca_bundle(ca_bundle.as_deref())
+            .map_err(|e| field_error("ca_bundle_pem", e))?;
+        updates.push(format!("ca_bundle_pem = ?{}", values.len() + 1));
+        values.push(Box::new(ca_bundle));
+    }
+
+    if data.dns_min_records.is_some() || data.dns_min_ttl.is_some() {
+        let new_min_records = data.dns_min_records.flatten();
+        let new_min_ttl = data.dns_min_ttl.flatten();
+        if let Err(e) = validate_dns_assertions(new_min_records, new_min_ttl) {
+            return Err(fields_error(&["dns_min_records", "dns_min_ttl"], e));
+        }
+        if data.dns_min_records.is_some() {
+            updates.push(format!("dns_min_records = ?{}", values.len() + 1));
+            values.push(Box::new(new_min_records));
+        }
+        if data.dns_min_ttl.is_some() {
+            updates.push(format!("dns_min_ttl = ?{}", values.len() + 1));
+            values.push(Box::new(new_min_ttl));
+        }
+    }
+
+    if let Some(ref resolver) = data.dns_resolver {
+        let dns_resolver = normalize_dns_resolver(resolver.as_deref())
+            .map_err(|e| field_error("dns_resolver", e))?;
+        updates.push(format!("dns_resolver = ?{}", values.len() + 1));
+        values.push(Box::new(dns_resolver));
+    }
+
+    for (field, value) in [("tcp_send", &data.tcp_send), ("tcp_expect", &data.tcp_expect)] {
+        if let Some(ref v) = value {
+            // A masked tcp_send sent back unchanged keeps the stored probe
+            if field == "tcp_send" && v.as_deref() == Some(REDACTED_TCP_SEND) {
+                continue;
             }
-            None => values.push(Box::new(None::<u32>)),
+            let probe = normalize_tcp_probe(field, v.as_deref())
+                .map_err(|e| field_error(field, e))?;
+            updates.push(format!("{} = ?{}", field, values.len() + 1));
+            values.push(Box::new(probe));
         }
     }
 
+    if let Some(ref proxy) = data.proxy_url {
+        let proxy_url = normalize_proxy_url(proxy.as_deref())
+            .map_err(|e| field_error("proxy_url", e))?;
+        updates.push(format!("proxy_url = ?{}", values.len() + 1));
+        values.push(Box::new(proxy_url));
+    }
+
     if updates.is_empty() {
         return Ok(Json(serde_json::json!({"message": "No changes"})));
     }
@@ -633,10 +1211,15 @@ pub fn update_monitor(
     let sql = format!("UPDATE monitors SET {} WHERE id = ?{}", updates.join(", "), values.len() + 1);
     values.push(Box::new(id.to_string()));
 
+    let before = get_monitor_from_db(&conn, id).ok();
     let params_vec: Vec<&dyn rusqlite::types::ToSql> = values.iter().map(|v| v.as_ref()).collect();
     conn.execute(&sql, params_vec.as_slice())
         .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
+    if let (Some(before), Ok(after)) = (before, get_monitor_from_db(&conn, id)) {
+        record_audit(&conn, "monitor.updated", Some(id), &audit_actor(&conn, &token.0), Some(monitor_diff(&before, &after)));
+    }
+
     Ok(Json(serde_json::json!({"message": "Monitor updated"})))
 }
 
@@ -650,11 +1233,78 @@ pub fn delete_monitor(
 ) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     verify_manage_key(&conn, id, &token.0)?;
+    let actor = audit_actor(&conn, &token.0);
+    match remove_monitor(&conn, id, &actor)
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
+    {
+        Some(restore_until) => Ok(Json(serde_json::json!({"message": "Monitor deleted", "restore_until": restore_until}))),
+        None => Ok(Json(serde_json::json!({"message": "Monitor deleted"}))),
+    }
+}
 
-    conn.execute("DELETE FROM monitors WHERE id = ?1", params![id])
-        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+/// Delete a monitor and audit it. Returns the restore deadline for a soft
+/// delete, or None when MONITOR_RESTORE_DAYS=0 removed it outright.
+fn remove_monitor(conn: &rusqlite::Connection, id: &str, actor: &str) -> rusqlite::Result<Option<String>> {
+    let summary = get_monitor_from_db(conn, id).ok().map(|m| serde_json::json!({"name": m.name, "url": m.url}));
+
+    let restore_days = monitor_restore_days();
+    if restore_days == 0 {
+        conn.execute("DELETE FROM monitors WHERE id = ?1", params![id])?;
+        record_audit(conn, "monitor.deleted", Some(id), actor, summary);
+        return Ok(None);
+    }
+
+    // Soft delete: hidden everywhere and no longer checked, but restorable until purged
+    let restore_until: String = conn.query_row(
+        "UPDATE monitors SET deleted_at = datetime('now'), updated_at = datetime('now') WHERE id = ?1
+         RETURNING datetime(deleted_at, ?2)",
+        params![id, format!("+{} days", restore_days)],
+        |row| row.get(0),
+    )?;
+    record_audit(conn, "monitor.deleted", Some(id), actor, summary);
+    Ok(Some(restore_until))
+}
+
+/// Bring back a soft-deleted monitor. Only possible within the restore grace period.
+#[post("/monitors/<id>/restore")]
+pub fn restore_monitor(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<Monitor>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    let not_found = || (Status::NotFound, Json(serde_json::json!({
+        "error": "Deleted monitor not found", "code": "NOT_FOUND"
+    })));
+
+    // verify_manage_key only sees live monitors, so check the key against the deleted row here
+    let (stored_hash, expired): (String, bool) = conn.query_row(
+        "SELECT manage_key_hash, deleted_at < datetime('now', ?2) FROM monitors
+         WHERE id = ?1 AND deleted_at IS NOT NULL",
+        params![id, format!("-{} days", monitor_restore_days())],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|_| not_found())?;
+
+    if hash_key(&token.0) != stored_hash && !api_key_allows(&conn, &token.0, Scope::Write) {
+        return Err((Status::Forbidden, Json(serde_json::json!({
+            "error": "Invalid manage key", "code": "FORBIDDEN"
+        }))));
+    }
+    if expired {
+        return Err((Status::Gone, Json(serde_json::json!({
+            "error": "Restore grace period has expired", "code": "GRACE_PERIOD_EXPIRED"
+        }))));
+    }
 
-    Ok(Json(serde_json::json!({"message": "Monitor deleted"})))
+    conn.execute(
+        "UPDATE monitors SET deleted_at = NULL, updated_at = datetime('now') WHERE id = ?1",
+        params![id],
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    record_audit(&conn, "monitor.restored", Some(id), &audit_actor(&conn, &token.0), None);
+
+    get_monitor_from_db(&conn, id)
+        .map(Json)
+        .map_err(|_| not_found())
 }
 
 // ── Pause / Resume ──
@@ -667,7 +1317,7 @@ pub fn pause_monitor(
 ) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     verify_manage_key(&conn, id, &token.0)?;
-    conn.execute("UPDATE monitors SET is_paused = 1, updated_at = datetime('now') WHERE id = ?1", params![id])
+    set_paused(&conn, id, true, &audit_actor(&conn, &token.0))
         .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
     Ok(Json(serde_json::json!({"message": "Monitor paused"})))
 }
@@ -680,7 +1330,283 @@ pub fn resume_monitor(
 ) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     verify_manage_key(&conn, id, &token.0)?;
-    conn.execute("UPDATE monitors SET is_paused = 0, updated_at = datetime('now') WHERE id = ?1", params![id])
+    set_paused(&conn, id, false, &audit_actor(&conn, &token.0))
         .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
     Ok(Json(serde_json::json!({"message": "Monitor resumed"})))
 }
+
+fn set_paused(conn: &rusqlite::Connection, id: &str, paused: bool, actor: &str) -> rusqlite::Result<()> {
+    conn.execute("UPDATE monitors SET is_paused = ?1, updated_at = datetime('now') WHERE id = ?2", params![paused, id])?;
+    record_audit(conn, if paused { "monitor.paused" } else { "monitor.resumed" }, Some(id), actor, None);
+    Ok(())
+}
+
+// ── Bulk Actions ──
+
+/// Largest explicit `ids` list accepted by a bulk action.
+const MAX_BULK_ACTION_IDS: usize = 500;
+
+/// Pause, resume, or delete a set of monitors picked by tag or id. An admin
+/// (or admin-scoped API key) bearer token covers every monitor; otherwise each
+/// monitor needs its manage key in `manage_keys`, or a write-scoped API key as
+/// the bearer token. Monitors matched by tag that the caller can't manage are
+/// left out of the results rather than reported.
+#[post("/monitors/bulk-action", format = "json", data = "<input>")]
+pub fn bulk_monitor_action(
+    input: Json<BulkAction>,
+    token: OptionalManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<BulkActionResponse>, (Status, Json<serde_json::Value>)> {
+    let data = input.into_inner();
+    let validation = |msg: &str| (Status::BadRequest, Json(serde_json::json!({"error": msg, "code": "VALIDATION_ERROR"})));
+    let action = data.action.trim().to_lowercase();
+    if !["pause", "resume", "delete"].contains(&action.as_str()) {
+        return Err(validation("action must be 'pause', 'resume', or 'delete'"));
+    }
+    let tag = data.tag.as_deref().map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty());
+    let by_tag = match (&tag, &data.ids) {
+        (Some(_), None) => true,
+        (None, Some(ids)) if !ids.is_empty() => false,
+        _ => return Err(validation("Provide either tag or a non-empty ids list")),
+    };
+    if data.ids.as_ref().is_some_and(|ids| ids.len() > MAX_BULK_ACTION_IDS) {
+        return Err(validation(&format!("Maximum {} ids per bulk action", MAX_BULK_ACTION_IDS)));
+    }
+
+    let conn = db.conn();
+    let admin = token.0.as_deref().is_some_and(|t| verify_admin_key(&conn, t).is_ok());
+
+    let ids: Vec<String> = match &tag {
+        Some(tag) => {
+            let mut stmt = conn.prepare("SELECT id, tags FROM monitors WHERE deleted_at IS NULL AND tags != '' ORDER BY created_at")
+                .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+            let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+                .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+            rows.filter_map(|r| r.ok())
+                .filter(|(_, tags)| parse_tags(tags).iter().any(|t| t == tag))
+                .map(|(id, _)| id)
+                .collect()
+        }
+        None => {
+            let mut seen = std::collections::HashSet::new();
+            data.ids.unwrap_or_default().into_iter().filter(|id| seen.insert(id.clone())).collect()
+        }
+    };
+
+    let mut results = Vec::new();
+    for id in ids {
+        let key = data.manage_keys.get(&id).map(String::as_str).or(token.0.as_deref());
+        let authorized = if admin {
+            let exists = conn.query_row(
+                "SELECT COUNT(*) FROM monitors WHERE id = ?1 AND deleted_at IS NULL", params![id], |r| r.get::<_, i64>(0),
+            ).unwrap_or(0) > 0;
+            if exists { Ok(()) } else {
+                Err((Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))
+            }
+        } else {
+            match key {
+                Some(key) => verify_manage_key(&conn, &id, key),
+                None => Err((Status::Unauthorized, Json(serde_json::json!({"error": "No manage key for this monitor", "code": "UNAUTHORIZED"})))),
+            }
+        };
+        if let Err((_, Json(err))) = authorized {
+            if !by_tag {
+                results.push(BulkActionResult {
+                    id,
+                    ok: false,
+                    error: err["error"].as_str().map(String::from),
+                    code: err["code"].as_str().map(String::from),
+                });
+            }
+            continue;
+        }
+
+        let actor = if admin { "admin".to_string() } else { audit_actor(&conn, key.unwrap_or_default()) };
+        let outcome = match action.as_str() {
+            "pause" => set_paused(&conn, &id, true, &actor),
+            "resume" => set_paused(&conn, &id, false, &actor),
+            _ => remove_monitor(&conn, &id, &actor).map(|_| ()),
+        };
+        results.push(match outcome {
+            Ok(()) => BulkActionResult { id, ok: true, error: None, code: None },
+            Err(_) => BulkActionResult { id, ok: false, error: Some("Internal server error".into()), code: Some("SERVER_ERROR".into()) },
+        });
+    }
+
+    let succeeded = results.iter().filter(|r| r.ok).count();
+    Ok(Json(BulkActionResponse {
+        action,
+        failed: results.len() - succeeded,
+        succeeded,
+        results,
+    }))
+}
+
+// ── Pause / Resume All ──
+
+/// Mute every monitor at once (admin only). Returns how many were running and are now paused.
+#[post("/admin/monitors/pause-all")]
+pub fn pause_all_monitors(token: ManageToken, db: &State<Arc<Db>>) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    set_all_paused(db, &token, true)
+}
+
+/// Resume every paused monitor (admin only), including ones paused individually.
+#[post("/admin/monitors/resume-all")]
+pub fn resume_all_monitors(token: ManageToken, db: &State<Arc<Db>>) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    set_all_paused(db, &token, false)
+}
+
+fn set_all_paused(db: &Db, token: &ManageToken, paused: bool) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+    let ids: Vec<String> = {
+        let mut stmt = conn.prepare("SELECT id FROM monitors WHERE deleted_at IS NULL AND is_paused != ?1")
+            .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+        let rows = stmt.query_map(params![paused], |r| r.get(0))
+            .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    // One audit entry per monitor, same as pausing them one by one
+    for id in &ids {
+        set_paused(&conn, id, paused, "admin")
+            .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    }
+    let key = if paused { "paused" } else { "resumed" };
+    Ok(Json(serde_json::json!({ key: ids.len() })))
+}
+
+// ── Rotate Manage Key ──
+
+// ── Check Now ──
+
+/// Run an immediate out-of-band check and return the heartbeat it recorded.
+/// The result goes through the normal pipeline, so it can open or resolve incidents.
+#[post("/monitors/<id>/check-now")]
+pub async fn check_monitor_now(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+    broadcaster: &State<Arc<EventBroadcaster>>,
+    clients: &State<Arc<CheckerClients>>,
+    states: &State<Arc<CheckStates>>,
+) -> Result<Json<Heartbeat>, (Status, Json<serde_json::Value>)> {
+    {
+        let conn = db.conn();
+        verify_manage_key(&conn, id, &token.0)?;
+        let archived: bool = conn.query_row("SELECT is_archived FROM monitors WHERE id = ?1", params![id], |r| r.get(0))
+            .unwrap_or(false);
+        if archived {
+            return Err((Status::Conflict, Json(serde_json::json!({
+                "error": "Monitor is archived; unarchive it before checking", "code": "MONITOR_ARCHIVED"
+            }))));
+        }
+    }
+
+    // Asked for explicitly, so never answered from another monitor's cached response
+    if !check_monitor(db, broadcaster, clients, states, id, true).await {
+        return Err((Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))));
+    }
+
+    let conn = db.read();
+    conn.query_row(
+        "SELECT id, monitor_id, status, response_time_ms, status_code, error_message, checked_at, seq, location_id, failure_kind
+         FROM heartbeats WHERE monitor_id = ?1 AND location_id IS NULL ORDER BY seq DESC LIMIT 1",
+        params![id],
+        |row| Ok(Heartbeat {
+            id: row.get(0)?,
+            monitor_id: row.get(1)?,
+            status: row.get(2)?,
+            response_time_ms: row.get(3)?,
+            status_code: row.get(4)?,
+            error_message: row.get(5)?,
+            checked_at: row.get(6)?,
+            seq: row.get(7)?,
+            location_id: row.get(8)?,
+            failure_kind: row.get(9)?,
+        }),
+    ).map(Json)
+    .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))
+}
+
+// ── Check State ──
+
+/// The checker's view of a monitor: confirmation counters, last check
+/// duration, and when it is next due. Useful for debugging flapping.
+#[get("/monitors/<id>/check-state")]
+pub fn get_check_state(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+    states: &State<Arc<CheckStates>>,
+) -> Result<Json<CheckStateResponse>, (Status, Json<serde_json::Value>)> {
+    let conn = db.read();
+    verify_manage_key(&conn, id, &token.0)?;
+    let (current_status, is_paused) = conn.query_row(
+        "SELECT current_status, is_paused FROM monitors WHERE id = ?1",
+        params![id],
+        |r| Ok((r.get::<_, String>(0)?, r.get::<_, bool>(1)?)),
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+
+    Ok(Json(CheckStateResponse {
+        monitor_id: id.to_string(),
+        current_status,
+        is_paused,
+        state: states.get(id),
+    }))
+}
+
+// ── Archive / Unarchive ──
+
+/// Archive a monitor: checks stop and the config becomes read-only, but its
+/// history, uptime and SLA stay queryable.
+#[post("/monitors/<id>/archive")]
+pub fn archive_monitor(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_manage_key(&conn, id, &token.0)?;
+    conn.execute("UPDATE monitors SET is_archived = 1, updated_at = datetime('now') WHERE id = ?1", params![id])
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    record_audit(&conn, "monitor.archived", Some(id), &audit_actor(&conn, &token.0), None);
+    Ok(Json(serde_json::json!({"message": "Monitor archived"})))
+}
+
+#[post("/monitors/<id>/unarchive")]
+pub fn unarchive_monitor(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_manage_key(&conn, id, &token.0)?;
+    conn.execute("UPDATE monitors SET is_archived = 0, updated_at = datetime('now') WHERE id = ?1", params![id])
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    record_audit(&conn, "monitor.unarchived", Some(id), &audit_actor(&conn, &token.0), None);
+    Ok(Json(serde_json::json!({"message": "Monitor unarchived"})))
+}
+
+/// Replace a monitor's manage key. The old key stops working as soon as this returns.
+#[post("/monitors/<id>/rotate-key")]
+pub fn rotate_monitor_key(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_manage_key(&conn, id, &token.0)?;
+
+    let manage_key = generate_key();
+    conn.execute(
+        "UPDATE monitors SET manage_key_hash = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![hash_key(&manage_key), id],
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    record_audit(&conn, "monitor.key_rotated", Some(id), &audit_actor(&conn, &token.0), None);
+
+    Ok(Json(serde_json::json!({
+        "message": "Manage key rotated",
+        "manage_key": manage_key,
+        "manage_url": format!("/monitor/{}?key={}", id, manage_key),
+    })))
+}
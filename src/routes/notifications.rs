@@ -1,45 +1,172 @@
 use rocket::{get, post, patch, delete, serde::json::Json, State, http::Status};
 use crate::db::Db;
-use crate::models::{NotificationChannel, CreateNotification};
+use crate::models::{NotificationChannel, CreateNotification, ChannelFilter};
 use crate::auth::ManageToken;
-use super::verify_manage_key;
+use super::{verify_manage_key, verify_read_access, verify_admin_key, tags_to_string, parse_tags};
 use rusqlite::params;
 use std::sync::Arc;
 
 // ── Notification Channels ──
 
 #[post("/monitors/<id>/notifications", format = "json", data = "<input>")]
-pub fn create_notification(
+pub async fn create_notification(
     id: &str,
     input: Json<CreateNotification>,
     token: ManageToken,
     db: &State<Arc<Db>>,
 ) -> Result<Json<NotificationChannel>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
-    verify_manage_key(&conn, id, &token.0)?;
-
+    verify_manage_key(&db.conn(), id, &token.0)?;
     let data = input.into_inner();
-    if !["webhook", "email"].contains(&data.channel_type.as_str()) {
+    check_webhook_url(&data).await?;
+
+    insert_channel(&db.conn(), Some(id), data).map(Json)
+}
+
+/// Webhook URLs and ntfy servers must not target internal addresses (SSRF). May resolve
+/// DNS, so callers must not hold the DB lock across it.
+async fn check_webhook_url(data: &CreateNotification) -> Result<(), (Status, Json<serde_json::Value>)> {
+    let (field, url) = match data.channel_type.as_str() {
+        "webhook" => ("url", data.config["url"].as_str()),
+        // Malformed servers are left to validate_ntfy_config
+        "ntfy" => ("server", data.config["server"].as_str().filter(|s| s.starts_with("http://") || s.starts_with("https://"))),
+        _ => return Ok(()),
+    };
+    let Some(url) = url else {
+        return Ok(());
+    };
+    crate::notifications::check_webhook_target(url).await.map_err(|e| (Status::BadRequest, Json(serde_json::json!({
+        "error": format!("config.{} {} (set ALLOW_PRIVATE_WEBHOOKS=1 to allow internal targets)", field, e),
+        "code": "WEBHOOK_URL_NOT_ALLOWED"
+    }))))
+}
+
+/// Validate and insert a channel; `monitor_id` None creates a global channel.
+fn insert_channel(
+    conn: &rusqlite::Connection,
+    monitor_id: Option<&str>,
+    data: CreateNotification,
+) -> Result<NotificationChannel, (Status, Json<serde_json::Value>)> {
+    if !["webhook", "email", "ntfy", "teams", "opsgenie"].contains(&data.channel_type.as_str()) {
         return Err((Status::BadRequest, Json(serde_json::json!({
-            "error": "channel_type must be 'webhook' or 'email'", "code": "VALIDATION_ERROR"
+            "error": "channel_type must be 'webhook', 'email', 'ntfy', 'teams', or 'opsgenie'", "code": "VALIDATION_ERROR"
         }))));
     }
 
+    if data.channel_type == "webhook" {
+        if let Some(version) = data.config.get("payload_version").filter(|v| !v.is_null()) {
+            if !matches!(version.as_u64(), Some(1) | Some(2)) {
+                return Err((Status::BadRequest, Json(serde_json::json!({
+                    "error": "config.payload_version must be 1 or 2", "code": "VALIDATION_ERROR"
+                }))));
+            }
+        }
+    }
+
+    if data.channel_type == "ntfy" {
+        validate_ntfy_config(&data.config)?;
+    }
+
+    if data.channel_type == "teams" {
+        let url = data.config["url"].as_str().unwrap_or("");
+        if !crate::notifications::is_teams_webhook_url(url) {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "config.url must be an https Teams incoming webhook URL (*.webhook.office.com, outlook.office.com, or a Power Automate workflow URL)",
+                "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+
+    if data.channel_type == "opsgenie" {
+        validate_opsgenie_config(&data.config)?;
+    }
+
+    let filter = data.filter.map(normalize_filter).transpose()?;
+    let filter_str = filter.as_ref().map(|f| serde_json::to_string(f).unwrap_or_default());
+
     let nid = uuid::Uuid::new_v4().to_string();
     conn.execute(
-        "INSERT INTO notification_channels (id, monitor_id, name, channel_type, config) VALUES (?1, ?2, ?3, ?4, ?5)",
-        params![nid, id, data.name, data.channel_type, data.config.to_string()],
+        "INSERT INTO notification_channels (id, monitor_id, name, channel_type, config, filter) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![nid, monitor_id, data.name, data.channel_type, data.config.to_string(), filter_str],
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
-    Ok(Json(NotificationChannel {
+    Ok(NotificationChannel {
         id: nid,
-        monitor_id: id.to_string(),
+        monitor_id: monitor_id.map(|s| s.to_string()),
         name: data.name,
         channel_type: data.channel_type,
         config: data.config,
+        filter,
         is_enabled: true,
         created_at: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string(),
-    }))
+    })
+}
+
+/// An ntfy channel needs a non-empty `topic`; `server` is optional but must be
+/// an http(s) URL when given.
+fn validate_ntfy_config(config: &serde_json::Value) -> Result<(), (Status, Json<serde_json::Value>)> {
+    if config["topic"].as_str().map(str::trim).unwrap_or("").is_empty() {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "config.topic is required for ntfy channels", "code": "VALIDATION_ERROR"
+        }))));
+    }
+    if let Some(server) = config.get("server").filter(|v| !v.is_null()) {
+        let valid = server.as_str().is_some_and(|s| s.starts_with("http://") || s.starts_with("https://"));
+        if !valid {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "config.server must be an http(s) URL", "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+    Ok(())
+}
+
+/// An Opsgenie channel needs a GenieKey-style `api_key` (a UUID); `region` is
+/// optional and must be `us` or `eu`.
+fn validate_opsgenie_config(config: &serde_json::Value) -> Result<(), (Status, Json<serde_json::Value>)> {
+    if !config["api_key"].as_str().is_some_and(crate::notifications::is_opsgenie_api_key) {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "config.api_key must be an Opsgenie API integration key (xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx)",
+            "code": "VALIDATION_ERROR"
+        }))));
+    }
+    if let Some(region) = config.get("region").filter(|v| !v.is_null()) {
+        if !matches!(region.as_str(), Some("us") | Some("eu")) {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "config.region must be 'us' or 'eu'", "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+    Ok(())
+}
+
+/// Validate a channel filter and normalize its tags the same way monitor tags are stored.
+fn normalize_filter(filter: ChannelFilter) -> Result<ChannelFilter, (Status, Json<serde_json::Value>)> {
+    if let Some(ref sev) = filter.min_severity {
+        if crate::notifications::Severity::parse(sev).is_none() {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "filter.min_severity must be 'info', 'warning', or 'critical'", "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+    Ok(ChannelFilter {
+        min_severity: filter.min_severity.map(|s| s.to_lowercase()),
+        tags: parse_tags(&tags_to_string(&filter.tags)),
+    })
+}
+
+fn row_to_channel(row: &rusqlite::Row) -> rusqlite::Result<NotificationChannel> {
+    let config_str: String = row.get(4)?;
+    let filter_str: Option<String> = row.get(7)?;
+    Ok(NotificationChannel {
+        id: row.get(0)?,
+        monitor_id: row.get(1)?,
+        name: row.get(2)?,
+        channel_type: row.get(3)?,
+        config: serde_json::from_str(&config_str).unwrap_or(serde_json::Value::Null),
+        filter: filter_str.and_then(|f| serde_json::from_str(&f).ok()),
+        is_enabled: row.get(5)?,
+        created_at: row.get(6)?,
+    })
 }
 
 #[get("/monitors/<id>/notifications")]
@@ -49,45 +176,82 @@ pub fn list_notifications(
     db: &State<Arc<Db>>,
 ) -> Result<Json<Vec<NotificationChannel>>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
-    verify_manage_key(&conn, id, &token.0)?;
+    verify_read_access(&conn, id, &token.0)?;
 
     let mut stmt = conn.prepare(
-        "SELECT id, monitor_id, name, channel_type, config, is_enabled, created_at FROM notification_channels WHERE monitor_id = ?1"
+        "SELECT id, monitor_id, name, channel_type, config, is_enabled, created_at, filter FROM notification_channels WHERE monitor_id = ?1"
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
-    let channels = stmt.query_map(params![id], |row| {
-        let config_str: String = row.get(4)?;
-        Ok(NotificationChannel {
-            id: row.get(0)?,
-            monitor_id: row.get(1)?,
-            name: row.get(2)?,
-            channel_type: row.get(3)?,
-            config: serde_json::from_str(&config_str).unwrap_or(serde_json::Value::Null),
-            is_enabled: row.get(5)?,
-            created_at: row.get(6)?,
-        })
-    }).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
-    .filter_map(|r| r.ok())
-    .collect();
+    let channels = stmt.query_map(params![id], row_to_channel)
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
+        .filter_map(|r| r.ok())
+        .collect();
 
     Ok(Json(channels))
 }
 
-#[delete("/notifications/<id>")]
-pub fn delete_notification(
-    id: &str,
+// ── Global Notification Channels (admin) ──
+
+#[post("/notifications", format = "json", data = "<input>")]
+pub async fn create_global_notification(
+    input: Json<CreateNotification>,
     token: ManageToken,
     db: &State<Arc<Db>>,
-) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+) -> Result<Json<NotificationChannel>, (Status, Json<serde_json::Value>)> {
+    verify_admin_key(&db.conn(), &token.0)?;
+    let data = input.into_inner();
+    check_webhook_url(&data).await?;
+    insert_channel(&db.conn(), None, data).map(Json)
+}
+
+#[get("/notifications")]
+pub fn list_global_notifications(
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<Vec<NotificationChannel>>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT id, monitor_id, name, channel_type, config, is_enabled, created_at, filter FROM notification_channels WHERE monitor_id IS NULL ORDER BY created_at ASC"
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+
+    let channels = stmt.query_map([], row_to_channel)
+        .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
+        .filter_map(|r| r.ok())
+        .collect();
 
-    let monitor_id: String = conn.query_row(
+    Ok(Json(channels))
+}
+
+/// Authorize a change to an existing channel: the monitor's manage key for
+/// per-monitor channels, the admin key for global ones.
+fn verify_channel_access(
+    conn: &rusqlite::Connection,
+    channel_id: &str,
+    token: &str,
+) -> Result<(), (Status, Json<serde_json::Value>)> {
+    let monitor_id: Option<String> = conn.query_row(
         "SELECT monitor_id FROM notification_channels WHERE id = ?1",
-        params![id],
+        params![channel_id],
         |row| row.get(0),
     ).map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Notification not found", "code": "NOT_FOUND"}))))?;
 
-    verify_manage_key(&conn, &monitor_id, &token.0)?;
+    match monitor_id {
+        Some(mid) => verify_manage_key(conn, &mid, token),
+        None => verify_admin_key(conn, token),
+    }
+}
+
+#[delete("/notifications/<id>")]
+pub fn delete_notification(
+    id: &str,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+
+    verify_channel_access(&conn, id, &token.0)?;
 
     conn.execute("DELETE FROM notification_channels WHERE id = ?1", params![id])
         .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
@@ -99,6 +263,8 @@ pub fn delete_notification(
 pub struct UpdateNotification {
     pub is_enabled: Option<bool>,
     pub name: Option<String>,
+    /// Replaces the routing filter; `{}` clears it
+    pub filter: Option<ChannelFilter>,
 }
 
 #[patch("/notifications/<id>", format = "json", data = "<input>")]
@@ -110,13 +276,7 @@ pub fn update_notification(
 ) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
 
-    let monitor_id: String = conn.query_row(
-        "SELECT monitor_id FROM notification_channels WHERE id = ?1",
-        params![id],
-        |row| row.get(0),
-    ).map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Notification not found", "code": "NOT_FOUND"}))))?;
-
-    verify_manage_key(&conn, &monitor_id, &token.0)?;
+    verify_channel_access(&conn, id, &token.0)?;
 
     let data = input.into_inner();
     if let Some(enabled) = data.is_enabled {
@@ -131,6 +291,18 @@ pub fn update_notification(
             params![name, id],
         ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
     }
+    if let Some(filter) = data.filter {
+        let filter = normalize_filter(filter)?;
+        let filter_str = if filter == ChannelFilter::default() {
+            None
+        } else {
+            Some(serde_json::to_string(&filter).unwrap_or_default())
+        };
+        conn.execute(
+            "UPDATE notification_channels SET filter = ?1 WHERE id = ?2",
+            params![filter_str, id],
+        ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    }
 
     Ok(Json(serde_json::json!({"message": "Notification channel updated"})))
 }
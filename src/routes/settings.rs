@@ -1,7 +1,9 @@
 use rocket::{get, put, serde::json::Json, State, http::Status};
 use crate::db::Db;
-use crate::models::{SettingsResponse, UpdateSettings, StatusPageBranding};
-use crate::auth::{ManageToken, hash_key};
+use crate::models::{SettingsResponse, UpdateSettings, UpdateMonitorDefaults, MonitorDefaults, StatusPageBranding};
+use crate::auth::ManageToken;
+use crate::causes;
+use super::{field_error, verify_admin_key};
 use rusqlite::params;
 use std::sync::Arc;
 
@@ -37,6 +39,102 @@ pub(crate) fn load_branding(conn: &rusqlite::Connection) -> StatusPageBranding {
     }
 }
 
+/// Global headers for HTTP checks, as (name, value) pairs. Per-monitor
+/// headers with the same name take precedence in the checker.
+pub fn load_default_headers(conn: &rusqlite::Connection) -> Vec<(String, String)> {
+    get_setting(conn, "checker_default_headers")
+        .and_then(|s| serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&s).ok())
+        .map(|obj| obj.into_iter()
+            .filter_map(|(k, v)| v.as_str().map(|v| (k, v.to_string())))
+            .collect())
+        .unwrap_or_default()
+}
+
+/// Ways a status page's overall status can count `unknown` monitors (first is the default):
+/// as unknown, as operational, or not at all.
+pub(crate) const UNKNOWN_STATUS_TREATMENTS: [&str; 3] = ["unknown", "operational", "excluded"];
+
+pub(crate) fn load_unknown_status_treatment(conn: &rusqlite::Connection) -> String {
+    get_setting(conn, "unknown_status_treatment")
+        .filter(|t| UNKNOWN_STATUS_TREATMENTS.contains(&t.as_str()))
+        .unwrap_or_else(|| UNKNOWN_STATUS_TREATMENTS[0].to_string())
+}
+
+/// A monitor status as it counts toward a page's overall; `None` leaves the monitor out.
+pub(crate) fn overall_status<'a>(status: &'a str, unknown_treatment: &str) -> Option<&'a str> {
+    match (status, unknown_treatment) {
+        ("unknown", "operational") => Some("up"),
+        ("unknown", "excluded") => None,
+        _ => Some(status),
+    }
+}
+
+/// Built-in values for fields a new monitor omits, before any admin overrides.
+const BUILTIN_MONITOR_DEFAULTS: MonitorDefaults = MonitorDefaults {
+    interval_seconds: 600,
+    timeout_ms: 10000,
+    confirmation_threshold: 2,
+};
+
+/// Defaults `create_monitor` applies to omitted fields.
+pub(crate) fn load_monitor_defaults(conn: &rusqlite::Connection) -> MonitorDefaults {
+    let get = |key: &str, builtin: u32| get_setting(conn, key).and_then(|v| v.parse().ok()).unwrap_or(builtin);
+    MonitorDefaults {
+        interval_seconds: get("default_interval_seconds", BUILTIN_MONITOR_DEFAULTS.interval_seconds),
+        timeout_ms: get("default_timeout_ms", BUILTIN_MONITOR_DEFAULTS.timeout_ms),
+        confirmation_threshold: get("default_confirmation_threshold", BUILTIN_MONITOR_DEFAULTS.confirmation_threshold),
+    }
+}
+
+/// Same bounds `create_monitor` enforces on the fields themselves.
+fn validate_monitor_defaults(d: &UpdateMonitorDefaults) -> Result<(), String> {
+    if let Some(Some(v)) = d.interval_seconds {
+        if v < 600 {
+            return Err("monitor_defaults.interval_seconds must be at least 600".into());
+        }
+    }
+    if let Some(Some(v)) = d.timeout_ms {
+        if !(1000..=60000).contains(&v) {
+            return Err("monitor_defaults.timeout_ms must be between 1000 and 60000".into());
+        }
+    }
+    if let Some(Some(v)) = d.confirmation_threshold {
+        if !(1..=10).contains(&v) {
+            return Err("monitor_defaults.confirmation_threshold must be between 1 and 10".into());
+        }
+    }
+    Ok(())
+}
+
+fn settings_response(conn: &rusqlite::Connection) -> SettingsResponse {
+    SettingsResponse {
+        title: get_setting(conn, "branding_title"),
+        description: get_setting(conn, "branding_description"),
+        logo_url: get_setting(conn, "branding_logo_url"),
+        default_headers: get_setting(conn, "checker_default_headers")
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        incident_cause_templates: get_setting(conn, causes::SETTING_KEY)
+            .and_then(|s| serde_json::from_str(&s).ok()),
+        monitor_defaults: load_monitor_defaults(conn),
+        unknown_status_treatment: load_unknown_status_treatment(conn),
+    }
+}
+
+/// Default headers must be an object of header-name → string value.
+fn validate_default_headers(headers: &serde_json::Value) -> Result<(), String> {
+    let obj = headers.as_object().ok_or("default_headers must be a JSON object")?;
+    for (k, v) in obj {
+        if reqwest::header::HeaderName::from_bytes(k.as_bytes()).is_err() {
+            return Err(format!("default_headers: invalid header name '{}'", k));
+        }
+        let valid_value = v.as_str().is_some_and(|s| reqwest::header::HeaderValue::from_str(s).is_ok());
+        if !valid_value {
+            return Err(format!("default_headers: value for '{}' must be a valid header string", k));
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn branding_is_empty(b: &StatusPageBranding) -> bool {
     b.title.is_none() && b.description.is_none() && b.logo_url.is_none()
 }
@@ -46,11 +144,7 @@ pub(crate) fn branding_is_empty(b: &StatusPageBranding) -> bool {
 #[get("/settings")]
 pub fn get_settings(db: &State<Arc<Db>>) -> Result<Json<SettingsResponse>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
-    Ok(Json(SettingsResponse {
-        title: get_setting(&conn, "branding_title"),
-        description: get_setting(&conn, "branding_description"),
-        logo_url: get_setting(&conn, "branding_logo_url"),
-    }))
+    Ok(Json(settings_response(&conn)))
 }
 
 #[put("/settings", data = "<body>")]
@@ -61,15 +155,25 @@ pub fn update_settings(
 ) -> Result<Json<SettingsResponse>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
 
-    let stored_hash: String = conn.query_row(
-        "SELECT value FROM settings WHERE key = 'admin_key_hash'",
-        [],
-        |row| row.get(0),
-    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Admin key not configured"}))))?;
+    verify_admin_key(&conn, &token.0)?;
 
-    let provided_hash = hash_key(&token.0);
-    if provided_hash != stored_hash {
-        return Err((Status::Forbidden, Json(serde_json::json!({"error": "Invalid admin key"}))));
+    if let Some(ref headers) = body.default_headers {
+        validate_default_headers(headers)
+            .map_err(|e| (Status::BadRequest, Json(serde_json::json!({"error": e, "code": "VALIDATION_ERROR"}))))?;
+    }
+    if let Some(ref templates) = body.incident_cause_templates {
+        causes::validate_templates(templates)
+            .map_err(|e| (Status::BadRequest, Json(serde_json::json!({"error": e, "code": "VALIDATION_ERROR"}))))?;
+    }
+    if let Some(ref defaults) = body.monitor_defaults {
+        validate_monitor_defaults(defaults)
+            .map_err(|e| (Status::BadRequest, Json(serde_json::json!({"error": e, "code": "VALIDATION_ERROR"}))))?;
+    }
+    if let Some(ref treatment) = body.unknown_status_treatment {
+        if !treatment.is_empty() && !UNKNOWN_STATUS_TREATMENTS.contains(&treatment.as_str()) {
+            return Err(field_error("unknown_status_treatment", format!(
+                "unknown_status_treatment must be one of: {}", UNKNOWN_STATUS_TREATMENTS.join(", "))));
+        }
     }
 
     if let Some(ref title) = body.title {
@@ -97,9 +201,47 @@ pub fn update_settings(
         }
     }
 
-    Ok(Json(SettingsResponse {
-        title: get_setting(&conn, "branding_title"),
-        description: get_setting(&conn, "branding_description"),
-        logo_url: get_setting(&conn, "branding_logo_url"),
-    }))
+    if let Some(ref headers) = body.default_headers {
+        if headers.as_object().is_some_and(|o| o.is_empty()) {
+            delete_setting(&conn, "checker_default_headers").ok();
+        } else {
+            set_setting(&conn, "checker_default_headers", &headers.to_string())
+                .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+        }
+    }
+
+    if let Some(ref templates) = body.incident_cause_templates {
+        if templates.as_object().is_some_and(|o| o.is_empty()) {
+            delete_setting(&conn, causes::SETTING_KEY).ok();
+        } else {
+            set_setting(&conn, causes::SETTING_KEY, &templates.to_string())
+                .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+        }
+    }
+
+    if let Some(ref treatment) = body.unknown_status_treatment {
+        if treatment.is_empty() || treatment == UNKNOWN_STATUS_TREATMENTS[0] {
+            delete_setting(&conn, "unknown_status_treatment").ok();
+        } else {
+            set_setting(&conn, "unknown_status_treatment", treatment)
+                .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+        }
+    }
+
+    if let Some(ref defaults) = body.monitor_defaults {
+        for (key, value) in [
+            ("default_interval_seconds", defaults.interval_seconds),
+            ("default_timeout_ms", defaults.timeout_ms),
+            ("default_confirmation_threshold", defaults.confirmation_threshold),
+        ] {
+            match value {
+                Some(Some(v)) => set_setting(&conn, key, &v.to_string())
+                    .map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?,
+                Some(None) => { delete_setting(&conn, key).ok(); }
+                None => {}
+            }
+        }
+    }
+
+    Ok(Json(settings_response(&conn)))
 }
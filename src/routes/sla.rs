@@ -1,17 +1,50 @@
-use rocket::{get, serde::json::Json, State, http::Status};
+use rocket::{get, serde::json::Json, State, http::{Status, ContentType}};
 use crate::db::Db;
-use crate::models::SlaStatus;
+use crate::models::{SlaStatus, SlaReport, SlaReportDay};
 use super::get_monitor_from_db;
 use rusqlite::params;
 use std::sync::Arc;
 
+/// Count total and successful checks since `offset` (an SQLite modifier such
+/// as "-30 days") and return them with the uptime percentage. Up and degraded
+/// both count as successful; maintenance checks are planned downtime and left
+/// out of both counts; no data counts as 100%.
+pub fn sla_uptime(conn: &rusqlite::Connection, monitor_id: &str, offset: &str) -> rusqlite::Result<(u32, u32, f64)> {
+    let (total_checks, successful_checks): (u32, u32) = conn.query_row(
+        "SELECT COUNT(*), SUM(CASE WHEN status = 'up' OR status = 'degraded' THEN 1 ELSE 0 END)
+         FROM heartbeats
+         WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2) AND status != 'maintenance'",
+        params![monitor_id, offset],
+        |row| Ok((row.get(0)?, row.get::<_, u32>(1).unwrap_or(0))),
+    )?;
+    let pct = if total_checks > 0 {
+        (successful_checks as f64 / total_checks as f64) * 100.0
+    } else {
+        100.0
+    };
+    Ok((total_checks, successful_checks, pct))
+}
+
+/// Rate the error budget is being spent over the window since `offset`: the
+/// window's failure ratio divided by the ratio the target allows. 1.0 spends
+/// the budget exactly over the SLA period. None without checks in the window
+/// or with a 100% target (no budget to burn).
+pub fn burn_rate(conn: &rusqlite::Connection, monitor_id: &str, offset: &str, target: f64) -> rusqlite::Result<Option<f64>> {
+    let (total, successful, _) = sla_uptime(conn, monitor_id, offset)?;
+    let allowed = 1.0 - target / 100.0;
+    if total == 0 || allowed <= 0.0 {
+        return Ok(None);
+    }
+    Ok(Some((total - successful) as f64 / total as f64 / allowed))
+}
+
 /// GET /api/v1/monitors/:id/sla — SLA status with error budget tracking
 #[get("/monitors/<id>/sla")]
 pub fn monitor_sla(
     id: &str,
     db: &State<Arc<Db>>,
 ) -> Result<Json<SlaStatus>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+    let conn = db.read();
     let err_map = |_: rusqlite::Error| {
         (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})))
     };
@@ -31,21 +64,7 @@ pub fn monitor_sla(
     let period_days = monitor.sla_period_days.unwrap_or(30);
     let offset_str = format!("-{} days", period_days);
 
-    // Count total and successful checks in the SLA period
-    let (total_checks, successful_checks): (u32, u32) = conn.query_row(
-        "SELECT COUNT(*), SUM(CASE WHEN status = 'up' OR status = 'degraded' THEN 1 ELSE 0 END)
-         FROM heartbeats
-         WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2)",
-        params![id, offset_str],
-        |row| Ok((row.get(0)?, row.get::<_, u32>(1).unwrap_or(0))),
-    ).map_err(err_map)?;
-
-    // Calculate current uptime percentage
-    let current_pct = if total_checks > 0 {
-        (successful_checks as f64 / total_checks as f64) * 100.0
-    } else {
-        100.0 // No data = assume 100%
-    };
+    let (total_checks, successful_checks, current_pct) = sla_uptime(&conn, id, &offset_str).map_err(err_map)?;
 
     // Calculate error budget
     let total_period_seconds = period_days as f64 * 86400.0;
@@ -53,19 +72,23 @@ pub fn monitor_sla(
 
     // Estimate actual downtime from heartbeat failure ratio
     // Use actual elapsed time in the period for more accurate calculation
-    let elapsed_seconds: f64 = conn.query_row(
-        "SELECT CAST((julianday('now') - julianday(MIN(checked_at))) * 86400.0 AS REAL)
+    let (elapsed_seconds, maintenance_checks): (f64, u32) = conn.query_row(
+        "SELECT CAST((julianday('now') - julianday(MIN(checked_at))) * 86400.0 AS REAL),
+                SUM(CASE WHEN status = 'maintenance' THEN 1 ELSE 0 END)
          FROM heartbeats
          WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2)",
         params![id, offset_str],
-        |row| row.get::<_, Option<f64>>(0),
-    ).map_err(err_map)?.unwrap_or(0.0);
+        |row| Ok((row.get::<_, Option<f64>>(0)?.unwrap_or(0.0), row.get::<_, Option<u32>>(1)?.unwrap_or(0))),
+    ).map_err(err_map)?;
 
-    let downtime_estimate_seconds = if total_checks > 0 && elapsed_seconds > 0.0 {
-        let failure_ratio = (total_checks - successful_checks) as f64 / total_checks as f64;
-        failure_ratio * elapsed_seconds
+    // Maintenance checks share the elapsed time but never count as downtime
+    let all_checks = total_checks + maintenance_checks;
+    let (downtime_estimate_seconds, excluded_maintenance_seconds) = if all_checks > 0 && elapsed_seconds > 0.0 {
+        let failure_ratio = (total_checks - successful_checks) as f64 / all_checks as f64;
+        let maintenance_ratio = maintenance_checks as f64 / all_checks as f64;
+        (failure_ratio * elapsed_seconds, maintenance_ratio * elapsed_seconds)
     } else {
-        0.0
+        (0.0, 0.0)
     };
 
     let budget_remaining_seconds = budget_total_seconds - downtime_estimate_seconds;
@@ -88,6 +111,9 @@ pub fn monitor_sla(
         "met"
     };
 
+    let burn_rate_1h = burn_rate(&conn, id, "-1 hours", target).map_err(err_map)?;
+    let burn_rate_24h = burn_rate(&conn, id, "-24 hours", target).map_err(err_map)?;
+
     // Calculate period boundaries
     let (period_start, period_end): (String, String) = conn.query_row(
         "SELECT datetime('now', ?1), datetime('now')",
@@ -103,11 +129,121 @@ pub fn monitor_sla(
         total_checks,
         successful_checks,
         downtime_estimate_seconds: (downtime_estimate_seconds * 100.0).round() / 100.0,
+        excluded_maintenance_seconds: (excluded_maintenance_seconds * 100.0).round() / 100.0,
         budget_total_seconds: (budget_total_seconds * 100.0).round() / 100.0,
         budget_remaining_seconds: (budget_remaining_seconds * 100.0).round() / 100.0,
         budget_used_pct: (budget_used_pct * 100.0).round() / 100.0,
+        burn_rate_1h: burn_rate_1h.map(|r| (r * 100.0).round() / 100.0),
+        burn_rate_24h: burn_rate_24h.map(|r| (r * 100.0).round() / 100.0),
         status: status.to_string(),
         period_start,
         period_end,
     }))
 }
+
+/// GET /api/v1/monitors/:id/sla/report — per-day uptime over a week or month
+/// with the aggregate against the SLA target, as JSON or CSV
+#[get("/monitors/<id>/sla/report?<period>&<format>")]
+pub fn monitor_sla_report(
+    id: &str,
+    period: Option<&str>,
+    format: Option<&str>,
+    db: &State<Arc<Db>>,
+) -> Result<(ContentType, String), (Status, Json<serde_json::Value>)> {
+    let err_map = |_: rusqlite::Error| {
+        (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})))
+    };
+    let period = period.unwrap_or("month");
+    let period_days: u32 = match period {
+        "week" => 7,
+        "month" => 30,
+        _ => return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "period must be 'week' or 'month'", "code": "VALIDATION_ERROR"
+        })))),
+    };
+    let csv = match format.unwrap_or("json") {
+        "json" => false,
+        "csv" => true,
+        _ => return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "format must be 'json' or 'csv'", "code": "VALIDATION_ERROR"
+        })))),
+    };
+
+    let conn = db.read();
+    let monitor = get_monitor_from_db(&conn, id)
+        .map_err(|_| (Status::NotFound, Json(serde_json::json!({
+            "error": "Monitor not found", "code": "NOT_FOUND"
+        }))))?;
+    let target = monitor.sla_target.ok_or_else(|| {
+        (Status::NotFound, Json(serde_json::json!({
+            "error": "No SLA target configured for this monitor",
+            "code": "SLA_NOT_CONFIGURED"
+        })))
+    })?;
+
+    let offset_str = format!("-{} days", period_days);
+    let (total_checks, successful_checks, current_pct) = sla_uptime(&conn, id, &offset_str).map_err(err_map)?;
+
+    // Same counting rules as sla_uptime, split by UTC day
+    let mut stmt = conn.prepare(
+        "SELECT date(checked_at), COUNT(*), SUM(CASE WHEN status = 'up' OR status = 'degraded' THEN 1 ELSE 0 END)
+         FROM heartbeats
+         WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2) AND status != 'maintenance'
+         GROUP BY date(checked_at)
+         ORDER BY date(checked_at)"
+    ).map_err(err_map)?;
+    let days: Vec<SlaReportDay> = stmt.query_map(params![id, offset_str], |row| {
+        let total: u32 = row.get(1)?;
+        let successful: u32 = row.get::<_, Option<u32>>(2)?.unwrap_or(0);
+        Ok(SlaReportDay {
+            date: row.get(0)?,
+            total_checks: total,
+            successful_checks: successful,
+            uptime_pct: ((successful as f64 / total as f64) * 100_000.0).round() / 1000.0,
+        })
+    }).map_err(err_map)?
+    .filter_map(|r| r.ok())
+    .collect();
+
+    let (period_start, period_end): (String, String) = conn.query_row(
+        "SELECT datetime('now', ?1), datetime('now')",
+        params![offset_str],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(err_map)?;
+
+    let report = SlaReport {
+        monitor_id: id.to_string(),
+        period: period.to_string(),
+        period_days,
+        period_start,
+        period_end,
+        target_pct: target,
+        total_checks,
+        successful_checks,
+        current_pct: (current_pct * 1000.0).round() / 1000.0,
+        status: if current_pct < target { "breached" } else { "met" }.to_string(),
+        days,
+    };
+
+    if csv {
+        Ok((ContentType::CSV, sla_report_csv(&report)))
+    } else {
+        let body = serde_json::to_string(&report).map_err(|_| {
+            (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})))
+        })?;
+        Ok((ContentType::JSON, body))
+    }
+}
+
+/// One row per day, then a `total` row carrying the aggregate and the target.
+fn sla_report_csv(report: &SlaReport) -> String {
+    let mut out = String::from("date,total_checks,successful_checks,uptime_pct,target_pct\n");
+    for day in &report.days {
+        out.push_str(&format!("{},{},{},{},\n", day.date, day.total_checks, day.successful_checks, day.uptime_pct));
+    }
+    out.push_str(&format!(
+        "total,{},{},{},{}\n",
+        report.total_checks, report.successful_checks, report.current_pct, report.target_pct,
+    ));
+    out
+}
@@ -1,7 +1,7 @@
 use rocket::{get, serde::json::Json, State, http::Status};
 use crate::db::Db;
 use crate::models::{StatusOverview, StatusMonitor};
-use super::{parse_tags, settings::{load_branding, branding_is_empty}};
+use super::{parse_tags, settings::{load_branding, branding_is_empty, load_unknown_status_treatment, overall_status}};
 use rusqlite::params;
 use std::sync::Arc;
 
@@ -13,9 +13,9 @@ use std::sync::Arc;
 /// Also supports ?search=, ?status=, ?tag=, ?group= filters.
 #[get("/status?<search>&<status>&<tag>&<group>&<ids>")]
 pub fn status_page(search: Option<&str>, status: Option<&str>, tag: Option<&str>, group: Option<&str>, ids: Option<&str>, db: &State<Arc<Db>>) -> Result<Json<StatusOverview>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+    let conn = db.read();
 
-    let mut sql = String::from("SELECT id, name, url, current_status, last_checked_at, tags, group_name FROM monitors WHERE is_public = 1");
+    let mut sql = String::from("SELECT id, name, url, current_status, last_checked_at, tags, group_name, description FROM monitors WHERE is_public = 1 AND deleted_at IS NULL");
     let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
 
     if let Some(q) = search {
@@ -84,10 +84,11 @@ pub fn status_page(search: Option<&str>, status: Option<&str>, tag: Option<&str>
         let status: String = row.get(3)?;
         let tags_str: String = row.get::<_, String>(5).unwrap_or_default();
         let group_name: Option<String> = row.get::<_, Option<String>>(6).unwrap_or(None);
-        Ok((id, row.get(1)?, row.get(2)?, status, row.get::<_, Option<String>>(4)?, tags_str, group_name))
+        let description: Option<String> = row.get::<_, Option<String>>(7).unwrap_or(None);
+        Ok((id, row.get(1)?, row.get(2)?, status, row.get::<_, Option<String>>(4)?, tags_str, group_name, description))
     }).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
     .filter_map(|r| r.ok())
-    .map(|(id, name, url, status, last_checked, tags_str, group_name)| {
+    .map(|(id, name, url, status, last_checked, tags_str, group_name, description)| {
         let total_24h: u32 = conn.query_row(
             "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND checked_at > datetime('now', '-24 hours')",
             params![&id], |row| row.get(0),
@@ -116,6 +117,7 @@ pub fn status_page(search: Option<&str>, status: Option<&str>, tag: Option<&str>
         StatusMonitor {
             id,
             name,
+            description,
             url,
             current_status: status,
             last_checked_at: last_checked,
@@ -125,17 +127,22 @@ pub fn status_page(search: Option<&str>, status: Option<&str>, tag: Option<&str>
             active_incident,
             tags: parse_tags(&tags_str),
             group_name,
+            section: None,
         }
     })
     .collect();
 
-    let overall = if monitors.is_empty() {
+    let unknown_treatment = load_unknown_status_treatment(&conn);
+    let counted: Vec<&str> = monitors.iter()
+        .filter_map(|m| overall_status(&m.current_status, &unknown_treatment))
+        .collect();
+    let overall = if counted.is_empty() {
         "unknown".to_string()
-    } else if monitors.iter().any(|m| m.current_status == "down") {
+    } else if counted.contains(&"down") {
         "major_outage".to_string()
-    } else if monitors.iter().all(|m| m.current_status == "up" || m.current_status == "maintenance") {
+    } else if counted.iter().all(|s| *s == "up" || *s == "maintenance") {
         "operational".to_string()
-    } else if monitors.iter().any(|m| m.current_status == "unknown") {
+    } else if counted.contains(&"unknown") {
         "unknown".to_string()
     } else {
         "degraded".to_string()
@@ -1,11 +1,13 @@
-use rocket::{get, post, patch, delete, serde::json::Json, State, http::Status};
+use rocket::{get, post, patch, delete, serde::json::Json, State, http::{Status, ContentType}};
 use crate::db::Db;
-use crate::auth::{ManageToken, generate_key, hash_key};
+use crate::auth::{ManageToken, OptionalManageToken, ClientIp, generate_key, hash_key};
 use crate::models::{
     StatusPage, CreateStatusPage, UpdateStatusPage, CreateStatusPageResponse,
-    StatusPageDetail, StatusMonitor, AddMonitorsToPage,
+    StatusPageDetail, StatusMonitor, StatusPageSection, AddMonitorsToPage, StatusPageBranding, SubscribeToPage,
+    MaintenanceWindow,
 };
-use super::parse_tags;
+use crate::notifications::html_escape;
+use super::{RateLimiter, parse_tags, verify_admin_key, active_maintenance_windows, settings::{load_branding, load_unknown_status_treatment, overall_status}};
 use rusqlite::params;
 use std::sync::Arc;
 use uuid::Uuid;
@@ -14,10 +16,18 @@ use uuid::Uuid;
 
 fn get_status_page(conn: &rusqlite::Connection, slug_or_id: &str) -> Result<(StatusPage, String), (Status, Json<serde_json::Value>)> {
     // Try by slug first, then by id
-    let result = conn.query_row(
+    load_status_page(conn, "slug = ?1 OR id = ?1", slug_or_id)
+}
+
+fn load_status_page(conn: &rusqlite::Connection, filter: &str, value: &str) -> Result<(StatusPage, String), (Status, Json<serde_json::Value>)> {
+    let sql = format!(
         "SELECT id, slug, title, description, logo_url, custom_domain, is_public, manage_key_hash, created_at, updated_at
-         FROM status_pages WHERE slug = ?1 OR id = ?1",
-        params![slug_or_id],
+         FROM status_pages WHERE {}",
+        filter
+    );
+    let result = conn.query_row(
+        &sql,
+        params![value],
         |row| {
             let id: String = row.get(0)?;
             let manage_key_hash: String = row.get(7)?;
@@ -59,6 +69,20 @@ fn verify_page_key(conn: &rusqlite::Connection, slug_or_id: &str, token: &str) -
     Ok(page)
 }
 
+/// Public pages are open to everyone; private ones need the admin key or the page's own manage key.
+fn verify_page_view(conn: &rusqlite::Connection, page: &StatusPage, stored_hash: &str, token: Option<&str>) -> Result<(), (Status, Json<serde_json::Value>)> {
+    if page.is_public {
+        return Ok(());
+    }
+    let token = token.ok_or_else(|| (Status::Unauthorized, Json(serde_json::json!({
+        "error": "This status page is private; an admin key is required", "code": "UNAUTHORIZED"
+    }))))?;
+    if hash_key(token) == stored_hash {
+        return Ok(());
+    }
+    verify_admin_key(conn, token)
+}
+
 fn validate_slug(slug: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
     if slug.is_empty() || slug.len() > 100 {
         return Err((Status::BadRequest, Json(serde_json::json!({
@@ -71,6 +95,12 @@ fn validate_slug(slug: &str) -> Result<(), (Status, Json<serde_json::Value>)> {
             "code": "VALIDATION_ERROR"
         }))));
     }
+    // Reserved: /status-pages/by-slug/<slug> would shadow this page's sub-routes
+    if slug.eq_ignore_ascii_case("by-slug") {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "Slug 'by-slug' is reserved", "code": "VALIDATION_ERROR"
+        }))));
+    }
     Ok(())
 }
 
@@ -118,9 +148,9 @@ pub fn create_status_page(
 
     let conn = db.conn();
 
-    // Check slug uniqueness
+    // Check slug uniqueness (slugs and ids share the same lookup namespace)
     let slug_exists: bool = conn.query_row(
-        "SELECT COUNT(*) FROM status_pages WHERE slug = ?1",
+        "SELECT COUNT(*) FROM status_pages WHERE slug = ?1 OR id = ?1",
         params![&input.slug],
         |row| row.get::<_, i64>(0),
     ).map(|c| c > 0).unwrap_or(false);
@@ -188,7 +218,7 @@ pub fn create_status_page(
 
 #[get("/status-pages")]
 pub fn list_status_pages(db: &State<Arc<Db>>) -> Result<Json<Vec<StatusPage>>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+    let conn = db.read();
 
     let mut stmt = conn.prepare(
         "SELECT sp.id, sp.slug, sp.title, sp.description, sp.logo_url, sp.custom_domain, sp.is_public, sp.created_at, sp.updated_at,
@@ -223,32 +253,179 @@ pub fn list_status_pages(db: &State<Arc<Db>>) -> Result<Json<Vec<StatusPage>>, (
 #[get("/status-pages/<slug_or_id>")]
 pub fn get_status_page_detail(
     slug_or_id: &str,
+    token: OptionalManageToken,
     db: &State<Arc<Db>>,
 ) -> Result<Json<StatusPageDetail>, (Status, Json<serde_json::Value>)> {
-    let conn = db.conn();
+    let conn = db.read();
+    let (page, hash) = get_status_page(&conn, slug_or_id)?;
+    verify_page_view(&conn, &page, &hash, token.0.as_deref())?;
+    page_detail(&conn, page).map(Json)
+}
 
-    let (page, _hash) = get_status_page(&conn, slug_or_id)?;
+// ── Get Status Page by Slug ──
 
-    // Non-public pages require auth (not enforced here for simplicity — they're just unlisted)
-    // The page is accessible by slug/id if you know it, similar to unlisted YouTube videos
+#[get("/status-pages/by-slug/<slug>")]
+pub fn get_status_page_by_slug(
+    slug: &str,
+    token: OptionalManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<StatusPageDetail>, (Status, Json<serde_json::Value>)> {
+    let conn = db.read();
+    let (page, hash) = load_status_page(&conn, "slug = ?1", slug)?;
+    verify_page_view(&conn, &page, &hash, token.0.as_deref())?;
+    page_detail(&conn, page).map(Json)
+}
 
-    // Get monitors assigned to this page
+// ── Status Page as HTML ──
+
+// Ranked below get_status_page_by_slug so /status-pages/by-slug/page resolves the slug
+#[get("/status-pages/<slug_or_id>/page", rank = 2)]
+pub fn status_page_html(
+    slug_or_id: &str,
+    token: OptionalManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<(ContentType, String), (Status, Json<serde_json::Value>)> {
+    let conn = db.read();
+    let (page, hash) = get_status_page(&conn, slug_or_id)?;
+    verify_page_view(&conn, &page, &hash, token.0.as_deref())?;
+    let detail = page_detail(&conn, page)?;
+    let branding = load_branding(&conn);
+    Ok((ContentType::HTML, render_status_page_html(&detail, &branding)))
+}
+
+fn status_label(status: &str) -> &'static str {
+    match status {
+        "up" => "Operational",
+        "down" => "Down",
+        "degraded" => "Degraded",
+        "maintenance" => "Maintenance",
+        "paused" => "Paused",
+        _ => "Unknown",
+    }
+}
+
+fn overall_label(overall: &str) -> &'static str {
+    match overall {
+        "operational" => "All systems operational",
+        "major_outage" => "Major outage",
+        "degraded" => "Degraded performance",
+        _ => "Status unknown",
+    }
+}
+
+/// Server-rendered, script-free page. The page's own description and logo win;
+/// the global branding settings fill in whatever the page leaves unset.
+fn render_status_page_html(detail: &StatusPageDetail, branding: &StatusPageBranding) -> String {
+    let description = detail.description.as_ref().or(branding.description.as_ref());
+    let logo_url = detail.logo_url.as_ref().or(branding.logo_url.as_ref())
+        .filter(|u| u.starts_with("https://") || u.starts_with("http://"));
+
+    let mut html = format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta http-equiv="refresh" content="60">
+<title>{title}</title>
+<style>
+body {{ font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; max-width: 760px; margin: 0 auto; padding: 24px; color: #1f2937; }}
+header img {{ max-height: 48px; }}
+.overall {{ padding: 12px 16px; border-radius: 6px; font-weight: 600; margin: 16px 0; }}
+.overall.operational {{ background: #dcfce7; }}
+.overall.degraded, .overall.unknown {{ background: #fef9c3; }}
+.overall.major_outage {{ background: #fee2e2; }}
+.banner {{ padding: 12px 16px; border-radius: 6px; margin: 16px 0; background: #dbeafe; }}
+table {{ width: 100%; border-collapse: collapse; margin-bottom: 24px; }}
+td {{ padding: 8px 4px; border-bottom: 1px solid #e5e7eb; }}
+td.status {{ text-align: right; font-weight: 600; }}
+.up {{ color: #16a34a; }} .down {{ color: #dc2626; }} .degraded {{ color: #ca8a04; }}
+.maintenance, .paused, .unknown {{ color: #6b7280; }}
+</style>
+</head>
+<body>
+<header>
+"#,
+        title = html_escape(&detail.title),
+    );
+
+    if let Some(url) = logo_url {
+        html.push_str(&format!("<img src=\"{}\" alt=\"\">\n", html_escape(url)));
+    }
+    html.push_str(&format!("<h1>{}</h1>\n", html_escape(&detail.title)));
+    if let Some(desc) = description {
+        html.push_str(&format!("<p>{}</p>\n", html_escape(desc)));
+    }
+    html.push_str("</header>\n");
+    html.push_str(&format!(
+        "<div class=\"overall {}\">{}</div>\n",
+        html_escape(&detail.overall),
+        overall_label(&detail.overall),
+    ));
+    for w in &detail.maintenance {
+        html.push_str(&format!(
+            "<div class=\"banner\">Maintenance: {} (until {})</div>\n",
+            html_escape(&w.title),
+            html_escape(&w.ends_at),
+        ));
+    }
+
+    // Monitors arrive ordered by section, then group_name (unset last), so consecutive runs form
+    // the headings. Pages that use sections are headed by them instead of by group_name.
+    let use_sections = detail.monitors.iter().any(|m| m.section.is_some());
+    let mut current_group: Option<Option<&str>> = None;
+    for m in &detail.monitors {
+        let group = if use_sections { m.section.as_deref() } else { m.group_name.as_deref() };
+        if current_group != Some(group) {
+            if current_group.is_some() {
+                html.push_str("</table>\n");
+            }
+            match group {
+                Some(name) => html.push_str(&format!("<h2>{}</h2>\n", html_escape(name))),
+                None if current_group.is_some() => html.push_str("<h2>Other</h2>\n"),
+                None => {}
+            }
+            html.push_str("<table>\n");
+            current_group = Some(group);
+        }
+        html.push_str(&format!(
+            "<tr><td>{name}</td><td>{uptime:.2}% (24h)</td><td class=\"status {status}\">{label}</td></tr>\n",
+            name = html_escape(&m.name),
+            uptime = m.uptime_24h,
+            status = html_escape(&m.current_status),
+            label = status_label(&m.current_status),
+        ));
+    }
+    if current_group.is_some() {
+        html.push_str("</table>\n");
+    } else {
+        html.push_str("<p>No monitors on this page yet.</p>\n");
+    }
+
+    html.push_str("<footer><small>Powered by Watchpost</small></footer>\n</body>\n</html>\n");
+    html
+}
+
+/// Monitors on a page with their 24h/7d stats, ordered by section, then group_name (unset last), then name
+fn page_monitors(conn: &rusqlite::Connection, page_id: &str) -> Result<Vec<StatusMonitor>, (Status, Json<serde_json::Value>)> {
     let mut stmt = conn.prepare(
-        "SELECT m.id, m.name, m.url, m.current_status, m.last_checked_at, m.tags, m.group_name
+        "SELECT m.id, m.name, m.url, m.current_status, m.last_checked_at, m.tags, m.group_name, spm.section, m.description
          FROM monitors m
          INNER JOIN status_page_monitors spm ON spm.monitor_id = m.id
-         WHERE spm.status_page_id = ?1
-         ORDER BY m.group_name NULLS LAST, m.name"
+         WHERE spm.status_page_id = ?1 AND m.deleted_at IS NULL
+         ORDER BY spm.section NULLS LAST, m.group_name NULLS LAST, m.name"
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
-    let monitors: Vec<StatusMonitor> = stmt.query_map(params![&page.id], |row| {
+    let monitors = stmt.query_map(params![page_id], |row| {
         let id: String = row.get(0)?;
         let tags_str: String = row.get::<_, String>(5).unwrap_or_default();
         let group_name: Option<String> = row.get::<_, Option<String>>(6).unwrap_or(None);
-        Ok((id, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?, row.get::<_, Option<String>>(4)?, tags_str, group_name))
+        let section: Option<String> = row.get::<_, Option<String>>(7).unwrap_or(None);
+        let description: Option<String> = row.get::<_, Option<String>>(8).unwrap_or(None);
+        Ok((id, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?, row.get::<_, Option<String>>(4)?, tags_str, group_name, section, description))
     }).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
     .filter_map(|r| r.ok())
-    .map(|(id, name, url, status, last_checked, tags_str, group_name)| {
+    .map(|(id, name, url, status, last_checked, tags_str, group_name, section, description)| {
         let total_24h: u32 = conn.query_row(
             "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND checked_at > datetime('now', '-24 hours')",
             params![&id], |row| row.get(0),
@@ -277,6 +454,7 @@ pub fn get_status_page_detail(
         StatusMonitor {
             id,
             name,
+            description,
             url,
             current_status: status,
             last_checked_at: last_checked,
@@ -286,23 +464,63 @@ pub fn get_status_page_detail(
             active_incident,
             tags: parse_tags(&tags_str),
             group_name,
+            section,
         }
     })
     .collect();
 
-    let overall = if monitors.is_empty() {
-        "unknown".to_string()
-    } else if monitors.iter().any(|m| m.current_status == "down") {
-        "major_outage".to_string()
-    } else if monitors.iter().all(|m| m.current_status == "up" || m.current_status == "maintenance") {
-        "operational".to_string()
-    } else if monitors.iter().any(|m| m.current_status == "unknown") {
-        "unknown".to_string()
-    } else {
-        "degraded".to_string()
+    Ok(monitors)
+}
+
+/// Consecutive runs of the ordered monitor list form the sections
+fn group_sections(monitors: &[StatusMonitor]) -> Vec<StatusPageSection> {
+    let mut sections: Vec<StatusPageSection> = Vec::new();
+    for m in monitors {
+        match sections.last_mut() {
+            Some(last) if last.name == m.section => last.monitors.push(m.clone()),
+            _ => sections.push(StatusPageSection { name: m.section.clone(), monitors: vec![m.clone()] }),
+        }
+    }
+    sections
+}
+
+/// Worst-wins summary of one page's monitors: down, then degraded (any other non-up status),
+/// then unknown. Monitors in maintenance, by status or by an active window, are left out, as are
+/// unknown monitors when the `unknown_status_treatment` setting excludes them.
+fn page_overall(monitors: &[StatusMonitor], maintenance: &[MaintenanceWindow], unknown_treatment: &str) -> String {
+    if monitors.is_empty() {
+        return "unknown".to_string();
+    }
+    let severity = |status: &str| match status {
+        "up" => 0,
+        "unknown" => 1,
+        "down" => 3,
+        _ => 2,
     };
+    let worst = monitors.iter()
+        .filter(|m| m.current_status != "maintenance" && !maintenance.iter().any(|w| w.monitor_id == m.id))
+        .filter_map(|m| overall_status(&m.current_status, unknown_treatment))
+        .map(severity)
+        .max()
+        .unwrap_or(0);
+    match worst {
+        0 => "operational",
+        1 => "unknown",
+        2 => "degraded",
+        _ => "major_outage",
+    }.to_string()
+}
+
+fn page_detail(conn: &rusqlite::Connection, page: StatusPage) -> Result<StatusPageDetail, (Status, Json<serde_json::Value>)> {
+    let monitors = page_monitors(conn, &page.id)?;
 
-    Ok(Json(StatusPageDetail {
+    let mut maintenance: Vec<MaintenanceWindow> = monitors.iter()
+        .flat_map(|m| active_maintenance_windows(conn, &m.id))
+        .collect();
+    maintenance.sort_by(|a, b| a.ends_at.cmp(&b.ends_at));
+    let overall = page_overall(&monitors, &maintenance, &load_unknown_status_treatment(conn));
+
+    Ok(StatusPageDetail {
         id: page.id,
         slug: page.slug,
         title: page.title,
@@ -310,11 +528,13 @@ pub fn get_status_page_detail(
         logo_url: page.logo_url,
         custom_domain: page.custom_domain,
         is_public: page.is_public,
+        sections: group_sections(&monitors),
         monitors,
+        maintenance,
         overall,
         created_at: page.created_at,
         updated_at: page.updated_at,
-    }))
+    })
 }
 
 // ── Update Status Page ──
@@ -335,7 +555,7 @@ pub fn update_status_page(
         validate_slug(new_slug)?;
         if new_slug != &page.slug {
             let exists: bool = conn.query_row(
-                "SELECT COUNT(*) FROM status_pages WHERE slug = ?1 AND id != ?2",
+                "SELECT COUNT(*) FROM status_pages WHERE (slug = ?1 OR id = ?1) AND id != ?2",
                 params![new_slug, &page.id],
                 |row| row.get::<_, i64>(0),
             ).map(|c| c > 0).unwrap_or(false);
@@ -446,33 +666,58 @@ pub fn add_page_monitors(
         }))));
     }
 
+    // Blank clears the section; anything else is trimmed and bounded like a title
+    let section = input.section.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    if section.is_some_and(|s| s.chars().count() > 100) {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "section must be at most 100 characters", "code": "VALIDATION_ERROR"
+        }))));
+    }
+
     let mut added = 0u32;
     let mut skipped = 0u32;
     let mut errors: Vec<serde_json::Value> = Vec::new();
 
     for mid in &input.monitor_ids {
         // Verify monitor exists
-        let exists: bool = conn.query_row(
-            "SELECT COUNT(*) FROM monitors WHERE id = ?1",
+        let monitor_public: Option<bool> = conn.query_row(
+            "SELECT is_public FROM monitors WHERE id = ?1 AND deleted_at IS NULL",
             params![mid],
-            |row| row.get::<_, i64>(0),
-        ).map(|c| c > 0).unwrap_or(false);
+            |row| row.get::<_, i32>(0).map(|p| p != 0),
+        ).ok();
 
-        if !exists {
+        let Some(monitor_public) = monitor_public else {
             errors.push(serde_json::json!({
                 "monitor_id": mid,
                 "error": "Monitor not found"
             }));
             continue;
+        };
+
+        // A public page would expose a private monitor to anyone
+        if page.is_public && !monitor_public {
+            errors.push(serde_json::json!({
+                "monitor_id": mid,
+                "error": "Private monitors can only be added to private status pages"
+            }));
+            continue;
         }
 
-        // Insert (ignore duplicates)
+        // Insert (ignore duplicates, but move them when a section was given)
         match conn.execute(
-            "INSERT OR IGNORE INTO status_page_monitors (status_page_id, monitor_id) VALUES (?1, ?2)",
-            params![&page.id, mid],
+            "INSERT OR IGNORE INTO status_page_monitors (status_page_id, monitor_id, section) VALUES (?1, ?2, ?3)",
+            params![&page.id, mid, section],
         ) {
             Ok(n) if n > 0 => added += 1,
-            Ok(_) => skipped += 1,
+            Ok(_) => {
+                if input.section.is_some() {
+                    conn.execute(
+                        "UPDATE status_page_monitors SET section = ?3 WHERE status_page_id = ?1 AND monitor_id = ?2",
+                        params![&page.id, mid, section],
+                    ).ok();
+                }
+                skipped += 1;
+            }
             Err(_) => errors.push(serde_json::json!({
                 "monitor_id": mid,
                 "error": "Internal server error"
@@ -524,70 +769,143 @@ pub fn remove_page_monitor(
 
 // ── List Monitors on a Status Page ──
 
-#[get("/status-pages/<slug_or_id>/monitors")]
+// Ranked below get_status_page_by_slug so /status-pages/by-slug/monitors resolves the slug
+#[get("/status-pages/<slug_or_id>/monitors", rank = 2)]
 pub fn list_page_monitors(
     slug_or_id: &str,
+    token: OptionalManageToken,
     db: &State<Arc<Db>>,
 ) -> Result<Json<Vec<StatusMonitor>>, (Status, Json<serde_json::Value>)> {
+    let conn = db.read();
+    let (page, hash) = get_status_page(&conn, slug_or_id)?;
+    verify_page_view(&conn, &page, &hash, token.0.as_deref())?;
+
+    page_monitors(&conn, &page.id).map(Json)
+}
+
+// ── Email Subscriptions ──
+
+fn normalize_email(email: &str) -> Result<String, (Status, Json<serde_json::Value>)> {
+    let email = email.trim().to_lowercase();
+    let valid = email.len() <= 254
+        && !email.chars().any(|c| c.is_whitespace() || c == '<' || c == '>' || c == ',')
+        && match email.split_once('@') {
+            Some((local, domain)) => !local.is_empty() && domain.contains('.') && !domain.contains('@')
+                && !domain.starts_with('.') && !domain.ends_with('.'),
+            None => false,
+        };
+    if !valid {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "A valid email address is required", "code": "VALIDATION_ERROR"
+        }))));
+    }
+    Ok(email)
+}
+
+#[post("/status-pages/<slug_or_id>/subscribe", data = "<body>")]
+pub fn subscribe_to_page(
+    slug_or_id: &str,
+    body: Json<SubscribeToPage>,
+    token: OptionalManageToken,
+    db: &State<Arc<Db>>,
+    rate_limiter: &State<RateLimiter>,
+    client_ip: ClientIp,
+) -> Result<(Status, Json<serde_json::Value>), (Status, Json<serde_json::Value>)> {
+    // Each request mails an arbitrary address, so cap it per IP (own bucket, same limit)
+    if !rate_limiter.check(&format!("subscribe:{}", client_ip.0)) {
+        return Err((Status::TooManyRequests, Json(serde_json::json!({
+            "error": "Rate limit exceeded",
+            "code": "RATE_LIMIT_EXCEEDED"
+        }))));
+    }
+    let email = normalize_email(&body.email)?;
     let conn = db.conn();
-    let (page, _) = get_status_page(&conn, slug_or_id)?;
+    let (page, hash) = get_status_page(&conn, slug_or_id)?;
+    verify_page_view(&conn, &page, &hash, token.0.as_deref())?;
+
+    let existing: Option<bool> = conn.query_row(
+        "SELECT confirmed FROM status_page_subscribers WHERE status_page_id = ?1 AND email = ?2",
+        params![&page.id, &email],
+        |row| row.get::<_, i32>(0).map(|c| c != 0),
+    ).ok();
+    if let Some(confirmed) = existing {
+        let error = if confirmed {
+            "This email is already subscribed to this status page"
+        } else {
+            "A confirmation email was already sent to this address"
+        };
+        return Err((Status::Conflict, Json(serde_json::json!({
+            "error": error, "code": "ALREADY_SUBSCRIBED"
+        }))));
+    }
 
-    let mut stmt = conn.prepare(
-        "SELECT m.id, m.name, m.url, m.current_status, m.last_checked_at, m.tags, m.group_name
-         FROM monitors m
-         INNER JOIN status_page_monitors spm ON spm.monitor_id = m.id
-         WHERE spm.status_page_id = ?1
-         ORDER BY m.group_name NULLS LAST, m.name"
+    let token = generate_key();
+    let id = Uuid::new_v4().to_string();
+    let unsubscribe_hash = hash_key(&crate::notifications::unsubscribe_token(&conn, &id));
+    conn.execute(
+        "INSERT INTO status_page_subscribers (id, status_page_id, email, token, unsubscribe_hash) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![&id, &page.id, &email, hash_key(&token), unsubscribe_hash],
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+    drop(conn);
+
+    // The token only ever travels by email, so owning the inbox is the confirmation
+    let (to, title) = (email.clone(), page.title.clone());
+    rocket::tokio::spawn(async move {
+        crate::notifications::send_subscription_confirmation(&to, &title, &token).await;
+    });
+
+    Ok((Status::Accepted, Json(serde_json::json!({
+        "message": "Check your inbox to confirm the subscription",
+        "status_page_id": page.id,
+        "email": email,
+        "confirmed": false
+    }))))
+}
 
-    let monitors: Vec<StatusMonitor> = stmt.query_map(params![&page.id], |row| {
-        let id: String = row.get(0)?;
-        let tags_str: String = row.get::<_, String>(5).unwrap_or_default();
-        let group_name: Option<String> = row.get::<_, Option<String>>(6).unwrap_or(None);
-        Ok((id, row.get(1)?, row.get(2)?, row.get::<_, String>(3)?, row.get::<_, Option<String>>(4)?, tags_str, group_name))
-    }).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?
-    .filter_map(|r| r.ok())
-    .map(|(id, name, url, status, last_checked, tags_str, group_name)| {
-        let total_24h: u32 = conn.query_row(
-            "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND checked_at > datetime('now', '-24 hours')",
-            params![&id], |row| row.get(0),
-        ).unwrap_or(0);
-        let up_24h: u32 = conn.query_row(
-            "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND status = 'up' AND checked_at > datetime('now', '-24 hours')",
-            params![&id], |row| row.get(0),
-        ).unwrap_or(0);
-        let total_7d: u32 = conn.query_row(
-            "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND checked_at > datetime('now', '-7 days')",
-            params![&id], |row| row.get(0),
-        ).unwrap_or(0);
-        let up_7d: u32 = conn.query_row(
-            "SELECT COUNT(*) FROM heartbeats WHERE monitor_id = ?1 AND status = 'up' AND checked_at > datetime('now', '-7 days')",
-            params![&id], |row| row.get(0),
-        ).unwrap_or(0);
-        let avg_ms: Option<f64> = conn.query_row(
-            "SELECT AVG(response_time_ms) FROM heartbeats WHERE monitor_id = ?1 AND status = 'up' AND checked_at > datetime('now', '-24 hours')",
-            params![&id], |row| row.get(0),
-        ).ok();
-        let active_incident = conn.query_row(
-            "SELECT COUNT(*) FROM incidents WHERE monitor_id = ?1 AND resolved_at IS NULL",
-            params![&id], |row| row.get::<_, u32>(0),
-        ).unwrap_or(0) > 0;
+#[get("/subscriptions/<token>/confirm")]
+pub fn confirm_subscription(
+    token: &str,
+    db: &State<Arc<Db>>,
+) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let token_hash = hash_key(token);
+    let conn = db.conn();
+    let (page_id, email): (String, String) = conn.query_row(
+        "SELECT status_page_id, email FROM status_page_subscribers WHERE token = ?1",
+        params![&token_hash],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).map_err(|_| (Status::NotFound, Json(serde_json::json!({
+        "error": "Subscription not found", "code": "NOT_FOUND"
+    }))))?;
 
-        StatusMonitor {
-            id,
-            name,
-            url,
-            current_status: status,
-            last_checked_at: last_checked,
-            uptime_24h: if total_24h > 0 { (up_24h as f64 / total_24h as f64) * 100.0 } else { 100.0 },
-            uptime_7d: if total_7d > 0 { (up_7d as f64 / total_7d as f64) * 100.0 } else { 100.0 },
-            avg_response_ms_24h: avg_ms,
-            active_incident,
-            tags: parse_tags(&tags_str),
-            group_name,
-        }
-    })
-    .collect();
+    conn.execute(
+        "UPDATE status_page_subscribers SET confirmed = 1, confirmed_at = COALESCE(confirmed_at, datetime('now')) WHERE token = ?1",
+        params![&token_hash],
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Subscription confirmed",
+        "status_page_id": page_id,
+        "email": email,
+        "confirmed": true
+    })))
+}
+
+#[get("/subscriptions/<token>/unsubscribe")]
+pub fn unsubscribe_from_page(
+    token: &str,
+    db: &State<Arc<Db>>,
+) -> Result<Json<serde_json::Value>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    let deleted = conn.execute(
+        "DELETE FROM status_page_subscribers WHERE token = ?1 OR unsubscribe_hash = ?1",
+        params![hash_key(token)],
+    ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
+
+    if deleted == 0 {
+        return Err((Status::NotFound, Json(serde_json::json!({
+            "error": "Subscription not found", "code": "NOT_FOUND"
+        }))));
+    }
 
-    Ok(Json(monitors))
+    Ok(Json(serde_json::json!({"message": "Unsubscribed"})))
 }
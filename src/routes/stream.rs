@@ -1,14 +1,69 @@
 use rocket::{get, State};
 use rocket::response::stream::{Event, EventStream};
-use crate::sse::EventBroadcaster;
+use crate::db::Db;
+use crate::sse::{EventBroadcaster, Frame, LastEventId, StreamFilter};
 use std::sync::Arc;
 
-#[get("/events")]
-pub fn global_events(broadcaster: &State<Arc<EventBroadcaster>>) -> EventStream![Event + '_] {
-    crate::sse::global_stream(broadcaster)
+#[get("/events?<group>&<tag>")]
+pub fn global_events<'a>(
+    group: Option<&str>,
+    tag: Option<&str>,
+    last_event_id: LastEventId,
+    broadcaster: &'a State<Arc<EventBroadcaster>>,
+    db: &State<Arc<Db>>,
+) -> EventStream![Event + 'a] {
+    crate::sse::global_stream(broadcaster, db.inner().clone(), StreamFilter::new(group, tag), last_event_id.0)
 }
 
 #[get("/monitors/<id>/events")]
-pub fn monitor_events<'a>(id: &'a str, broadcaster: &'a State<Arc<EventBroadcaster>>) -> EventStream![Event + 'a] {
-    crate::sse::monitor_stream(broadcaster, id.to_string())
+pub fn monitor_events<'a>(id: &str, last_event_id: LastEventId, broadcaster: &'a State<Arc<EventBroadcaster>>) -> EventStream![Event + 'a] {
+    crate::sse::monitor_stream(broadcaster, id.to_string(), last_event_id.0)
+}
+
+/// WebSocket mirror of `global_events`. Each message is the global event JSON
+/// with the event `id` and `event` type added; idle connections get a ping.
+#[get("/ws?<group>&<tag>")]
+pub fn ws_events(
+    ws: rocket_ws::WebSocket,
+    group: Option<&str>,
+    tag: Option<&str>,
+    broadcaster: &State<Arc<EventBroadcaster>>,
+    db: &State<Arc<Db>>,
+) -> rocket_ws::Channel<'static> {
+    use rocket::futures::{SinkExt, StreamExt};
+    use rocket_ws::Message;
+
+    let broadcaster = broadcaster.inner().clone();
+    let db = db.inner().clone();
+    let filter = StreamFilter::new(group, tag);
+    let mut rx = broadcaster.sender.subscribe();
+
+    ws.channel(move |mut stream| Box::pin(async move {
+        let keepalive = broadcaster.keepalive;
+        let mut deadline = keepalive.map(|d| rocket::tokio::time::Instant::now() + d);
+        loop {
+            let outgoing = rocket::tokio::select! {
+                frame = crate::sse::next_frame(&mut rx, deadline) => match frame {
+                    Frame::Event(stamped) if !filter.matches_monitor(&db, &stamped.event.monitor_id) => continue,
+                    Frame::Event(stamped) => {
+                        let mut msg = crate::sse::global_event_json(&stamped);
+                        msg["id"] = serde_json::json!(stamped.id);
+                        msg["event"] = serde_json::json!(stamped.event.event_type);
+                        Message::Text(msg.to_string())
+                    }
+                    Frame::Lagged(n) => Message::Text(serde_json::json!({"event": "lag", "skipped": n}).to_string()),
+                    Frame::Keepalive => Message::Ping(Vec::new()),
+                    Frame::Closed => break,
+                },
+                incoming = stream.next() => match incoming {
+                    // Client messages are ignored; pings are answered by the ws layer
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                },
+            };
+            stream.send(outgoing).await?;
+            deadline = keepalive.map(|d| rocket::tokio::time::Instant::now() + d);
+        }
+        Ok(())
+    }))
 }
@@ -1,14 +1,20 @@
-use rocket::{get, serde::json::Json};
+use rocket::{get, serde::json::Json, State};
+use crate::checker::CheckerHealth;
+use std::sync::Arc;
 use rocket::http::ContentType;
 
 // ── Health ──
 
 #[get("/health")]
-pub fn health() -> Json<serde_json::Value> {
+pub fn health(checker: &State<Arc<CheckerHealth>>) -> Json<serde_json::Value> {
     Json(serde_json::json!({
         "service": "watchpost",
         "status": "ok",
-        "version": "0.1.0"
+        "version": "0.1.0",
+        "checker": {
+            "restarts": checker.restarts(),
+            "last_run_at": checker.last_run_at(),
+        }
     }))
 }
 
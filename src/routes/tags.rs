@@ -1,6 +1,9 @@
-use rocket::{get, serde::json::Json, State, http::Status};
+use rocket::{get, post, serde::json::Json, State, http::Status};
 use crate::db::Db;
-use super::parse_tags;
+use crate::auth::ManageToken;
+use crate::models::{ChannelFilter, GroupUptime, RenameTag, RenameTagResponse};
+use super::{parse_tags, tags_to_string, verify_admin_key, audit_actor, record_audit};
+use rusqlite::params;
 use std::sync::Arc;
 
 // ── Tags ──
@@ -9,7 +12,7 @@ use std::sync::Arc;
 pub fn list_tags(db: &State<Arc<Db>>) -> Result<Json<Vec<String>>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     let mut stmt = conn.prepare(
-        "SELECT DISTINCT tags FROM monitors WHERE is_public = 1 AND tags != ''"
+        "SELECT DISTINCT tags FROM monitors WHERE is_public = 1 AND deleted_at IS NULL AND tags != ''"
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
     let mut all_tags: std::collections::BTreeSet<String> = std::collections::BTreeSet::new();
@@ -27,13 +30,101 @@ pub fn list_tags(db: &State<Arc<Db>>) -> Result<Json<Vec<String>>, (Status, Json
     Ok(Json(all_tags.into_iter().collect()))
 }
 
+/// Replace `from` with `to` in a tag list, dropping the duplicate when `to`
+/// is already there. Returns None when `from` isn't present.
+fn rename_in(tags: &[String], from: &str, to: &str) -> Option<(Vec<String>, bool)> {
+    if !tags.iter().any(|t| t == from) {
+        return None;
+    }
+    let merged = tags.iter().any(|t| t == to);
+    let mut out: Vec<String> = Vec::with_capacity(tags.len());
+    for t in tags {
+        let t = if t == from { to } else { t.as_str() };
+        if !out.iter().any(|o| o == t) {
+            out.push(t.to_string());
+        }
+    }
+    Some((out, merged))
+}
+
+/// Rename a tag on every monitor (and in notification channel tag filters).
+/// If a monitor already carries the target tag, the two are merged.
+#[post("/tags/rename", format = "json", data = "<input>")]
+pub fn rename_tag(
+    input: Json<RenameTag>,
+    token: ManageToken,
+    db: &State<Arc<Db>>,
+) -> Result<Json<RenameTagResponse>, (Status, Json<serde_json::Value>)> {
+    let conn = db.conn();
+    verify_admin_key(&conn, &token.0)?;
+
+    // Normalize the same way monitor tags are stored
+    let from = tags_to_string(std::slice::from_ref(&input.from));
+    let to = tags_to_string(std::slice::from_ref(&input.to));
+    if from.is_empty() || to.is_empty() || from.contains(',') || to.contains(',') {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "from and to must each be a single non-empty tag", "code": "VALIDATION_ERROR"
+        }))));
+    }
+    if from == to {
+        return Err((Status::BadRequest, Json(serde_json::json!({
+            "error": "from and to are the same tag", "code": "VALIDATION_ERROR"
+        }))));
+    }
+
+    let internal = |_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
+    let tx = conn.unchecked_transaction().map_err(internal)?;
+
+    let monitors: Vec<(String, String)> = {
+        let mut stmt = tx.prepare("SELECT id, tags FROM monitors WHERE tags != ''").map_err(internal)?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(internal)?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    let (mut monitors_updated, mut merged) = (0, 0);
+    for (id, tags_str) in monitors {
+        if let Some((tags, was_merged)) = rename_in(&parse_tags(&tags_str), &from, &to) {
+            tx.execute(
+                "UPDATE monitors SET tags = ?1, updated_at = datetime('now') WHERE id = ?2",
+                params![tags_to_string(&tags), id],
+            ).map_err(internal)?;
+            monitors_updated += 1;
+            merged += was_merged as usize;
+        }
+    }
+
+    let channels: Vec<(String, String)> = {
+        let mut stmt = tx.prepare("SELECT id, filter FROM notification_channels WHERE filter IS NOT NULL").map_err(internal)?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?))).map_err(internal)?;
+        rows.filter_map(|r| r.ok()).collect()
+    };
+    let mut channels_updated = 0;
+    for (id, filter_str) in channels {
+        let Ok(mut filter) = serde_json::from_str::<ChannelFilter>(&filter_str) else { continue };
+        if let Some((tags, _)) = rename_in(&filter.tags, &from, &to) {
+            filter.tags = tags;
+            tx.execute(
+                "UPDATE notification_channels SET filter = ?1 WHERE id = ?2",
+                params![serde_json::to_string(&filter).unwrap_or_default(), id],
+            ).map_err(internal)?;
+            channels_updated += 1;
+        }
+    }
+
+    record_audit(&tx, "tag.renamed", None, &audit_actor(&tx, &token.0), Some(serde_json::json!({
+        "from": from, "to": to, "monitors_updated": monitors_updated, "merged": merged,
+    })));
+    tx.commit().map_err(internal)?;
+
+    Ok(Json(RenameTagResponse { from, to, monitors_updated, merged, channels_updated }))
+}
+
 // ── Groups ──
 
 #[get("/groups")]
 pub fn list_groups(db: &State<Arc<Db>>) -> Result<Json<Vec<String>>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     let mut stmt = conn.prepare(
-        "SELECT DISTINCT group_name FROM monitors WHERE is_public = 1 AND group_name IS NOT NULL AND group_name != '' ORDER BY group_name"
+        "SELECT DISTINCT group_name FROM monitors WHERE is_public = 1 AND deleted_at IS NULL AND group_name IS NOT NULL AND group_name != '' ORDER BY group_name"
     ).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"}))))?;
 
     let groups: Vec<String> = stmt.query_map([], |row| row.get(0))
@@ -43,3 +134,51 @@ pub fn list_groups(db: &State<Arc<Db>>) -> Result<Json<Vec<String>>, (Status, Js
 
     Ok(Json(groups))
 }
+
+/// Uptime and latency pooled across a group's public monitors, so a team's
+/// reliability isn't just the average of its monitors' percentages.
+#[get("/groups/<name>/uptime")]
+pub fn get_group_uptime(name: &str, db: &State<Arc<Db>>) -> Result<Json<GroupUptime>, (Status, Json<serde_json::Value>)> {
+    let conn = db.read();
+    let monitor_count: u32 = conn.query_row(
+        "SELECT COUNT(*) FROM monitors WHERE group_name = ?1 AND is_public = 1 AND deleted_at IS NULL",
+        params![name],
+        |row| row.get(0),
+    ).unwrap_or(0);
+    if monitor_count == 0 {
+        return Err((Status::NotFound, Json(serde_json::json!({"error": "Group not found", "code": "NOT_FOUND"}))));
+    }
+
+    let calc = |hours: u32| -> (f64, u32, Option<f64>) {
+        let (total, up, avg): (u32, u32, Option<f64>) = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(CASE WHEN h.status = 'up' THEN 1 ELSE 0 END), 0),
+                    AVG(CASE WHEN h.status = 'up' THEN h.response_time_ms END)
+             FROM heartbeats h JOIN monitors m ON m.id = h.monitor_id
+             WHERE m.group_name = ?1 AND m.is_public = 1 AND m.deleted_at IS NULL
+               AND h.checked_at > datetime('now', ?2)",
+            params![name, format!("-{} hours", hours)],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).unwrap_or((0, 0, None));
+        let pct = if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 };
+        (pct, total, avg)
+    };
+
+    let (u24, t24, a24) = calc(24);
+    let (u7d, t7d, a7d) = calc(168);
+    let (u30d, t30d, a30d) = calc(720);
+
+    Ok(Json(GroupUptime {
+        group: name.to_string(),
+        monitor_count,
+        uptime_24h: u24,
+        uptime_7d: u7d,
+        uptime_30d: u30d,
+        total_checks_24h: t24,
+        total_checks_7d: t7d,
+        total_checks_30d: t30d,
+        avg_response_ms_24h: a24,
+        avg_response_ms_7d: a7d,
+        avg_response_ms_30d: a30d,
+    }))
+}
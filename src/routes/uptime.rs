@@ -7,86 +7,161 @@ use std::sync::Arc;
 
 // ── Uptime History ──
 
-#[get("/uptime-history?<days>")]
+/// Parse a `tz` parameter into minutes east of UTC. Accepts `UTC`/`Z` and fixed
+/// offsets like `+05:30`, `-0800`, `+9` or `UTC+2`. IANA zone names are rejected
+/// since there's no zone database to resolve their DST rules against.
+fn parse_tz_offset(tz: &str) -> Result<i32, String> {
+    let invalid = || format!("Invalid tz '{}'. Use a UTC offset like +05:30 or -08:00", tz);
+    let s = tz.trim();
+    let s = s.strip_prefix("UTC").or_else(|| s.strip_prefix("GMT")).unwrap_or(s);
+    if s.is_empty() || s == "Z" {
+        return Ok(0);
+    }
+    let (sign, rest) = match s.as_bytes()[0] {
+        b'+' => (1, &s[1..]),
+        b'-' => (-1, &s[1..]),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = match rest.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if rest.len() == 4 => rest.split_at(2),
+        None => (rest, "0"),
+    };
+    if hours.is_empty() || hours.len() > 2 || !hours.bytes().all(|b| b.is_ascii_digit()) || !minutes.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let (hours, minutes): (i32, i32) = (hours.parse().map_err(|_| invalid())?, minutes.parse().map_err(|_| invalid())?);
+    let offset = sign * (hours * 60 + minutes);
+    if minutes >= 60 || !(-12 * 60..=14 * 60).contains(&offset) {
+        return Err(invalid());
+    }
+    Ok(offset)
+}
+
+/// Per-day uptime across all monitors (`monitor_id` NULL) or one monitor.
+/// Completed days come from the `daily_uptime` rollup; days after the latest
+/// rollup (always including today) are computed live from heartbeats.
+/// A non-zero `tz_offset` (minutes east of UTC) shifts the day boundary.
+fn query_uptime_history(conn: &rusqlite::Connection, monitor_id: Option<&str>, days: u32, tz_offset: i32) -> rusqlite::Result<Vec<UptimeHistoryDay>> {
+    if tz_offset != 0 {
+        return query_shifted_uptime_history(conn, monitor_id, days, tz_offset);
+    }
+    let mut stmt = conn.prepare(
+        "WITH watermark AS (
+             SELECT COALESCE(date(MAX(day), '+1 day'), '0000-01-01') AS live_from FROM daily_uptime
+         ),
+         per_monitor AS (
+             SELECT day, total_checks, up_checks, down_checks, up_rt_sum, up_rt_count
+             FROM daily_uptime
+             WHERE day >= date('now', ?1) AND (?2 IS NULL OR monitor_id = ?2)
+             UNION ALL
+             SELECT date(checked_at), COUNT(*),
+                    SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN status = 'down' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN status = 'up' THEN response_time_ms ELSE 0 END),
+                    SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END)
+             FROM heartbeats
+             WHERE checked_at >= date('now', ?1)
+               AND checked_at >= (SELECT live_from FROM watermark)
+               AND (?2 IS NULL OR monitor_id = ?2)
+             GROUP BY date(checked_at)
+         )
+         SELECT day, SUM(total_checks), SUM(up_checks), SUM(down_checks),
+                CAST(SUM(up_rt_sum) AS REAL) / NULLIF(SUM(up_rt_count), 0)
+         FROM per_monitor
+         GROUP BY day ORDER BY day ASC"
+    )?;
+
+    let offset_str = format!("-{} days", days);
+    let rows = stmt.query_map(params![offset_str, monitor_id], row_to_history_day)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rows)
+}
+
+/// Local-day history for a UTC offset. The UTC `daily_uptime` rollup can't be
+/// re-bucketed, so this reads raw heartbeats plus the hourly aggregates of
+/// downsampled ones (bucketed by the hour's start).
+fn query_shifted_uptime_history(conn: &rusqlite::Connection, monitor_id: Option<&str>, days: u32, tz_offset: i32) -> rusqlite::Result<Vec<UptimeHistoryDay>> {
+    let mut stmt = conn.prepare(
+        "WITH per_source AS (
+             SELECT date(hour, ?3) AS day, total_checks, up_checks, down_checks, up_rt_sum, up_rt_count
+             FROM heartbeat_hourly
+             WHERE hour >= datetime('now', ?1, '-1 day') AND (?2 IS NULL OR monitor_id = ?2)
+             UNION ALL
+             SELECT date(checked_at, ?3), COUNT(*),
+                    SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN status = 'down' THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN status = 'up' THEN response_time_ms ELSE 0 END),
+                    SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END)
+             FROM heartbeats
+             WHERE checked_at >= datetime('now', ?1, '-1 day') AND (?2 IS NULL OR monitor_id = ?2)
+             GROUP BY date(checked_at, ?3)
+         )
+         SELECT day, SUM(total_checks), SUM(up_checks), SUM(down_checks),
+                CAST(SUM(up_rt_sum) AS REAL) / NULLIF(SUM(up_rt_count), 0)
+         FROM per_source
+         WHERE day >= date('now', ?3, ?1)
+         GROUP BY day ORDER BY day ASC"
+    )?;
+
+    let offset_str = format!("-{} days", days);
+    let shift = format!("{:+} minutes", tz_offset);
+    let rows = stmt.query_map(params![offset_str, monitor_id, shift], row_to_history_day)?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(rows)
+}
+
+fn row_to_history_day(row: &rusqlite::Row) -> rusqlite::Result<UptimeHistoryDay> {
+    let total: u32 = row.get(1)?;
+    let up: u32 = row.get(2)?;
+    let pct = if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 };
+    Ok(UptimeHistoryDay {
+        date: row.get(0)?,
+        uptime_pct: pct,
+        total_checks: total,
+        up_checks: up,
+        down_checks: row.get(3)?,
+        avg_response_ms: row.get(4)?,
+    })
+}
+
+fn tz_param(tz: Option<&str>) -> Result<i32, (Status, Json<serde_json::Value>)> {
+    tz.map_or(Ok(0), parse_tz_offset)
+        .map_err(|e| (Status::BadRequest, Json(serde_json::json!({"error": e, "code": "VALIDATION_ERROR"}))))
+}
+
+#[get("/uptime-history?<days>&<tz>")]
 pub fn uptime_history(
     days: Option<u32>,
+    tz: Option<&str>,
     db: &State<Arc<Db>>,
 ) -> Result<Json<Vec<UptimeHistoryDay>>, (Status, Json<serde_json::Value>)> {
     let days = days.unwrap_or(30).clamp(1, 90);
-    let conn = db.conn();
+    let tz_offset = tz_param(tz)?;
+    let conn = db.read();
     let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
 
-    let mut stmt = conn.prepare(
-        "SELECT date(checked_at) as day, \
-         COUNT(*) as total, \
-         SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END) as up_count, \
-         SUM(CASE WHEN status = 'down' THEN 1 ELSE 0 END) as down_count, \
-         AVG(CASE WHEN status = 'up' THEN response_time_ms ELSE NULL END) as avg_rt \
-         FROM heartbeats \
-         WHERE checked_at > datetime('now', ?1) \
-         GROUP BY day ORDER BY day ASC"
-    ).map_err(err_map)?;
-
-    let offset_str = format!("-{} days", days);
-    let rows: Vec<UptimeHistoryDay> = stmt.query_map(params![offset_str], |row| {
-        let total: u32 = row.get(1)?;
-        let up: u32 = row.get(2)?;
-        let pct = if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 };
-        Ok(UptimeHistoryDay {
-            date: row.get(0)?,
-            uptime_pct: pct,
-            total_checks: total,
-            up_checks: up,
-            down_checks: row.get(3)?,
-            avg_response_ms: row.get(4)?,
-        })
-    }).map_err(err_map)?
-    .filter_map(|r| r.ok())
-    .collect();
-
+    let rows = query_uptime_history(&conn, None, days, tz_offset).map_err(err_map)?;
     Ok(Json(rows))
 }
 
-#[get("/monitors/<id>/uptime-history?<days>")]
+#[get("/monitors/<id>/uptime-history?<days>&<tz>")]
 pub fn monitor_uptime_history(
     id: &str,
     days: Option<u32>,
+    tz: Option<&str>,
     db: &State<Arc<Db>>,
 ) -> Result<Json<Vec<UptimeHistoryDay>>, (Status, Json<serde_json::Value>)> {
     let days = days.unwrap_or(30).clamp(1, 90);
-    let conn = db.conn();
+    let tz_offset = tz_param(tz)?;
+    let conn = db.read();
     let err_map = |_: rusqlite::Error| (Status::InternalServerError, Json(serde_json::json!({"error": "Internal server error"})));
 
     get_monitor_from_db(&conn, id)
         .map_err(|_| (Status::NotFound, Json(serde_json::json!({"error": "Monitor not found", "code": "NOT_FOUND"}))))?;
 
-    let mut stmt = conn.prepare(
-        "SELECT date(checked_at) as day, \
-         COUNT(*) as total, \
-         SUM(CASE WHEN status = 'up' THEN 1 ELSE 0 END) as up_count, \
-         SUM(CASE WHEN status = 'down' THEN 1 ELSE 0 END) as down_count, \
-         AVG(CASE WHEN status = 'up' THEN response_time_ms ELSE NULL END) as avg_rt \
-         FROM heartbeats \
-         WHERE monitor_id = ?1 AND checked_at > datetime('now', ?2) \
-         GROUP BY day ORDER BY day ASC"
-    ).map_err(err_map)?;
-
-    let offset_str = format!("-{} days", days);
-    let rows: Vec<UptimeHistoryDay> = stmt.query_map(params![id, offset_str], |row| {
-        let total: u32 = row.get(1)?;
-        let up: u32 = row.get(2)?;
-        let pct = if total > 0 { (up as f64 / total as f64) * 100.0 } else { 100.0 };
-        Ok(UptimeHistoryDay {
-            date: row.get(0)?,
-            uptime_pct: pct,
-            total_checks: total,
-            up_checks: up,
-            down_checks: row.get(3)?,
-            avg_response_ms: row.get(4)?,
-        })
-    }).map_err(err_map)?
-    .filter_map(|r| r.ok())
-    .collect();
-
+    let rows = query_uptime_history(&conn, Some(id), days, tz_offset).map_err(err_map)?;
     Ok(Json(rows))
 }
@@ -29,17 +29,48 @@ pub struct WebhookDeliveriesListResponse {
     pub total: i64,
 }
 
-/// GET /api/v1/monitors/:id/webhook-deliveries — list webhook delivery attempts (manage key required)
-#[get("/monitors/<monitor_id>/webhook-deliveries?<limit>&<after>&<event>&<status>")]
+/// Per-channel rollup of delivery attempts. A delivery (one `delivery_group`)
+/// counts as succeeded if any of its attempts succeeded.
+#[derive(Debug, Serialize)]
+pub struct WebhookChannelSummary {
+    pub url: String,
+    pub attempts: i64,
+    pub deliveries: i64,
+    pub succeeded: i64,
+    pub failed: i64,
+    pub last_status: String,
+    pub last_status_code: Option<i64>,
+    pub last_event: String,
+    pub last_attempt_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookChannelsResponse {
+    pub channels: Vec<WebhookChannelSummary>,
+    pub total: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum WebhookDeliveriesResponse {
+    List(WebhookDeliveriesListResponse),
+    Grouped(WebhookChannelsResponse),
+}
+
+/// GET /api/v1/monitors/:id/webhook-deliveries — list webhook delivery attempts (manage key required).
+/// `?group_by=channel` returns one summary row per destination URL instead.
+#[get("/monitors/<monitor_id>/webhook-deliveries?<limit>&<after>&<event>&<status>&<group_by>")]
+#[allow(clippy::too_many_arguments)]
 pub fn list_webhook_deliveries(
     monitor_id: &str,
     limit: Option<i64>,
     after: Option<i64>,
     event: Option<&str>,
     status: Option<&str>,
+    group_by: Option<&str>,
     token: ManageToken,
     db: &State<Arc<Db>>,
-) -> Result<Json<WebhookDeliveriesListResponse>, (Status, Json<serde_json::Value>)> {
+) -> Result<Json<WebhookDeliveriesResponse>, (Status, Json<serde_json::Value>)> {
     let conn = db.conn();
     verify_manage_key(&conn, monitor_id, &token.0)?;
 
@@ -64,6 +95,22 @@ pub fn list_webhook_deliveries(
 
     let where_clause = where_parts.join(" AND ");
 
+    match group_by {
+        None => {}
+        Some("channel") => {
+            let channels = group_by_channel(&conn, &where_clause, &bind_values)?;
+            return Ok(Json(WebhookDeliveriesResponse::Grouped(WebhookChannelsResponse {
+                total: channels.len() as i64,
+                channels,
+            })));
+        }
+        Some(_) => {
+            return Err((Status::BadRequest, Json(serde_json::json!({
+                "error": "group_by must be 'channel'", "code": "VALIDATION_ERROR"
+            }))));
+        }
+    }
+
     // Count total
     let count_sql = format!("SELECT COUNT(*) FROM webhook_deliveries WHERE {}", where_clause);
     let total: i64 = {
@@ -102,8 +149,52 @@ pub fn list_webhook_deliveries(
 
     let deliveries: Vec<WebhookDeliveryResponse> = rows.filter_map(|r| r.ok()).collect();
 
-    Ok(Json(WebhookDeliveriesListResponse {
+    Ok(Json(WebhookDeliveriesResponse::List(WebhookDeliveriesListResponse {
         deliveries,
         total,
-    }))
+    })))
+}
+
+/// Summarize matching attempts per destination URL, most recently active first.
+fn group_by_channel(
+    conn: &rusqlite::Connection,
+    where_clause: &str,
+    bind_values: &[Box<dyn rusqlite::types::ToSql>],
+) -> Result<Vec<WebhookChannelSummary>, (Status, Json<serde_json::Value>)> {
+    let sql = format!(
+        "WITH matching AS (SELECT * FROM webhook_deliveries WHERE {where_clause}),
+              groups AS (
+                SELECT url, delivery_group, MAX(status = 'success') AS ok
+                FROM matching GROUP BY url, delivery_group
+              ),
+              latest AS (
+                SELECT url, status, status_code, event, created_at, seq,
+                       ROW_NUMBER() OVER (PARTITION BY url ORDER BY seq DESC) AS rn
+                FROM matching
+              )
+         SELECT l.url,
+                (SELECT COUNT(*) FROM matching m WHERE m.url = l.url),
+                (SELECT COUNT(*) FROM groups g WHERE g.url = l.url),
+                (SELECT COUNT(*) FROM groups g WHERE g.url = l.url AND g.ok = 1),
+                (SELECT COUNT(*) FROM groups g WHERE g.url = l.url AND g.ok = 0),
+                l.status, l.status_code, l.event, l.created_at
+         FROM latest l WHERE l.rn = 1
+         ORDER BY l.seq DESC"
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "DB error"}))))?;
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> = bind_values.iter().map(|b| b.as_ref()).collect();
+    let rows = stmt.query_map(params_ref.as_slice(), |row| {
+        Ok(WebhookChannelSummary {
+            url: row.get(0)?,
+            attempts: row.get(1)?,
+            deliveries: row.get(2)?,
+            succeeded: row.get(3)?,
+            failed: row.get(4)?,
+            last_status: row.get(5)?,
+            last_status_code: row.get(6)?,
+            last_event: row.get(7)?,
+            last_attempt_at: row.get(8)?,
+        })
+    }).map_err(|_| (Status::InternalServerError, Json(serde_json::json!({"error": "DB error"}))))?;
+    Ok(rows.filter_map(|r| r.ok()).collect())
 }
@@ -1,6 +1,12 @@
+use rocket::request::{FromRequest, Outcome, Request};
 use rocket::response::stream::{Event, EventStream};
 use rocket::tokio::sync::broadcast;
 use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use rocket::tokio::time::Instant;
+use crate::db::Db;
 
 /// SSE event data sent to subscribers.
 #[derive(Debug, Clone, Serialize)]
@@ -10,61 +16,282 @@ pub struct SseEvent {
     pub data: serde_json::Value,
 }
 
+/// Default idle interval before a `:keepalive` comment is sent to SSE subscribers.
+const DEFAULT_KEEPALIVE_SECS: u64 = 15;
+
+/// An event stamped with its broadcast id (monotonic per process, starting at 1).
+#[derive(Debug, Clone)]
+pub struct BroadcastEvent {
+    pub id: u64,
+    pub event: SseEvent,
+}
+
+/// Recently sent events kept for Last-Event-ID replay, plus the id counter.
+struct ReplayBuffer {
+    last_id: u64,
+    events: VecDeque<BroadcastEvent>,
+}
+
 /// Global event broadcaster. Subscribers receive all events.
 pub struct EventBroadcaster {
-    pub sender: broadcast::Sender<SseEvent>,
+    pub sender: broadcast::Sender<BroadcastEvent>,
+    /// Idle time before streams emit a keepalive comment (None disables).
+    pub keepalive: Option<Duration>,
+    capacity: usize,
+    replay: Mutex<ReplayBuffer>,
 }
 
 impl EventBroadcaster {
+    /// Keepalive is read from SSE_KEEPALIVE_SECS (default 15, 0 disables).
     pub fn new(capacity: usize) -> Self {
+        let secs = std::env::var("SSE_KEEPALIVE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_KEEPALIVE_SECS);
         let (sender, _) = broadcast::channel(capacity);
-        EventBroadcaster { sender }
+        EventBroadcaster {
+            sender,
+            keepalive: (secs > 0).then(|| Duration::from_secs(secs)),
+            capacity,
+            replay: Mutex::new(ReplayBuffer { last_id: 0, events: VecDeque::with_capacity(capacity) }),
+        }
     }
 
     pub fn send(&self, event: SseEvent) {
+        // Hold the replay lock while sending so ids reach subscribers in order
+        let mut replay = self.replay.lock().unwrap_or_else(|e| e.into_inner());
+        replay.last_id += 1;
+        let stamped = BroadcastEvent { id: replay.last_id, event };
+        if replay.events.len() >= self.capacity {
+            replay.events.pop_front();
+        }
+        replay.events.push_back(stamped.clone());
         // Ignore send errors (no subscribers)
-        let _ = self.sender.send(event);
+        let _ = self.sender.send(stamped);
+    }
+
+    /// Buffered events with an id greater than `after`, oldest first.
+    /// Only the last `capacity` events are retained.
+    pub fn replay_after(&self, after: u64) -> Vec<BroadcastEvent> {
+        let replay = self.replay.lock().unwrap_or_else(|e| e.into_inner());
+        replay.events.iter().filter(|e| e.id > after).cloned().collect()
+    }
+}
+
+/// The `Last-Event-ID` header sent by reconnecting SSE clients, if present and numeric.
+pub struct LastEventId(pub Option<u64>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LastEventId {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let id = request.headers().get_one("Last-Event-ID").and_then(|v| v.trim().parse().ok());
+        Outcome::Success(LastEventId(id))
     }
 }
 
-/// Create an SSE stream for all events.
-pub fn global_stream(broadcaster: &EventBroadcaster) -> EventStream![Event + '_] {
+/// Per-subscriber filter for the global stream. Empty fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct StreamFilter {
+    pub group: Option<String>,
+    pub tag: Option<String>,
+}
+
+impl StreamFilter {
+    pub fn new(group: Option<&str>, tag: Option<&str>) -> Self {
+        StreamFilter {
+            group: group.map(|g| g.trim().to_string()).filter(|g| !g.is_empty()),
+            tag: tag.map(|t| t.trim().to_lowercase()).filter(|t| !t.is_empty()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.group.is_none() && self.tag.is_none()
+    }
+
+    /// Check a monitor's group and tags against the filter.
+    pub fn matches_monitor(&self, db: &Db, monitor_id: &str) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let conn = db.conn();
+        let row: Option<(Option<String>, String)> = conn.query_row(
+            "SELECT group_name, tags FROM monitors WHERE id = ?1",
+            rusqlite::params![monitor_id],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        ).ok();
+        let Some((group_name, tags)) = row else { return false };
+        if let Some(ref g) = self.group {
+            if group_name.as_deref() != Some(g.as_str()) {
+                return false;
+            }
+        }
+        if let Some(ref t) = self.tag {
+            if !tags.split(',').any(|x| x.trim() == t) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// The next thing a stream should emit.
+#[derive(Debug)]
+pub enum Frame {
+    Event(BroadcastEvent),
+    Lagged(u64),
+    Keepalive,
+    Closed,
+}
+
+/// Wait for the next broadcast event, or a keepalive if `deadline` passes first.
+/// Streams push the deadline forward only when they actually send something, so
+/// events filtered out for this subscriber don't postpone the keepalive.
+pub async fn next_frame(rx: &mut broadcast::Receiver<BroadcastEvent>, deadline: Option<Instant>) -> Frame {
+    let result = match deadline {
+        Some(at) => match rocket::tokio::time::timeout_at(at, rx.recv()).await {
+            Ok(r) => r,
+            Err(_) => return Frame::Keepalive,
+        },
+        None => rx.recv().await,
+    };
+    match result {
+        Ok(evt) => Frame::Event(evt),
+        Err(broadcast::error::RecvError::Lagged(n)) => Frame::Lagged(n),
+        Err(broadcast::error::RecvError::Closed) => Frame::Closed,
+    }
+}
+
+/// Comment frame sent on idle connections so proxies don't time them out.
+pub fn keepalive_event() -> Event {
+    Event::comment("keepalive")
+}
+
+fn lag_event(n: u64) -> Event {
+    Event::data(format!("{{\"skipped\":{}}}", n)).event("lag")
+}
+
+/// JSON body of a global-stream event, shared by the SSE and WebSocket endpoints.
+pub fn global_event_json(stamped: &BroadcastEvent) -> serde_json::Value {
+    serde_json::json!({
+        "monitor_id": stamped.event.monitor_id,
+        "data": stamped.event.data,
+    })
+}
+
+fn global_event(stamped: &BroadcastEvent) -> Event {
+    let data = serde_json::to_string(&global_event_json(stamped)).unwrap_or_default();
+    Event::data(data).event(stamped.event.event_type.clone()).id(stamped.id.to_string())
+}
+
+fn monitor_event(stamped: &BroadcastEvent) -> Event {
+    let data = serde_json::to_string(&stamped.event.data).unwrap_or_default();
+    Event::data(data).event(stamped.event.event_type.clone()).id(stamped.id.to_string())
+}
+
+/// Create an SSE stream for all events, optionally filtered by monitor group or tag.
+/// With `last_event_id`, buffered events newer than it are replayed first.
+pub fn global_stream(broadcaster: &EventBroadcaster, db: Arc<Db>, filter: StreamFilter, last_event_id: Option<u64>) -> EventStream![Event + '_] {
+    // Subscribe before snapshotting the buffer so nothing falls in the gap;
+    // anything seen in both is skipped by id below.
     let mut rx = broadcaster.sender.subscribe();
+    let replay = last_event_id.map(|after| broadcaster.replay_after(after)).unwrap_or_default();
+    let keepalive = broadcaster.keepalive;
     EventStream! {
+        let mut seen = last_event_id.unwrap_or(0);
+        for stamped in replay {
+            seen = stamped.id;
+            if filter.matches_monitor(&db, &stamped.event.monitor_id) {
+                yield global_event(&stamped);
+            }
+        }
+        let mut deadline = keepalive.map(|d| Instant::now() + d);
         loop {
-            match rx.recv().await {
-                Ok(evt) => {
-                    let data = serde_json::to_string(&serde_json::json!({
-                        "monitor_id": evt.monitor_id,
-                        "data": evt.data,
-                    })).unwrap_or_default();
-                    yield Event::data(data).event(evt.event_type);
-                }
-                Err(broadcast::error::RecvError::Lagged(n)) => {
-                    yield Event::data(format!("{{\"skipped\":{}}}", n)).event("lag");
-                }
-                Err(broadcast::error::RecvError::Closed) => break,
+            match next_frame(&mut rx, deadline).await {
+                Frame::Event(stamped) if stamped.id <= seen => continue,
+                Frame::Event(stamped) if !filter.matches_monitor(&db, &stamped.event.monitor_id) => continue,
+                Frame::Event(stamped) => yield global_event(&stamped),
+                Frame::Lagged(n) => yield lag_event(n),
+                Frame::Keepalive => yield keepalive_event(),
+                Frame::Closed => break,
             }
+            deadline = keepalive.map(|d| Instant::now() + d);
         }
-    }
+    }.heartbeat(None)
 }
 
 /// Create an SSE stream filtered to a specific monitor.
-pub fn monitor_stream<'a>(broadcaster: &'a EventBroadcaster, monitor_id: String) -> EventStream![Event + 'a] {
+/// With `last_event_id`, buffered events newer than it are replayed first.
+pub fn monitor_stream(broadcaster: &EventBroadcaster, monitor_id: String, last_event_id: Option<u64>) -> EventStream![Event + '_] {
     let mut rx = broadcaster.sender.subscribe();
+    let replay = last_event_id.map(|after| broadcaster.replay_after(after)).unwrap_or_default();
+    let keepalive = broadcaster.keepalive;
     EventStream! {
+        let mut seen = last_event_id.unwrap_or(0);
+        for stamped in replay {
+            seen = stamped.id;
+            if stamped.event.monitor_id == monitor_id {
+                yield monitor_event(&stamped);
+            }
+        }
+        let mut deadline = keepalive.map(|d| Instant::now() + d);
         loop {
-            match rx.recv().await {
-                Ok(evt) if evt.monitor_id == monitor_id => {
-                    let data = serde_json::to_string(&evt.data).unwrap_or_default();
-                    yield Event::data(data).event(evt.event_type);
-                }
-                Ok(_) => continue, // Different monitor, skip
-                Err(broadcast::error::RecvError::Lagged(n)) => {
-                    yield Event::data(format!("{{\"skipped\":{}}}", n)).event("lag");
-                }
-                Err(broadcast::error::RecvError::Closed) => break,
+            match next_frame(&mut rx, deadline).await {
+                Frame::Event(stamped) if stamped.id <= seen => continue,
+                Frame::Event(stamped) if stamped.event.monitor_id == monitor_id => yield monitor_event(&stamped),
+                Frame::Event(_) => continue, // Different monitor, skip
+                Frame::Lagged(n) => yield lag_event(n),
+                Frame::Keepalive => yield keepalive_event(),
+                Frame::Closed => break,
             }
+            deadline = keepalive.map(|d| Instant::now() + d);
+        }
+    }.heartbeat(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_event(monitor_id: &str) -> SseEvent {
+        SseEvent {
+            event_type: "check.completed".to_string(),
+            monitor_id: monitor_id.to_string(),
+            data: serde_json::json!({}),
+        }
+    }
+
+    #[rocket::async_test]
+    async fn test_keepalive_when_quiet() {
+        let broadcaster = EventBroadcaster::new(8);
+        let mut rx = broadcaster.sender.subscribe();
+        let deadline = Some(Instant::now() + Duration::from_millis(20));
+        assert!(matches!(next_frame(&mut rx, deadline).await, Frame::Keepalive));
+    }
+
+    #[rocket::async_test]
+    async fn test_event_before_keepalive() {
+        let broadcaster = EventBroadcaster::new(8);
+        let mut rx = broadcaster.sender.subscribe();
+        broadcaster.send(test_event("m1"));
+        let deadline = Some(Instant::now() + Duration::from_secs(5));
+        match next_frame(&mut rx, deadline).await {
+            Frame::Event(stamped) => assert_eq!(stamped.event.monitor_id, "m1"),
+            other => panic!("expected event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_replay_after_returns_newer_events() {
+        let broadcaster = EventBroadcaster::new(3);
+        for m in ["m1", "m2", "m3", "m4"] {
+            broadcaster.send(test_event(m));
         }
+        // Capacity 3: id 1 has been evicted
+        let all: Vec<u64> = broadcaster.replay_after(0).iter().map(|e| e.id).collect();
+        assert_eq!(all, vec![2, 3, 4]);
+        let newer: Vec<String> = broadcaster.replay_after(3).into_iter().map(|e| e.event.monitor_id).collect();
+        assert_eq!(newer, vec!["m4".to_string()]);
     }
 }
@@ -0,0 +1,103 @@
+// WebSocket tests need a real listener (the local client can't upgrade
+// connections), so these launch the server on a free port.
+
+use rocket::futures::StreamExt;
+use std::sync::Arc;
+use tokio_tungstenite::tungstenite::Message;
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+async fn launch_ws_server() -> (u16, Arc<watchpost::sse::EventBroadcaster>, String) {
+    let db_path = format!("/tmp/watchpost_test_{}.db", uuid::Uuid::new_v4());
+    let database = Arc::new(watchpost::db::Db::new(&db_path).expect("DB init failed"));
+    let broadcaster = Arc::new(watchpost::sse::EventBroadcaster::new(64));
+    let port = free_port();
+
+    let figment = rocket::Config::figment()
+        .merge(("address", "127.0.0.1"))
+        .merge(("port", port))
+        .merge(("log_level", "off"));
+    let rocket = rocket::custom(figment)
+        .manage(database)
+        .manage(broadcaster.clone())
+        .mount("/api/v1", rocket::routes![watchpost::routes::ws_events]);
+    rocket::tokio::spawn(rocket.launch());
+
+    // Wait for the listener to come up
+    for _ in 0..50 {
+        if rocket::tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+            break;
+        }
+        rocket::tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    }
+    (port, broadcaster, db_path)
+}
+
+fn insert_monitor(db_path: &str, id: &str, group: Option<&str>) {
+    let conn = rusqlite::Connection::open(db_path).unwrap();
+    conn.execute(
+        "INSERT INTO monitors (id, name, url, manage_key_hash, group_name) VALUES (?1, ?1, 'https://example.com', 'x', ?2)",
+        rusqlite::params![id, group],
+    ).unwrap();
+}
+
+fn event(monitor_id: &str) -> watchpost::sse::SseEvent {
+    watchpost::sse::SseEvent {
+        event_type: "check.completed".to_string(),
+        monitor_id: monitor_id.to_string(),
+        data: serde_json::json!({"status": "up"}),
+    }
+}
+
+async fn next_text<S>(ws: &mut S) -> serde_json::Value
+where
+    S: rocket::futures::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    loop {
+        let msg = rocket::tokio::time::timeout(std::time::Duration::from_secs(3), ws.next())
+            .await
+            .expect("timed out waiting for message")
+            .expect("stream ended")
+            .expect("ws error");
+        if let Message::Text(text) = msg {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+#[rocket::async_test]
+async fn test_ws_receives_events() {
+    let (port, broadcaster, _db_path) = launch_ws_server().await;
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}/api/v1/ws", port))
+        .await
+        .expect("ws connect");
+
+    // Give the server a moment to subscribe before publishing
+    rocket::tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    broadcaster.send(event("mon-1"));
+
+    let msg = next_text(&mut ws).await;
+    assert_eq!(msg["event"], "check.completed");
+    assert_eq!(msg["monitor_id"], "mon-1");
+    assert_eq!(msg["data"]["status"], "up");
+    assert_eq!(msg["id"], 1);
+}
+
+#[rocket::async_test]
+async fn test_ws_group_filter() {
+    let (port, broadcaster, db_path) = launch_ws_server().await;
+    insert_monitor(&db_path, "a-api", Some("Team A"));
+    insert_monitor(&db_path, "b-api", Some("Team B"));
+
+    let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://127.0.0.1:{}/api/v1/ws?group=Team%20A", port))
+        .await
+        .expect("ws connect");
+    rocket::tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    broadcaster.send(event("b-api"));
+    broadcaster.send(event("a-api"));
+
+    let msg = next_text(&mut ws).await;
+    assert_eq!(msg["monitor_id"], "a-api");
+}